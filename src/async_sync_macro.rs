@@ -208,7 +208,7 @@ macro_rules! validate_params {
     ($($param:expr => $error_msg:expr),* $(,)?) => {
         $(
             if $param.is_none() || $param.as_ref().map(|s| s.is_empty()).unwrap_or(false) {
-                return Err(crate::error::PayupError::Validation($error_msg.to_string()));
+                return Err(crate::error::PayupError::ValidationError($error_msg.to_string()));
             }
         )*
     };
@@ -224,6 +224,7 @@ macro_rules! handle_api_response {
             $response.json().await
                 .map_err(|e| crate::error::PayupError::Deserialization(e.to_string()))
         } else {
+            let retry_after = crate::http_utils::parse_retry_after($response.headers());
             let error_body = $response.text().await
                 .unwrap_or_else(|_| format!("HTTP error {}", status.as_u16()));
             
@@ -232,9 +233,13 @@ macro_rules! handle_api_response {
                 401 => Err(crate::error::PayupError::Unauthorized(error_body)),
                 403 => Err(crate::error::PayupError::Forbidden(error_body)),
                 404 => Err(crate::error::PayupError::NotFound(error_body)),
-                429 => Err(crate::error::PayupError::RateLimited(error_body)),
-                500..=599 => Err(crate::error::PayupError::ServerError(error_body)),
-                _ => Err(crate::error::PayupError::Http(format!("HTTP {}: {}", status, error_body))),
+                429 => Err(crate::error::PayupError::RateLimitError { retry_after }),
+                500..=599 => Err(crate::error::PayupError::ServerError(status.as_u16())),
+                _ => Err(crate::error::PayupError::ApiError {
+                    code: status.to_string(),
+                    message: error_body,
+                    provider: "HTTP".to_string(),
+                }),
             }
         }
     }};
@@ -0,0 +1,411 @@
+use std::sync::Arc;
+use base64::{Engine as _, engine::general_purpose};
+use serde::de::DeserializeOwned;
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::{PayupError, Result};
+use reqwest::{Method, StatusCode};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, default_transport, SendVia, SendViaBlocking, Transport};
+use crate::http_utils::build_url;
+use super::{xml, BraintreeConfig, ValidationError, BRAINTREE_API_VERSION};
+
+/// Root element of every gateway error body.
+const ERROR_ROOT: &str = "api-error-response";
+
+/// Rate limit and circuit breaker every Braintree request goes through.
+const RATE_LIMIT_ENDPOINT: &str = "braintree";
+
+pub struct BraintreeClient {
+    pub config: BraintreeConfig,
+    transport: Arc<dyn Transport>,
+}
+
+/// Page of matching ids returned by `advanced_search_ids`.
+#[derive(Debug, Clone, Deserialize)]
+struct SearchResults {
+    #[serde(default)]
+    ids: Vec<String>,
+}
+
+#[derive(Debug, Serialize)]
+struct IdsSearch<'a> {
+    ids: &'a [String],
+}
+
+impl BraintreeClient {
+    pub fn new(config: BraintreeConfig) -> Result<Self> {
+        if config.merchant_id.is_empty() {
+            return Err(PayupError::AuthenticationError(
+                "Braintree merchant ID is empty".to_string()
+            ));
+        }
+        if config.public_key.is_empty() || config.private_key.is_empty() {
+            return Err(PayupError::AuthenticationError(
+                "Braintree public and private keys are required".to_string()
+            ));
+        }
+
+        Ok(Self {
+            config,
+            transport: default_transport(),
+        })
+    }
+
+    /// Sends every request through `transport` instead of the shared one.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
+    pub fn authorization_header(&self) -> String {
+        let credentials = format!("{}:{}", self.config.public_key, self.config.private_key);
+        format!("Basic {}", general_purpose::STANDARD.encode(credentials.as_bytes()))
+    }
+
+    fn url(&self, endpoint: &str) -> String {
+        build_url(&self.config.merchant_url(), endpoint)
+    }
+
+    /// A request to `endpoint` carrying the headers every gateway call needs.
+    fn blocking_request(&self, method: Method, endpoint: &str) -> reqwest::blocking::RequestBuilder {
+        get_shared_blocking_client()
+            .request(method, self.url(endpoint))
+            .header("Authorization", self.authorization_header())
+            .header("Accept", "application/xml")
+            .header("X-ApiVersion", BRAINTREE_API_VERSION)
+    }
+
+    fn async_request(&self, method: Method, endpoint: &str) -> reqwest::RequestBuilder {
+        get_shared_client()
+            .request(method, self.url(endpoint))
+            .header("Authorization", self.authorization_header())
+            .header("Accept", "application/xml")
+            .header("X-ApiVersion", BRAINTREE_API_VERSION)
+    }
+
+    // Requests go through the "braintree" rate limit and circuit breaker.
+    // Braintree has no idempotency keys, so POSTs are sent exactly once and
+    // only GET, PUT and DELETE are retried.
+
+    /// GETs `endpoint` and deserializes the `root` element of the response.
+    pub fn get<T: DeserializeOwned>(&self, endpoint: &str, root: &str) -> Result<T> {
+        let (name, value) = self.get_any(endpoint)?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    /// GETs `endpoint` and returns the raw root element name and contents,
+    /// for responses whose root depends on the record type.
+    pub fn get_any(&self, endpoint: &str) -> Result<(String, Value)> {
+        let response = self.blocking_request(Method::GET, endpoint)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)?;
+        parse_response(response.status(), &response.text()?)
+    }
+
+    /// POSTs `body` wrapped in `request_root` and deserializes the `root`
+    /// element of the response.
+    pub fn post<T, B>(&self, endpoint: &str, request_root: &str, body: &B, root: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize,
+    {
+        let (name, value) = self.post_any(endpoint, request_root, body)?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    pub fn post_any<B: Serialize>(&self, endpoint: &str, request_root: &str, body: &B) -> Result<(String, Value)> {
+        let response = self.blocking_request(Method::POST, endpoint)
+            .header("Content-Type", "application/xml")
+            .body(xml::to_xml(request_root, body)?)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)?;
+        parse_response(response.status(), &response.text()?)
+    }
+
+    pub fn put<T, B>(&self, endpoint: &str, request_root: &str, body: &B, root: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize,
+    {
+        let (name, value) = self.put_any(endpoint, request_root, body)?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    pub fn put_any<B: Serialize>(&self, endpoint: &str, request_root: &str, body: &B) -> Result<(String, Value)> {
+        let response = self.blocking_request(Method::PUT, endpoint)
+            .header("Content-Type", "application/xml")
+            .body(xml::to_xml(request_root, body)?)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)?;
+        parse_response(response.status(), &response.text()?)
+    }
+
+    pub fn delete(&self, endpoint: &str) -> Result<bool> {
+        self.blocking_request(Method::DELETE, endpoint)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)?;
+        Ok(true)
+    }
+
+    /// Runs an advanced search under `resource` (e.g. `/transactions`) and
+    /// returns up to `limit` matching records, skipping the first `offset`,
+    /// from the `root` collection.
+    pub fn search<T, C>(&self, resource: &str, criteria: &C, root: &str, offset: usize, limit: Option<usize>) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+        C: Serialize,
+    {
+        let endpoint = format!("{}/advanced_search_ids", resource);
+        let results: SearchResults = self.post(&endpoint, "search", criteria, "search-results")?;
+        let ids = page_ids(results.ids, offset, limit);
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let endpoint = format!("{}/advanced_search", resource);
+        self.post(&endpoint, "search", &IdsSearch { ids: &ids }, root)
+    }
+
+    pub async fn async_get<T: DeserializeOwned>(&self, endpoint: &str, root: &str) -> Result<T> {
+        let (name, value) = self.async_get_any(endpoint).await?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    pub async fn async_get_any(&self, endpoint: &str) -> Result<(String, Value)> {
+        let response = self.async_request(Method::GET, endpoint)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)
+            .await?;
+        let status = response.status();
+        parse_response(status, &response.text().await?)
+    }
+
+    pub async fn async_post<T, B>(&self, endpoint: &str, request_root: &str, body: &B, root: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize,
+    {
+        let (name, value) = self.async_post_any(endpoint, request_root, body).await?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    pub async fn async_post_any<B: Serialize>(&self, endpoint: &str, request_root: &str, body: &B) -> Result<(String, Value)> {
+        let response = self.async_request(Method::POST, endpoint)
+            .header("Content-Type", "application/xml")
+            .body(xml::to_xml(request_root, body)?)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)
+            .await?;
+        let status = response.status();
+        parse_response(status, &response.text().await?)
+    }
+
+    pub async fn async_put<T, B>(&self, endpoint: &str, request_root: &str, body: &B, root: &str) -> Result<T>
+    where
+        T: DeserializeOwned,
+        B: Serialize,
+    {
+        let (name, value) = self.async_put_any(endpoint, request_root, body).await?;
+        expect_root(&name, root)?;
+        serde_json::from_value(value).map_err(PayupError::from)
+    }
+
+    pub async fn async_put_any<B: Serialize>(&self, endpoint: &str, request_root: &str, body: &B) -> Result<(String, Value)> {
+        let response = self.async_request(Method::PUT, endpoint)
+            .header("Content-Type", "application/xml")
+            .body(xml::to_xml(request_root, body)?)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)
+            .await?;
+        let status = response.status();
+        parse_response(status, &response.text().await?)
+    }
+
+    pub async fn async_delete(&self, endpoint: &str) -> Result<bool> {
+        self.async_request(Method::DELETE, endpoint)
+            .send_limited(RATE_LIMIT_ENDPOINT, &*self.transport)
+            .await?;
+        Ok(true)
+    }
+
+    pub async fn async_search<T, C>(&self, resource: &str, criteria: &C, root: &str, offset: usize, limit: Option<usize>) -> Result<Vec<T>>
+    where
+        T: DeserializeOwned,
+        C: Serialize,
+    {
+        let endpoint = format!("{}/advanced_search_ids", resource);
+        let results: SearchResults = self.async_post(&endpoint, "search", criteria, "search-results").await?;
+        let ids = page_ids(results.ids, offset, limit);
+        if ids.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let endpoint = format!("{}/advanced_search", resource);
+        self.async_post(&endpoint, "search", &IdsSearch { ids: &ids }, root).await
+    }
+}
+
+/// Parses a successful response, which still holds an `api-error-response`
+/// when a transaction was declined.
+fn parse_response(status: StatusCode, body: &str) -> Result<(String, Value)> {
+    let (name, value) = xml::parse(body)?;
+    if name == ERROR_ROOT {
+        return Err(api_error(status, &value));
+    }
+    Ok((name, value))
+}
+
+/// Builds the error for a non-2xx gateway response; [`error_from_response`]
+/// uses this for the `"braintree"` endpoint. Validation failures come back
+/// as a 422 with an `api-error-response` body.
+///
+/// [`error_from_response`]: crate::http_utils::error_from_response
+pub(crate) fn error_from_body(status: StatusCode, body: &str) -> Option<PayupError> {
+    let message = match status.as_u16() {
+        422 => match xml::parse(body) {
+            Ok((name, value)) if name == ERROR_ROOT => return Some(api_error(status, &value)),
+            _ => body.to_string(),
+        },
+        401 => return Some(PayupError::AuthenticationError(
+            "Braintree rejected the API keys".to_string()
+        )),
+        403 => "API keys lack permission for this operation".to_string(),
+        404 => "Resource not found".to_string(),
+        426 => "Braintree requires a newer API version".to_string(),
+        // Left to the caller, which turns a 5xx into a retryable `ServerError`
+        _ if status.is_server_error() => return None,
+        _ => body.to_string(),
+    };
+    Some(PayupError::ApiError {
+        code: status.to_string(),
+        message,
+        provider: "Braintree".to_string(),
+    })
+}
+
+fn expect_root(name: &str, root: &str) -> Result<()> {
+    if name != root {
+        return Err(PayupError::Deserialization(format!(
+            "Expected <{}> but Braintree returned <{}>",
+            root, name
+        )));
+    }
+    Ok(())
+}
+
+fn page_ids(ids: Vec<String>, offset: usize, limit: Option<usize>) -> Vec<String> {
+    ids.into_iter()
+        .skip(offset)
+        .take(limit.unwrap_or(usize::MAX))
+        .collect()
+}
+
+/// Builds an `ApiError` from an `api-error-response`, preferring the first
+/// validation error's code and falling back to the processor response for
+/// declined transactions.
+fn api_error(status: StatusCode, value: &Value) -> PayupError {
+    let message = value["message"].as_str().unwrap_or("Unknown error").to_string();
+    let code = collect_validation_errors(&value["errors"])
+        .into_iter()
+        .next()
+        .map(|e| e.code)
+        .or_else(|| {
+            value["transaction"]["processor_response_code"]
+                .as_str()
+                .map(String::from)
+        })
+        .unwrap_or_else(|| status.to_string());
+
+    PayupError::ApiError {
+        code,
+        message,
+        provider: "Braintree".to_string(),
+    }
+}
+
+/// Flattens the nested `<errors>` tree (one level per request attribute)
+/// into a list of validation errors.
+pub fn collect_validation_errors(errors: &Value) -> Vec<ValidationError> {
+    let mut found = Vec::new();
+    match errors {
+        Value::Object(fields) => {
+            for (key, field) in fields {
+                if key == "errors" {
+                    if let Value::Array(items) = field {
+                        found.extend(
+                            items.iter().filter_map(|e| serde_json::from_value(e.clone()).ok())
+                        );
+                    }
+                } else {
+                    found.extend(collect_validation_errors(field));
+                }
+            }
+        }
+        Value::Array(items) => {
+            for item in items {
+                found.extend(collect_validation_errors(item));
+            }
+        }
+        _ => {}
+    }
+    found
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::Environment;
+
+    fn client() -> BraintreeClient {
+        BraintreeClient::new(BraintreeConfig::new(
+            "merchant".to_string(),
+            "public".to_string(),
+            "private".to_string(),
+            Environment::Sandbox,
+        )).unwrap()
+    }
+
+    #[test]
+    fn test_requires_credentials() {
+        let config = BraintreeConfig::new(
+            String::new(),
+            "public".to_string(),
+            "private".to_string(),
+            Environment::Sandbox,
+        );
+        assert!(BraintreeClient::new(config).is_err());
+    }
+
+    #[test]
+    fn test_authorization_header() {
+        // base64("public:private")
+        assert_eq!(client().authorization_header(), "Basic cHVibGljOnByaXZhdGU=");
+    }
+
+    #[test]
+    fn test_error_response_uses_validation_code() {
+        let body = r#"<api-error-response>
+              <errors>
+                <errors type="array"/>
+                <transaction>
+                  <errors type="array">
+                    <error>
+                      <code>81502</code>
+                      <attribute type="symbol">amount</attribute>
+                      <message>Amount is required.</message>
+                    </error>
+                  </errors>
+                </transaction>
+              </errors>
+              <message>Amount is required.</message>
+            </api-error-response>"#;
+
+        let status = StatusCode::UNPROCESSABLE_ENTITY;
+        match error_from_body(status, body) {
+            Some(PayupError::ApiError { code, message, provider }) => {
+                assert_eq!(code, "81502");
+                assert_eq!(message, "Amount is required.");
+                assert_eq!(provider, "Braintree");
+            }
+            other => panic!("expected ApiError, got {:?}", other),
+        }
+    }
+}
@@ -0,0 +1,110 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use super::{BraintreeClient, Address, PaymentMethod, SearchText};
+use super::payment_methods::PaymentMethodKind;
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Customer {
+    pub id: String,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub website: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub custom_fields: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub addresses: Vec<Address>,
+    #[serde(default)]
+    pub credit_cards: Vec<PaymentMethod>,
+    #[serde(default)]
+    pub paypal_accounts: Vec<PaymentMethod>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomerRequest {
+    pub id: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+    pub website: Option<String>,
+    pub payment_method_nonce: Option<String>,
+    pub custom_fields: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CustomerSearch {
+    pub email: Option<SearchText>,
+}
+
+impl Customer {
+    pub fn create(client: &BraintreeClient, request: &CustomerRequest) -> Result<Self> {
+        client.post("/customers", "customer", request, "customer")
+    }
+
+    pub async fn async_create(client: &BraintreeClient, request: &CustomerRequest) -> Result<Self> {
+        client.async_post("/customers", "customer", request, "customer").await
+    }
+
+    pub fn find(client: &BraintreeClient, customer_id: &str) -> Result<Self> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.get(&endpoint, "customer")
+    }
+
+    pub async fn async_find(client: &BraintreeClient, customer_id: &str) -> Result<Self> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.async_get(&endpoint, "customer").await
+    }
+
+    pub fn update(client: &BraintreeClient, customer_id: &str, request: &CustomerRequest) -> Result<Self> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.put(&endpoint, "customer", request, "customer")
+    }
+
+    pub async fn async_update(client: &BraintreeClient, customer_id: &str, request: &CustomerRequest) -> Result<Self> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.async_put(&endpoint, "customer", request, "customer").await
+    }
+
+    pub fn delete(client: &BraintreeClient, customer_id: &str) -> Result<bool> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.delete(&endpoint)
+    }
+
+    pub async fn async_delete(client: &BraintreeClient, customer_id: &str) -> Result<bool> {
+        let endpoint = format!("/customers/{}", customer_id);
+        client.async_delete(&endpoint).await
+    }
+
+    pub fn search(client: &BraintreeClient, criteria: &CustomerSearch, offset: usize, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.search("/customers", criteria, "customers", offset, limit)
+    }
+
+    pub async fn async_search(client: &BraintreeClient, criteria: &CustomerSearch, offset: usize, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.async_search("/customers", criteria, "customers", offset, limit).await
+    }
+
+    /// All vaulted payment methods, tagged with their kind.
+    pub fn payment_methods(&self) -> Vec<PaymentMethod> {
+        let cards = self.credit_cards.iter().cloned().map(|mut m| {
+            m.kind = PaymentMethodKind::CreditCard;
+            m
+        });
+        let paypal = self.paypal_accounts.iter().cloned().map(|mut m| {
+            m.kind = PaymentMethodKind::PayPalAccount;
+            m
+        });
+        cards.chain(paypal).collect()
+    }
+
+    pub fn default_payment_method(&self) -> Option<PaymentMethod> {
+        self.payment_methods()
+            .into_iter()
+            .find(|m| m.default == Some(true))
+    }
+}
@@ -0,0 +1,182 @@
+pub mod xml;
+pub mod client;
+pub mod transactions;
+pub mod customers;
+pub mod payment_methods;
+pub mod subscriptions;
+pub mod webhooks;
+pub mod provider;
+
+use serde::{Deserialize, Serialize};
+
+pub use client::BraintreeClient;
+pub use provider::BraintreeProvider;
+pub use transactions::{Transaction, TransactionRequest, TransactionStatus, TransactionType};
+pub use customers::{Customer, CustomerRequest};
+pub use payment_methods::{PaymentMethod, PaymentMethodRequest};
+pub use subscriptions::{Subscription, SubscriptionRequest, SubscriptionStatus};
+pub use webhooks::{BraintreeWebhookHandler, WebhookKind, WebhookNotification};
+
+// Braintree gateway endpoints
+pub const BRAINTREE_SANDBOX_URL: &str = "https://api.sandbox.braintreegateway.com";
+pub const BRAINTREE_PRODUCTION_URL: &str = "https://api.braintreegateway.com";
+
+/// Gateway API version sent as `X-ApiVersion` on every request.
+pub const BRAINTREE_API_VERSION: &str = "6";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub enum Environment {
+    Sandbox,
+    Production,
+}
+
+impl Environment {
+    pub fn base_url(&self) -> &str {
+        match self {
+            Environment::Sandbox => BRAINTREE_SANDBOX_URL,
+            Environment::Production => BRAINTREE_PRODUCTION_URL,
+        }
+    }
+}
+
+// Braintree configuration
+#[derive(Debug, Clone)]
+pub struct BraintreeConfig {
+    pub merchant_id: String,
+    pub public_key: String,
+    pub private_key: String,
+    pub environment: Environment,
+    /// Overrides the environment's gateway URL, e.g. for a local stand-in.
+    pub base_url: Option<String>,
+}
+
+impl BraintreeConfig {
+    pub fn new(
+        merchant_id: String,
+        public_key: String,
+        private_key: String,
+        environment: Environment,
+    ) -> Self {
+        Self {
+            merchant_id,
+            public_key,
+            private_key,
+            environment,
+            base_url: None,
+        }
+    }
+
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = Some(base_url.into());
+        self
+    }
+
+    /// Root of the merchant-scoped API, e.g. `https://api.braintreegateway.com/merchants/abc`.
+    pub fn merchant_url(&self) -> String {
+        let base = self.base_url.as_deref().unwrap_or_else(|| self.environment.base_url());
+        format!("{}/merchants/{}", base.trim_end_matches('/'), self.merchant_id)
+    }
+}
+
+// Braintree common types
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct Address {
+    pub id: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub company: Option<String>,
+    pub street_address: Option<String>,
+    pub extended_address: Option<String>,
+    pub locality: Option<String>,
+    pub region: Option<String>,
+    pub postal_code: Option<String>,
+    pub country_code_alpha2: Option<String>,
+}
+
+/// A single validation error from an `api-error-response`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValidationError {
+    pub code: String,
+    pub attribute: Option<String>,
+    pub message: String,
+}
+
+/// Exact-match criterion for advanced search, e.g. `<customer-id><is>c1</is></customer-id>`.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchText {
+    pub is: String,
+}
+
+impl SearchText {
+    pub fn is(value: impl Into<String>) -> Self {
+        Self { is: value.into() }
+    }
+}
+
+/// Converts an amount in minor units to Braintree's decimal string.
+pub fn format_amount(amount: i64, currency: &str) -> String {
+    if is_zero_decimal(currency) {
+        return amount.to_string();
+    }
+    let sign = if amount < 0 { "-" } else { "" };
+    let abs = amount.unsigned_abs();
+    format!("{}{}.{:02}", sign, abs / 100, abs % 100)
+}
+
+/// Parses Braintree's decimal amount string into minor units.
+pub fn parse_amount(amount: &str, currency: &str) -> Option<i64> {
+    if is_zero_decimal(currency) {
+        return amount.split('.').next()?.parse().ok();
+    }
+    let (negative, digits) = match amount.strip_prefix('-') {
+        Some(rest) => (true, rest),
+        None => (false, amount),
+    };
+    let (whole, frac) = digits.split_once('.').unwrap_or((digits, "0"));
+    let frac = format!("{:0<2}", frac);
+    let minor = whole.parse::<i64>().ok()? * 100 + frac.get(..2)?.parse::<i64>().ok()?;
+    Some(if negative { -minor } else { minor })
+}
+
+fn is_zero_decimal(currency: &str) -> bool {
+    matches!(
+        currency.to_lowercase().as_str(),
+        "jpy" | "krw" | "vnd" | "clp" | "isk" | "huf" | "twd" | "ugx"
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_amount_conversion() {
+        assert_eq!(format_amount(1000, "usd"), "10.00");
+        assert_eq!(format_amount(5, "usd"), "0.05");
+        assert_eq!(format_amount(-250, "eur"), "-2.50");
+        assert_eq!(format_amount(500, "jpy"), "500");
+
+        assert_eq!(parse_amount("10.00", "usd"), Some(1000));
+        assert_eq!(parse_amount("0.5", "usd"), Some(50));
+        assert_eq!(parse_amount("12", "usd"), Some(1200));
+        assert_eq!(parse_amount("500", "JPY"), Some(500));
+        assert_eq!(parse_amount("abc", "usd"), None);
+    }
+
+    #[test]
+    fn test_merchant_url() {
+        let config = BraintreeConfig::new(
+            "merchant123".to_string(),
+            "public".to_string(),
+            "private".to_string(),
+            Environment::Sandbox,
+        );
+        assert_eq!(
+            config.merchant_url(),
+            "https://api.sandbox.braintreegateway.com/merchants/merchant123"
+        );
+
+        let local = config.with_base_url("http://127.0.0.1:3000/");
+        assert_eq!(local.merchant_url(), "http://127.0.0.1:3000/merchants/merchant123");
+    }
+}
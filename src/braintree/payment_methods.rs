@@ -0,0 +1,126 @@
+use serde::{Deserialize, Serialize};
+use serde_json::Value;
+use crate::error::{PayupError, Result};
+use super::{BraintreeClient, Address, Subscription};
+
+/// A vaulted payment method. Braintree returns a different root element per
+/// instrument (`<credit-card>`, `<paypal-account>`, …); card-only and
+/// PayPal-only fields are `None` for the other kinds.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentMethod {
+    #[serde(skip_deserializing, default)]
+    pub kind: PaymentMethodKind,
+    pub token: String,
+    pub customer_id: Option<String>,
+    pub default: Option<bool>,
+    pub image_url: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub bin: Option<String>,
+    pub last_4: Option<String>,
+    pub card_type: Option<String>,
+    pub expiration_month: Option<String>,
+    pub expiration_year: Option<String>,
+    pub cardholder_name: Option<String>,
+    pub billing_address: Option<Address>,
+    pub email: Option<String>,
+    #[serde(default)]
+    pub subscriptions: Vec<Subscription>,
+}
+
+#[derive(Debug, Clone, Default, PartialEq, Eq, Serialize, Deserialize)]
+pub enum PaymentMethodKind {
+    #[default]
+    CreditCard,
+    PayPalAccount,
+    VenmoAccount,
+    ApplePayCard,
+    AndroidPayCard,
+    UsBankAccount,
+    Other(String),
+}
+
+impl From<&str> for PaymentMethodKind {
+    fn from(root: &str) -> Self {
+        match root {
+            "credit-card" => PaymentMethodKind::CreditCard,
+            "paypal-account" => PaymentMethodKind::PayPalAccount,
+            "venmo-account" => PaymentMethodKind::VenmoAccount,
+            "apple-pay-card" => PaymentMethodKind::ApplePayCard,
+            "android-pay-card" => PaymentMethodKind::AndroidPayCard,
+            "us-bank-account" => PaymentMethodKind::UsBankAccount,
+            other => PaymentMethodKind::Other(other.to_string()),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentMethodRequest {
+    pub customer_id: Option<String>,
+    pub payment_method_nonce: Option<String>,
+    pub token: Option<String>,
+    pub cardholder_name: Option<String>,
+    pub billing_address: Option<Address>,
+    pub options: Option<PaymentMethodOptions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PaymentMethodOptions {
+    pub make_default: Option<bool>,
+    pub verify_card: Option<bool>,
+    pub fail_on_duplicate_payment_method: Option<bool>,
+}
+
+impl PaymentMethod {
+    /// Builds a payment method from a response whose root names its kind.
+    pub fn from_response(root: &str, value: Value) -> Result<Self> {
+        let mut method: PaymentMethod = serde_json::from_value(value).map_err(PayupError::from)?;
+        method.kind = PaymentMethodKind::from(root);
+        Ok(method)
+    }
+
+    /// Vaults the method behind a client-side nonce for a customer.
+    pub fn create(client: &BraintreeClient, request: &PaymentMethodRequest) -> Result<Self> {
+        let (root, value) = client.post_any("/payment_methods", "payment-method", request)?;
+        Self::from_response(&root, value)
+    }
+
+    pub async fn async_create(client: &BraintreeClient, request: &PaymentMethodRequest) -> Result<Self> {
+        let (root, value) = client.async_post_any("/payment_methods", "payment-method", request).await?;
+        Self::from_response(&root, value)
+    }
+
+    pub fn find(client: &BraintreeClient, token: &str) -> Result<Self> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        let (root, value) = client.get_any(&endpoint)?;
+        Self::from_response(&root, value)
+    }
+
+    pub async fn async_find(client: &BraintreeClient, token: &str) -> Result<Self> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        let (root, value) = client.async_get_any(&endpoint).await?;
+        Self::from_response(&root, value)
+    }
+
+    pub fn update(client: &BraintreeClient, token: &str, request: &PaymentMethodRequest) -> Result<Self> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        let (root, value) = client.put_any(&endpoint, "payment-method", request)?;
+        Self::from_response(&root, value)
+    }
+
+    pub async fn async_update(client: &BraintreeClient, token: &str, request: &PaymentMethodRequest) -> Result<Self> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        let (root, value) = client.async_put_any(&endpoint, "payment-method", request).await?;
+        Self::from_response(&root, value)
+    }
+
+    pub fn delete(client: &BraintreeClient, token: &str) -> Result<bool> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        client.delete(&endpoint)
+    }
+
+    pub async fn async_delete(client: &BraintreeClient, token: &str) -> Result<bool> {
+        let endpoint = format!("/payment_methods/any/{}", token);
+        client.async_delete(&endpoint).await
+    }
+}
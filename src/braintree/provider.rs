@@ -0,0 +1,741 @@
+use async_trait::async_trait;
+use std::sync::Arc;
+use crate::error::{PayupError, Result};
+use crate::http_client::{default_transport, Transport};
+use crate::payment_provider::{
+    PaymentProvider, PaymentFeature, Customer as UnifiedCustomer, PaymentMethod as UnifiedPaymentMethod,
+    PaymentMethodType, CardDetails, Charge as UnifiedCharge, ChargeStatus,
+    Refund as UnifiedRefund, RefundStatus, Subscription as UnifiedSubscription,
+    SubscriptionStatus, Money as UnifiedMoney
+};
+use super::{
+    BraintreeClient, BraintreeConfig, BraintreeWebhookHandler, Environment, format_amount, parse_amount,
+    customers::{Customer, CustomerRequest, CustomerSearch},
+    payment_methods::{PaymentMethod, PaymentMethodKind, PaymentMethodRequest, PaymentMethodOptions},
+    subscriptions::{Subscription, SubscriptionRequest, SubscriptionSearch, SubscriptionStatus as BraintreeSubscriptionStatus},
+    transactions::{Transaction, TransactionRequest, TransactionOptions, TransactionSearch, TransactionStatus, TransactionType},
+    SearchText,
+};
+
+pub struct BraintreeProvider {
+    client: std::sync::Arc<tokio::sync::Mutex<BraintreeClient>>,
+    /// Currency of the default merchant account, used for amount conversion
+    currency: String,
+}
+
+impl BraintreeProvider {
+    pub fn new(
+        merchant_id: String,
+        public_key: String,
+        private_key: String,
+        environment: Environment,
+    ) -> Result<Self> {
+        Self::with_config(BraintreeConfig::new(merchant_id, public_key, private_key, environment))
+    }
+
+    pub fn with_config(config: BraintreeConfig) -> Result<Self> {
+        Self::with_transport(config, default_transport())
+    }
+
+    /// Sends every request through `transport` instead of the shared one.
+    pub fn with_transport(config: BraintreeConfig, transport: Arc<dyn Transport>) -> Result<Self> {
+        let client = BraintreeClient::new(config)?.with_transport(transport);
+
+        Ok(Self {
+            client: std::sync::Arc::new(tokio::sync::Mutex::new(client)),
+            currency: "usd".to_string(),
+        })
+    }
+
+    /// Sets the currency of the merchant account. Braintree fixes the currency
+    /// per merchant account, so charges in any other currency are rejected.
+    pub fn with_currency(mut self, currency: impl Into<String>) -> Self {
+        self.currency = currency.into().to_lowercase();
+        self
+    }
+
+    fn check_currency(&self, money: &UnifiedMoney) -> Result<()> {
+        if !money.currency.eq_ignore_ascii_case(&self.currency) {
+            return Err(PayupError::ValidationError(format!(
+                "Braintree merchant account settles in {}, not {}",
+                self.currency, money.currency
+            )));
+        }
+        Ok(())
+    }
+
+    fn map_money(&self, amount: &str, currency: Option<&str>) -> UnifiedMoney {
+        let currency = currency.unwrap_or(&self.currency).to_lowercase();
+        UnifiedMoney {
+            amount: parse_amount(amount, &currency).unwrap_or(0),
+            currency,
+        }
+    }
+
+    fn map_customer(&self, customer: &Customer) -> UnifiedCustomer {
+        UnifiedCustomer {
+            id: Some(customer.id.clone()),
+            email: customer.email.clone(),
+            name: match (&customer.first_name, &customer.last_name) {
+                (Some(first), Some(last)) => Some(format!("{} {}", first, last)),
+                (Some(first), None) => Some(first.clone()),
+                (None, Some(last)) => Some(last.clone()),
+                _ => None,
+            },
+            phone: customer.phone.clone(),
+            metadata: customer.custom_fields.clone(),
+        }
+    }
+
+    fn map_customer_request(&self, customer: &UnifiedCustomer) -> CustomerRequest {
+        let (first_name, last_name) = match &customer.name {
+            Some(name) => {
+                let parts: Vec<&str> = name.split_whitespace().collect();
+                match parts.len() {
+                    0 => (None, None),
+                    1 => (Some(parts[0].to_string()), None),
+                    _ => (Some(parts[0].to_string()), Some(parts[1..].join(" "))),
+                }
+            }
+            None => (None, None),
+        };
+
+        CustomerRequest {
+            first_name,
+            last_name,
+            email: customer.email.clone(),
+            phone: customer.phone.clone(),
+            custom_fields: customer.metadata.clone(),
+            ..Default::default()
+        }
+    }
+
+    fn map_payment_method(&self, method: &PaymentMethod) -> UnifiedPaymentMethod {
+        let method_type = match &method.kind {
+            PaymentMethodKind::CreditCard => PaymentMethodType::Card,
+            PaymentMethodKind::PayPalAccount => PaymentMethodType::PayPal,
+            PaymentMethodKind::ApplePayCard => PaymentMethodType::ApplePay,
+            PaymentMethodKind::AndroidPayCard => PaymentMethodType::GooglePay,
+            PaymentMethodKind::UsBankAccount => PaymentMethodType::BankAccount,
+            PaymentMethodKind::VenmoAccount => PaymentMethodType::Other("venmo".to_string()),
+            PaymentMethodKind::Other(kind) => PaymentMethodType::Other(kind.clone()),
+        };
+
+        let card = method.last_4.as_ref().map(|last4| CardDetails {
+            number: None,
+            exp_month: method.expiration_month.clone().unwrap_or_default(),
+            exp_year: method.expiration_year.clone().unwrap_or_default(),
+            cvv: None,
+            brand: method.card_type.clone(),
+            last4: Some(last4.clone()),
+        });
+
+        UnifiedPaymentMethod {
+            id: Some(method.token.clone()),
+            method_type,
+            card,
+            bank_account: None,
+        }
+    }
+
+    fn map_transaction_status(&self, status: &TransactionStatus) -> ChargeStatus {
+        match status {
+            TransactionStatus::Authorizing | TransactionStatus::Authorized => ChargeStatus::Pending,
+            TransactionStatus::SubmittedForSettlement
+            | TransactionStatus::Settling
+            | TransactionStatus::SettlementPending => ChargeStatus::Processing,
+            TransactionStatus::Settled => ChargeStatus::Succeeded,
+            TransactionStatus::Voided | TransactionStatus::AuthorizationExpired => ChargeStatus::Canceled,
+            TransactionStatus::Failed
+            | TransactionStatus::GatewayRejected
+            | TransactionStatus::ProcessorDeclined
+            | TransactionStatus::SettlementDeclined => ChargeStatus::Failed,
+            TransactionStatus::Unknown => ChargeStatus::Pending,
+        }
+    }
+
+    fn map_transaction(&self, transaction: &Transaction) -> UnifiedCharge {
+        let mut metadata = transaction.custom_fields.clone().unwrap_or_default();
+        if let Some(order_id) = &transaction.order_id {
+            metadata.insert("order_id".to_string(), order_id.clone());
+        }
+        if let Some(code) = &transaction.processor_response_code {
+            metadata.insert("processor_response_code".to_string(), code.clone());
+        }
+
+        UnifiedCharge {
+            id: Some(transaction.id.clone()),
+            amount: self.map_money(&transaction.amount, transaction.currency_iso_code.as_deref()),
+            customer_id: transaction.customer.as_ref().and_then(|c| c.id.clone()),
+            payment_method_id: transaction.credit_card.as_ref().and_then(|c| c.token.clone()),
+            status: self.map_transaction_status(&transaction.status),
+            description: None,
+            metadata: if metadata.is_empty() { None } else { Some(metadata) },
+            created_at: transaction.created_at.as_deref().and_then(parse_timestamp),
+        }
+    }
+
+    fn map_refund(&self, transaction: &Transaction) -> UnifiedRefund {
+        UnifiedRefund {
+            id: Some(transaction.id.clone()),
+            charge_id: transaction.refunded_transaction_id.clone().unwrap_or_default(),
+            amount: Some(self.map_money(&transaction.amount, transaction.currency_iso_code.as_deref())),
+            reason: None,
+            status: match transaction.status {
+                TransactionStatus::Settled => RefundStatus::Succeeded,
+                TransactionStatus::Voided => RefundStatus::Canceled,
+                TransactionStatus::Failed
+                | TransactionStatus::GatewayRejected
+                | TransactionStatus::ProcessorDeclined
+                | TransactionStatus::SettlementDeclined => RefundStatus::Failed,
+                _ => RefundStatus::Pending,
+            },
+            metadata: None,
+        }
+    }
+
+    fn map_subscription(&self, subscription: &Subscription, customer_id: Option<&str>) -> UnifiedSubscription {
+        let customer_id = customer_id
+            .map(String::from)
+            .or_else(|| {
+                subscription.transactions.iter()
+                    .find_map(|t| t.customer.as_ref().and_then(|c| c.id.clone()))
+            })
+            .unwrap_or_default();
+
+        UnifiedSubscription {
+            id: Some(subscription.id.clone()),
+            customer_id,
+            plan_id: Some(subscription.plan_id.clone()),
+            price_id: None,
+            status: match subscription.status {
+                BraintreeSubscriptionStatus::Active => SubscriptionStatus::Active,
+                BraintreeSubscriptionStatus::PastDue => SubscriptionStatus::PastDue,
+                BraintreeSubscriptionStatus::Canceled
+                | BraintreeSubscriptionStatus::Expired => SubscriptionStatus::Canceled,
+                BraintreeSubscriptionStatus::Pending
+                | BraintreeSubscriptionStatus::Unknown => SubscriptionStatus::Incomplete,
+            },
+            current_period_start: subscription.billing_period_start_date.as_deref().and_then(parse_timestamp),
+            current_period_end: subscription.billing_period_end_date.as_deref().and_then(parse_timestamp),
+            cancel_at_period_end: subscription.never_expires == Some(false)
+                && subscription.number_of_billing_cycles.is_some()
+                && subscription.number_of_billing_cycles == subscription.current_billing_cycle,
+        }
+    }
+
+    /// A sale settled straight away, charged to the nonce or token in `payment_method_id`.
+    fn map_sale_request(&self, charge: &UnifiedCharge) -> Result<TransactionRequest> {
+        let payment_method = charge.payment_method_id.as_ref()
+            .ok_or_else(|| PayupError::ValidationError(
+                "Braintree requires a payment method nonce or vaulted token to create a transaction".to_string()
+            ))?;
+
+        let mut request = TransactionRequest::sale(
+            format_amount(charge.amount.amount, &charge.amount.currency),
+            payment_method,
+        );
+        request.customer_id = charge.customer_id.clone();
+        request.order_id = charge.metadata.as_ref()
+            .and_then(|m| m.get("order_id"))
+            .cloned();
+        request.options = Some(TransactionOptions {
+            submit_for_settlement: Some(true),
+            store_in_vault_on_success: None,
+        });
+        Ok(request)
+    }
+
+    /// A subscription to `plan_id` billed to the customer's default payment method.
+    async fn map_subscription_request(&self, client: &BraintreeClient, subscription: &UnifiedSubscription) -> Result<SubscriptionRequest> {
+        let plan_id = subscription.plan_id.clone()
+            .or_else(|| subscription.price_id.clone())
+            .ok_or_else(|| PayupError::ValidationError(
+                "Braintree subscriptions require a plan_id".to_string()
+            ))?;
+
+        let customer = Customer::async_find(client, &subscription.customer_id).await?;
+        let payment_method = customer.default_payment_method()
+            .ok_or_else(|| PayupError::ValidationError(format!(
+                "Braintree customer {} has no default payment method",
+                subscription.customer_id
+            )))?;
+
+        Ok(SubscriptionRequest {
+            plan_id: Some(plan_id),
+            payment_method_token: Some(payment_method.token),
+            ..Default::default()
+        })
+    }
+}
+
+/// Parses Braintree's `datetime` (RFC 3339) or `date` (`YYYY-MM-DD`) values.
+fn parse_timestamp(value: &str) -> Option<i64> {
+    chrono::DateTime::parse_from_rfc3339(value)
+        .map(|dt| dt.timestamp())
+        .ok()
+        .or_else(|| {
+            chrono::NaiveDate::parse_from_str(value, "%Y-%m-%d")
+                .ok()
+                .and_then(|d| d.and_hms_opt(0, 0, 0))
+                .map(|dt| dt.and_utc().timestamp())
+        })
+}
+
+/// An idempotency key as a customer or subscription ID, which Braintree
+/// limits to 36 letters, digits, `-` and `_`.
+fn record_id(idempotency_key: &str) -> Result<String> {
+    let valid = !idempotency_key.is_empty()
+        && idempotency_key.len() <= 36
+        && idempotency_key.chars().all(|c| c.is_ascii_alphanumeric() || c == '-' || c == '_');
+    if !valid {
+        return Err(PayupError::ValidationError(format!(
+            "Braintree idempotency key {:?} must be at most 36 letters, digits, '-' or '_'",
+            idempotency_key
+        )));
+    }
+    Ok(idempotency_key.to_string())
+}
+
+/// `Ok(None)` when the lookup came back 404, so callers can create the record instead.
+fn found<T>(result: Result<T>) -> Result<Option<T>> {
+    match result {
+        Ok(record) => Ok(Some(record)),
+        Err(PayupError::ApiError { code, .. }) if code.starts_with("404") => Ok(None),
+        Err(e) => Err(e),
+    }
+}
+
+/// Whether settlement was already submitted for the transaction.
+fn is_submitted(status: &TransactionStatus) -> bool {
+    matches!(
+        status,
+        TransactionStatus::SubmittedForSettlement
+            | TransactionStatus::SettlementPending
+            | TransactionStatus::Settling
+            | TransactionStatus::Settled
+    )
+}
+
+#[async_trait]
+impl PaymentProvider for BraintreeProvider {
+    fn name(&self) -> &str {
+        "braintree"
+    }
+
+    fn supported_currencies(&self) -> Vec<String> {
+        vec![self.currency.clone()]
+    }
+
+    fn supported_features(&self) -> Vec<PaymentFeature> {
+        vec![
+            PaymentFeature::OneTimePayments,
+            PaymentFeature::RecurringPayments,
+            PaymentFeature::Refunds,
+            PaymentFeature::PartialRefunds,
+            PaymentFeature::PaymentMethods,
+            PaymentFeature::Webhooks,
+            PaymentFeature::DigitalWallets,
+            PaymentFeature::Subscriptions,
+            PaymentFeature::CustomerManagement,
+        ]
+    }
+
+    async fn create_customer(&self, customer: &UnifiedCustomer) -> Result<UnifiedCustomer> {
+        let client = self.client.lock().await;
+        let mut request = self.map_customer_request(customer);
+        request.id = customer.id.clone();
+
+        let created = Customer::async_create(&client, &request).await?;
+        Ok(self.map_customer(&created))
+    }
+
+    /// Braintree has no idempotency keys, so the key doubles as the customer
+    /// ID (unless the customer brings its own) and a retry returns the
+    /// customer the first attempt created.
+    async fn create_customer_idempotent(&self, customer: &UnifiedCustomer, idempotency_key: &str) -> Result<UnifiedCustomer> {
+        let customer_id = match &customer.id {
+            Some(id) => id.clone(),
+            None => record_id(idempotency_key)?,
+        };
+        let client = self.client.lock().await;
+
+        if let Some(existing) = found(Customer::async_find(&client, &customer_id).await)? {
+            return Ok(self.map_customer(&existing));
+        }
+
+        let mut request = self.map_customer_request(customer);
+        request.id = Some(customer_id);
+        let created = Customer::async_create(&client, &request).await?;
+        Ok(self.map_customer(&created))
+    }
+
+    async fn get_customer(&self, customer_id: &str) -> Result<UnifiedCustomer> {
+        let client = self.client.lock().await;
+        let customer = Customer::async_find(&client, customer_id).await?;
+        Ok(self.map_customer(&customer))
+    }
+
+    async fn update_customer(&self, customer: &UnifiedCustomer) -> Result<UnifiedCustomer> {
+        let client = self.client.lock().await;
+
+        let customer_id = customer.id.as_ref()
+            .ok_or_else(|| PayupError::ValidationError("Customer ID is required for update".to_string()))?;
+
+        let request = self.map_customer_request(customer);
+        let updated = Customer::async_update(&client, customer_id, &request).await?;
+        Ok(self.map_customer(&updated))
+    }
+
+    async fn delete_customer(&self, customer_id: &str) -> Result<bool> {
+        let client = self.client.lock().await;
+        Customer::async_delete(&client, customer_id).await
+    }
+
+    async fn list_customers(&self, limit: Option<u32>, offset: Option<u32>) -> Result<Vec<UnifiedCustomer>> {
+        let client = self.client.lock().await;
+
+        let customers = Customer::async_search(
+            &client,
+            &CustomerSearch::default(),
+            offset.unwrap_or(0) as usize,
+            limit.map(|l| l as usize),
+        ).await?;
+
+        Ok(customers.iter().map(|c| self.map_customer(c)).collect())
+    }
+
+    async fn create_payment_method(&self, _payment_method: &UnifiedPaymentMethod) -> Result<UnifiedPaymentMethod> {
+        Err(PayupError::UnsupportedOperation(
+            "Braintree vaults payment methods from client-side nonces. Use attach_payment_method with a nonce.".to_string()
+        ))
+    }
+
+    async fn get_payment_method(&self, payment_method_id: &str) -> Result<UnifiedPaymentMethod> {
+        let client = self.client.lock().await;
+        let method = PaymentMethod::async_find(&client, payment_method_id).await?;
+        Ok(self.map_payment_method(&method))
+    }
+
+    /// Vaults the nonce in `payment_method_id` for the customer; Braintree
+    /// cannot move an already-vaulted token between customers.
+    async fn attach_payment_method(&self, payment_method_id: &str, customer_id: &str) -> Result<UnifiedPaymentMethod> {
+        let client = self.client.lock().await;
+
+        let request = PaymentMethodRequest {
+            customer_id: Some(customer_id.to_string()),
+            payment_method_nonce: Some(payment_method_id.to_string()),
+            options: Some(PaymentMethodOptions {
+                verify_card: Some(true),
+                ..Default::default()
+            }),
+            ..Default::default()
+        };
+
+        let method = PaymentMethod::async_create(&client, &request).await?;
+        Ok(self.map_payment_method(&method))
+    }
+
+    async fn detach_payment_method(&self, payment_method_id: &str) -> Result<UnifiedPaymentMethod> {
+        let client = self.client.lock().await;
+
+        let method = PaymentMethod::async_find(&client, payment_method_id).await?;
+        PaymentMethod::async_delete(&client, payment_method_id).await?;
+        Ok(self.map_payment_method(&method))
+    }
+
+    async fn create_charge(&self, charge: &UnifiedCharge) -> Result<UnifiedCharge> {
+        self.check_currency(&charge.amount)?;
+        let client = self.client.lock().await;
+
+        let request = self.map_sale_request(charge)?;
+        let transaction = Transaction::async_sale(&client, &request).await?;
+        Ok(self.map_transaction(&transaction))
+    }
+
+    /// Braintree has no idempotency keys, so the key is sent as the sale's
+    /// `order_id` (in place of `metadata["order_id"]`) and a retry returns
+    /// the sale already carrying it.
+    async fn create_charge_idempotent(&self, charge: &UnifiedCharge, idempotency_key: &str) -> Result<UnifiedCharge> {
+        self.check_currency(&charge.amount)?;
+        let client = self.client.lock().await;
+
+        let criteria = TransactionSearch {
+            order_id: Some(SearchText::is(idempotency_key)),
+            transaction_type: Some(vec![TransactionType::Sale]),
+            ..Default::default()
+        };
+        if let Some(existing) = Transaction::async_search(&client, &criteria, Some(1)).await?.first() {
+            return Ok(self.map_transaction(existing));
+        }
+
+        let mut request = self.map_sale_request(charge)?;
+        request.order_id = Some(idempotency_key.to_string());
+        let transaction = Transaction::async_sale(&client, &request).await?;
+        Ok(self.map_transaction(&transaction))
+    }
+
+    async fn get_charge(&self, charge_id: &str) -> Result<UnifiedCharge> {
+        let client = self.client.lock().await;
+        let transaction = Transaction::async_find(&client, charge_id).await?;
+        Ok(self.map_transaction(&transaction))
+    }
+
+    async fn capture_charge(&self, charge_id: &str, amount: Option<UnifiedMoney>) -> Result<UnifiedCharge> {
+        if let Some(money) = &amount {
+            self.check_currency(money)?;
+        }
+        let client = self.client.lock().await;
+
+        let amount = amount.map(|m| format_amount(m.amount, &m.currency));
+        let transaction = Transaction::async_submit_for_settlement(&client, charge_id, amount).await?;
+        Ok(self.map_transaction(&transaction))
+    }
+
+    /// Settlement can only be submitted once per transaction, so a retry
+    /// returns the transaction as it stands once it is past `authorized`.
+    /// The key itself is not needed.
+    async fn capture_charge_idempotent(&self, charge_id: &str, amount: Option<UnifiedMoney>, _idempotency_key: &str) -> Result<UnifiedCharge> {
+        if let Some(money) = &amount {
+            self.check_currency(money)?;
+        }
+        let client = self.client.lock().await;
+
+        let transaction = Transaction::async_find(&client, charge_id).await?;
+        if is_submitted(&transaction.status) {
+            return Ok(self.map_transaction(&transaction));
+        }
+
+        let amount = amount.map(|m| format_amount(m.amount, &m.currency));
+        let transaction = Transaction::async_submit_for_settlement(&client, charge_id, amount).await?;
+        Ok(self.map_transaction(&transaction))
+    }
+
+    async fn list_charges(&self, customer_id: Option<&str>, limit: Option<u32>) -> Result<Vec<UnifiedCharge>> {
+        let client = self.client.lock().await;
+
+        let criteria = TransactionSearch {
+            customer_id: customer_id.map(SearchText::is),
+            transaction_type: Some(vec![TransactionType::Sale]),
+            ..Default::default()
+        };
+        let transactions = Transaction::async_search(&client, &criteria, limit.map(|l| l as usize)).await?;
+
+        Ok(transactions.iter().map(|t| self.map_transaction(t)).collect())
+    }
+
+    async fn create_refund(&self, refund: &UnifiedRefund) -> Result<UnifiedRefund> {
+        if let Some(money) = &refund.amount {
+            self.check_currency(money)?;
+        }
+        let client = self.client.lock().await;
+
+        let amount = refund.amount.as_ref().map(|m| format_amount(m.amount, &m.currency));
+        let transaction = Transaction::async_refund(&client, &refund.charge_id, amount, None).await?;
+        Ok(self.map_refund(&transaction))
+    }
+
+    /// The key is sent as the refund's `order_id`, and a retry returns the
+    /// refund of the same transaction already carrying it.
+    async fn create_refund_idempotent(&self, refund: &UnifiedRefund, idempotency_key: &str) -> Result<UnifiedRefund> {
+        if let Some(money) = &refund.amount {
+            self.check_currency(money)?;
+        }
+        let client = self.client.lock().await;
+
+        let original = Transaction::async_find(&client, &refund.charge_id).await?;
+        for refund_id in &original.refund_ids {
+            let existing = Transaction::async_find(&client, refund_id).await?;
+            if existing.order_id.as_deref() == Some(idempotency_key) {
+                return Ok(self.map_refund(&existing));
+            }
+        }
+
+        let amount = refund.amount.as_ref().map(|m| format_amount(m.amount, &m.currency));
+        let order_id = Some(idempotency_key.to_string());
+        let transaction = Transaction::async_refund(&client, &refund.charge_id, amount, order_id).await?;
+        Ok(self.map_refund(&transaction))
+    }
+
+    async fn get_refund(&self, refund_id: &str) -> Result<UnifiedRefund> {
+        let client = self.client.lock().await;
+
+        let transaction = Transaction::async_find(&client, refund_id).await?;
+        if !transaction.is_refund() {
+            return Err(PayupError::ValidationError(format!(
+                "Braintree transaction {} is not a refund",
+                refund_id
+            )));
+        }
+        Ok(self.map_refund(&transaction))
+    }
+
+    async fn list_refunds(&self, charge_id: Option<&str>, limit: Option<u32>) -> Result<Vec<UnifiedRefund>> {
+        let client = self.client.lock().await;
+        let limit = limit.map(|l| l as usize);
+
+        let refunds = match charge_id {
+            // Refunds of one transaction are listed on it rather than searchable
+            Some(charge_id) => {
+                let original = Transaction::async_find(&client, charge_id).await?;
+                let mut refunds = Vec::new();
+                for refund_id in original.refund_ids.iter().take(limit.unwrap_or(usize::MAX)) {
+                    refunds.push(Transaction::async_find(&client, refund_id).await?);
+                }
+                refunds
+            }
+            None => {
+                let criteria = TransactionSearch {
+                    customer_id: None,
+                    transaction_type: Some(vec![TransactionType::Credit]),
+                    ..Default::default()
+                };
+                Transaction::async_search(&client, &criteria, limit).await?
+            }
+        };
+
+        Ok(refunds.iter().map(|t| self.map_refund(t)).collect())
+    }
+
+    /// Subscribes the customer's default payment method to `plan_id`.
+    async fn create_subscription(&self, subscription: &UnifiedSubscription) -> Result<UnifiedSubscription> {
+        let client = self.client.lock().await;
+
+        let request = self.map_subscription_request(&client, subscription).await?;
+        let created = Subscription::async_create(&client, &request).await?;
+        Ok(self.map_subscription(&created, Some(&subscription.customer_id)))
+    }
+
+    /// Braintree has no idempotency keys, so the key doubles as the
+    /// subscription ID (unless the subscription brings its own) and a retry
+    /// returns the subscription the first attempt created.
+    async fn create_subscription_idempotent(&self, subscription: &UnifiedSubscription, idempotency_key: &str) -> Result<UnifiedSubscription> {
+        let subscription_id = match &subscription.id {
+            Some(id) => id.clone(),
+            None => record_id(idempotency_key)?,
+        };
+        let client = self.client.lock().await;
+
+        if let Some(existing) = found(Subscription::async_find(&client, &subscription_id).await)? {
+            return Ok(self.map_subscription(&existing, Some(&subscription.customer_id)));
+        }
+
+        let mut request = self.map_subscription_request(&client, subscription).await?;
+        request.id = Some(subscription_id);
+        let created = Subscription::async_create(&client, &request).await?;
+        Ok(self.map_subscription(&created, Some(&subscription.customer_id)))
+    }
+
+    async fn get_subscription(&self, subscription_id: &str) -> Result<UnifiedSubscription> {
+        let client = self.client.lock().await;
+        let subscription = Subscription::async_find(&client, subscription_id).await?;
+        Ok(self.map_subscription(&subscription, None))
+    }
+
+    async fn update_subscription(&self, subscription: &UnifiedSubscription) -> Result<UnifiedSubscription> {
+        let client = self.client.lock().await;
+
+        let subscription_id = subscription.id.as_ref()
+            .ok_or_else(|| PayupError::ValidationError("Subscription ID is required for update".to_string()))?;
+
+        let request = SubscriptionRequest {
+            plan_id: subscription.plan_id.clone(),
+            ..Default::default()
+        };
+
+        let updated = Subscription::async_update(&client, subscription_id, &request).await?;
+        Ok(self.map_subscription(&updated, Some(&subscription.customer_id)))
+    }
+
+    async fn cancel_subscription(&self, subscription_id: &str, at_period_end: bool) -> Result<UnifiedSubscription> {
+        let client = self.client.lock().await;
+
+        let subscription = if at_period_end {
+            Subscription::async_end_after_current_cycle(&client, subscription_id).await?
+        } else {
+            Subscription::async_cancel(&client, subscription_id).await?
+        };
+        Ok(self.map_subscription(&subscription, None))
+    }
+
+    async fn list_subscriptions(&self, customer_id: Option<&str>, limit: Option<u32>) -> Result<Vec<UnifiedSubscription>> {
+        let client = self.client.lock().await;
+        let limit = limit.map(|l| l as usize).unwrap_or(usize::MAX);
+
+        // Subscriptions hang off payment methods, so a customer's are read
+        // from the customer record rather than searched
+        let subscriptions = match customer_id {
+            Some(customer_id) => {
+                let customer = Customer::async_find(&client, customer_id).await?;
+                customer.payment_methods()
+                    .into_iter()
+                    .flat_map(|m| m.subscriptions)
+                    .take(limit)
+                    .map(|s| self.map_subscription(&s, Some(customer_id)))
+                    .collect()
+            }
+            None => {
+                let criteria = SubscriptionSearch {
+                    status: Some(vec![
+                        BraintreeSubscriptionStatus::Active,
+                        BraintreeSubscriptionStatus::PastDue,
+                        BraintreeSubscriptionStatus::Pending,
+                    ]),
+                };
+                Subscription::async_search(&client, &criteria, Some(limit)).await?
+                    .iter()
+                    .map(|s| self.map_subscription(s, None))
+                    .collect()
+            }
+        };
+
+        Ok(subscriptions)
+    }
+
+    /// `payload` is `bt_payload`, `signature` is `bt_signature` and `secret`
+    /// is the API private key (the configured one when empty).
+    async fn verify_webhook(&self, payload: &[u8], signature: &str, secret: &str) -> Result<bool> {
+        let client = self.client.lock().await;
+
+        let private_key = if secret.is_empty() {
+            client.config.private_key.clone()
+        } else {
+            secret.to_string()
+        };
+        let handler = BraintreeWebhookHandler::new(client.config.public_key.clone(), private_key);
+
+        let payload = std::str::from_utf8(payload)
+            .map_err(|e| PayupError::WebhookVerificationFailed(format!("Invalid bt_payload: {}", e)))?;
+        match handler.verify_signature(signature, payload) {
+            Ok(()) => Ok(true),
+            Err(PayupError::WebhookVerificationFailed(_)) => Ok(false),
+            Err(e) => Err(e),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_parse_timestamp() {
+        assert_eq!(parse_timestamp("2024-01-15T10:00:00Z"), Some(1705312800));
+        assert_eq!(parse_timestamp("2024-01-15"), Some(1705276800));
+        assert_eq!(parse_timestamp("soon"), None);
+    }
+
+    #[test]
+    fn test_rejects_other_currencies() {
+        let provider = BraintreeProvider::new(
+            "merchant".to_string(),
+            "public".to_string(),
+            "private".to_string(),
+            Environment::Sandbox,
+        ).unwrap().with_currency("EUR");
+
+        assert_eq!(provider.supported_currencies(), vec!["eur".to_string()]);
+        assert!(provider.check_currency(&UnifiedMoney { amount: 100, currency: "eur".to_string() }).is_ok());
+        assert!(provider.check_currency(&UnifiedMoney { amount: 100, currency: "usd".to_string() }).is_err());
+    }
+}
@@ -0,0 +1,136 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use super::{BraintreeClient, Transaction};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Subscription {
+    pub id: String,
+    pub plan_id: String,
+    pub status: SubscriptionStatus,
+    pub payment_method_token: Option<String>,
+    pub price: Option<String>,
+    pub balance: Option<String>,
+    pub merchant_account_id: Option<String>,
+    pub billing_day_of_month: Option<i64>,
+    pub billing_period_start_date: Option<String>,
+    pub billing_period_end_date: Option<String>,
+    pub first_billing_date: Option<String>,
+    pub next_billing_date: Option<String>,
+    pub paid_through_date: Option<String>,
+    pub current_billing_cycle: Option<i64>,
+    pub number_of_billing_cycles: Option<i64>,
+    pub never_expires: Option<bool>,
+    pub trial_period: Option<bool>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    #[serde(default)]
+    pub transactions: Vec<Transaction>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub enum SubscriptionStatus {
+    Active,
+    Canceled,
+    Expired,
+    #[serde(rename = "Past Due")]
+    PastDue,
+    Pending,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionRequest {
+    pub id: Option<String>,
+    pub plan_id: Option<String>,
+    pub payment_method_token: Option<String>,
+    pub price: Option<String>,
+    pub merchant_account_id: Option<String>,
+    pub first_billing_date: Option<String>,
+    pub number_of_billing_cycles: Option<i64>,
+    pub never_expires: Option<bool>,
+    pub options: Option<SubscriptionOptions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionOptions {
+    pub start_immediately: Option<bool>,
+    pub prorate_charges: Option<bool>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct SubscriptionSearch {
+    pub status: Option<Vec<SubscriptionStatus>>,
+}
+
+impl Subscription {
+    pub fn create(client: &BraintreeClient, request: &SubscriptionRequest) -> Result<Self> {
+        client.post("/subscriptions", "subscription", request, "subscription")
+    }
+
+    pub async fn async_create(client: &BraintreeClient, request: &SubscriptionRequest) -> Result<Self> {
+        client.async_post("/subscriptions", "subscription", request, "subscription").await
+    }
+
+    pub fn find(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}", subscription_id);
+        client.get(&endpoint, "subscription")
+    }
+
+    pub async fn async_find(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}", subscription_id);
+        client.async_get(&endpoint, "subscription").await
+    }
+
+    pub fn update(client: &BraintreeClient, subscription_id: &str, request: &SubscriptionRequest) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}", subscription_id);
+        client.put(&endpoint, "subscription", request, "subscription")
+    }
+
+    pub async fn async_update(client: &BraintreeClient, subscription_id: &str, request: &SubscriptionRequest) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}", subscription_id);
+        client.async_put(&endpoint, "subscription", request, "subscription").await
+    }
+
+    /// Cancels immediately. Braintree has no cancel-at-period-end; stop
+    /// renewal with `end_after_current_cycle` instead.
+    pub fn cancel(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}/cancel", subscription_id);
+        client.put(&endpoint, "subscription", &SubscriptionRequest::default(), "subscription")
+    }
+
+    pub async fn async_cancel(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let endpoint = format!("/subscriptions/{}/cancel", subscription_id);
+        client.async_put(&endpoint, "subscription", &SubscriptionRequest::default(), "subscription").await
+    }
+
+    /// Caps the subscription at its current billing cycle so it expires
+    /// instead of renewing.
+    pub fn end_after_current_cycle(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let current = Self::find(client, subscription_id)?;
+        let request = SubscriptionRequest {
+            number_of_billing_cycles: Some(current.current_billing_cycle.unwrap_or(1).max(1)),
+            never_expires: Some(false),
+            ..Default::default()
+        };
+        Self::update(client, subscription_id, &request)
+    }
+
+    pub async fn async_end_after_current_cycle(client: &BraintreeClient, subscription_id: &str) -> Result<Self> {
+        let current = Self::async_find(client, subscription_id).await?;
+        let request = SubscriptionRequest {
+            number_of_billing_cycles: Some(current.current_billing_cycle.unwrap_or(1).max(1)),
+            never_expires: Some(false),
+            ..Default::default()
+        };
+        Self::async_update(client, subscription_id, &request).await
+    }
+
+    pub fn search(client: &BraintreeClient, criteria: &SubscriptionSearch, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.search("/subscriptions", criteria, "subscriptions", 0, limit)
+    }
+
+    pub async fn async_search(client: &BraintreeClient, criteria: &SubscriptionSearch, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.async_search("/subscriptions", criteria, "subscriptions", 0, limit).await
+    }
+}
@@ -0,0 +1,239 @@
+use std::collections::HashMap;
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use super::{BraintreeClient, Address, SearchText};
+
+/// Collection root returned by `/transactions/advanced_search`.
+const SEARCH_ROOT: &str = "credit-card-transactions";
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Transaction {
+    pub id: String,
+    pub status: TransactionStatus,
+    #[serde(rename = "type")]
+    pub transaction_type: TransactionType,
+    pub amount: String,
+    pub currency_iso_code: Option<String>,
+    pub order_id: Option<String>,
+    pub merchant_account_id: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub customer: Option<TransactionCustomer>,
+    pub credit_card: Option<CardDetails>,
+    pub billing: Option<Address>,
+    pub payment_instrument_type: Option<String>,
+    pub processor_response_code: Option<String>,
+    pub processor_response_text: Option<String>,
+    pub gateway_rejection_reason: Option<String>,
+    pub subscription_id: Option<String>,
+    pub refunded_transaction_id: Option<String>,
+    #[serde(default)]
+    pub refund_ids: Vec<String>,
+    pub custom_fields: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionStatus {
+    AuthorizationExpired,
+    Authorized,
+    Authorizing,
+    SettlementPending,
+    SettlementDeclined,
+    Failed,
+    GatewayRejected,
+    ProcessorDeclined,
+    Settled,
+    Settling,
+    SubmittedForSettlement,
+    Voided,
+    #[serde(other)]
+    Unknown,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TransactionType {
+    Sale,
+    Credit,
+}
+
+/// Customer details echoed back on a transaction.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TransactionCustomer {
+    pub id: Option<String>,
+    pub first_name: Option<String>,
+    pub last_name: Option<String>,
+    pub email: Option<String>,
+    pub phone: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CardDetails {
+    pub token: Option<String>,
+    pub bin: Option<String>,
+    pub last_4: Option<String>,
+    pub card_type: Option<String>,
+    pub expiration_month: Option<String>,
+    pub expiration_year: Option<String>,
+    pub cardholder_name: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionRequest {
+    #[serde(rename = "type")]
+    pub transaction_type: Option<TransactionType>,
+    pub amount: String,
+    pub payment_method_nonce: Option<String>,
+    pub payment_method_token: Option<String>,
+    pub customer_id: Option<String>,
+    pub order_id: Option<String>,
+    pub merchant_account_id: Option<String>,
+    pub custom_fields: Option<HashMap<String, String>>,
+    pub options: Option<TransactionOptions>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionOptions {
+    pub submit_for_settlement: Option<bool>,
+    pub store_in_vault_on_success: Option<bool>,
+}
+
+/// Body for settlement and refund calls, which only take an optional amount.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct AmountRequest {
+    pub amount: Option<String>,
+    pub order_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TransactionSearch {
+    pub customer_id: Option<SearchText>,
+    pub order_id: Option<SearchText>,
+    #[serde(rename = "type")]
+    pub transaction_type: Option<Vec<TransactionType>>,
+}
+
+impl TransactionRequest {
+    /// A sale charged to a client-side nonce or a vaulted token (`tokenized` is
+    /// treated as a token when it doesn't look like a nonce).
+    pub fn sale(amount: String, tokenized: &str) -> Self {
+        let (nonce, token) = if is_nonce(tokenized) {
+            (Some(tokenized.to_string()), None)
+        } else {
+            (None, Some(tokenized.to_string()))
+        };
+        Self {
+            transaction_type: Some(TransactionType::Sale),
+            amount,
+            payment_method_nonce: nonce,
+            payment_method_token: token,
+            ..Default::default()
+        }
+    }
+}
+
+/// Client-side nonces are UUIDs, or `fake-…` in the sandbox; vault tokens are neither.
+fn is_nonce(value: &str) -> bool {
+    value.starts_with("fake-") || uuid::Uuid::parse_str(value).is_ok()
+}
+
+impl Transaction {
+    pub fn sale(client: &BraintreeClient, request: &TransactionRequest) -> Result<Self> {
+        client.post("/transactions", "transaction", request, "transaction")
+    }
+
+    pub async fn async_sale(client: &BraintreeClient, request: &TransactionRequest) -> Result<Self> {
+        client.async_post("/transactions", "transaction", request, "transaction").await
+    }
+
+    pub fn find(client: &BraintreeClient, transaction_id: &str) -> Result<Self> {
+        let endpoint = format!("/transactions/{}", transaction_id);
+        client.get(&endpoint, "transaction")
+    }
+
+    pub async fn async_find(client: &BraintreeClient, transaction_id: &str) -> Result<Self> {
+        let endpoint = format!("/transactions/{}", transaction_id);
+        client.async_get(&endpoint, "transaction").await
+    }
+
+    /// Captures an authorized transaction, optionally for less than the authorized amount.
+    pub fn submit_for_settlement(client: &BraintreeClient, transaction_id: &str, amount: Option<String>) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/submit_for_settlement", transaction_id);
+        let request = AmountRequest { amount, order_id: None };
+        client.put(&endpoint, "transaction", &request, "transaction")
+    }
+
+    pub async fn async_submit_for_settlement(client: &BraintreeClient, transaction_id: &str, amount: Option<String>) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/submit_for_settlement", transaction_id);
+        let request = AmountRequest { amount, order_id: None };
+        client.async_put(&endpoint, "transaction", &request, "transaction").await
+    }
+
+    pub fn void(client: &BraintreeClient, transaction_id: &str) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/void", transaction_id);
+        client.put(&endpoint, "transaction", &AmountRequest::default(), "transaction")
+    }
+
+    pub async fn async_void(client: &BraintreeClient, transaction_id: &str) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/void", transaction_id);
+        client.async_put(&endpoint, "transaction", &AmountRequest::default(), "transaction").await
+    }
+
+    /// Refunds a settled transaction in full, or partially when `amount` is
+    /// given. The refund is itself a `credit` transaction, tagged with
+    /// `order_id` when one is given.
+    pub fn refund(client: &BraintreeClient, transaction_id: &str, amount: Option<String>, order_id: Option<String>) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/refund", transaction_id);
+        let request = AmountRequest { amount, order_id };
+        client.post(&endpoint, "transaction", &request, "transaction")
+    }
+
+    pub async fn async_refund(client: &BraintreeClient, transaction_id: &str, amount: Option<String>, order_id: Option<String>) -> Result<Self> {
+        let endpoint = format!("/transactions/{}/refund", transaction_id);
+        let request = AmountRequest { amount, order_id };
+        client.async_post(&endpoint, "transaction", &request, "transaction").await
+    }
+
+    pub fn search(client: &BraintreeClient, criteria: &TransactionSearch, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.search("/transactions", criteria, SEARCH_ROOT, 0, limit)
+    }
+
+    pub async fn async_search(client: &BraintreeClient, criteria: &TransactionSearch, limit: Option<usize>) -> Result<Vec<Self>> {
+        client.async_search("/transactions", criteria, SEARCH_ROOT, 0, limit).await
+    }
+
+    pub fn is_refund(&self) -> bool {
+        self.transaction_type == TransactionType::Credit && self.refunded_transaction_id.is_some()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sale_distinguishes_nonce_and_token() {
+        let with_nonce = TransactionRequest::sale("10.00".to_string(), "fake-valid-nonce");
+        assert_eq!(with_nonce.payment_method_nonce.as_deref(), Some("fake-valid-nonce"));
+        assert!(with_nonce.payment_method_token.is_none());
+
+        let with_token = TransactionRequest::sale("10.00".to_string(), "8kzzq7");
+        assert_eq!(with_token.payment_method_token.as_deref(), Some("8kzzq7"));
+        assert!(with_token.payment_method_nonce.is_none());
+    }
+
+    #[test]
+    fn test_request_xml() {
+        let mut request = TransactionRequest::sale("10.00".to_string(), "fake-valid-nonce");
+        request.options = Some(TransactionOptions {
+            submit_for_settlement: Some(true),
+            store_in_vault_on_success: None,
+        });
+
+        let xml = super::super::xml::to_xml("transaction", &request).unwrap();
+        assert!(xml.contains("<type>sale</type>"));
+        assert!(xml.contains("<amount>10.00</amount>"));
+        assert!(xml.contains("<submit-for-settlement type=\"boolean\">true</submit-for-settlement>"));
+    }
+}
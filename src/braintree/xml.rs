@@ -0,0 +1,280 @@
+//! Conversion between Braintree's gateway XML and `serde_json::Value`.
+//!
+//! The gateway speaks kebab-case XML with `type` attributes for non-string
+//! scalars (`<amount>10.00</amount>`, `<count type="integer">2</count>`,
+//! `<items type="array">…</items>`, `<id nil="true"/>`). Going through
+//! `Value` lets the resource types stay plain snake_case serde structs.
+
+use quick_xml::events::Event;
+use quick_xml::escape::escape;
+use quick_xml::Reader;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use crate::error::{PayupError, Result};
+
+#[derive(Debug, Default)]
+struct Node {
+    name: String,
+    attrs: Vec<(String, String)>,
+    children: Vec<Node>,
+    text: String,
+}
+
+impl Node {
+    fn attr(&self, key: &str) -> Option<&str> {
+        self.attrs.iter().find(|(k, _)| k == key).map(|(_, v)| v.as_str())
+    }
+}
+
+/// Serializes `value` as a Braintree request document with `root` as the
+/// top-level element. `None` fields are omitted.
+pub fn to_xml<T: Serialize>(root: &str, value: &T) -> Result<String> {
+    let value = serde_json::to_value(value).map_err(PayupError::from)?;
+    let mut out = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>");
+    write_element(&mut out, root, &value);
+    Ok(out)
+}
+
+/// Parses a gateway response, returning the root element name and its contents.
+pub fn parse(xml: &str) -> Result<(String, Value)> {
+    let root = read_tree(xml)?;
+    let value = node_to_value(&root);
+    Ok((root.name, value))
+}
+
+/// Parses a gateway response whose root element must be `root`.
+pub fn from_xml<T: DeserializeOwned>(xml: &str, root: &str) -> Result<T> {
+    let (name, value) = parse(xml)?;
+    if name != root {
+        return Err(PayupError::Deserialization(format!(
+            "Expected <{}> but Braintree returned <{}>",
+            root, name
+        )));
+    }
+    serde_json::from_value(value).map_err(PayupError::from)
+}
+
+fn write_element(out: &mut String, name: &str, value: &Value) {
+    let tag = name.replace('_', "-");
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => out.push_str(&format!("<{0} type=\"boolean\">{1}</{0}>", tag, b)),
+        Value::Number(n) if n.is_i64() || n.is_u64() => {
+            out.push_str(&format!("<{0} type=\"integer\">{1}</{0}>", tag, n))
+        }
+        Value::Number(n) => out.push_str(&format!("<{0}>{1}</{0}>", tag, n)),
+        Value::String(s) => out.push_str(&format!("<{0}>{1}</{0}>", tag, escape(s.as_str()))),
+        Value::Array(items) => {
+            out.push_str(&format!("<{} type=\"array\">", tag));
+            for item in items {
+                write_element(out, "item", item);
+            }
+            out.push_str(&format!("</{}>", tag));
+        }
+        Value::Object(fields) => {
+            out.push_str(&format!("<{}>", tag));
+            for (key, field) in fields {
+                write_element(out, key, field);
+            }
+            out.push_str(&format!("</{}>", tag));
+        }
+    }
+}
+
+fn read_tree(xml: &str) -> Result<Node> {
+    let mut reader = Reader::from_str(xml);
+    reader.trim_text(true);
+
+    let mut stack: Vec<Node> = Vec::new();
+    loop {
+        let event = reader
+            .read_event()
+            .map_err(|e| PayupError::Deserialization(format!("Invalid Braintree XML: {}", e)))?;
+        match event {
+            Event::Start(start) => stack.push(start_node(&start)?),
+            Event::Empty(start) => {
+                let node = start_node(&start)?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Text(text) => {
+                if let Some(node) = stack.last_mut() {
+                    let text = text
+                        .unescape()
+                        .map_err(|e| PayupError::Deserialization(format!("Invalid Braintree XML: {}", e)))?;
+                    node.text.push_str(&text);
+                }
+            }
+            Event::CData(data) => {
+                if let Some(node) = stack.last_mut() {
+                    node.text.push_str(&String::from_utf8_lossy(&data));
+                }
+            }
+            Event::End(_) => {
+                let node = stack.pop().ok_or_else(|| {
+                    PayupError::Deserialization("Unbalanced Braintree XML".to_string())
+                })?;
+                match stack.last_mut() {
+                    Some(parent) => parent.children.push(node),
+                    None => return Ok(node),
+                }
+            }
+            Event::Eof => {
+                return Err(PayupError::Deserialization(
+                    "Braintree XML ended before the root element closed".to_string(),
+                ))
+            }
+            _ => {}
+        }
+    }
+}
+
+fn start_node(start: &quick_xml::events::BytesStart) -> Result<Node> {
+    let mut node = Node {
+        name: String::from_utf8_lossy(start.name().as_ref()).to_string(),
+        ..Default::default()
+    };
+    for attr in start.attributes() {
+        let attr = attr.map_err(|e| PayupError::Deserialization(format!("Invalid Braintree XML: {}", e)))?;
+        let value = attr
+            .unescape_value()
+            .map_err(|e| PayupError::Deserialization(format!("Invalid Braintree XML: {}", e)))?;
+        node.attrs.push((
+            String::from_utf8_lossy(attr.key.as_ref()).to_string(),
+            value.to_string(),
+        ));
+    }
+    Ok(node)
+}
+
+fn node_to_value(node: &Node) -> Value {
+    if node.attr("nil") == Some("true") {
+        return Value::Null;
+    }
+
+    match node.attr("type") {
+        Some("array") => {
+            return Value::Array(node.children.iter().map(node_to_value).collect());
+        }
+        // Search results wrap records alongside paging scalars
+        // (`<current-page-number>`, `<total-items>`), which are dropped here
+        Some("collection") => {
+            return Value::Array(
+                node.children
+                    .iter()
+                    .filter(|c| !c.children.is_empty())
+                    .map(node_to_value)
+                    .collect(),
+            );
+        }
+        Some("integer") => {
+            if let Ok(n) = node.text.trim().parse::<i64>() {
+                return Value::from(n);
+            }
+        }
+        Some("boolean") => return Value::Bool(node.text.trim() == "true"),
+        _ => {}
+    }
+
+    if node.children.is_empty() {
+        // Untyped empty elements (`<custom-fields/>`) carry nothing, whatever
+        // shape the field would otherwise have
+        if node.text.is_empty() {
+            return Value::Null;
+        }
+        return Value::String(node.text.clone());
+    }
+
+    let mut map = Map::new();
+    for child in &node.children {
+        let key = child.name.replace('-', "_");
+        let value = node_to_value(child);
+        // Repeated siblings without type="array" are still a list
+        let repeated = node.children.iter().filter(|c| c.name == child.name).count() > 1;
+        if repeated {
+            if let Value::Array(items) = map.entry(key).or_insert_with(|| Value::Array(Vec::new())) {
+                items.push(value);
+            }
+        } else {
+            map.insert(key, value);
+        }
+    }
+    Value::Object(map)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use serde::Deserialize;
+
+    #[test]
+    fn test_to_xml() {
+        let body = serde_json::json!({
+            "amount": "10.00",
+            "payment_method_nonce": "fake-valid-nonce",
+            "customer_id": null,
+            "options": { "submit_for_settlement": true },
+            "note": "Tom & Jerry",
+        });
+        let xml = to_xml("transaction", &body).unwrap();
+        assert!(xml.starts_with("<?xml"));
+        assert!(xml.contains("<amount>10.00</amount>"));
+        assert!(xml.contains("<payment-method-nonce>fake-valid-nonce</payment-method-nonce>"));
+        assert!(xml.contains("<options><submit-for-settlement type=\"boolean\">true</submit-for-settlement></options>"));
+        assert!(xml.contains("<note>Tom &amp; Jerry</note>"));
+        assert!(!xml.contains("customer-id"));
+    }
+
+    #[test]
+    fn test_parse_typed_values() {
+        let xml = r#"<?xml version="1.0" encoding="UTF-8"?>
+            <subscription>
+              <id>sub_1</id>
+              <billing-day-of-month type="integer">15</billing-day-of-month>
+              <never-expires type="boolean">true</never-expires>
+              <description nil="true"/>
+              <add-ons type="array"/>
+              <transactions type="array">
+                <transaction><id>t1</id></transaction>
+                <transaction><id>t2</id></transaction>
+              </transactions>
+            </subscription>"#;
+
+        let (root, value) = parse(xml).unwrap();
+        assert_eq!(root, "subscription");
+        assert_eq!(value["id"], "sub_1");
+        assert_eq!(value["billing_day_of_month"], 15);
+        assert_eq!(value["never_expires"], true);
+        assert!(value["description"].is_null());
+        assert_eq!(value["add_ons"], serde_json::json!([]));
+        assert_eq!(value["transactions"][1]["id"], "t2");
+    }
+
+    #[test]
+    fn test_parse_collection_skips_paging() {
+        let xml = r#"<customers type="collection">
+              <current-page-number type="integer">1</current-page-number>
+              <total-items type="integer">2</total-items>
+              <customer><id>c1</id></customer>
+              <customer><id>c2</id></customer>
+            </customers>"#;
+
+        let (_, value) = parse(xml).unwrap();
+        assert_eq!(value, serde_json::json!([{ "id": "c1" }, { "id": "c2" }]));
+    }
+
+    #[test]
+    fn test_from_xml_checks_root() {
+        #[derive(Deserialize)]
+        struct Thing {
+            id: String,
+        }
+
+        let thing: Thing = from_xml("<thing><id>1</id></thing>", "thing").unwrap();
+        assert_eq!(thing.id, "1");
+        assert!(from_xml::<Thing>("<other><id>1</id></other>", "thing").is_err());
+    }
+}
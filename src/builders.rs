@@ -78,6 +78,8 @@ pub struct PageRequest {
     pub limit: Option<usize>,
     pub starting_after: Option<String>,
     pub ending_before: Option<String>,
+    /// Stop auto-pagination after this many items in total
+    pub max_items: Option<usize>,
 }
 
 impl PageRequest {
@@ -86,14 +88,29 @@ impl PageRequest {
             limit: None,
             starting_after: None,
             ending_before: None,
+            max_items: None,
         }
     }
 
+    /// Number of items fetched per request
     pub fn limit(mut self, limit: usize) -> Self {
         self.limit = Some(limit);
         self
     }
 
+    pub fn max_items(mut self, max_items: usize) -> Self {
+        self.max_items = Some(max_items);
+        self
+    }
+
+    /// The page size to request: `limit`, but never more than `max_items`.
+    pub fn page_size(&self) -> Option<usize> {
+        match (self.limit, self.max_items) {
+            (Some(limit), Some(max_items)) => Some(limit.min(max_items)),
+            (limit, _) => limit,
+        }
+    }
+
     pub fn starting_after(mut self, cursor: impl Into<String>) -> Self {
         self.starting_after = Some(cursor.into());
         self.ending_before = None;
@@ -1,7 +1,7 @@
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
 use std::collections::HashMap;
-use crate::error::{PayupError, Result};
+use crate::error::{ErrorCategory, PayupError, Result};
 
 /// Circuit breaker states
 #[derive(Debug, Clone, Copy, PartialEq)]
@@ -253,12 +253,7 @@ impl CircuitBreaker {
 
     /// Check if an error should trigger circuit breaker
     fn is_circuit_breaking_error(error: &PayupError) -> bool {
-        match error {
-            PayupError::NetworkError(_) => true,
-            PayupError::ServerError(status) if *status >= 500 => true,
-            PayupError::TimeoutError(_) => true,
-            _ => false,
-        }
+        matches!(error.category(), ErrorCategory::ProviderOutage | ErrorCategory::Network)
     }
 
     /// Get the current state of a circuit
@@ -2,6 +2,7 @@ use std::time::Duration;
 
 pub mod api {
     pub const STRIPE_BASE_URL: &str = "https://api.stripe.com/v1/";
+    pub const STRIPE_FILES_URL: &str = "https://files.stripe.com/v1/";
     pub const STRIPE_API_VERSION: &str = "2020-08-27";
     
     pub const PAYPAL_BASE_URL: &str = "https://api.paypal.com/";
@@ -42,6 +43,10 @@ pub mod http {
     pub const STATUS_RATE_LIMITED: u16 = 429;
     pub const STATUS_SERVER_ERROR: u16 = 500;
     pub const STATUS_SERVICE_UNAVAILABLE: u16 = 503;
+
+    pub const HEADER_IDEMPOTENCY_KEY: &str = "Idempotency-Key";
+    pub const HEADER_PAYPAL_REQUEST_ID: &str = "PayPal-Request-Id";
+    pub const HEADER_STRIPE_ACCOUNT: &str = "Stripe-Account";
 }
 
 #[derive(Debug, Clone)]
@@ -1,5 +1,6 @@
 use std::fmt;
 use std::error::Error as StdError;
+use serde::Deserialize;
 
 #[derive(Debug)]
 pub enum PayupError {
@@ -19,12 +20,13 @@ pub enum PayupError {
     // Validation errors
     ValidationError(String),
     
-    // Rate limiting
+    // Rate limiting: the provider answered 429
     RateLimitError {
         retry_after: Option<u64>,
     },
     
-    // Rate limit exceeded (for internal rate limiter)
+    // Rate limit exceeded: the internal rate limiter refused to send the
+    // request. Match `ErrorCategory::RateLimited` to handle both.
     RateLimitExceeded(String),
     
     // Timeout error
@@ -39,21 +41,29 @@ pub enum PayupError {
     // Generic errors
     GenericError(String),
     
-    // Provider-specific errors
-    StripeError {
-        error_type: String,
-        code: Option<String>,
-        message: String,
-        param: Option<String>,
-    },
+    // Provider-specific errors, parsed from the error body of a non-2xx response
+    // Boxed to keep `Result<T>` small
+    StripeError(Box<StripeErrorDetails>),
     
     PayPalError {
+        status: u16,
         name: String,
         message: String,
         debug_id: Option<String>,
         details: Option<Vec<PayPalErrorDetail>>,
     },
     
+    SquareError(Box<SquareErrorDetails>),
+    
+    // The customer has to complete a step, such as 3D Secure, before the
+    // operation can finish; hand `client_secret` to the provider's client SDK
+    ActionRequired {
+        provider: String,
+        intent_id: String,
+        client_secret: Option<String>,
+        redirect_url: Option<String>,
+    },
+    
     // Unsupported operation
     UnsupportedOperation(String),
     
@@ -61,25 +71,51 @@ pub enum PayupError {
     WebhookVerificationFailed(String),
     
     // Additional HTTP errors for better granularity
+    #[deprecated(note = "use NetworkError, or the provider error parsed from the response body")]
     Http(String),
     BadRequest(String),
     Unauthorized(String),
     Forbidden(String),
     NotFound(String),
+    #[deprecated(note = "use RateLimitError or RateLimitExceeded")]
     RateLimited(String),
     
     // Runtime errors
     Runtime(String),
     
     // Validation errors with better context
+    #[deprecated(note = "use ValidationError")]
     Validation(String),
     
     // Serialization/Deserialization with context
+    #[deprecated(note = "use SerializationError or Deserialization")]
     Serialization(String),
     Deserialization(String),
 }
 
+/// The `error` object of a non-2xx Stripe response
 #[derive(Debug)]
+pub struct StripeErrorDetails {
+    pub status: u16,
+    pub error_type: String,
+    pub code: Option<String>,
+    pub decline_code: Option<String>,
+    pub message: String,
+    pub param: Option<String>,
+    pub request_id: Option<String>,
+}
+
+/// The first entry of the `errors` array of a non-2xx Square response
+#[derive(Debug)]
+pub struct SquareErrorDetails {
+    pub status: u16,
+    pub category: String,
+    pub code: String,
+    pub detail: Option<String>,
+    pub field: Option<String>,
+}
+
+#[derive(Debug, Deserialize)]
 pub struct PayPalErrorDetail {
     pub field: Option<String>,
     pub issue: String,
@@ -87,6 +123,7 @@ pub struct PayPalErrorDetail {
 }
 
 impl fmt::Display for PayupError {
+    #[allow(deprecated)]
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         match self {
             PayupError::NetworkError(e) => 
@@ -119,12 +156,23 @@ impl fmt::Display for PayupError {
             PayupError::GenericError(msg) => 
                 write!(f, "Error: {}", msg),
             
-            PayupError::StripeError { error_type, code, message, param } => 
-                self.format_stripe_error(f, error_type, code.as_deref(), message, param.as_deref()),
+            PayupError::StripeError(e) => 
+                self.format_stripe_error(f, &e.error_type, e.code.as_deref(), &e.message, e.param.as_deref(), e.request_id.as_deref()),
             
             PayupError::PayPalError { name, message, debug_id, .. } => 
                 self.format_paypal_error(f, name, message, debug_id.as_deref()),
             
+            PayupError::SquareError(e) => 
+                self.format_square_error(f, &e.category, &e.code, e.detail.as_deref(), e.field.as_deref()),
+            
+            PayupError::ActionRequired { provider, intent_id, redirect_url, .. } => {
+                write!(f, "{} requires customer action to complete {}", provider, intent_id)?;
+                if let Some(url) = redirect_url {
+                    write!(f, " [redirect: {}]", url)?;
+                }
+                Ok(())
+            }
+            
             PayupError::UnsupportedOperation(msg) => 
                 write!(f, "Unsupported operation: {}", msg),
             
@@ -179,6 +227,7 @@ impl PayupError {
         code: Option<&str>,
         message: &str,
         param: Option<&str>,
+        request_id: Option<&str>,
     ) -> fmt::Result {
         write!(f, "Stripe {} error", error_type)?;
         if let Some(c) = code {
@@ -188,6 +237,9 @@ impl PayupError {
         if let Some(p) = param {
             write!(f, " [param: {}]", p)?;
         }
+        if let Some(id) = request_id {
+            write!(f, " [request_id: {}]", id)?;
+        }
         Ok(())
     }
 
@@ -204,6 +256,228 @@ impl PayupError {
         }
         Ok(())
     }
+
+    fn format_square_error(
+        &self,
+        f: &mut fmt::Formatter<'_>,
+        category: &str,
+        code: &str,
+        detail: Option<&str>,
+        field: Option<&str>,
+    ) -> fmt::Result {
+        write!(f, "Square {} ({})", category, code)?;
+        if let Some(d) = detail {
+            write!(f, ": {}", d)?;
+        }
+        if let Some(field) = field {
+            write!(f, " [field: {}]", field)?;
+        }
+        Ok(())
+    }
+}
+
+/// Provider-independent classification of a [`PayupError`], so callers can
+/// react the same way whichever processor failed.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ErrorCategory {
+    /// The card or payment method was declined by the issuer or processor
+    CardDeclined,
+    /// A decline caused specifically by a lack of funds
+    InsufficientFunds,
+    /// Bad or missing credentials, or insufficient permissions
+    Authentication,
+    /// The request itself was rejected: missing parameters, unknown IDs, invalid state
+    InvalidRequest,
+    /// Too many requests, from the provider or the local rate limiter
+    RateLimited,
+    /// The provider failed or is unavailable
+    ProviderOutage,
+    /// The request did not get a response
+    Network,
+    /// A failure in this library, such as an unreadable response body
+    Internal,
+}
+
+impl ErrorCategory {
+    pub fn as_str(&self) -> &'static str {
+        match self {
+            ErrorCategory::CardDeclined => "card_declined",
+            ErrorCategory::InsufficientFunds => "insufficient_funds",
+            ErrorCategory::Authentication => "auth",
+            ErrorCategory::InvalidRequest => "invalid_request",
+            ErrorCategory::RateLimited => "rate_limited",
+            ErrorCategory::ProviderOutage => "provider_outage",
+            ErrorCategory::Network => "network",
+            ErrorCategory::Internal => "internal",
+        }
+    }
+}
+
+impl fmt::Display for ErrorCategory {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// PayPal `details[].issue` values that mean the payer's instrument was refused
+const PAYPAL_DECLINE_ISSUES: &[&str] = &[
+    "INSTRUMENT_DECLINED",
+    "TRANSACTION_REFUSED",
+    "PAYER_CANNOT_PAY",
+    "CARD_EXPIRED",
+];
+
+impl PayupError {
+    /// Classifies the error independently of the provider that raised it.
+    ///
+    /// Match on the category rather than on variants where several variants
+    /// mean the same thing: `RateLimitError` (the provider answered 429) and
+    /// `RateLimitExceeded` (the local limiter refused to send) are both
+    /// `ErrorCategory::RateLimited`.
+    #[allow(deprecated)]
+    pub fn category(&self) -> ErrorCategory {
+        match self {
+            PayupError::NetworkError(e) if e.is_decode() => ErrorCategory::Internal,
+            PayupError::NetworkError(_) | PayupError::TimeoutError(_) => ErrorCategory::Network,
+
+            PayupError::Http(_) => ErrorCategory::Network,
+
+            PayupError::RateLimitError { .. }
+            | PayupError::RateLimitExceeded(_)
+            | PayupError::RateLimited(_) => ErrorCategory::RateLimited,
+
+            PayupError::AuthenticationError(_)
+            | PayupError::Unauthorized(_)
+            | PayupError::Forbidden(_)
+            | PayupError::WebhookVerificationFailed(_) => ErrorCategory::Authentication,
+
+            PayupError::ValidationError(_)
+            | PayupError::Validation(_)
+            | PayupError::BadRequest(_)
+            | PayupError::NotFound(_)
+            | PayupError::UnsupportedOperation(_) => ErrorCategory::InvalidRequest,
+
+            PayupError::ServerError(status) => status_category(*status),
+
+            // Stripe reports the same situation off-session as the
+            // `authentication_required` decline
+            PayupError::ActionRequired { .. } => ErrorCategory::CardDeclined,
+
+            PayupError::ApiError { code, provider, .. } => api_error_category(code, provider),
+
+            PayupError::StripeError(e) => {
+                let reason = e.decline_code.as_deref().or(e.code.as_deref());
+                match e.error_type.as_str() {
+                    "card_error" if reason == Some("insufficient_funds") => ErrorCategory::InsufficientFunds,
+                    "card_error" => ErrorCategory::CardDeclined,
+                    "authentication_error" => ErrorCategory::Authentication,
+                    "rate_limit_error" => ErrorCategory::RateLimited,
+                    "api_error" => ErrorCategory::ProviderOutage,
+                    _ => status_category(e.status),
+                }
+            }
+
+            PayupError::PayPalError { status, name, details, .. } => {
+                let issues: Vec<&str> = details.iter().flatten().map(|d| d.issue.as_str()).collect();
+                if issues.contains(&"INSUFFICIENT_FUNDS") {
+                    ErrorCategory::InsufficientFunds
+                } else if issues.iter().any(|issue| PAYPAL_DECLINE_ISSUES.contains(issue)) {
+                    ErrorCategory::CardDeclined
+                } else {
+                    match name.as_str() {
+                        "AUTHENTICATION_FAILURE" | "NOT_AUTHORIZED" => ErrorCategory::Authentication,
+                        "RATE_LIMIT_REACHED" => ErrorCategory::RateLimited,
+                        _ => status_category(*status),
+                    }
+                }
+            }
+
+            PayupError::SquareError(e) => match e.category.as_str() {
+                "PAYMENT_METHOD_ERROR" if e.code == "INSUFFICIENT_FUNDS" => ErrorCategory::InsufficientFunds,
+                "PAYMENT_METHOD_ERROR" => ErrorCategory::CardDeclined,
+                "AUTHENTICATION_ERROR" => ErrorCategory::Authentication,
+                "RATE_LIMIT_ERROR" => ErrorCategory::RateLimited,
+                "API_ERROR" => ErrorCategory::ProviderOutage,
+                _ => status_category(e.status),
+            },
+
+            PayupError::SerializationError(_)
+            | PayupError::Serialization(_)
+            | PayupError::GenericError(_)
+            | PayupError::Runtime(_)
+            | PayupError::Deserialization(_) => ErrorCategory::Internal,
+        }
+    }
+
+    /// Whether sending the same request again may succeed. Rate limits,
+    /// outages and network failures are transient; declines and invalid
+    /// requests fail the same way every time.
+    pub fn is_retryable(&self) -> bool {
+        match self {
+            // Stripe's lock contention on the object being modified
+            PayupError::StripeError(e) if e.code.as_deref() == Some("lock_timeout") => true,
+            _ => matches!(
+                self.category(),
+                ErrorCategory::RateLimited | ErrorCategory::ProviderOutage | ErrorCategory::Network
+            ),
+        }
+    }
+
+    /// The provider's identifier for the failed request (Stripe's `Request-Id`
+    /// header, PayPal's `debug_id`), to quote when contacting their support.
+    pub fn request_id(&self) -> Option<&str> {
+        match self {
+            PayupError::StripeError(e) => e.request_id.as_deref(),
+            PayupError::PayPalError { debug_id, .. } => debug_id.as_deref(),
+            _ => None,
+        }
+    }
+
+    /// The processor's reason for a decline, such as Stripe's
+    /// `insufficient_funds` or PayPal's `INSTRUMENT_DECLINED`. `None` unless
+    /// the error is a decline.
+    pub fn decline_code(&self) -> Option<&str> {
+        if !matches!(self.category(), ErrorCategory::CardDeclined | ErrorCategory::InsufficientFunds) {
+            return None;
+        }
+        match self {
+            PayupError::StripeError(e) => e.decline_code.as_deref().or(e.code.as_deref()),
+            PayupError::PayPalError { name, details, .. } => details
+                .iter()
+                .flatten()
+                .map(|d| d.issue.as_str())
+                .next()
+                .or(Some(name.as_str())),
+            PayupError::SquareError(e) => Some(&e.code),
+            PayupError::ApiError { code, .. } => Some(code),
+            PayupError::ActionRequired { .. } => Some("authentication_required"),
+            _ => None,
+        }
+    }
+}
+
+/// Category implied by an HTTP status alone.
+fn status_category(status: u16) -> ErrorCategory {
+    match status {
+        401 | 403 => ErrorCategory::Authentication,
+        429 => ErrorCategory::RateLimited,
+        400..=499 => ErrorCategory::InvalidRequest,
+        500..=599 => ErrorCategory::ProviderOutage,
+        _ => ErrorCategory::Internal,
+    }
+}
+
+/// `ApiError` codes are either an HTTP status (`"404 Not Found"`) or a
+/// provider code, such as Braintree's numeric processor responses.
+fn api_error_category(code: &str, provider: &str) -> ErrorCategory {
+    let numeric = code.split_whitespace().next().and_then(|c| c.parse::<u16>().ok());
+    match numeric {
+        Some(2001) if provider == "Braintree" => ErrorCategory::InsufficientFunds,
+        Some(2000..=2999) if provider == "Braintree" => ErrorCategory::CardDeclined,
+        Some(3000) if provider == "Braintree" => ErrorCategory::ProviderOutage,
+        Some(status @ 100..=599) => status_category(status),
+        _ => ErrorCategory::InvalidRequest,
+    }
 }
 
 impl StdError for PayupError {
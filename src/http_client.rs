@@ -1,8 +1,13 @@
+use std::fmt;
 use std::sync::Arc;
 use std::time::Duration;
+use async_trait::async_trait;
 use reqwest::{Client, Response, header::{HeaderMap, HeaderValue, AUTHORIZATION, CONTENT_TYPE}};
 use serde::{Deserialize, Serialize};
+use crate::config::http::{HEADER_IDEMPOTENCY_KEY, HEADER_PAYPAL_REQUEST_ID};
 use crate::error::PayupError;
+use crate::http_utils::error_from_response;
+use crate::rate_limiter::get_rate_limiter;
 use once_cell::sync::Lazy;
 
 /// Global shared HTTP client with optimized connection pooling settings
@@ -52,10 +57,220 @@ pub static SHARED_BLOCKING_CLIENT: Lazy<Arc<reqwest::blocking::Client>> = Lazy::
     )
 });
 
+/// Executes the HTTP requests of every provider client.
+///
+/// Provider code builds a `reqwest` request and hands it to a `Transport`
+/// instead of sending it itself, so swapping the transport changes how every
+/// request reaches the network: a [`ReqwestTransport`] around a client with a
+/// proxy or custom TLS roots, or an in-memory fake that answers from canned
+/// `http::Response`s (both `reqwest` response types implement `From` them).
+#[async_trait]
+pub trait Transport: fmt::Debug + Send + Sync {
+    async fn execute(&self, request: reqwest::Request) -> Result<Response, reqwest::Error>;
+
+    fn execute_blocking(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error>;
+}
+
+/// The default [`Transport`], backed by pooled `reqwest` clients.
+#[derive(Debug, Clone)]
+pub struct ReqwestTransport {
+    client: Arc<Client>,
+    blocking_client: Option<Arc<reqwest::blocking::Client>>,
+}
+
+impl ReqwestTransport {
+    /// Sends async requests with `client` and blocking requests with the
+    /// shared blocking client, unless [`ReqwestTransport::with_blocking_client`] is used.
+    pub fn new(client: Client) -> Self {
+        Self {
+            client: Arc::new(client),
+            blocking_client: None,
+        }
+    }
+
+    /// Uses the process-wide pooled clients.
+    pub fn shared() -> Self {
+        Self {
+            client: SHARED_CLIENT.clone(),
+            blocking_client: None,
+        }
+    }
+
+    pub fn with_blocking_client(mut self, blocking_client: reqwest::blocking::Client) -> Self {
+        self.blocking_client = Some(Arc::new(blocking_client));
+        self
+    }
+}
+
+impl Default for ReqwestTransport {
+    fn default() -> Self {
+        Self::shared()
+    }
+}
+
+#[async_trait]
+impl Transport for ReqwestTransport {
+    async fn execute(&self, request: reqwest::Request) -> Result<Response, reqwest::Error> {
+        self.client.execute(request).await
+    }
+
+    fn execute_blocking(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        // The shared blocking client is only created once a blocking request is made
+        match &self.blocking_client {
+            Some(blocking_client) => blocking_client.execute(request),
+            None => SHARED_BLOCKING_CLIENT.execute(request),
+        }
+    }
+}
+
+static DEFAULT_TRANSPORT: Lazy<Arc<dyn Transport>> = Lazy::new(|| Arc::new(ReqwestTransport::shared()));
+
+/// The transport provider clients use unless one is injected.
+pub fn default_transport() -> Arc<dyn Transport> {
+    DEFAULT_TRANSPORT.clone()
+}
+
+/// Sends an async `reqwest` request builder through a [`Transport`].
+#[async_trait]
+pub trait SendVia {
+    async fn send_via(self, transport: &dyn Transport) -> Result<Response, reqwest::Error>;
+
+    /// Sends through the rate limit and circuit breaker configured for
+    /// `endpoint` (e.g. `"stripe"`). Any non-2xx response becomes an error,
+    /// built by [`error_from_response`] from the provider's error body.
+    ///
+    /// Failures are only retried when resending is safe: the method is
+    /// idempotent or the request carries an idempotency key header.
+    async fn send_limited(self, endpoint: &str, transport: &dyn Transport) -> Result<Response, PayupError>;
+
+    /// Like [`SendVia::send_limited`], but retries regardless of method, for
+    /// requests whose idempotency key travels in the body (as on Square).
+    async fn send_limited_idempotent(self, endpoint: &str, transport: &dyn Transport) -> Result<Response, PayupError>;
+}
+
+#[async_trait]
+impl SendVia for reqwest::RequestBuilder {
+    async fn send_via(self, transport: &dyn Transport) -> Result<Response, reqwest::Error> {
+        transport.execute(self.build()?).await
+    }
+
+    async fn send_limited(self, endpoint: &str, transport: &dyn Transport) -> Result<Response, PayupError> {
+        let request = self.build()?;
+        let retry_safe = is_retry_safe(request.method(), request.headers());
+        execute_limited(request, endpoint, transport, retry_safe).await
+    }
+
+    async fn send_limited_idempotent(self, endpoint: &str, transport: &dyn Transport) -> Result<Response, PayupError> {
+        execute_limited(self.build()?, endpoint, transport, true).await
+    }
+}
+
+async fn execute_limited(
+    request: reqwest::Request,
+    endpoint: &str,
+    transport: &dyn Transport,
+    retry_safe: bool,
+) -> Result<Response, PayupError> {
+    let rate_limiter = get_rate_limiter();
+    // Streamed bodies can only be sent once
+    if retry_safe && request.try_clone().is_some() {
+        rate_limiter.execute_with_retry_async(endpoint, || async {
+            let attempt = request.try_clone().expect("request body is cloneable");
+            error_for_status(transport.execute(attempt).await?, endpoint).await
+        }).await
+    } else {
+        rate_limiter.execute_once_async(endpoint, async {
+            error_for_status(transport.execute(request).await?, endpoint).await
+        }).await
+    }
+}
+
+async fn error_for_status(response: Response, endpoint: &str) -> Result<Response, PayupError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let headers = response.headers().clone();
+    let body = response.text().await.unwrap_or_default();
+    Err(error_from_response(endpoint, status, &headers, &body))
+}
+
+/// Sends a blocking `reqwest` request builder through a [`Transport`].
+pub trait SendViaBlocking {
+    fn send_via(self, transport: &dyn Transport) -> Result<reqwest::blocking::Response, reqwest::Error>;
+
+    /// Blocking counterpart of [`SendVia::send_limited`].
+    fn send_limited(self, endpoint: &str, transport: &dyn Transport) -> Result<reqwest::blocking::Response, PayupError>;
+
+    /// Blocking counterpart of [`SendVia::send_limited_idempotent`].
+    fn send_limited_idempotent(self, endpoint: &str, transport: &dyn Transport) -> Result<reqwest::blocking::Response, PayupError>;
+}
+
+impl SendViaBlocking for reqwest::blocking::RequestBuilder {
+    fn send_via(self, transport: &dyn Transport) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        transport.execute_blocking(self.build()?)
+    }
+
+    fn send_limited(self, endpoint: &str, transport: &dyn Transport) -> Result<reqwest::blocking::Response, PayupError> {
+        let request = self.build()?;
+        let retry_safe = is_retry_safe(request.method(), request.headers());
+        execute_limited_blocking(request, endpoint, transport, retry_safe)
+    }
+
+    fn send_limited_idempotent(self, endpoint: &str, transport: &dyn Transport) -> Result<reqwest::blocking::Response, PayupError> {
+        execute_limited_blocking(self.build()?, endpoint, transport, true)
+    }
+}
+
+fn execute_limited_blocking(
+    request: reqwest::blocking::Request,
+    endpoint: &str,
+    transport: &dyn Transport,
+    retry_safe: bool,
+) -> Result<reqwest::blocking::Response, PayupError> {
+    let rate_limiter = get_rate_limiter();
+    // Streamed bodies can only be sent once
+    if retry_safe && request.try_clone().is_some() {
+        rate_limiter.execute_with_retry_blocking(endpoint, || {
+            let attempt = request.try_clone().expect("request body is cloneable");
+            error_for_status_blocking(transport.execute_blocking(attempt)?, endpoint)
+        })
+    } else {
+        rate_limiter.execute_once_blocking(endpoint, || {
+            error_for_status_blocking(transport.execute_blocking(request)?, endpoint)
+        })
+    }
+}
+
+fn error_for_status_blocking(
+    response: reqwest::blocking::Response,
+    endpoint: &str,
+) -> Result<reqwest::blocking::Response, PayupError> {
+    let status = response.status();
+    if status.is_success() {
+        return Ok(response);
+    }
+    let headers = response.headers().clone();
+    let body = response.text().unwrap_or_default();
+    Err(error_from_response(endpoint, status, &headers, &body))
+}
+
+/// Whether a request can be sent again without risking a duplicate side effect.
+fn is_retry_safe(method: &reqwest::Method, headers: &HeaderMap) -> bool {
+    method.is_idempotent()
+        || headers.contains_key(HEADER_IDEMPOTENCY_KEY)
+        || headers.contains_key(HEADER_PAYPAL_REQUEST_ID)
+}
+
 #[derive(Clone)]
 pub struct HttpClient {
-    client: Arc<Client>,
-    blocking_client: Arc<reqwest::blocking::Client>,
+    transport: Arc<dyn Transport>,
     base_url: String,
     auth_header: Option<String>,
 }
@@ -63,13 +278,17 @@ pub struct HttpClient {
 impl HttpClient {
     pub fn new(base_url: impl Into<String>) -> Self {
         Self {
-            client: SHARED_CLIENT.clone(),
-            blocking_client: SHARED_BLOCKING_CLIENT.clone(),
+            transport: default_transport(),
             base_url: base_url.into(),
             auth_header: None,
         }
     }
 
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
     pub fn with_auth(mut self, auth_type: &str, token: &str) -> Self {
         self.auth_header = Some(format!("{} {}", auth_type, token));
         self
@@ -97,12 +316,11 @@ impl HttpClient {
         T: for<'de> Deserialize<'de>,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.client
+        let response = SHARED_CLIENT
             .get(&url)
             .headers(self.build_headers())
-            .send()
-            .await
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)
+            .await?;
         
         self.handle_response(response).await
     }
@@ -113,13 +331,12 @@ impl HttpClient {
         B: Serialize,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.client
+        let response = SHARED_CLIENT
             .post(&url)
             .headers(self.build_headers())
             .json(body)
-            .send()
-            .await
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)
+            .await?;
         
         self.handle_response(response).await
     }
@@ -130,13 +347,12 @@ impl HttpClient {
         B: Serialize,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.client
+        let response = SHARED_CLIENT
             .put(&url)
             .headers(self.build_headers())
             .json(body)
-            .send()
-            .await
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)
+            .await?;
         
         self.handle_response(response).await
     }
@@ -146,12 +362,11 @@ impl HttpClient {
         T: for<'de> Deserialize<'de>,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.client
+        let response = SHARED_CLIENT
             .delete(&url)
             .headers(self.build_headers())
-            .send()
-            .await
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)
+            .await?;
         
         self.handle_response(response).await
     }
@@ -164,13 +379,12 @@ impl HttpClient {
         let mut headers = self.build_headers();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
         
-        let response = self.client
+        let response = SHARED_CLIENT
             .post(&url)
             .headers(headers)
             .form(params)
-            .send()
-            .await
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)
+            .await?;
         
         self.handle_response(response).await
     }
@@ -183,10 +397,11 @@ impl HttpClient {
             response.json::<T>().await
                 .map_err(|e| PayupError::Deserialization(e.to_string()))
         } else {
-            let status = response.status().as_u16();
+            let status = response.status();
+            let headers = response.headers().clone();
             let error_text = response.text().await
-                .unwrap_or_else(|_| format!("HTTP error {}", status));
-            Err(PayupError::Http(format!("HTTP {}: {}", status, error_text)))
+                .unwrap_or_else(|_| format!("HTTP error {}", status.as_u16()));
+            Err(error_from_response("HTTP", status, &headers, &error_text))
         }
     }
 
@@ -195,11 +410,10 @@ impl HttpClient {
         T: for<'de> Deserialize<'de>,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.blocking_client
+        let response = SHARED_BLOCKING_CLIENT
             .get(&url)
             .headers(self.build_headers())
-            .send()
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)?;
         
         self.handle_blocking_response(response)
     }
@@ -210,12 +424,11 @@ impl HttpClient {
         B: Serialize,
     {
         let url = format!("{}{}", self.base_url, endpoint);
-        let response = self.blocking_client
+        let response = SHARED_BLOCKING_CLIENT
             .post(&url)
             .headers(self.build_headers())
             .json(body)
-            .send()
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)?;
         
         self.handle_blocking_response(response)
     }
@@ -228,12 +441,11 @@ impl HttpClient {
         let mut headers = self.build_headers();
         headers.insert(CONTENT_TYPE, HeaderValue::from_static("application/x-www-form-urlencoded"));
         
-        let response = self.blocking_client
+        let response = SHARED_BLOCKING_CLIENT
             .post(&url)
             .headers(headers)
             .form(params)
-            .send()
-            .map_err(|e| PayupError::Http(e.to_string()))?;
+            .send_via(&*self.transport)?;
         
         self.handle_blocking_response(response)
     }
@@ -246,10 +458,11 @@ impl HttpClient {
             response.json::<T>()
                 .map_err(|e| PayupError::Deserialization(e.to_string()))
         } else {
-            let status = response.status().as_u16();
+            let status = response.status();
+            let headers = response.headers().clone();
             let error_text = response.text()
-                .unwrap_or_else(|_| format!("HTTP error {}", status));
-            Err(PayupError::Http(format!("HTTP {}: {}", status, error_text)))
+                .unwrap_or_else(|_| format!("HTTP error {}", status.as_u16()));
+            Err(error_from_response("HTTP", status, &headers, &error_text))
         }
     }
 }
@@ -313,8 +526,7 @@ impl RequestBuilder {
     }
 
     pub fn json<T: Serialize>(mut self, body: T) -> Result<Self, PayupError> {
-        self.body = Some(serde_json::to_value(body)
-            .map_err(|e| PayupError::Serialization(e.to_string()))?);
+        self.body = Some(serde_json::to_value(body)?);
         Ok(self)
     }
 
@@ -391,7 +603,7 @@ impl RequestBuilder {
                     client.post_blocking(&endpoint, &serde_json::Value::Null)
                 }
             },
-            _ => Err(PayupError::Http("Blocking mode only supports GET and POST".to_string())),
+            _ => Err(PayupError::UnsupportedOperation("Blocking mode only supports GET and POST".to_string())),
         }
     }
 
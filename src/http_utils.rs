@@ -1,7 +1,8 @@
 use reqwest::blocking::{RequestBuilder, Response};
-use reqwest::{RequestBuilder as AsyncRequestBuilder, Response as AsyncResponse};
+use reqwest::header::{HeaderMap, RETRY_AFTER};
+use reqwest::{RequestBuilder as AsyncRequestBuilder, Response as AsyncResponse, StatusCode};
 use serde::Deserialize;
-use crate::error::{PayupError, Result};
+use crate::error::{PayPalErrorDetail, PayupError, Result, SquareErrorDetails, StripeErrorDetails};
 
 #[derive(Clone)]
 pub struct HttpRequestBuilder {
@@ -35,14 +36,10 @@ impl HttpRequestBuilder {
             return Ok(response);
         }
 
-        let status = response.status().to_string();
+        let status = response.status();
+        let headers = response.headers().clone();
         let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
-        
-        Err(PayupError::ApiError {
-            code: status,
-            message: error_text,
-            provider: self.provider_name.clone(),
-        })
+        Err(error_from_response(&self.provider_name, status, &headers, &error_text))
     }
 
     async fn handle_async_response_status(&self, response: AsyncResponse) -> Result<AsyncResponse> {
@@ -50,14 +47,10 @@ impl HttpRequestBuilder {
             return Ok(response);
         }
 
-        let status = response.status().to_string();
+        let status = response.status();
+        let headers = response.headers().clone();
         let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        
-        Err(PayupError::ApiError {
-            code: status,
-            message: error_text,
-            provider: self.provider_name.clone(),
-        })
+        Err(error_from_response(&self.provider_name, status, &headers, &error_text))
     }
 
     pub fn add_common_headers(mut builder: RequestBuilder, auth_header: &str) -> RequestBuilder {
@@ -103,6 +96,138 @@ pub fn build_url(base_url: &str, endpoint: &str) -> String {
     format!("{}{}", base_url, endpoint)
 }
 
+/// Seconds to wait according to a `Retry-After` header, given either as
+/// delta-seconds or as an HTTP date. A date in the past yields `0`.
+pub fn parse_retry_after(headers: &HeaderMap) -> Option<u64> {
+    let value = headers.get(RETRY_AFTER)?.to_str().ok()?.trim();
+    if let Ok(seconds) = value.parse::<u64>() {
+        return Some(seconds);
+    }
+
+    let date = chrono::DateTime::parse_from_rfc2822(value).ok()?;
+    let seconds = (date.with_timezone(&chrono::Utc) - chrono::Utc::now()).num_seconds();
+    Some(seconds.max(0) as u64)
+}
+
+/// Builds the error for a non-2xx response from `provider`.
+///
+/// A 429 is always a [`PayupError::RateLimitError`] carrying its
+/// `Retry-After`. Otherwise Stripe, PayPal and Square error bodies become
+/// their provider-specific variants, with the provider's request ID,
+/// Braintree's XML errors become [`PayupError::ApiError`]s, and
+/// anything unrecognised falls back to [`PayupError::ServerError`] for a 5xx
+/// or [`PayupError::ApiError`].
+pub fn error_from_response(provider: &str, status: StatusCode, headers: &HeaderMap, body: &str) -> PayupError {
+    if status == StatusCode::TOO_MANY_REQUESTS {
+        return PayupError::RateLimitError {
+            retry_after: parse_retry_after(headers),
+        };
+    }
+
+    let parsed = match provider.to_ascii_lowercase().as_str() {
+        "stripe" => stripe_error(status, headers, body),
+        "paypal" => paypal_error(status, headers, body),
+        "square" => square_error(status, body),
+        #[cfg(feature = "braintree")]
+        "braintree" => crate::braintree::client::error_from_body(status, body),
+        _ => None,
+    };
+    parsed.unwrap_or_else(|| {
+        if status.is_server_error() {
+            PayupError::ServerError(status.as_u16())
+        } else {
+            PayupError::ApiError {
+                code: status.to_string(),
+                message: body.to_string(),
+                provider: provider.to_string(),
+            }
+        }
+    })
+}
+
+fn header_str<'a>(headers: &'a HeaderMap, name: &str) -> Option<&'a str> {
+    headers.get(name).and_then(|value| value.to_str().ok())
+}
+
+#[derive(Deserialize)]
+struct StripeErrorBody {
+    error: StripeErrorObject,
+}
+
+#[derive(Deserialize)]
+struct StripeErrorObject {
+    #[serde(rename = "type")]
+    error_type: String,
+    code: Option<String>,
+    decline_code: Option<String>,
+    message: Option<String>,
+    param: Option<String>,
+}
+
+/// `{"error": {"type": ..., "code": ..., "decline_code": ...}}`, with the
+/// request ID from the `Request-Id` header.
+fn stripe_error(status: StatusCode, headers: &HeaderMap, body: &str) -> Option<PayupError> {
+    let error = serde_json::from_str::<StripeErrorBody>(body).ok()?.error;
+    Some(PayupError::StripeError(Box::new(StripeErrorDetails {
+        status: status.as_u16(),
+        message: error.message.unwrap_or_else(|| error.error_type.clone()),
+        error_type: error.error_type,
+        code: error.code,
+        decline_code: error.decline_code,
+        param: error.param,
+        request_id: header_str(headers, "request-id").map(String::from),
+    })))
+}
+
+#[derive(Deserialize)]
+struct PayPalErrorBody {
+    name: String,
+    message: Option<String>,
+    debug_id: Option<String>,
+    details: Option<Vec<PayPalErrorDetail>>,
+}
+
+/// `{"name": ..., "message": ..., "debug_id": ..., "details": [...]}`,
+/// falling back to the `PayPal-Debug-Id` header for the debug ID.
+fn paypal_error(status: StatusCode, headers: &HeaderMap, body: &str) -> Option<PayupError> {
+    let error = serde_json::from_str::<PayPalErrorBody>(body).ok()?;
+    Some(PayupError::PayPalError {
+        status: status.as_u16(),
+        message: error.message.unwrap_or_else(|| error.name.clone()),
+        name: error.name,
+        debug_id: error
+            .debug_id
+            .or_else(|| header_str(headers, "paypal-debug-id").map(String::from)),
+        details: error.details,
+    })
+}
+
+#[derive(Deserialize)]
+struct SquareErrorBody {
+    errors: Vec<SquareErrorObject>,
+}
+
+#[derive(Deserialize)]
+struct SquareErrorObject {
+    category: String,
+    code: String,
+    detail: Option<String>,
+    field: Option<String>,
+}
+
+/// `{"errors": [{"category": ..., "code": ..., "detail": ...}]}`, reporting
+/// the first error.
+fn square_error(status: StatusCode, body: &str) -> Option<PayupError> {
+    let error = serde_json::from_str::<SquareErrorBody>(body).ok()?.errors.into_iter().next()?;
+    Some(PayupError::SquareError(Box::new(SquareErrorDetails {
+        status: status.as_u16(),
+        category: error.category,
+        code: error.code,
+        detail: error.detail,
+        field: error.field,
+    })))
+}
+
 pub trait ResponseHandler {
     fn extract_error_details(&self) -> (String, String);
 }
@@ -117,6 +242,75 @@ mod tests {
         assert_eq!(result, "https://api.example.com/v1/users");
     }
 
+    #[test]
+    fn test_parse_retry_after() {
+        let mut headers = HeaderMap::new();
+        assert_eq!(parse_retry_after(&headers), None);
+
+        headers.insert(RETRY_AFTER, "120".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(120));
+
+        headers.insert(RETRY_AFTER, "Wed, 21 Oct 2015 07:28:00 GMT".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), Some(0));
+
+        let soon = (chrono::Utc::now() + chrono::Duration::seconds(90)).to_rfc2822();
+        headers.insert(RETRY_AFTER, soon.parse().unwrap());
+        let seconds = parse_retry_after(&headers).unwrap();
+        assert!((88..=90).contains(&seconds), "got {}", seconds);
+
+        headers.insert(RETRY_AFTER, "soon".parse().unwrap());
+        assert_eq!(parse_retry_after(&headers), None);
+    }
+
+    #[test]
+    fn test_error_from_response_parses_provider_bodies() {
+        use crate::error::ErrorCategory;
+
+        let mut headers = HeaderMap::new();
+        headers.insert("request-id", "req_123".parse().unwrap());
+        let stripe = error_from_response(
+            "stripe",
+            StatusCode::PAYMENT_REQUIRED,
+            &headers,
+            r#"{"error":{"type":"card_error","code":"card_declined","decline_code":"insufficient_funds","message":"Your card has insufficient funds."}}"#,
+        );
+        assert_eq!(stripe.category(), ErrorCategory::InsufficientFunds);
+        assert_eq!(stripe.decline_code(), Some("insufficient_funds"));
+        assert_eq!(stripe.request_id(), Some("req_123"));
+        assert!(!stripe.is_retryable());
+
+        let paypal = error_from_response(
+            "PayPal",
+            StatusCode::UNPROCESSABLE_ENTITY,
+            &HeaderMap::new(),
+            r#"{"name":"UNPROCESSABLE_ENTITY","message":"The requested action could not be performed.","debug_id":"f1a2b3",
+                "details":[{"issue":"INSTRUMENT_DECLINED","description":"The instrument presented was declined."}]}"#,
+        );
+        assert_eq!(paypal.category(), ErrorCategory::CardDeclined);
+        assert_eq!(paypal.decline_code(), Some("INSTRUMENT_DECLINED"));
+        assert_eq!(paypal.request_id(), Some("f1a2b3"));
+
+        let square = error_from_response(
+            "square",
+            StatusCode::UNAUTHORIZED,
+            &HeaderMap::new(),
+            r#"{"errors":[{"category":"AUTHENTICATION_ERROR","code":"UNAUTHORIZED","detail":"This request could not be authorized."}]}"#,
+        );
+        assert_eq!(square.category(), ErrorCategory::Authentication);
+        assert_eq!(square.decline_code(), None);
+
+        let outage = error_from_response("stripe", StatusCode::BAD_GATEWAY, &HeaderMap::new(), "<html>");
+        assert!(matches!(outage, PayupError::ServerError(502)));
+        assert_eq!(outage.category(), ErrorCategory::ProviderOutage);
+        assert!(outage.is_retryable());
+
+        let mut headers = HeaderMap::new();
+        headers.insert(RETRY_AFTER, "2".parse().unwrap());
+        let limited = error_from_response("square", StatusCode::TOO_MANY_REQUESTS, &headers, "{}");
+        assert!(matches!(limited, PayupError::RateLimitError { retry_after: Some(2) }));
+        assert!(limited.is_retryable());
+    }
+
     #[test]
     fn test_method_names() {
         assert_eq!(Method::Get.method_name(), "GET");
@@ -103,6 +103,7 @@
 //!     * Ability to cancel a subscription
 
 /// Stripe API Implementation
+#[cfg(feature = "stripe")]
 pub mod stripe;
 
 /// Error handling module
@@ -116,12 +117,19 @@ pub mod payment_provider;
 pub mod provider_factory;
 
 /// PayPal API Implementation
+#[cfg(feature = "paypal")]
 pub mod paypal;
 
 /// Square API Implementation
+#[cfg(feature = "square")]
 pub mod square;
 
+/// Braintree API Implementation
+#[cfg(feature = "braintree")]
+pub mod braintree;
+
 /// Extended Stripe API implementations
+#[cfg(feature = "stripe")]
 pub mod stripe_ext;
 
 /// Cryptocurrency payment support
@@ -146,6 +154,9 @@ pub mod config;
 /// Builder patterns and utilities
 pub mod builders;
 
+/// Auto-paginating iterators and streams over list endpoints
+pub mod pagination;
+
 /// Async/sync code generation macros
 pub mod async_sync_macro;
 
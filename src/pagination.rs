@@ -0,0 +1,185 @@
+//! Walks cursor-paginated list endpoints one page at a time.
+//!
+//! Each provider knows how to fetch a page and where its next cursor lives
+//! (Stripe's `has_more` plus the last object's ID, Square's `cursor`, PayPal's
+//! `next` link); [`Paginator`] and [`PageStream`] turn that into a flat
+//! sequence of items, requesting the next page only once the current one has
+//! been consumed.
+
+use std::collections::VecDeque;
+use std::future::Future;
+use std::pin::Pin;
+
+use futures::stream::{self, Stream, StreamExt};
+
+use crate::error::Result;
+
+/// One page of a list endpoint.
+#[derive(Debug, Clone)]
+pub struct Page<T> {
+    pub items: Vec<T>,
+    /// Cursor for the following page, `None` on the last page
+    pub next_cursor: Option<String>,
+}
+
+type FetchPage<T> = Box<dyn FnMut(Option<String>) -> Result<Page<T>> + Send>;
+
+/// Blocking iterator over every item of a list endpoint.
+///
+/// A failed page request is yielded as an `Err`, after which the iterator ends.
+pub struct Paginator<T> {
+    fetch: FetchPage<T>,
+    buffer: VecDeque<T>,
+    cursor: Option<String>,
+    done: bool,
+    remaining: Option<usize>,
+}
+
+impl<T> Paginator<T> {
+    /// Starts at `cursor` (the first page when `None`) and yields at most
+    /// `max_items` items. `fetch` is called with the cursor of each page.
+    pub fn new<F>(cursor: Option<String>, max_items: Option<usize>, fetch: F) -> Self
+    where
+        F: FnMut(Option<String>) -> Result<Page<T>> + Send + 'static,
+    {
+        Self {
+            fetch: Box::new(fetch),
+            buffer: VecDeque::new(),
+            cursor,
+            done: false,
+            remaining: max_items,
+        }
+    }
+}
+
+impl<T> Iterator for Paginator<T> {
+    type Item = Result<T>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.remaining == Some(0) {
+            return None;
+        }
+
+        loop {
+            if let Some(item) = self.buffer.pop_front() {
+                if let Some(remaining) = self.remaining.as_mut() {
+                    *remaining -= 1;
+                }
+                return Some(Ok(item));
+            }
+            if self.done {
+                return None;
+            }
+
+            match (self.fetch)(self.cursor.take()) {
+                Ok(page) => {
+                    self.done = page.next_cursor.is_none();
+                    self.cursor = page.next_cursor;
+                    self.buffer.extend(page.items);
+                }
+                Err(e) => {
+                    self.done = true;
+                    return Some(Err(e));
+                }
+            }
+        }
+    }
+}
+
+/// Async stream over every item of a list endpoint; see [`page_stream`].
+pub type PageStream<T> = Pin<Box<dyn Stream<Item = Result<T>> + Send>>;
+
+/// Async counterpart of [`Paginator::new`].
+///
+/// A failed page request is yielded as an `Err`, after which the stream ends.
+pub fn page_stream<T, F, Fut>(cursor: Option<String>, max_items: Option<usize>, fetch: F) -> PageStream<T>
+where
+    T: Send + 'static,
+    F: FnMut(Option<String>) -> Fut + Send + 'static,
+    Fut: Future<Output = Result<Page<T>>> + Send + 'static,
+{
+    // The state holds the cursor of the next page to fetch, or `None` once
+    // the last page (or an error) has been seen
+    let pages = stream::unfold((fetch, Some(cursor)), |(mut fetch, next)| async move {
+        let cursor = next?;
+        let (items, next) = match fetch(cursor).await {
+            Ok(page) => {
+                let next = page.next_cursor.map(Some);
+                (page.items.into_iter().map(Ok).collect::<Vec<_>>(), next)
+            }
+            Err(e) => (vec![Err(e)], None),
+        };
+        Some((stream::iter(items), (fetch, next)))
+    });
+
+    let items = pages.flatten();
+    match max_items {
+        Some(max_items) => Box::pin(items.take(max_items)),
+        None => Box::pin(items),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::error::PayupError;
+    use futures::TryStreamExt;
+    use std::sync::{Arc, Mutex};
+
+    /// Three pages of numbers, `cursor` being the index of the page.
+    fn numbers(cursor: Option<String>) -> Result<Page<u32>> {
+        let index: u32 = cursor.map(|c| c.parse().unwrap()).unwrap_or(0);
+        Ok(Page {
+            items: (index * 10..index * 10 + 3).collect(),
+            next_cursor: (index < 2).then(|| (index + 1).to_string()),
+        })
+    }
+
+    #[test]
+    fn test_paginator_walks_every_page() {
+        let items: Vec<u32> = Paginator::new(None, None, numbers).collect::<Result<_>>().unwrap();
+        assert_eq!(items, vec![0, 1, 2, 10, 11, 12, 20, 21, 22]);
+    }
+
+    #[test]
+    fn test_paginator_stops_fetching_at_max_items() {
+        let fetched = Arc::new(Mutex::new(Vec::new()));
+        let log = fetched.clone();
+        let items: Vec<u32> = Paginator::new(None, Some(4), move |cursor: Option<String>| {
+            log.lock().unwrap().push(cursor.clone());
+            numbers(cursor)
+        })
+        .collect::<Result<_>>()
+        .unwrap();
+
+        assert_eq!(items, vec![0, 1, 2, 10]);
+        assert_eq!(*fetched.lock().unwrap(), vec![None, Some("1".to_string())]);
+    }
+
+    #[test]
+    fn test_paginator_ends_after_an_error() {
+        let mut paginator = Paginator::new(None, None, |cursor: Option<String>| match cursor {
+            None => Ok(Page { items: vec![1], next_cursor: Some("next".to_string()) }),
+            Some(_) => Err(PayupError::ServerError(503)),
+        });
+
+        assert_eq!(paginator.next().unwrap().unwrap(), 1);
+        assert!(matches!(paginator.next(), Some(Err(PayupError::ServerError(503)))));
+        assert!(paginator.next().is_none());
+    }
+
+    #[tokio::test]
+    async fn test_page_stream_walks_pages_up_to_max_items() {
+        let all: Vec<u32> = page_stream(None, None, |cursor| async move { numbers(cursor) })
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(all.len(), 9);
+
+        let capped: Vec<u32> = page_stream(Some("1".to_string()), Some(5), |cursor| async move { numbers(cursor) })
+            .try_collect()
+            .await
+            .unwrap();
+        assert_eq!(capped, vec![10, 11, 12, 20, 21]);
+    }
+}
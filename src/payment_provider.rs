@@ -1,6 +1,6 @@
 use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
-use crate::error::Result;
+use crate::error::{PayupError, Result};
 
 // Common payment types
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,6 +126,53 @@ pub enum SubscriptionStatus {
     Unpaid,
 }
 
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dispute {
+    pub id: String,
+    pub charge_id: Option<String>,
+    pub amount: Money,
+    pub status: DisputeStatus,
+    pub reason: Option<String>, // Provider reason code, e.g. "fraudulent" or "MERCHANDISE_OR_SERVICE_NOT_RECEIVED"
+    pub evidence_due_by: Option<i64>,
+    pub created_at: Option<i64>,
+}
+
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+pub enum DisputeStatus {
+    NeedsResponse,
+    UnderReview,
+    Won,
+    Lost,
+    Accepted,
+    Closed, // Inquiry or warning closed without becoming a chargeback
+    Other(String), // Provider status with no unified equivalent
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DisputeEvidence {
+    pub explanation: Option<String>,
+    pub shipping_carrier: Option<String>,
+    pub tracking_number: Option<String>,
+    pub files: Vec<DisputeEvidenceFile>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeEvidenceFile {
+    pub kind: DisputeEvidenceKind,
+    pub filename: String,
+    pub content: Vec<u8>,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Serialize, Deserialize)]
+pub enum DisputeEvidenceKind {
+    Receipt,
+    ProofOfDelivery,
+    CustomerCommunication,
+    RefundPolicy,
+    ServiceDocumentation,
+    Other,
+}
+
 // Unified Payment Provider Trait
 #[async_trait]
 pub trait PaymentProvider: Send + Sync {
@@ -167,6 +214,65 @@ pub trait PaymentProvider: Send + Sync {
     
     // Webhook operations
     async fn verify_webhook(&self, payload: &[u8], signature: &str, secret: &str) -> Result<bool>;
+    
+    // Idempotent variants
+    //
+    // Repeating a call with the same `idempotency_key` returns the original
+    // result instead of performing the operation again, so a request that
+    // timed out can be retried without double-charging. The plain methods
+    // above use a fresh key per call. Providers without support for a given
+    // operation return `UnsupportedOperation`.
+    async fn create_customer_idempotent(&self, _customer: &Customer, _idempotency_key: &str) -> Result<Customer> {
+        Err(unsupported_idempotent(self.name(), "create_customer"))
+    }
+    
+    async fn create_charge_idempotent(&self, _charge: &Charge, _idempotency_key: &str) -> Result<Charge> {
+        Err(unsupported_idempotent(self.name(), "create_charge"))
+    }
+    
+    async fn capture_charge_idempotent(&self, _charge_id: &str, _amount: Option<Money>, _idempotency_key: &str) -> Result<Charge> {
+        Err(unsupported_idempotent(self.name(), "capture_charge"))
+    }
+    
+    async fn create_refund_idempotent(&self, _refund: &Refund, _idempotency_key: &str) -> Result<Refund> {
+        Err(unsupported_idempotent(self.name(), "create_refund"))
+    }
+    
+    async fn create_subscription_idempotent(&self, _subscription: &Subscription, _idempotency_key: &str) -> Result<Subscription> {
+        Err(unsupported_idempotent(self.name(), "create_subscription"))
+    }
+    
+    // Dispute operations
+    //
+    // Accepting a dispute concedes it to the customer. Submitting evidence
+    // uploads any files and submits the response in one step, so it cannot
+    // be amended afterwards.
+    async fn list_disputes(&self, _limit: Option<u32>) -> Result<Vec<Dispute>> {
+        Err(unsupported(self.name(), "list_disputes"))
+    }
+    
+    async fn get_dispute(&self, _dispute_id: &str) -> Result<Dispute> {
+        Err(unsupported(self.name(), "get_dispute"))
+    }
+    
+    async fn accept_dispute(&self, _dispute_id: &str) -> Result<Dispute> {
+        Err(unsupported(self.name(), "accept_dispute"))
+    }
+    
+    async fn submit_dispute_evidence(&self, _dispute_id: &str, _evidence: &DisputeEvidence) -> Result<Dispute> {
+        Err(unsupported(self.name(), "submit_dispute_evidence"))
+    }
+}
+
+fn unsupported(provider: &str, operation: &str) -> PayupError {
+    PayupError::UnsupportedOperation(format!("{} does not support {}", provider, operation))
+}
+
+fn unsupported_idempotent(provider: &str, operation: &str) -> PayupError {
+    PayupError::UnsupportedOperation(format!(
+        "{} does not support idempotency keys for {}",
+        provider, operation
+    ))
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -1,7 +1,7 @@
 use serde::{Deserialize, Serialize};
 use base64::{Engine as _, engine::general_purpose};
 use crate::error::{PayupError, Result};
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, default_transport, SendVia, SendViaBlocking, Transport};
 use super::PayPalEnvironment;
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -35,6 +35,16 @@ impl PayPalAuth {
         client_id: String,
         client_secret: String,
         environment: PayPalEnvironment,
+    ) -> Result<Self> {
+        Self::get_access_token_with_transport(client_id, client_secret, environment, &*default_transport())
+    }
+
+    /// Fetches a token through `transport` rather than the shared one.
+    pub fn get_access_token_with_transport(
+        client_id: String,
+        client_secret: String,
+        environment: PayPalEnvironment,
+        transport: &dyn Transport,
     ) -> Result<Self> {
         let client = get_shared_blocking_client();
         let url = format!("{}/v1/oauth2/token", environment.base_url());
@@ -48,7 +58,7 @@ impl PayPalAuth {
             .header("Authorization", format!("Basic {}", encoded))
             .header("Content-Type", "application/x-www-form-urlencoded")
             .body("grant_type=client_credentials")
-            .send()
+            .send_via(transport)
             .map_err(PayupError::from)?;
 
         if !response.status().is_success() {
@@ -69,6 +79,15 @@ impl PayPalAuth {
         client_id: String,
         client_secret: String,
         environment: PayPalEnvironment,
+    ) -> Result<Self> {
+        Self::async_get_access_token_with_transport(client_id, client_secret, environment, &*default_transport()).await
+    }
+
+    pub async fn async_get_access_token_with_transport(
+        client_id: String,
+        client_secret: String,
+        environment: PayPalEnvironment,
+        transport: &dyn Transport,
     ) -> Result<Self> {
         let client = get_shared_client();
         let url = format!("{}/v1/oauth2/token", environment.base_url());
@@ -82,7 +101,7 @@ impl PayPalAuth {
             .header("Authorization", format!("Basic {}", encoded))
             .header("Content-Type", "application/x-www-form-urlencoded")
             .body("grant_type=client_credentials")
-            .send()
+            .send_via(transport)
             .await
             .map_err(PayupError::from)?;
 
@@ -1,32 +1,41 @@
-use reqwest::blocking::Client as HttpClient;
-use reqwest::Client as AsyncHttpClient;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
 use crate::error::{PayupError, Result};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, default_transport, SendVia, SendViaBlocking, Transport};
 use crate::http_utils::{HttpRequestBuilder, build_url};
 use crate::rate_limiter::get_rate_limiter;
-use super::{PayPalConfig, PayPalAuth};
+use crate::config::http::HEADER_PAYPAL_REQUEST_ID;
+use crate::builders::PageRequest;
+use crate::pagination::{page_stream, Page, PageStream, Paginator};
+use super::{PayPalConfig, PayPalAuth, PayPalLink};
 
+#[derive(Clone)]
 pub struct PayPalClient {
     pub config: PayPalConfig,
     pub auth: Option<PayPalAuth>,
-    http_client: HttpClient,
-    async_http_client: AsyncHttpClient,
+    transport: Arc<dyn Transport>,
     request_builder: HttpRequestBuilder,
 }
 
 impl PayPalClient {
     pub fn new(config: PayPalConfig) -> Result<Self> {
-        let auth = PayPalAuth::new(
+        Self::with_transport(config, default_transport())
+    }
+
+    /// Like [`PayPalClient::new`], but every request, including the token
+    /// fetch, goes through `transport`.
+    pub fn with_transport(config: PayPalConfig, transport: Arc<dyn Transport>) -> Result<Self> {
+        let auth = PayPalAuth::get_access_token_with_transport(
             config.client_id.clone(),
             config.client_secret.clone(),
             config.environment.clone(),
+            &*transport,
         )?;
 
         Ok(Self {
             config,
             auth: Some(auth),
-            http_client: HttpClient::new(),
-            async_http_client: AsyncHttpClient::new(),
+            transport,
             request_builder: HttpRequestBuilder::new("PayPal"),
         })
     }
@@ -43,20 +52,22 @@ impl PayPalClient {
     }
 
     fn refresh_auth(&mut self) -> Result<()> {
-        self.auth = Some(PayPalAuth::new(
+        self.auth = Some(PayPalAuth::get_access_token_with_transport(
             self.config.client_id.clone(),
             self.config.client_secret.clone(),
             self.config.environment.clone(),
+            &*self.transport,
         )?);
         Ok(())
     }
 
     pub async fn async_ensure_auth(&mut self) -> Result<()> {
         if self.needs_auth_refresh() {
-            self.auth = Some(PayPalAuth::async_get_access_token(
+            self.auth = Some(PayPalAuth::async_get_access_token_with_transport(
                 self.config.client_id.clone(),
                 self.config.client_secret.clone(),
                 self.config.environment.clone(),
+                &*self.transport,
             ).await?);
         }
         Ok(())
@@ -77,11 +88,11 @@ impl PayPalClient {
         let url = build_url(self.config.environment.base_url(), endpoint);
         let auth_header = self.get_auth_header()?;
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .get(&url)
             .header("Authorization", auth_header)
             .header("Content-Type", "application/json")
-            .send()
+            .send_via(&*self.transport)
             .map_err(PayupError::from)?;
 
         self.request_builder.process_response(response)
@@ -96,17 +107,19 @@ impl PayPalClient {
         let auth_header = self.get_auth_header()?;
         let rate_limiter = get_rate_limiter();
         let request_builder = self.request_builder.clone();
+        let transport = self.transport.clone();
         
         rate_limiter.execute_with_retry_async("paypal", move || {
             let url = url.clone();
             let auth_header = auth_header.clone();
             let request_builder = request_builder.clone();
+            let transport = transport.clone();
             async move {
-                let response = AsyncHttpClient::new()
+                let response = get_shared_client()
                     .get(&url)
                     .header("Authorization", auth_header)
                     .header("Content-Type", "application/json")
-                    .send()
+                    .send_via(&*transport)
                     .await
                     .map_err(PayupError::from)?;
 
@@ -115,7 +128,70 @@ impl PayPalClient {
         }).await
     }
 
+    /// Fetches one page of a list endpoint whose items are under `items_key`.
+    /// The page's cursor is the endpoint of its `next` link.
+    pub fn get_page<T>(&mut self, endpoint: &str, items_key: &str) -> Result<Page<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let body: serde_json::Value = self.get(endpoint)?;
+        page_from_body(body, items_key)
+    }
+
+    pub async fn async_get_page<T>(&mut self, endpoint: &str, items_key: &str) -> Result<Page<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let body: serde_json::Value = self.async_get(endpoint).await?;
+        page_from_body(body, items_key)
+    }
+
+    /// Iterates over every item of a list endpoint, following its `next`
+    /// links. `page.limit` becomes `page_size`; `page.starting_after` may be
+    /// the `href` of a `next` link to resume from.
+    pub fn list_iter<T>(&self, endpoint: &str, items_key: &'static str, page: PageRequest) -> Paginator<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let mut client = self.clone();
+        let first = with_page_size(endpoint, page.page_size());
+        let start = page.starting_after.as_deref().map(link_endpoint);
+        Paginator::new(start, page.max_items, move |cursor| {
+            client.get_page(cursor.as_deref().unwrap_or(&first), items_key)
+        })
+    }
+
+    /// Async counterpart of [`PayPalClient::list_iter`].
+    pub fn list_stream<T>(&self, endpoint: &str, items_key: &'static str, page: PageRequest) -> PageStream<T>
+    where
+        T: for<'de> Deserialize<'de> + Send + 'static,
+    {
+        let client = self.clone();
+        let first = with_page_size(endpoint, page.page_size());
+        let start = page.starting_after.as_deref().map(link_endpoint);
+        page_stream(start, page.max_items, move |cursor| {
+            let mut client = client.clone();
+            let endpoint = cursor.unwrap_or_else(|| first.clone());
+            async move { client.async_get_page(&endpoint, items_key).await }
+        })
+    }
+
     pub fn post<T, B>(&mut self, endpoint: &str, body: &B) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        self.post_with_request_id(endpoint, body, None)
+    }
+
+    /// POSTs with a `PayPal-Request-Id` header so PayPal can deduplicate
+    /// retries. A random id is used when `request_id` is `None`.
+    pub fn post_with_request_id<T, B>(
+        &mut self,
+        endpoint: &str,
+        body: &B,
+        request_id: Option<&str>,
+    ) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
         B: Serialize,
@@ -123,19 +199,36 @@ impl PayPalClient {
         self.ensure_auth()?;
         let url = build_url(self.config.environment.base_url(), endpoint);
         let auth_header = self.get_auth_header()?;
+        let request_id = resolve_request_id(request_id);
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .post(&url)
             .header("Authorization", auth_header)
             .header("Content-Type", "application/json")
+            .header(HEADER_PAYPAL_REQUEST_ID, request_id)
             .json(body)
-            .send()
+            .send_via(&*self.transport)
             .map_err(PayupError::from)?;
 
         self.request_builder.process_response(response)
     }
 
     pub async fn async_post<T, B>(&mut self, endpoint: &str, body: &B) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        self.async_post_with_request_id(endpoint, body, None).await
+    }
+
+    /// Async counterpart of [`PayPalClient::post_with_request_id`]. The id is
+    /// fixed before the first attempt, so rate-limiter retries reuse it.
+    pub async fn async_post_with_request_id<T, B>(
+        &mut self,
+        endpoint: &str,
+        body: &B,
+        request_id: Option<&str>,
+    ) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
         B: Serialize,
@@ -145,20 +238,25 @@ impl PayPalClient {
         let auth_header = self.get_auth_header()?;
         let rate_limiter = get_rate_limiter();
         let request_builder = self.request_builder.clone();
+        let transport = self.transport.clone();
         let body_json = serde_json::to_value(body).map_err(PayupError::from)?;
+        let request_id = resolve_request_id(request_id);
         
         rate_limiter.execute_with_retry_async("paypal", move || {
             let url = url.clone();
             let auth_header = auth_header.clone();
             let request_builder = request_builder.clone();
+            let transport = transport.clone();
             let body_json = body_json.clone();
+            let request_id = request_id.clone();
             async move {
-                let response = AsyncHttpClient::new()
+                let response = get_shared_client()
                     .post(&url)
                     .header("Authorization", auth_header)
                     .header("Content-Type", "application/json")
+                    .header(HEADER_PAYPAL_REQUEST_ID, request_id)
                     .json(&body_json)
-                    .send()
+                    .send_via(&*transport)
                     .await
                     .map_err(PayupError::from)?;
 
@@ -167,6 +265,80 @@ impl PayPalClient {
         }).await
     }
 
+    /// POSTs `multipart/form-data` with `input` as a JSON part followed by
+    /// one part per `(filename, contents)` file, as the dispute evidence
+    /// endpoints expect.
+    pub fn post_multipart<T, B>(&mut self, endpoint: &str, input: &B, files: &[(String, Vec<u8>)]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        self.ensure_auth()?;
+        let url = build_url(self.config.environment.base_url(), endpoint);
+        let auth_header = self.get_auth_header()?;
+        let input_json = serde_json::to_string(input).map_err(PayupError::from)?;
+
+        let mut form = reqwest::blocking::multipart::Form::new().part(
+            "input",
+            reqwest::blocking::multipart::Part::text(input_json)
+                .mime_str("application/json")
+                .map_err(PayupError::from)?,
+        );
+        for (index, (filename, contents)) in files.iter().enumerate() {
+            form = form.part(
+                format!("file{}", index + 1),
+                reqwest::blocking::multipart::Part::bytes(contents.clone()).file_name(filename.clone()),
+            );
+        }
+
+        let response = get_shared_blocking_client()
+            .post(&url)
+            .header("Authorization", auth_header)
+            .header(HEADER_PAYPAL_REQUEST_ID, resolve_request_id(None))
+            .multipart(form)
+            .send_via(&*self.transport)
+            .map_err(PayupError::from)?;
+
+        self.request_builder.process_response(response)
+    }
+
+    /// Async counterpart of [`PayPalClient::post_multipart`]. A multipart
+    /// body can only be sent once, so it bypasses rate-limiter retries.
+    pub async fn async_post_multipart<T, B>(&mut self, endpoint: &str, input: &B, files: &[(String, Vec<u8>)]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        self.async_ensure_auth().await?;
+        let url = build_url(self.config.environment.base_url(), endpoint);
+        let auth_header = self.get_auth_header()?;
+        let input_json = serde_json::to_string(input).map_err(PayupError::from)?;
+
+        let mut form = reqwest::multipart::Form::new().part(
+            "input",
+            reqwest::multipart::Part::text(input_json)
+                .mime_str("application/json")
+                .map_err(PayupError::from)?,
+        );
+        for (index, (filename, contents)) in files.iter().enumerate() {
+            form = form.part(
+                format!("file{}", index + 1),
+                reqwest::multipart::Part::bytes(contents.clone()).file_name(filename.clone()),
+            );
+        }
+
+        let response = get_shared_client()
+            .post(&url)
+            .header("Authorization", auth_header)
+            .header(HEADER_PAYPAL_REQUEST_ID, resolve_request_id(None))
+            .multipart(form)
+            .send_via(&*self.transport)
+            .await
+            .map_err(PayupError::from)?;
+
+        self.request_builder.process_async_response(response).await
+    }
+
     pub fn patch<T, B>(&mut self, endpoint: &str, body: &B) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
@@ -176,12 +348,12 @@ impl PayPalClient {
         let url = build_url(self.config.environment.base_url(), endpoint);
         let auth_header = self.get_auth_header()?;
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .patch(&url)
             .header("Authorization", auth_header)
             .header("Content-Type", "application/json")
             .json(body)
-            .send()
+            .send_via(&*self.transport)
             .map_err(PayupError::from)?;
 
         self.request_builder.process_response(response)
@@ -192,12 +364,59 @@ impl PayPalClient {
         let url = build_url(self.config.environment.base_url(), endpoint);
         let auth_header = self.get_auth_header()?;
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .delete(&url)
             .header("Authorization", auth_header)
-            .send()
+            .send_via(&*self.transport)
             .map_err(PayupError::from)?;
 
         Ok(response.status().is_success())
     }
-}
\ No newline at end of file
+}
+
+fn resolve_request_id(request_id: Option<&str>) -> String {
+    request_id
+        .map(str::to_string)
+        .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+}
+
+fn page_from_body<T>(mut body: serde_json::Value, items_key: &str) -> Result<Page<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    let items = match body.get_mut(items_key).map(serde_json::Value::take) {
+        Some(items) if !items.is_null() => serde_json::from_value(items)?,
+        _ => Vec::new(),
+    };
+    let links: Vec<PayPalLink> = match body.get_mut("links").map(serde_json::Value::take) {
+        Some(links) if !links.is_null() => serde_json::from_value(links)?,
+        _ => Vec::new(),
+    };
+    let next_cursor = links
+        .iter()
+        .find(|link| link.rel == "next")
+        .map(|link| link_endpoint(&link.href));
+    Ok(Page { items, next_cursor })
+}
+
+/// `next` links are absolute URLs; requests are made relative to the
+/// environment's base URL, so keep only the path and query.
+fn link_endpoint(href: &str) -> String {
+    match url::Url::parse(href) {
+        Ok(url) => match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        },
+        Err(_) => href.to_string(),
+    }
+}
+
+fn with_page_size(endpoint: &str, page_size: Option<usize>) -> String {
+    match page_size {
+        Some(page_size) => {
+            let separator = if endpoint.contains('?') { '&' } else { '?' };
+            format!("{}{}page_size={}", endpoint, separator, page_size)
+        }
+        None => endpoint.to_string(),
+    }
+}
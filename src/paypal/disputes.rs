@@ -0,0 +1,160 @@
+use serde::{Deserialize, Serialize};
+use crate::builders::PageRequest;
+use crate::error::Result;
+use crate::pagination::{PageStream, Paginator};
+use super::{PayPalClient, PayPalMoney, PayPalLink};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dispute {
+    pub dispute_id: String,
+    pub create_time: Option<String>,
+    pub update_time: Option<String>,
+    pub disputed_transactions: Option<Vec<DisputedTransaction>>,
+    pub reason: Option<String>,
+    pub status: Option<DisputeStatus>,
+    pub dispute_amount: Option<PayPalMoney>,
+    pub dispute_outcome: Option<DisputeOutcome>,
+    pub dispute_life_cycle_stage: Option<String>,
+    pub seller_response_due_date: Option<String>,
+    pub links: Option<Vec<PayPalLink>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(rename_all = "SCREAMING_SNAKE_CASE")]
+pub enum DisputeStatus {
+    Open,
+    WaitingForBuyerResponse,
+    WaitingForSellerResponse,
+    UnderReview,
+    Resolved,
+    Other,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputedTransaction {
+    pub buyer_transaction_id: Option<String>,
+    pub seller_transaction_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeOutcome {
+    /// e.g. `RESOLVED_BUYER_FAVOUR`, `RESOLVED_SELLER_FAVOUR`, `ACCEPTED` or `CANCELED_BY_BUYER`
+    pub outcome_code: String,
+    pub amount_refunded: Option<PayPalMoney>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeList {
+    pub items: Option<Vec<Dispute>>,
+    pub links: Option<Vec<PayPalLink>>,
+}
+
+/// Accept-claim and provide-evidence only answer with HATEOAS links
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeActionResponse {
+    pub links: Option<Vec<PayPalLink>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AcceptClaimRequest {
+    pub note: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProvideEvidenceRequest {
+    pub evidences: Vec<Evidence>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Evidence {
+    /// e.g. `PROOF_OF_FULFILLMENT`, `PROOF_OF_REFUND` or `OTHER`
+    pub evidence_type: String,
+    pub evidence_info: Option<EvidenceInfo>,
+    /// Names of the files uploaded alongside the request
+    pub documents: Option<Vec<EvidenceDocument>>,
+    pub notes: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceInfo {
+    pub tracking_info: Option<Vec<TrackingInfo>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TrackingInfo {
+    pub carrier_name: String,
+    pub tracking_number: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EvidenceDocument {
+    pub name: String,
+}
+
+impl Dispute {
+    pub fn get(client: &mut PayPalClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v1/customer/disputes/{}", dispute_id);
+        client.get(&endpoint)
+    }
+
+    pub async fn async_get(client: &mut PayPalClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v1/customer/disputes/{}", dispute_id);
+        client.async_get(&endpoint).await
+    }
+
+    pub fn list(client: &mut PayPalClient, page_size: Option<u32>) -> Result<DisputeList> {
+        client.get(&list_endpoint(page_size))
+    }
+
+    pub async fn async_list(client: &mut PayPalClient, page_size: Option<u32>) -> Result<DisputeList> {
+        client.async_get(&list_endpoint(page_size)).await
+    }
+
+    /// Iterates over all disputes, following `next` links as needed.
+    pub fn list_iter(client: &PayPalClient, page: PageRequest) -> Paginator<Self> {
+        client.list_iter("/v1/customer/disputes", "items", page)
+    }
+
+    pub fn list_stream(client: &PayPalClient, page: PageRequest) -> PageStream<Self> {
+        client.list_stream("/v1/customer/disputes", "items", page)
+    }
+
+    /// Accepts liability for the claim; PayPal refunds the buyer and closes the dispute.
+    pub fn accept_claim(client: &mut PayPalClient, dispute_id: &str, note: &str) -> Result<DisputeActionResponse> {
+        let endpoint = format!("/v1/customer/disputes/{}/accept-claim", dispute_id);
+        client.post(&endpoint, &AcceptClaimRequest { note: note.to_string() })
+    }
+
+    pub async fn async_accept_claim(client: &mut PayPalClient, dispute_id: &str, note: &str) -> Result<DisputeActionResponse> {
+        let endpoint = format!("/v1/customer/disputes/{}/accept-claim", dispute_id);
+        client.async_post(&endpoint, &AcceptClaimRequest { note: note.to_string() }).await
+    }
+
+    /// Provides evidence with the documents it names attached as `(filename, contents)`.
+    pub fn provide_evidence(
+        client: &mut PayPalClient,
+        dispute_id: &str,
+        request: &ProvideEvidenceRequest,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<DisputeActionResponse> {
+        let endpoint = format!("/v1/customer/disputes/{}/provide-evidence", dispute_id);
+        client.post_multipart(&endpoint, request, files)
+    }
+
+    pub async fn async_provide_evidence(
+        client: &mut PayPalClient,
+        dispute_id: &str,
+        request: &ProvideEvidenceRequest,
+        files: &[(String, Vec<u8>)],
+    ) -> Result<DisputeActionResponse> {
+        let endpoint = format!("/v1/customer/disputes/{}/provide-evidence", dispute_id);
+        client.async_post_multipart(&endpoint, request, files).await
+    }
+}
+
+fn list_endpoint(page_size: Option<u32>) -> String {
+    match page_size {
+        Some(page_size) => format!("/v1/customer/disputes?page_size={}", page_size),
+        None => "/v1/customer/disputes".to_string(),
+    }
+}
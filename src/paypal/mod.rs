@@ -1,5 +1,6 @@
 pub mod auth;
 pub mod client;
+pub mod disputes;
 pub mod orders;
 pub mod payments;
 pub mod subscriptions;
@@ -114,10 +114,24 @@ impl Order {
         client.post("/v2/checkout/orders", self)
     }
 
+    /// Creates the order under a caller-chosen `PayPal-Request-Id`, so a
+    /// retried call returns the original order instead of creating another.
+    pub fn create_with_request_id(&self, client: &mut PayPalClient, request_id: &str) -> Result<Self> {
+        client.post_with_request_id("/v2/checkout/orders", self, Some(request_id))
+    }
+
     pub async fn async_create(&self, client: &mut PayPalClient) -> Result<Self> {
         client.async_post("/v2/checkout/orders", self).await
     }
 
+    pub async fn async_create_with_request_id(
+        &self,
+        client: &mut PayPalClient,
+        request_id: &str,
+    ) -> Result<Self> {
+        client.async_post_with_request_id("/v2/checkout/orders", self, Some(request_id)).await
+    }
+
     pub fn get(client: &mut PayPalClient, order_id: &str) -> Result<Self> {
         let endpoint = format!("/v2/checkout/orders/{}", order_id);
         client.get(&endpoint)
@@ -143,6 +157,16 @@ impl Order {
         client: &mut PayPalClient,
         order_id: &str,
         capture_request: Option<CaptureRequest>,
+    ) -> Result<CaptureResponse> {
+        Self::capture_with_request_id(client, order_id, capture_request, &uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Captures with a caller-chosen `PayPal-Request-Id` so retries are not charged twice.
+    pub fn capture_with_request_id(
+        client: &mut PayPalClient,
+        order_id: &str,
+        capture_request: Option<CaptureRequest>,
+        request_id: &str,
     ) -> Result<CaptureResponse> {
         let endpoint = format!("/v2/checkout/orders/{}/capture", order_id);
         let body = capture_request.unwrap_or_else(|| CaptureRequest {
@@ -150,13 +174,22 @@ impl Order {
             final_capture: None,
             note_to_payer: None,
         });
-        client.post(&endpoint, &body)
+        client.post_with_request_id(&endpoint, &body, Some(request_id))
     }
 
     pub async fn async_capture(
         client: &mut PayPalClient,
         order_id: &str,
         capture_request: Option<CaptureRequest>,
+    ) -> Result<CaptureResponse> {
+        Self::async_capture_with_request_id(client, order_id, capture_request, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    pub async fn async_capture_with_request_id(
+        client: &mut PayPalClient,
+        order_id: &str,
+        capture_request: Option<CaptureRequest>,
+        request_id: &str,
     ) -> Result<CaptureResponse> {
         let endpoint = format!("/v2/checkout/orders/{}/capture", order_id);
         let body = capture_request.unwrap_or_else(|| CaptureRequest {
@@ -164,7 +197,7 @@ impl Order {
             final_capture: None,
             note_to_payer: None,
         });
-        client.async_post(&endpoint, &body).await
+        client.async_post_with_request_id(&endpoint, &body, Some(request_id)).await
     }
 
     pub fn authorize(
@@ -109,6 +109,16 @@ impl Payment {
         client: &mut PayPalClient,
         capture_id: &str,
         refund_request: Option<RefundRequest>,
+    ) -> Result<Refund> {
+        Self::refund_with_request_id(client, capture_id, refund_request, &uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Refunds with a caller-chosen `PayPal-Request-Id` so retries are not refunded twice.
+    pub fn refund_with_request_id(
+        client: &mut PayPalClient,
+        capture_id: &str,
+        refund_request: Option<RefundRequest>,
+        request_id: &str,
     ) -> Result<Refund> {
         let endpoint = format!("/v2/payments/captures/{}/refund", capture_id);
         let body = refund_request.unwrap_or_else(|| RefundRequest {
@@ -116,13 +126,22 @@ impl Payment {
             invoice_id: None,
             note_to_payer: None,
         });
-        client.post(&endpoint, &body)
+        client.post_with_request_id(&endpoint, &body, Some(request_id))
     }
 
     pub async fn async_refund(
         client: &mut PayPalClient,
         capture_id: &str,
         refund_request: Option<RefundRequest>,
+    ) -> Result<Refund> {
+        Self::async_refund_with_request_id(client, capture_id, refund_request, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    pub async fn async_refund_with_request_id(
+        client: &mut PayPalClient,
+        capture_id: &str,
+        refund_request: Option<RefundRequest>,
+        request_id: &str,
     ) -> Result<Refund> {
         let endpoint = format!("/v2/payments/captures/{}/refund", capture_id);
         let body = refund_request.unwrap_or_else(|| RefundRequest {
@@ -130,7 +149,7 @@ impl Payment {
             invoice_id: None,
             note_to_payer: None,
         });
-        client.async_post(&endpoint, &body).await
+        client.async_post_with_request_id(&endpoint, &body, Some(request_id)).await
     }
 }
 
@@ -161,26 +180,45 @@ impl Authorization {
         client: &mut PayPalClient,
         authorization_id: &str,
         amount: Option<PayPalMoney>,
+    ) -> Result<Payment> {
+        Self::capture_with_request_id(client, authorization_id, amount, &uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Captures the authorization with a caller-chosen `PayPal-Request-Id`.
+    pub fn capture_with_request_id(
+        client: &mut PayPalClient,
+        authorization_id: &str,
+        amount: Option<PayPalMoney>,
+        request_id: &str,
     ) -> Result<Payment> {
         let endpoint = format!("/v2/payments/authorizations/{}/capture", authorization_id);
         let body = serde_json::json!({
             "amount": amount,
             "final_capture": false
         });
-        client.post(&endpoint, &body)
+        client.post_with_request_id(&endpoint, &body, Some(request_id))
     }
 
     pub async fn async_capture(
         client: &mut PayPalClient,
         authorization_id: &str,
         amount: Option<PayPalMoney>,
+    ) -> Result<Payment> {
+        Self::async_capture_with_request_id(client, authorization_id, amount, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    pub async fn async_capture_with_request_id(
+        client: &mut PayPalClient,
+        authorization_id: &str,
+        amount: Option<PayPalMoney>,
+        request_id: &str,
     ) -> Result<Payment> {
         let endpoint = format!("/v2/payments/authorizations/{}/capture", authorization_id);
         let body = serde_json::json!({
             "amount": amount,
             "final_capture": false
         });
-        client.async_post(&endpoint, &body).await
+        client.async_post_with_request_id(&endpoint, &body, Some(request_id)).await
     }
 
     pub fn void(client: &mut PayPalClient, authorization_id: &str) -> Result<bool> {
@@ -1,18 +1,25 @@
 use async_trait::async_trait;
 use std::collections::HashMap;
+use std::sync::Arc;
 use crate::error::{PayupError, Result};
+use crate::http_client::{default_transport, Transport};
 use crate::payment_provider::{
     PaymentProvider, PaymentFeature, Customer as UnifiedCustomer, PaymentMethod as UnifiedPaymentMethod,
     PaymentMethodType, CardDetails, BankAccountDetails, Charge as UnifiedCharge, ChargeStatus,
     Refund as UnifiedRefund, RefundStatus, RefundReason, Subscription as UnifiedSubscription,
-    SubscriptionStatus, Money
+    SubscriptionStatus, Money, Dispute as UnifiedDispute, DisputeStatus, DisputeEvidence,
+    DisputeEvidenceKind
 };
 use super::{
     PayPalClient, PayPalConfig, PayPalEnvironment, PayPalAuth, PayPalMoney,
     PayPalPayer, PayPalName, PayPalAddress, PayPalPhone, PayPalPhoneNumber, PayPalTaxInfo,
     orders::{Order, OrderIntent, OrderStatus, PurchaseUnit, CaptureRequest, CaptureResponse},
     payments::{Payment, PaymentStatus, Refund as PayPalRefund, RefundStatus as PayPalRefundStatus, RefundRequest},
-    subscriptions::{self, Subscription as PayPalSubscription, SubscriptionStatus as PayPalSubscriptionStatus, Plan}
+    subscriptions::{self, Subscription as PayPalSubscription, SubscriptionStatus as PayPalSubscriptionStatus, Plan},
+    disputes::{
+        Dispute as PayPalDispute, DisputeStatus as PayPalDisputeStatus, Evidence as PayPalEvidence,
+        EvidenceDocument, EvidenceInfo, ProvideEvidenceRequest, TrackingInfo
+    }
 };
 
 pub struct PayPalProvider {
@@ -22,6 +29,16 @@ pub struct PayPalProvider {
 
 impl PayPalProvider {
     pub fn new(client_id: String, client_secret: String, environment: PayPalEnvironment) -> Result<Self> {
+        Self::with_transport(client_id, client_secret, environment, default_transport())
+    }
+
+    /// Sends every request, including the token fetch, through `transport`.
+    pub fn with_transport(
+        client_id: String,
+        client_secret: String,
+        environment: PayPalEnvironment,
+        transport: Arc<dyn Transport>,
+    ) -> Result<Self> {
         let config = PayPalConfig {
             client_id,
             client_secret,
@@ -29,7 +46,7 @@ impl PayPalProvider {
             webhook_id: None,
         };
         
-        let client = PayPalClient::new(config)?;
+        let client = PayPalClient::with_transport(config, transport)?;
         
         Ok(Self {
             client: std::sync::Arc::new(tokio::sync::Mutex::new(client)),
@@ -151,6 +168,39 @@ impl PayPalProvider {
         }
     }
 
+    fn map_dispute_to_unified(&self, dispute: &PayPalDispute) -> UnifiedDispute {
+        let status = match (&dispute.status, &dispute.dispute_outcome) {
+            (Some(PayPalDisputeStatus::Resolved), Some(outcome)) => match outcome.outcome_code.as_str() {
+                "RESOLVED_SELLER_FAVOUR" | "CANCELED_BY_BUYER" | "DENIED" => DisputeStatus::Won,
+                "ACCEPTED" => DisputeStatus::Accepted,
+                "RESOLVED_BUYER_FAVOUR" | "RESOLVED_WITH_PAYOUT" => DisputeStatus::Lost,
+                _ => DisputeStatus::Closed,
+            },
+            (Some(PayPalDisputeStatus::Resolved), None) => DisputeStatus::Closed,
+            (Some(PayPalDisputeStatus::WaitingForBuyerResponse), _)
+            | (Some(PayPalDisputeStatus::UnderReview), _) => DisputeStatus::UnderReview,
+            _ => DisputeStatus::NeedsResponse,
+        };
+
+        UnifiedDispute {
+            id: dispute.dispute_id.clone(),
+            charge_id: dispute.disputed_transactions.as_ref()
+                .and_then(|t| t.first())
+                .and_then(|t| t.seller_transaction_id.clone()),
+            amount: dispute.dispute_amount.as_ref()
+                .map(|m| self.map_paypal_money(m))
+                .unwrap_or_else(|| Money { amount: 0, currency: "usd".to_string() }),
+            status,
+            reason: dispute.reason.clone(),
+            evidence_due_by: dispute.seller_response_due_date.as_ref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|dt| dt.timestamp()),
+            created_at: dispute.create_time.as_ref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|dt| dt.timestamp()),
+        }
+    }
+
     fn map_subscription_status(&self, status: &PayPalSubscriptionStatus) -> SubscriptionStatus {
         match status {
             PayPalSubscriptionStatus::ApprovalPending => SubscriptionStatus::Incomplete,
@@ -187,6 +237,8 @@ impl PaymentProvider for PayPalProvider {
             PaymentFeature::Webhooks,
             PaymentFeature::Subscriptions,
             PaymentFeature::DigitalWallets,
+            PaymentFeature::Disputes,
+            PaymentFeature::DisputeManagement,
         ]
     }
 
@@ -201,6 +253,11 @@ impl PaymentProvider for PayPalProvider {
         Ok(result)
     }
 
+    async fn create_customer_idempotent(&self, customer: &UnifiedCustomer, _idempotency_key: &str) -> Result<UnifiedCustomer> {
+        // No request is sent to PayPal, so there is nothing to deduplicate
+        self.create_customer(customer).await
+    }
+
     async fn get_customer(&self, customer_id: &str) -> Result<UnifiedCustomer> {
         // PayPal doesn't have a traditional customer retrieval endpoint
         // This would typically be implemented by storing customer data separately
@@ -256,6 +313,10 @@ impl PaymentProvider for PayPalProvider {
     }
 
     async fn create_charge(&self, charge: &UnifiedCharge) -> Result<UnifiedCharge> {
+        self.create_charge_idempotent(charge, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_charge_idempotent(&self, charge: &UnifiedCharge, idempotency_key: &str) -> Result<UnifiedCharge> {
         // Create a PayPal order
         let mut order = Order::new();
         order.intent = OrderIntent::Capture;
@@ -287,7 +348,7 @@ impl PaymentProvider for PayPalProvider {
         }
 
         let mut client = self.client.lock().await;
-        let created_order = order.async_create(&mut *client).await?;
+        let created_order = order.async_create_with_request_id(&mut *client, idempotency_key).await?;
         Ok(self.map_order_to_charge(&created_order))
     }
 
@@ -298,6 +359,10 @@ impl PaymentProvider for PayPalProvider {
     }
 
     async fn capture_charge(&self, charge_id: &str, amount: Option<Money>) -> Result<UnifiedCharge> {
+        self.capture_charge_idempotent(charge_id, amount, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn capture_charge_idempotent(&self, charge_id: &str, amount: Option<Money>, idempotency_key: &str) -> Result<UnifiedCharge> {
         let capture_request = CaptureRequest {
             amount: amount.map(|m| self.map_money(&m)),
             final_capture: Some(true),
@@ -305,7 +370,7 @@ impl PaymentProvider for PayPalProvider {
         };
         
         let mut client = self.client.lock().await;
-        let _capture_response = Order::async_capture(&mut *client, charge_id, Some(capture_request)).await?;
+        let _capture_response = Order::async_capture_with_request_id(&mut *client, charge_id, Some(capture_request), idempotency_key).await?;
         
         // Get the updated order
         let order = Order::async_get(&mut *client, charge_id).await?;
@@ -321,6 +386,10 @@ impl PaymentProvider for PayPalProvider {
     }
 
     async fn create_refund(&self, refund: &UnifiedRefund) -> Result<UnifiedRefund> {
+        self.create_refund_idempotent(refund, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_refund_idempotent(&self, refund: &UnifiedRefund, idempotency_key: &str) -> Result<UnifiedRefund> {
         let mut client = self.client.lock().await;
         
         let refund_request = RefundRequest {
@@ -334,7 +403,7 @@ impl PaymentProvider for PayPalProvider {
             }),
         };
         
-        let paypal_refund = Payment::async_refund(&mut *client, &refund.charge_id, Some(refund_request)).await?;
+        let paypal_refund = Payment::async_refund_with_request_id(&mut *client, &refund.charge_id, Some(refund_request), idempotency_key).await?;
         
         Ok(UnifiedRefund {
             id: paypal_refund.id,
@@ -372,6 +441,10 @@ impl PaymentProvider for PayPalProvider {
     }
 
     async fn create_subscription(&self, subscription: &UnifiedSubscription) -> Result<UnifiedSubscription> {
+        self.create_subscription_idempotent(subscription, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_subscription_idempotent(&self, subscription: &UnifiedSubscription, idempotency_key: &str) -> Result<UnifiedSubscription> {
         let mut paypal_sub = PayPalSubscription::new();
         paypal_sub.plan_id = subscription.plan_id.clone().or(subscription.price_id.clone()).unwrap_or_default();
         paypal_sub.subscriber = Some(subscriptions::Subscriber {
@@ -383,7 +456,7 @@ impl PaymentProvider for PayPalProvider {
         });
         
         let mut client = self.client.lock().await;
-        let created = paypal_sub.async_create(&mut *client).await?;
+        let created = paypal_sub.async_create_with_request_id(&mut *client, idempotency_key).await?;
         
         Ok(UnifiedSubscription {
             id: created.id,
@@ -459,6 +532,42 @@ impl PaymentProvider for PayPalProvider {
         ))
     }
 
+    async fn list_disputes(&self, limit: Option<u32>) -> Result<Vec<UnifiedDispute>> {
+        let mut client = self.client.lock().await;
+        let disputes = PayPalDispute::async_list(&mut client, limit).await?;
+        Ok(disputes.items
+            .unwrap_or_default()
+            .iter()
+            .map(|d| self.map_dispute_to_unified(d))
+            .collect())
+    }
+
+    async fn get_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let mut client = self.client.lock().await;
+        let dispute = PayPalDispute::async_get(&mut client, dispute_id).await?;
+        Ok(self.map_dispute_to_unified(&dispute))
+    }
+
+    async fn accept_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let mut client = self.client.lock().await;
+        PayPalDispute::async_accept_claim(&mut client, dispute_id, "Claim accepted by merchant").await?;
+        // The action only returns links, so re-read the dispute for its new state
+        let dispute = PayPalDispute::async_get(&mut client, dispute_id).await?;
+        Ok(self.map_dispute_to_unified(&dispute))
+    }
+
+    async fn submit_dispute_evidence(&self, dispute_id: &str, evidence: &DisputeEvidence) -> Result<UnifiedDispute> {
+        let (request, files) = map_evidence_to_paypal(evidence);
+        if request.evidences.is_empty() {
+            return Err(PayupError::ValidationError("Dispute evidence is empty".to_string()));
+        }
+
+        let mut client = self.client.lock().await;
+        PayPalDispute::async_provide_evidence(&mut client, dispute_id, &request, &files).await?;
+        let dispute = PayPalDispute::async_get(&mut client, dispute_id).await?;
+        Ok(self.map_dispute_to_unified(&dispute))
+    }
+
     async fn verify_webhook(&self, payload: &[u8], signature: &str, secret: &str) -> Result<bool> {
         use super::webhooks::WebhookEvent;
         use std::collections::HashMap;
@@ -481,4 +590,84 @@ impl PaymentProvider for PayPalProvider {
         let mut client = self.client.lock().await;
         WebhookEvent::async_verify(&mut *client, headers, body, secret).await
     }
-}
\ No newline at end of file
+}
+
+/// Splits unified evidence into PayPal evidence entries and the files they name
+fn map_evidence_to_paypal(evidence: &DisputeEvidence) -> (ProvideEvidenceRequest, Vec<(String, Vec<u8>)>) {
+    let mut evidences = Vec::new();
+
+    if let Some(tracking_number) = &evidence.tracking_number {
+        evidences.push(PayPalEvidence {
+            evidence_type: "PROOF_OF_FULFILLMENT".to_string(),
+            evidence_info: Some(EvidenceInfo {
+                tracking_info: Some(vec![TrackingInfo {
+                    carrier_name: evidence.shipping_carrier.clone().unwrap_or_else(|| "OTHER".to_string()),
+                    tracking_number: tracking_number.clone(),
+                }]),
+            }),
+            documents: None,
+            notes: None,
+        });
+    }
+
+    for file in &evidence.files {
+        let evidence_type = match file.kind {
+            DisputeEvidenceKind::Receipt => "PROOF_OF_RECEIPT_COPY",
+            DisputeEvidenceKind::ProofOfDelivery => "PROOF_OF_FULFILLMENT",
+            DisputeEvidenceKind::RefundPolicy => "RETURN_POLICY",
+            DisputeEvidenceKind::CustomerCommunication
+            | DisputeEvidenceKind::ServiceDocumentation
+            | DisputeEvidenceKind::Other => "OTHER",
+        };
+        evidences.push(PayPalEvidence {
+            evidence_type: evidence_type.to_string(),
+            evidence_info: None,
+            documents: Some(vec![EvidenceDocument { name: file.filename.clone() }]),
+            notes: None,
+        });
+    }
+
+    if let Some(explanation) = &evidence.explanation {
+        evidences.push(PayPalEvidence {
+            evidence_type: "OTHER".to_string(),
+            evidence_info: None,
+            documents: None,
+            notes: Some(explanation.clone()),
+        });
+    }
+
+    let files = evidence.files.iter()
+        .map(|f| (f.filename.clone(), f.content.clone()))
+        .collect();
+    (ProvideEvidenceRequest { evidences }, files)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::payment_provider::DisputeEvidenceFile;
+
+    #[test]
+    fn test_map_evidence_to_paypal() {
+        let evidence = DisputeEvidence {
+            explanation: Some("Delivered to the billing address".to_string()),
+            shipping_carrier: Some("UPS".to_string()),
+            tracking_number: Some("1Z999".to_string()),
+            files: vec![DisputeEvidenceFile {
+                kind: DisputeEvidenceKind::Receipt,
+                filename: "receipt.pdf".to_string(),
+                content: b"%PDF".to_vec(),
+            }],
+        };
+
+        let (request, files) = map_evidence_to_paypal(&evidence);
+        let types: Vec<&str> = request.evidences.iter().map(|e| e.evidence_type.as_str()).collect();
+        assert_eq!(types, vec!["PROOF_OF_FULFILLMENT", "PROOF_OF_RECEIPT_COPY", "OTHER"]);
+
+        let tracking = request.evidences[0].evidence_info.as_ref().unwrap().tracking_info.as_ref().unwrap();
+        assert_eq!(tracking[0].carrier_name, "UPS");
+        assert_eq!(request.evidences[1].documents.as_ref().unwrap()[0].name, "receipt.pdf");
+        assert_eq!(request.evidences[2].notes.as_deref(), Some("Delivered to the billing address"));
+        assert_eq!(files, vec![("receipt.pdf".to_string(), b"%PDF".to_vec())]);
+    }
+}
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
+use crate::builders::PageRequest;
 use crate::error::Result;
+use crate::pagination::{PageStream, Paginator};
 use super::{PayPalClient, PayPalMoney, PayPalLink};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -233,6 +235,15 @@ impl Plan {
         client.get(&endpoint)
     }
 
+    /// Iterates over all plans, following `next` links as needed.
+    pub fn list_iter(client: &PayPalClient, page: PageRequest) -> Paginator<Self> {
+        client.list_iter("/v1/billing/plans", "plans", page)
+    }
+
+    pub fn list_stream(client: &PayPalClient, page: PageRequest) -> PageStream<Self> {
+        client.list_stream("/v1/billing/plans", "plans", page)
+    }
+
     pub fn update(&self, client: &mut PayPalClient) -> Result<bool> {
         if let Some(id) = &self.id {
             let endpoint = format!("/v1/billing/plans/{}", id);
@@ -291,6 +302,14 @@ impl Subscription {
         client.async_post("/v1/billing/subscriptions", self).await
     }
 
+    pub async fn async_create_with_request_id(
+        &self,
+        client: &mut PayPalClient,
+        request_id: &str,
+    ) -> Result<Self> {
+        client.async_post_with_request_id("/v1/billing/subscriptions", self, Some(request_id)).await
+    }
+
     pub fn get(client: &mut PayPalClient, subscription_id: &str) -> Result<Self> {
         let endpoint = format!("/v1/billing/subscriptions/{}", subscription_id);
         client.get(&endpoint)
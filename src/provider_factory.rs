@@ -1,18 +1,31 @@
 use std::sync::Arc;
 use crate::error::{PayupError, Result};
 use crate::payment_provider::PaymentProvider;
+#[cfg(feature = "stripe")]
 use crate::stripe::StripeProvider;
+#[cfg(feature = "paypal")]
 use crate::paypal::{PayPalProvider, PayPalEnvironment};
+#[cfg(feature = "square")]
 use crate::square::{SquareProvider, Environment as SquareEnvironment};
+#[cfg(feature = "braintree")]
+use crate::braintree::{BraintreeProvider, Environment as BraintreeEnvironment};
+
+/// Every provider this crate knows about, paired with whether its feature is enabled
+const KNOWN_PROVIDERS: &[(&str, bool)] = &[
+    ("stripe", cfg!(feature = "stripe")),
+    ("paypal", cfg!(feature = "paypal")),
+    ("square", cfg!(feature = "square")),
+    ("braintree", cfg!(feature = "braintree")),
+];
 
 /// Configuration for creating payment providers
 #[derive(Debug, Clone)]
 pub struct ProviderConfig {
-    /// Provider name (stripe, paypal, square)
+    /// Provider name (stripe, paypal, square, braintree)
     pub provider: String,
-    /// API key or access token
+    /// API key, access token or public key (for Braintree)
     pub api_key: String,
-    /// Optional client secret (for PayPal)
+    /// Optional client secret (for PayPal) or private key (for Braintree)
     pub client_secret: Option<String>,
     /// Environment (sandbox/production)
     pub sandbox: bool,
@@ -23,12 +36,25 @@ pub struct ProviderFactory;
 
 impl ProviderFactory {
     /// Create a new payment provider instance based on configuration
+    ///
+    /// Braintree also needs a merchant ID, which `ProviderConfig` has no
+    /// field for; create it with [`ProviderBuilder::merchant_id`] or
+    /// [`ProviderFactory::from_env`] instead.
     pub fn create(config: ProviderConfig) -> Result<Arc<dyn PaymentProvider>> {
+        Self::create_with_merchant_id(config, None)
+    }
+
+    fn create_with_merchant_id(config: ProviderConfig, merchant_id: Option<String>) -> Result<Arc<dyn PaymentProvider>> {
+        #[cfg(not(feature = "braintree"))]
+        let _ = merchant_id;
+
         match config.provider.to_lowercase().as_str() {
+            #[cfg(feature = "stripe")]
             "stripe" => {
                 let provider = StripeProvider::new(config.api_key);
                 Ok(Arc::new(provider))
             }
+            #[cfg(feature = "paypal")]
             "paypal" => {
                 let client_secret = config.client_secret
                     .ok_or_else(|| PayupError::ValidationError(
@@ -48,6 +74,7 @@ impl ProviderFactory {
                 )?;
                 Ok(Arc::new(provider))
             }
+            #[cfg(feature = "square")]
             "square" => {
                 let environment = if config.sandbox {
                     SquareEnvironment::Sandbox
@@ -58,6 +85,36 @@ impl ProviderFactory {
                 let provider = SquareProvider::new(config.api_key, environment)?;
                 Ok(Arc::new(provider))
             }
+            #[cfg(feature = "braintree")]
+            "braintree" => {
+                let merchant_id = merchant_id
+                    .ok_or_else(|| PayupError::ValidationError(
+                        "Braintree requires a merchant ID; set it with ProviderBuilder::merchant_id".to_string()
+                    ))?;
+                let private_key = config.client_secret
+                    .ok_or_else(|| PayupError::ValidationError(
+                        "Braintree requires both public key (api_key) and private key (client_secret)".to_string()
+                    ))?;
+                
+                let environment = if config.sandbox {
+                    BraintreeEnvironment::Sandbox
+                } else {
+                    BraintreeEnvironment::Production
+                };
+                
+                let provider = BraintreeProvider::new(
+                    merchant_id,
+                    config.api_key,
+                    private_key,
+                    environment
+                )?;
+                Ok(Arc::new(provider))
+            }
+            provider if KNOWN_PROVIDERS.iter().any(|&(name, _)| name == provider) => {
+                Err(PayupError::ValidationError(
+                    format!("Payment provider {} is not enabled; build payup with the `{}` feature", provider, provider)
+                ))
+            }
             provider => {
                 Err(PayupError::ValidationError(
                     format!("Unknown payment provider: {}", provider)
@@ -69,9 +126,10 @@ impl ProviderFactory {
     /// Create a provider from environment variables
     /// 
     /// Expected environment variables:
-    /// - PAYMENT_PROVIDER: stripe, paypal, or square
-    /// - PAYMENT_API_KEY: API key or client ID
-    /// - PAYMENT_CLIENT_SECRET: Client secret (for PayPal)
+    /// - PAYMENT_PROVIDER: stripe, paypal, square, or braintree
+    /// - PAYMENT_API_KEY: API key, client ID or public key
+    /// - PAYMENT_CLIENT_SECRET: Client secret (for PayPal) or private key (for Braintree)
+    /// - PAYMENT_MERCHANT_ID: Merchant ID (for Braintree)
     /// - PAYMENT_SANDBOX: true/false (default: false)
     pub fn from_env() -> Result<Arc<dyn PaymentProvider>> {
         let provider = std::env::var("PAYMENT_PROVIDER")
@@ -85,22 +143,27 @@ impl ProviderFactory {
             ))?;
         
         let client_secret = std::env::var("PAYMENT_CLIENT_SECRET").ok();
+        let merchant_id = std::env::var("PAYMENT_MERCHANT_ID").ok();
         
         let sandbox = std::env::var("PAYMENT_SANDBOX")
             .unwrap_or_else(|_| "false".to_string())
             .to_lowercase() == "true";
         
-        Self::create(ProviderConfig {
+        Self::create_with_merchant_id(ProviderConfig {
             provider,
             api_key,
             client_secret,
             sandbox,
-        })
+        }, merchant_id)
     }
     
-    /// Get a list of all available provider names
+    /// Get a list of the provider names compiled into this build
     pub fn available_providers() -> Vec<&'static str> {
-        vec!["stripe", "paypal", "square"]
+        KNOWN_PROVIDERS
+            .iter()
+            .filter(|&&(_, enabled)| enabled)
+            .map(|&(name, _)| name)
+            .collect()
     }
     
     /// Check if a provider is available
@@ -116,6 +179,7 @@ pub struct ProviderBuilder {
     provider: Option<String>,
     api_key: Option<String>,
     client_secret: Option<String>,
+    merchant_id: Option<String>,
     sandbox: bool,
 }
 
@@ -125,6 +189,7 @@ impl ProviderBuilder {
             provider: None,
             api_key: None,
             client_secret: None,
+            merchant_id: None,
             sandbox: false,
         }
     }
@@ -144,6 +209,12 @@ impl ProviderBuilder {
         self
     }
     
+    /// Merchant ID, required for Braintree
+    pub fn merchant_id(mut self, merchant_id: impl Into<String>) -> Self {
+        self.merchant_id = Some(merchant_id.into());
+        self
+    }
+    
     pub fn sandbox(mut self, sandbox: bool) -> Self {
         self.sandbox = sandbox;
         self
@@ -156,12 +227,12 @@ impl ProviderBuilder {
         let api_key = self.api_key
             .ok_or_else(|| PayupError::ValidationError("API key not specified".to_string()))?;
         
-        ProviderFactory::create(ProviderConfig {
+        ProviderFactory::create_with_merchant_id(ProviderConfig {
             provider,
             api_key,
             client_secret: self.client_secret,
             sandbox: self.sandbox,
-        })
+        }, self.merchant_id)
     }
 }
 
@@ -177,12 +248,13 @@ mod tests {
     
     #[test]
     fn test_provider_availability() {
-        assert!(ProviderFactory::is_provider_available("stripe"));
-        assert!(ProviderFactory::is_provider_available("paypal"));
-        assert!(ProviderFactory::is_provider_available("square"));
+        assert_eq!(ProviderFactory::is_provider_available("stripe"), cfg!(feature = "stripe"));
+        assert_eq!(ProviderFactory::is_provider_available("paypal"), cfg!(feature = "paypal"));
+        assert_eq!(ProviderFactory::is_provider_available("square"), cfg!(feature = "square"));
         assert!(!ProviderFactory::is_provider_available("unknown"));
     }
     
+    #[cfg(feature = "stripe")]
     #[test]
     fn test_provider_builder() {
         let result = ProviderBuilder::new()
@@ -205,4 +277,43 @@ mod tests {
         
         assert!(result.is_err());
     }
+    
+    #[cfg(not(feature = "square"))]
+    #[test]
+    fn test_disabled_provider() {
+        let result = ProviderFactory::create(ProviderConfig {
+            provider: "square".to_string(),
+            api_key: "test".to_string(),
+            client_secret: None,
+            sandbox: true,
+        });
+        
+        match result {
+            Err(PayupError::ValidationError(message)) => assert!(message.contains("`square` feature")),
+            _ => panic!("Expected a validation error for a disabled provider"),
+        }
+    }
+    
+    #[cfg(feature = "braintree")]
+    #[test]
+    fn test_braintree_provider() {
+        assert!(ProviderFactory::is_provider_available("braintree"));
+        
+        let provider = ProviderBuilder::new()
+            .provider("braintree")
+            .api_key("public_key")
+            .client_secret("private_key")
+            .merchant_id("merchant_id")
+            .sandbox(true)
+            .build()
+            .unwrap();
+        assert_eq!(provider.name(), "braintree");
+        
+        let missing_merchant = ProviderBuilder::new()
+            .provider("braintree")
+            .api_key("public_key")
+            .client_secret("private_key")
+            .build();
+        assert!(missing_merchant.is_err());
+    }
 }
\ No newline at end of file
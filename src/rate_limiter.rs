@@ -1,8 +1,9 @@
 use std::collections::HashMap;
 use std::sync::{Arc, Mutex};
 use std::time::{Duration, Instant};
+use rand::Rng;
 use tokio::time::sleep;
-use crate::error::{PayupError, Result};
+use crate::error::{ErrorCategory, PayupError, Result};
 use crate::circuit_breaker::get_circuit_breaker;
 
 /// Rate limiter for API calls with configurable limits per endpoint
@@ -127,6 +128,22 @@ impl RateLimiter {
         }
     }
 
+    /// Wait until a request can be made, blocking the current thread
+    pub fn wait_if_needed_blocking(&self, endpoint: &str) -> Result<()> {
+        loop {
+            match self.check_rate_limit(endpoint) {
+                Ok(()) => return Ok(()),
+                Err(PayupError::RateLimitExceeded(_)) => {
+                    let wait_time = self.calculate_wait_time(endpoint);
+                    if wait_time > Duration::ZERO {
+                        std::thread::sleep(wait_time);
+                    }
+                }
+                Err(e) => return Err(e),
+            }
+        }
+    }
+
     /// Calculate how long to wait before the next request can be made
     fn calculate_wait_time(&self, endpoint: &str) -> Duration {
         let limits = self.limits.lock().unwrap();
@@ -204,10 +221,13 @@ impl RateLimiter {
                         return Err(e);
                     }
 
+                    // Wait as long as the server asked, or with jittered exponential backoff
+                    let delay = match retry_delay(&e, backoff, limit.max_backoff) {
+                        Some(delay) => delay,
+                        None => return Err(e),
+                    };
                     attempt += 1;
-                    
-                    // Wait with exponential backoff
-                    sleep(backoff).await;
+                    sleep(delay).await;
                     
                     // Increase backoff for next attempt
                     backoff = std::cmp::min(backoff * 2, limit.max_backoff);
@@ -266,10 +286,13 @@ impl RateLimiter {
                         return Err(e);
                     }
 
+                    // Wait as long as the server asked, or with jittered exponential backoff
+                    let delay = match retry_delay(&e, backoff, limit.max_backoff) {
+                        Some(delay) => delay,
+                        None => return Err(e),
+                    };
                     attempt += 1;
-                    
-                    // Wait with exponential backoff
-                    sleep(backoff).await;
+                    sleep(delay).await;
                     
                     // Increase backoff for next attempt
                     backoff = std::cmp::min(backoff * 2, limit.max_backoff);
@@ -278,6 +301,89 @@ impl RateLimiter {
         }
     }
 
+    /// Execute a blocking function with rate limiting, circuit breaker, and retry logic
+    pub fn execute_with_retry_blocking<F, T>(
+        &self,
+        endpoint: &str,
+        f: F,
+    ) -> Result<T>
+    where
+        F: Fn() -> Result<T>,
+    {
+        let circuit_breaker = get_circuit_breaker();
+        let limit = self.limit_for(endpoint);
+
+        let mut attempt = 0;
+        let mut backoff = limit.initial_backoff;
+
+        loop {
+            circuit_breaker.check_circuit(endpoint)?;
+            self.wait_if_needed_blocking(endpoint)?;
+
+            match f() {
+                Ok(result) => {
+                    circuit_breaker.record_success(endpoint);
+                    return Ok(result);
+                },
+                Err(e) => {
+                    if is_circuit_breaking_error(&e) {
+                        circuit_breaker.record_failure(endpoint);
+                    }
+
+                    if !limit.auto_retry || attempt >= limit.max_retries || !is_retryable_error(&e) {
+                        return Err(e);
+                    }
+
+                    let delay = match retry_delay(&e, backoff, limit.max_backoff) {
+                        Some(delay) => delay,
+                        None => return Err(e),
+                    };
+                    attempt += 1;
+                    std::thread::sleep(delay);
+                    backoff = std::cmp::min(backoff * 2, limit.max_backoff);
+                }
+            }
+        }
+    }
+
+    /// Execute a single attempt with rate limiting and circuit breaker, but no retries.
+    ///
+    /// For requests whose body can only be sent once, such as streamed multipart uploads.
+    pub async fn execute_once_async<T, Fut>(&self, endpoint: &str, fut: Fut) -> Result<T>
+    where
+        Fut: std::future::Future<Output = Result<T>>,
+    {
+        let circuit_breaker = get_circuit_breaker();
+        circuit_breaker.check_circuit(endpoint)?;
+        self.wait_if_needed(endpoint).await?;
+
+        let result = fut.await;
+        record_outcome(endpoint, &result);
+        result
+    }
+
+    /// Blocking counterpart of [`RateLimiter::execute_once_async`]
+    pub fn execute_once_blocking<F, T>(&self, endpoint: &str, f: F) -> Result<T>
+    where
+        F: FnOnce() -> Result<T>,
+    {
+        let circuit_breaker = get_circuit_breaker();
+        circuit_breaker.check_circuit(endpoint)?;
+        self.wait_if_needed_blocking(endpoint)?;
+
+        let result = f();
+        record_outcome(endpoint, &result);
+        result
+    }
+
+    /// The configured limit for an endpoint, or the default limit
+    fn limit_for(&self, endpoint: &str) -> RateLimit {
+        let limits = self.limits.lock().unwrap();
+        limits.get(endpoint)
+            .map(|el| el.limit.clone())
+            .unwrap_or_else(|| self.default_limit.clone())
+    }
+
     /// Reset rate limit tracking for an endpoint
     pub fn reset_endpoint(&self, endpoint: &str) {
         let mut limits = self.limits.lock().unwrap();
@@ -295,25 +401,48 @@ impl RateLimiter {
     }
 }
 
+/// Report the result of a request to the endpoint's circuit breaker
+fn record_outcome<T>(endpoint: &str, result: &Result<T>) {
+    let circuit_breaker = get_circuit_breaker();
+    match result {
+        Ok(_) => circuit_breaker.record_success(endpoint),
+        Err(e) if is_circuit_breaking_error(e) => circuit_breaker.record_failure(endpoint),
+        Err(_) => {}
+    }
+}
+
 /// Check if an error is retryable
 fn is_retryable_error(error: &PayupError) -> bool {
+    error.is_retryable()
+}
+
+/// How long to wait before retrying after `error`.
+///
+/// A server-sent `Retry-After` overrides the computed backoff, and `None` is
+/// returned when it asks for longer than `max_backoff` so the caller sees the
+/// error instead of stalling. Jitter keeps clients that failed together from
+/// retrying in lockstep, without ever retrying earlier than the server asked.
+fn retry_delay(error: &PayupError, backoff: Duration, max_backoff: Duration) -> Option<Duration> {
     match error {
-        PayupError::NetworkError(_) => true,
-        PayupError::RateLimitExceeded(_) => true,
-        PayupError::ServerError(status) if *status >= 500 => true,
-        PayupError::TimeoutError(_) => true,
-        _ => false,
+        PayupError::RateLimitError { retry_after: Some(seconds) } => {
+            let delay = Duration::from_secs(*seconds);
+            if delay > max_backoff {
+                return None;
+            }
+            Some(delay + random_up_to(delay / 10))
+        }
+        _ => Some(backoff / 2 + random_up_to(backoff / 2)),
     }
 }
 
+fn random_up_to(max: Duration) -> Duration {
+    let nanos = max.as_nanos().min(u64::MAX as u128) as u64;
+    Duration::from_nanos(rand::thread_rng().gen_range(0..=nanos))
+}
+
 /// Check if an error should trigger circuit breaker
 fn is_circuit_breaking_error(error: &PayupError) -> bool {
-    match error {
-        PayupError::NetworkError(_) => true,
-        PayupError::ServerError(status) if *status >= 500 => true,
-        PayupError::TimeoutError(_) => true,
-        _ => false,
-    }
+    matches!(error.category(), ErrorCategory::ProviderOutage | ErrorCategory::Network)
 }
 
 /// Global rate limiter instance
@@ -467,4 +596,68 @@ mod tests {
         // Should be able to make requests again
         assert!(limiter.check_rate_limit("test").is_ok());
     }
-}
\ No newline at end of file
+
+    #[test]
+    fn test_execute_with_retry_blocking_retries_transient_errors() {
+        let limit = RateLimit {
+            max_requests: 10,
+            window: Duration::from_secs(1),
+            auto_retry: true,
+            max_retries: 2,
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+        };
+        let limiter = RateLimiter::with_default_limit(limit);
+        let calls = std::cell::Cell::new(0);
+
+        let result = limiter.execute_with_retry_blocking("blocking-retry-test", || {
+            calls.set(calls.get() + 1);
+            if calls.get() < 3 {
+                Err(PayupError::ServerError(503))
+            } else {
+                Ok("done")
+            }
+        });
+
+        assert_eq!(result.unwrap(), "done");
+        assert_eq!(calls.get(), 3);
+    }
+
+    #[test]
+    fn test_execute_with_retry_blocking_stops_on_client_errors() {
+        let limiter = RateLimiter::new();
+        let calls = std::cell::Cell::new(0);
+
+        let result: Result<()> = limiter.execute_with_retry_blocking("blocking-client-error-test", || {
+            calls.set(calls.get() + 1);
+            Err(PayupError::ValidationError("bad".to_string()))
+        });
+
+        assert!(result.is_err());
+        assert_eq!(calls.get(), 1);
+    }
+
+    #[test]
+    fn test_retry_delay_is_jittered_below_backoff() {
+        let backoff = Duration::from_millis(400);
+        for _ in 0..50 {
+            let delay = retry_delay(&PayupError::ServerError(503), backoff, Duration::from_secs(30)).unwrap();
+            assert!(delay >= Duration::from_millis(200) && delay <= backoff, "got {:?}", delay);
+        }
+    }
+
+    #[test]
+    fn test_retry_delay_honours_retry_after() {
+        let error = PayupError::RateLimitError { retry_after: Some(2) };
+        let delay = retry_delay(&error, Duration::from_millis(100), Duration::from_secs(30)).unwrap();
+        assert!(delay >= Duration::from_secs(2) && delay <= Duration::from_millis(2200), "got {:?}", delay);
+
+        // Asking for longer than the configured maximum gives up instead of waiting
+        assert!(retry_delay(&error, Duration::from_millis(100), Duration::from_secs(1)).is_none());
+
+        // Without a header the computed backoff applies
+        let error = PayupError::RateLimitError { retry_after: None };
+        let delay = retry_delay(&error, Duration::from_millis(100), Duration::from_secs(30)).unwrap();
+        assert!(delay <= Duration::from_millis(100));
+    }
+}
@@ -3,21 +3,21 @@ use crate::error::{PayupError, Result};
 /// Safely extracts an ID from an optional field
 pub fn get_id_or_error(id: Option<String>, resource_type: &str) -> Result<String> {
     id.ok_or_else(|| {
-        PayupError::Validation(format!("{} ID is required but was not provided", resource_type))
+        PayupError::ValidationError(format!("{} ID is required but was not provided", resource_type))
     })
 }
 
 /// Safely extracts a required string field
 pub fn require_string(value: Option<String>, field_name: &str) -> Result<String> {
     value.ok_or_else(|| {
-        PayupError::Validation(format!("{} is required but was not provided", field_name))
+        PayupError::ValidationError(format!("{} is required but was not provided", field_name))
     })
 }
 
 /// Safely extracts a required numeric field
 pub fn require_number<T: std::fmt::Display>(value: Option<T>, field_name: &str) -> Result<T> {
     value.ok_or_else(|| {
-        PayupError::Validation(format!("{} is required but was not provided", field_name))
+        PayupError::ValidationError(format!("{} is required but was not provided", field_name))
     })
 }
 
@@ -94,7 +94,7 @@ impl SafeParams {
                 self.params.push((key.to_string(), v.to_string()));
                 Ok(self)
             }
-            None => Err(PayupError::Validation(format!("{} is required", field_name))),
+            None => Err(PayupError::ValidationError(format!("{} is required", field_name))),
         }
     }
     
@@ -118,7 +118,7 @@ where
     if validator(&value) {
         Ok(value)
     } else {
-        Err(PayupError::Validation(error_msg.to_string()))
+        Err(PayupError::ValidationError(error_msg.to_string()))
     }
 }
 
@@ -126,7 +126,7 @@ where
 pub fn validate_string_length(value: &str, min: usize, max: usize, field_name: &str) -> Result<()> {
     let len = value.len();
     if len < min || len > max {
-        Err(PayupError::Validation(format!(
+        Err(PayupError::ValidationError(format!(
             "{} must be between {} and {} characters, got {}",
             field_name, min, max, len
         )))
@@ -141,7 +141,7 @@ where
     T: PartialOrd + std::fmt::Display,
 {
     if value < min || value > max {
-        Err(PayupError::Validation(format!(
+        Err(PayupError::ValidationError(format!(
             "{} must be between {} and {}, got {}",
             field_name, min, max, value
         )))
@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
 use super::{SquareClient, Money};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -223,6 +225,16 @@ impl CatalogObject {
         client.delete(&endpoint)
     }
 
+    /// Iterates over all catalog objects, optionally only those of the
+    /// comma-separated `types`, fetching further pages as needed.
+    pub fn list_iter(client: &SquareClient, types: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        client.list_iter(&list_endpoint(types), "objects", page)
+    }
+
+    pub fn list_stream(client: &SquareClient, types: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        client.list_stream(&list_endpoint(types), "objects", page)
+    }
+
     pub fn list(client: &SquareClient, types: Option<&str>, cursor: Option<&str>) -> Result<Vec<Self>> {
         let mut endpoint = String::from("/v2/catalog/list?");
         if let Some(t) = types {
@@ -233,4 +245,11 @@ impl CatalogObject {
         }
         client.get(&endpoint)
     }
-}
\ No newline at end of file
+}
+
+fn list_endpoint(types: Option<&str>) -> String {
+    match types {
+        Some(types) => format!("/v2/catalog/list?types={}", types),
+        None => "/v2/catalog/list".to_string(),
+    }
+}
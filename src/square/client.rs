@@ -1,16 +1,17 @@
-use reqwest::blocking::Client as HttpClient;
-use reqwest::Client as AsyncHttpClient;
+use std::sync::Arc;
 use serde::{Deserialize, Serialize};
-use crate::error::{PayupError, Result};
+use crate::error::{PayupError, Result, SquareErrorDetails};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, default_transport, SendVia, SendViaBlocking, Transport};
 use crate::http_utils::{HttpRequestBuilder, build_url};
-use crate::rate_limiter::get_rate_limiter;
-use super::{SquareConfig, SquareAuth, ApiResponse};
+use crate::builders::PageRequest;
+use crate::pagination::{page_stream, Page, PageStream, Paginator};
+use super::{SquareConfig, SquareAuth, ApiResponse, Error};
 
+#[derive(Clone)]
 pub struct SquareClient {
     pub config: SquareConfig,
     pub auth: SquareAuth,
-    http_client: HttpClient,
-    async_http_client: AsyncHttpClient,
+    transport: Arc<dyn Transport>,
     #[allow(dead_code)]
     request_builder: HttpRequestBuilder,
 }
@@ -26,61 +27,52 @@ impl SquareClient {
         Ok(Self {
             config,
             auth,
-            http_client: HttpClient::new(),
-            async_http_client: AsyncHttpClient::new(),
+            transport: default_transport(),
             request_builder: HttpRequestBuilder::new("Square"),
         })
     }
 
+    /// Sends every request through `transport` instead of the shared one.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        self
+    }
+
     pub fn get<T>(&self, endpoint: &str) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
     {
         let url = build_url(self.auth.base_url(), endpoint);
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .get(&url)
             .header("Authorization", self.auth.authorization_header())
             .header("Content-Type", "application/json")
             .header("Square-Version", "2024-01-01")
-            .send()
-            .map_err(PayupError::from)?;
+            .send_limited("square", &*self.transport)?;
 
         self.process_square_response(response)
     }
 
+    /// Non-2xx responses were already turned into errors by `send_limited`.
     fn process_square_response<T>(&self, response: reqwest::blocking::Response) -> Result<T>
     where
         T: for<'de> Deserialize<'de>,
     {
-        if !response.status().is_success() {
-            return self.handle_error_response(response);
-        }
-
+        let status = response.status().as_u16();
         let api_response: ApiResponse<T> = response.json().map_err(PayupError::from)?;
-        self.extract_data_from_response(api_response)
-    }
-
-    fn handle_error_response<T>(&self, response: reqwest::blocking::Response) -> Result<T> {
-        let status = response.status().to_string();
-        let error_text = response.text().unwrap_or_else(|_| "Unknown error".to_string());
-        Err(PayupError::ApiError {
-            code: status,
-            message: error_text,
-            provider: "Square".to_string(),
-        })
+        self.extract_data_from_response(status, api_response)
     }
 
-    fn extract_data_from_response<T>(&self, api_response: ApiResponse<T>) -> Result<T> {
-        if let Some(errors) = api_response.errors {
-            if let Some(first_error) = errors.first() {
-                return Err(PayupError::ApiError {
-                    code: first_error.code.clone(),
-                    message: first_error.detail.clone()
-                        .unwrap_or_else(|| first_error.category.clone()),
-                    provider: "Square".to_string(),
-                });
-            }
+    fn extract_data_from_response<T>(&self, status: u16, api_response: ApiResponse<T>) -> Result<T> {
+        if let Some(first_error) = api_response.errors.and_then(|errors| errors.into_iter().next()) {
+            return Err(PayupError::SquareError(Box::new(SquareErrorDetails {
+                status,
+                category: first_error.category,
+                code: first_error.code,
+                detail: first_error.detail,
+                field: first_error.field,
+            })));
         }
 
         api_response.data
@@ -92,23 +84,14 @@ impl SquareClient {
         T: for<'de> Deserialize<'de>,
     {
         let url = build_url(self.auth.base_url(), endpoint);
-        let auth_header = self.auth.authorization_header();
-        let rate_limiter = get_rate_limiter();
-        
-        let response = rate_limiter.execute_with_retry_async("square", move || {
-            let url = url.clone();
-            let auth_header = auth_header.clone();
-            async move {
-                AsyncHttpClient::new()
-                    .get(&url)
-                    .header("Authorization", auth_header)
-                    .header("Content-Type", "application/json")
-                    .header("Square-Version", "2024-01-01")
-                    .send()
-                    .await
-                    .map_err(PayupError::from)
-            }
-        }).await?;
+
+        let response = get_shared_client()
+            .get(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Content-Type", "application/json")
+            .header("Square-Version", "2024-01-01")
+            .send_limited("square", &*self.transport)
+            .await?;
 
         self.process_async_square_response(response).await
     }
@@ -117,21 +100,81 @@ impl SquareClient {
     where
         T: for<'de> Deserialize<'de>,
     {
-        if !response.status().is_success() {
-            return self.handle_async_error_response(response).await;
-        }
-
+        let status = response.status().as_u16();
         let api_response: ApiResponse<T> = response.json().await.map_err(PayupError::from)?;
-        self.extract_data_from_response(api_response)
+        self.extract_data_from_response(status, api_response)
+    }
+
+    /// Fetches one page of a list endpoint whose items are under `items_key`.
+    /// `cursor` is the `cursor` returned with the previous page, `None` for
+    /// the first.
+    pub fn get_page<T>(&self, endpoint: &str, items_key: &str, cursor: Option<String>, limit: Option<usize>) -> Result<Page<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let url = build_url(self.auth.base_url(), endpoint);
+
+        let response = get_shared_blocking_client()
+            .get(&url)
+            .query(&page_query(cursor, limit))
+            .header("Authorization", self.auth.authorization_header())
+            .header("Content-Type", "application/json")
+            .header("Square-Version", "2024-01-01")
+            .send_limited("square", &*self.transport)?;
+
+        let status = response.status().as_u16();
+        let body: serde_json::Value = response.json().map_err(PayupError::from)?;
+        page_from_body(status, body, items_key)
+    }
+
+    pub async fn async_get_page<T>(&self, endpoint: &str, items_key: &str, cursor: Option<String>, limit: Option<usize>) -> Result<Page<T>>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let url = build_url(self.auth.base_url(), endpoint);
+
+        let response = get_shared_client()
+            .get(&url)
+            .query(&page_query(cursor, limit))
+            .header("Authorization", self.auth.authorization_header())
+            .header("Content-Type", "application/json")
+            .header("Square-Version", "2024-01-01")
+            .send_limited("square", &*self.transport)
+            .await?;
+
+        let status = response.status().as_u16();
+        let body: serde_json::Value = response.json().await.map_err(PayupError::from)?;
+        page_from_body(status, body, items_key)
+    }
+
+    /// Iterates over every item of a list endpoint, following Square's
+    /// `cursor`. Square names the array after the resource (`customers`,
+    /// `payments`, `objects`, ...), given as `items_key`.
+    /// `page.starting_after` is taken as the cursor to start from.
+    pub fn list_iter<T>(&self, endpoint: &str, items_key: &'static str, page: PageRequest) -> Paginator<T>
+    where
+        T: for<'de> Deserialize<'de>,
+    {
+        let client = self.clone();
+        let endpoint = endpoint.to_string();
+        let limit = page.page_size();
+        Paginator::new(page.starting_after, page.max_items, move |cursor| {
+            client.get_page(&endpoint, items_key, cursor, limit)
+        })
     }
 
-    async fn handle_async_error_response<T>(&self, response: reqwest::Response) -> Result<T> {
-        let status = response.status().to_string();
-        let error_text = response.text().await.unwrap_or_else(|_| "Unknown error".to_string());
-        Err(PayupError::ApiError {
-            code: status,
-            message: error_text,
-            provider: "Square".to_string(),
+    /// Async counterpart of [`SquareClient::list_iter`].
+    pub fn list_stream<T>(&self, endpoint: &str, items_key: &'static str, page: PageRequest) -> PageStream<T>
+    where
+        T: for<'de> Deserialize<'de> + Send + 'static,
+    {
+        let client = self.clone();
+        let endpoint = endpoint.to_string();
+        let limit = page.page_size();
+        page_stream(page.starting_after, page.max_items, move |cursor| {
+            let client = client.clone();
+            let endpoint = endpoint.clone();
+            async move { client.async_get_page(&endpoint, items_key, cursor, limit).await }
         })
     }
 
@@ -141,15 +184,19 @@ impl SquareClient {
         B: Serialize,
     {
         let url = build_url(self.auth.base_url(), endpoint);
-        
-        let response = self.http_client
+        let body_json = serde_json::to_value(body).map_err(PayupError::from)?;
+
+        let request = get_shared_blocking_client()
             .post(&url)
             .header("Authorization", self.auth.authorization_header())
             .header("Content-Type", "application/json")
             .header("Square-Version", "2024-01-01")
-            .json(body)
-            .send()
-            .map_err(PayupError::from)?;
+            .json(&body_json);
+        let response = if has_idempotency_key(&body_json) {
+            request.send_limited_idempotent("square", &*self.transport)?
+        } else {
+            request.send_limited("square", &*self.transport)?
+        };
 
         self.process_square_response(response)
     }
@@ -160,26 +207,19 @@ impl SquareClient {
         B: Serialize,
     {
         let url = build_url(self.auth.base_url(), endpoint);
-        let auth_header = self.auth.authorization_header();
-        let rate_limiter = get_rate_limiter();
         let body_json = serde_json::to_value(body).map_err(PayupError::from)?;
-        
-        let response = rate_limiter.execute_with_retry_async("square", move || {
-            let url = url.clone();
-            let auth_header = auth_header.clone();
-            let body_json = body_json.clone();
-            async move {
-                AsyncHttpClient::new()
-                    .post(&url)
-                    .header("Authorization", auth_header)
-                    .header("Content-Type", "application/json")
-                    .header("Square-Version", "2024-01-01")
-                    .json(&body_json)
-                    .send()
-                    .await
-                    .map_err(PayupError::from)
-            }
-        }).await?;
+
+        let request = get_shared_client()
+            .post(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Content-Type", "application/json")
+            .header("Square-Version", "2024-01-01")
+            .json(&body_json);
+        let response = if has_idempotency_key(&body_json) {
+            request.send_limited_idempotent("square", &*self.transport).await?
+        } else {
+            request.send_limited("square", &*self.transport).await?
+        };
 
         self.process_async_square_response(response).await
     }
@@ -191,27 +231,88 @@ impl SquareClient {
     {
         let url = build_url(self.auth.base_url(), endpoint);
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .put(&url)
             .header("Authorization", self.auth.authorization_header())
             .header("Content-Type", "application/json")
             .header("Square-Version", "2024-01-01")
             .json(body)
-            .send()
-            .map_err(PayupError::from)?;
+            .send_limited("square", &*self.transport)?;
+
+        self.process_square_response(response)
+    }
+
+    /// POSTs `multipart/form-data` with `request` as a JSON part and the
+    /// file as `image_file`, as the dispute evidence file endpoint expects.
+    pub fn post_multipart<T, B>(&self, endpoint: &str, request: &B, filename: &str, contents: &[u8]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        let url = build_url(self.auth.base_url(), endpoint);
+        let request_json = serde_json::to_string(request).map_err(PayupError::from)?;
+        let form = reqwest::blocking::multipart::Form::new()
+            .part(
+                "request",
+                reqwest::blocking::multipart::Part::text(request_json)
+                    .mime_str("application/json")
+                    .map_err(PayupError::from)?,
+            )
+            .part(
+                "image_file",
+                reqwest::blocking::multipart::Part::bytes(contents.to_vec()).file_name(filename.to_string()),
+            );
+
+        let response = get_shared_blocking_client()
+            .post(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Square-Version", "2024-01-01")
+            .multipart(form)
+            .send_limited("square", &*self.transport)?;
 
         self.process_square_response(response)
     }
 
+    /// Async counterpart of [`SquareClient::post_multipart`]. A multipart
+    /// body can only be sent once, so it is rate limited but never retried.
+    pub async fn async_post_multipart<T, B>(&self, endpoint: &str, request: &B, filename: &str, contents: &[u8]) -> Result<T>
+    where
+        T: for<'de> Deserialize<'de>,
+        B: Serialize,
+    {
+        let url = build_url(self.auth.base_url(), endpoint);
+        let request_json = serde_json::to_string(request).map_err(PayupError::from)?;
+        let form = reqwest::multipart::Form::new()
+            .part(
+                "request",
+                reqwest::multipart::Part::text(request_json)
+                    .mime_str("application/json")
+                    .map_err(PayupError::from)?,
+            )
+            .part(
+                "image_file",
+                reqwest::multipart::Part::bytes(contents.to_vec()).file_name(filename.to_string()),
+            );
+
+        let response = get_shared_client()
+            .post(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Square-Version", "2024-01-01")
+            .multipart(form)
+            .send_limited("square", &*self.transport)
+            .await?;
+
+        self.process_async_square_response(response).await
+    }
+
     pub fn delete(&self, endpoint: &str) -> Result<bool> {
         let url = build_url(self.auth.base_url(), endpoint);
         
-        let response = self.http_client
+        let response = get_shared_blocking_client()
             .delete(&url)
             .header("Authorization", self.auth.authorization_header())
             .header("Square-Version", "2024-01-01")
-            .send()
-            .map_err(PayupError::from)?;
+            .send_limited("square", &*self.transport)?;
 
         Ok(response.status().is_success())
     }
@@ -222,49 +323,91 @@ impl SquareClient {
         B: Serialize,
     {
         let url = build_url(self.auth.base_url(), endpoint);
-        let auth_header = self.auth.authorization_header();
-        let rate_limiter = get_rate_limiter();
-        let body_json = serde_json::to_value(body).map_err(PayupError::from)?;
-        
-        let response = rate_limiter.execute_with_retry_async("square", move || {
-            let url = url.clone();
-            let auth_header = auth_header.clone();
-            let body_json = body_json.clone();
-            async move {
-                AsyncHttpClient::new()
-                    .put(&url)
-                    .header("Authorization", auth_header)
-                    .header("Content-Type", "application/json")
-                    .header("Square-Version", "2024-01-01")
-                    .json(&body_json)
-                    .send()
-                    .await
-                    .map_err(PayupError::from)
-            }
-        }).await?;
+
+        let response = get_shared_client()
+            .put(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Content-Type", "application/json")
+            .header("Square-Version", "2024-01-01")
+            .json(body)
+            .send_limited("square", &*self.transport)
+            .await?;
 
         self.process_async_square_response(response).await
     }
     
     pub async fn async_delete(&self, endpoint: &str) -> Result<bool> {
         let url = build_url(self.auth.base_url(), endpoint);
-        let auth_header = self.auth.authorization_header();
-        let rate_limiter = get_rate_limiter();
-        
-        let response = rate_limiter.execute_with_retry_async("square", move || {
-            let url = url.clone();
-            let auth_header = auth_header.clone();
-            async move {
-                AsyncHttpClient::new()
-                    .delete(&url)
-                    .header("Authorization", auth_header)
-                    .header("Square-Version", "2024-01-01")
-                    .send()
-                    .await
-                    .map_err(PayupError::from)
-            }
-        }).await?;
+
+        let response = get_shared_client()
+            .delete(&url)
+            .header("Authorization", self.auth.authorization_header())
+            .header("Square-Version", "2024-01-01")
+            .send_limited("square", &*self.transport)
+            .await?;
 
         Ok(response.status().is_success())
     }
-}
\ No newline at end of file
+}
+
+fn page_query(cursor: Option<String>, limit: Option<usize>) -> Vec<(&'static str, String)> {
+    let mut query = Vec::new();
+    if let Some(cursor) = cursor {
+        query.push(("cursor", cursor));
+    }
+    if let Some(limit) = limit {
+        query.push(("limit", limit.to_string()));
+    }
+    query
+}
+
+/// Splits a list response into its items and next cursor. Square leaves
+/// `items_key` out of an empty last page (`{}`), but any other body without
+/// it is an error rather than an empty page.
+fn page_from_body<T>(status: u16, mut body: serde_json::Value, items_key: &str) -> Result<Page<T>>
+where
+    T: for<'de> Deserialize<'de>,
+{
+    if let Some(first_error) = body.get_mut("errors")
+        .map(serde_json::Value::take)
+        .and_then(|errors| serde_json::from_value::<Vec<Error>>(errors).ok())
+        .and_then(|errors| errors.into_iter().next())
+    {
+        return Err(PayupError::SquareError(Box::new(SquareErrorDetails {
+            status,
+            category: first_error.category,
+            code: first_error.code,
+            detail: first_error.detail,
+            field: first_error.field,
+        })));
+    }
+
+    // An empty cursor also marks the last page
+    let next_cursor = body.get("cursor")
+        .and_then(serde_json::Value::as_str)
+        .filter(|cursor| !cursor.is_empty())
+        .map(String::from);
+    let items = match body.get_mut(items_key).map(serde_json::Value::take) {
+        Some(items) if !items.is_null() => serde_json::from_value(items)?,
+        _ if next_cursor.is_none() && is_empty_page(&body) => Vec::new(),
+        _ => {
+            return Err(PayupError::Deserialization(format!(
+                "Square list response has no `{}` array",
+                items_key
+            )))
+        }
+    };
+    Ok(Page { items, next_cursor })
+}
+
+/// Whether a list response carries nothing but (at most) a cursor.
+fn is_empty_page(body: &serde_json::Value) -> bool {
+    body.as_object()
+        .is_some_and(|fields| fields.keys().all(|key| key == "cursor" || key == "errors"))
+}
+
+/// Square deduplicates writes by the `idempotency_key` in their body, which
+/// makes such requests safe to retry.
+fn has_idempotency_key(body: &serde_json::Value) -> bool {
+    body.get("idempotency_key").is_some_and(|key| !key.is_null())
+}
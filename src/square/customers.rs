@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
 use super::{SquareClient, Address};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -175,6 +177,15 @@ impl Customer {
         client.async_delete(&endpoint).await
     }
 
+    /// Iterates over all customers, fetching further pages as needed.
+    pub fn list_iter(client: &SquareClient, page: PageRequest) -> Paginator<Self> {
+        client.list_iter("/v2/customers", "customers", page)
+    }
+
+    pub fn list_stream(client: &SquareClient, page: PageRequest) -> PageStream<Self> {
+        client.list_stream("/v2/customers", "customers", page)
+    }
+
     pub fn list(client: &SquareClient, cursor: Option<&str>, limit: Option<i32>) -> Result<Vec<Self>> {
         let mut endpoint = String::from("/v2/customers?");
         if let Some(c) = cursor {
@@ -0,0 +1,161 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use super::{SquareClient, Money};
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Dispute {
+    pub id: Option<String>,
+    pub amount_money: Option<Money>,
+    pub reason: Option<String>,
+    /// e.g. `EVIDENCE_REQUIRED`, `PROCESSING`, `WON`, `LOST` or `ACCEPTED`
+    pub state: Option<String>,
+    pub due_at: Option<String>,
+    pub disputed_payment: Option<DisputedPayment>,
+    pub card_brand: Option<String>,
+    pub created_at: Option<String>,
+    pub updated_at: Option<String>,
+    pub brand_dispute_id: Option<String>,
+    pub location_id: Option<String>,
+    pub version: Option<i32>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputedPayment {
+    pub payment_id: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeEvidence {
+    pub id: Option<String>,
+    pub dispute_id: Option<String>,
+    pub evidence_file: Option<DisputeEvidenceFile>,
+    pub evidence_text: Option<String>,
+    pub evidence_type: Option<String>,
+    pub uploaded_at: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DisputeEvidenceFile {
+    pub filename: Option<String>,
+    pub filetype: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDisputeEvidenceTextRequest {
+    pub idempotency_key: String,
+    pub evidence_type: Option<String>,
+    pub evidence_text: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateDisputeEvidenceFileRequest {
+    pub idempotency_key: String,
+    pub evidence_type: Option<String>,
+    pub content_type: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+struct EmptyRequest {}
+
+impl Dispute {
+    pub fn get(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}", dispute_id);
+        client.get(&endpoint)
+    }
+
+    pub async fn async_get(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}", dispute_id);
+        client.async_get(&endpoint).await
+    }
+
+    pub fn list(client: &SquareClient, location_id: Option<&str>) -> Result<Vec<Self>> {
+        client.get(&list_endpoint(location_id))
+    }
+
+    pub async fn async_list(client: &SquareClient, location_id: Option<&str>) -> Result<Vec<Self>> {
+        client.async_get(&list_endpoint(location_id)).await
+    }
+
+    /// Iterates over all disputes, optionally only those of one location,
+    /// fetching further pages as needed.
+    pub fn list_iter(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        client.list_iter(&list_endpoint(location_id), "disputes", page)
+    }
+
+    pub fn list_stream(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        client.list_stream(&list_endpoint(location_id), "disputes", page)
+    }
+
+    /// Accepts the loss; Square returns the disputed amount to the cardholder.
+    pub fn accept(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/accept", dispute_id);
+        client.post(&endpoint, &EmptyRequest {})
+    }
+
+    pub async fn async_accept(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/accept", dispute_id);
+        client.async_post(&endpoint, &EmptyRequest {}).await
+    }
+
+    /// Submits all uploaded evidence to the card brand; no evidence can be added afterwards.
+    pub fn submit_evidence(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/submit-evidence", dispute_id);
+        client.post(&endpoint, &EmptyRequest {})
+    }
+
+    pub async fn async_submit_evidence(client: &SquareClient, dispute_id: &str) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/submit-evidence", dispute_id);
+        client.async_post(&endpoint, &EmptyRequest {}).await
+    }
+}
+
+impl DisputeEvidence {
+    pub fn create_text(
+        client: &SquareClient,
+        dispute_id: &str,
+        request: &CreateDisputeEvidenceTextRequest,
+    ) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/evidence-text", dispute_id);
+        client.post(&endpoint, request)
+    }
+
+    pub async fn async_create_text(
+        client: &SquareClient,
+        dispute_id: &str,
+        request: &CreateDisputeEvidenceTextRequest,
+    ) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/evidence-text", dispute_id);
+        client.async_post(&endpoint, request).await
+    }
+
+    pub fn create_file(
+        client: &SquareClient,
+        dispute_id: &str,
+        request: &CreateDisputeEvidenceFileRequest,
+        filename: &str,
+        contents: &[u8],
+    ) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/evidence-files", dispute_id);
+        client.post_multipart(&endpoint, request, filename, contents)
+    }
+
+    pub async fn async_create_file(
+        client: &SquareClient,
+        dispute_id: &str,
+        request: &CreateDisputeEvidenceFileRequest,
+        filename: &str,
+        contents: &[u8],
+    ) -> Result<Self> {
+        let endpoint = format!("/v2/disputes/{}/evidence-files", dispute_id);
+        client.async_post_multipart(&endpoint, request, filename, contents).await
+    }
+}
+
+fn list_endpoint(location_id: Option<&str>) -> String {
+    match location_id {
+        Some(location_id) => format!("/v2/disputes?location_id={}", location_id),
+        None => "/v2/disputes".to_string(),
+    }
+}
@@ -3,6 +3,7 @@ pub mod client;
 pub mod payments;
 pub mod customers;
 pub mod catalog;
+pub mod disputes;
 pub mod provider;
 pub mod webhooks;
 
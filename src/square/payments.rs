@@ -1,5 +1,7 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
 use super::{SquareClient, Money};
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -145,6 +147,16 @@ impl Payment {
         client.post(&endpoint, &CancelPaymentRequest { reason: None })
     }
 
+    /// Iterates over all payments, optionally only those of one location,
+    /// fetching further pages as needed.
+    pub fn list_iter(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        client.list_iter(&location_endpoint("/v2/payments", location_id), "payments", page)
+    }
+
+    pub fn list_stream(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        client.list_stream(&location_endpoint("/v2/payments", location_id), "payments", page)
+    }
+
     pub fn list(client: &SquareClient, location_id: Option<&str>, limit: Option<i32>) -> Result<Vec<Self>> {
         let mut endpoint = String::from("/v2/payments?");
         if let Some(loc) = location_id {
@@ -187,6 +199,16 @@ impl Refund {
         client.async_get(&endpoint).await
     }
 
+    /// Iterates over all refunds, optionally only those of one location,
+    /// fetching further pages as needed.
+    pub fn list_iter(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        client.list_iter(&location_endpoint("/v2/refunds", location_id), "refunds", page)
+    }
+
+    pub fn list_stream(client: &SquareClient, location_id: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        client.list_stream(&location_endpoint("/v2/refunds", location_id), "refunds", page)
+    }
+
     pub fn list(client: &SquareClient, location_id: Option<&str>, limit: Option<i32>) -> Result<Vec<Self>> {
         let mut endpoint = String::from("/v2/refunds?");
         if let Some(loc) = location_id {
@@ -210,6 +232,13 @@ impl Refund {
     }
 }
 
+fn location_endpoint(path: &str, location_id: Option<&str>) -> String {
+    match location_id {
+        Some(location_id) => format!("{}?location_id={}", path, location_id),
+        None => path.to_string(),
+    }
+}
+
 // Helper function to create a simple payment
 pub fn create_simple_payment(
     source_id: &str,
@@ -1,15 +1,25 @@
 use async_trait::async_trait;
+use futures::TryStreamExt;
+use sha2::{Digest, Sha256};
+use std::sync::Arc;
+use crate::builders::PageRequest;
 use crate::error::{PayupError, Result};
+use crate::http_client::{default_transport, Transport};
 use crate::payment_provider::{
     PaymentProvider, PaymentFeature, Customer as UnifiedCustomer, PaymentMethod as UnifiedPaymentMethod,
     Charge as UnifiedCharge, ChargeStatus,
     Refund as UnifiedRefund, RefundStatus, RefundReason, Subscription as UnifiedSubscription,
-    Money as UnifiedMoney
+    Money as UnifiedMoney, Dispute as UnifiedDispute, DisputeStatus, DisputeEvidence,
+    DisputeEvidenceKind
 };
 use super::{
     SquareClient, SquareConfig, Environment, Money,
     payments::{Payment, CreatePaymentRequest, Refund as SquareRefund, RefundPaymentRequest},
     customers::{Customer, CreateCustomerRequest, UpdateCustomerRequest},
+    disputes::{
+        Dispute, DisputeEvidence as SquareDisputeEvidence, CreateDisputeEvidenceTextRequest,
+        CreateDisputeEvidenceFileRequest
+    },
 };
 
 pub struct SquareProvider {
@@ -20,12 +30,17 @@ pub struct SquareProvider {
 
 impl SquareProvider {
     pub fn new(access_token: String, environment: Environment) -> Result<Self> {
+        Self::with_transport(access_token, environment, default_transport())
+    }
+
+    /// Sends every request through `transport` instead of the shared one.
+    pub fn with_transport(access_token: String, environment: Environment, transport: Arc<dyn Transport>) -> Result<Self> {
         let config = SquareConfig {
             access_token,
             environment: environment.clone(),
             location_id: None,
         };
-        let client = SquareClient::new(config)?;
+        let client = SquareClient::new(config)?.with_transport(transport);
         
         Ok(Self {
             client: std::sync::Arc::new(tokio::sync::Mutex::new(client)),
@@ -86,6 +101,59 @@ impl SquareProvider {
         }
     }
 
+    fn map_dispute(&self, dispute: &Dispute) -> UnifiedDispute {
+        UnifiedDispute {
+            id: dispute.id.clone().unwrap_or_default(),
+            charge_id: dispute.disputed_payment.as_ref().and_then(|p| p.payment_id.clone()),
+            amount: dispute.amount_money.as_ref()
+                .map(|m| self.map_square_money(m))
+                .unwrap_or_else(|| UnifiedMoney { amount: 0, currency: "usd".to_string() }),
+            status: match dispute.state.as_deref() {
+                Some("PROCESSING") | Some("INQUIRY_PROCESSING") => DisputeStatus::UnderReview,
+                Some("WON") => DisputeStatus::Won,
+                Some("LOST") => DisputeStatus::Lost,
+                Some("ACCEPTED") => DisputeStatus::Accepted,
+                Some("INQUIRY_CLOSED") => DisputeStatus::Closed,
+                Some("EVIDENCE_REQUIRED") | Some("INQUIRY_EVIDENCE_REQUIRED") => DisputeStatus::NeedsResponse,
+                other => DisputeStatus::Other(other.unwrap_or_default().to_string()),
+            },
+            reason: dispute.reason.clone(),
+            evidence_due_by: dispute.due_at.as_ref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|dt| dt.timestamp()),
+            created_at: dispute.created_at.as_ref()
+                .and_then(|t| chrono::DateTime::parse_from_rfc3339(t).ok())
+                .map(|dt| dt.timestamp()),
+        }
+    }
+
+    fn map_evidence_kind(&self, kind: DisputeEvidenceKind) -> &'static str {
+        match kind {
+            DisputeEvidenceKind::Receipt => "RECEIPT",
+            DisputeEvidenceKind::ProofOfDelivery => "PROOF_OF_DELIVERY_DOCUMENTATION",
+            DisputeEvidenceKind::CustomerCommunication => "CARDHOLDER_COMMUNICATION",
+            DisputeEvidenceKind::RefundPolicy => "CANCELLATION_OR_REFUND_DOCUMENTATION",
+            DisputeEvidenceKind::ServiceDocumentation => "SERVICE_RECEIVED_DOCUMENTATION",
+            DisputeEvidenceKind::Other => "GENERIC_EVIDENCE",
+        }
+    }
+
+    /// Text evidence as `(evidence_type, evidence_text)` pairs
+    fn map_evidence_texts(&self, evidence: &DisputeEvidence) -> Vec<(&'static str, String)> {
+        let mut texts = Vec::new();
+        if let Some(explanation) = &evidence.explanation {
+            texts.push(("REBUTTAL_EXPLANATION", explanation.clone()));
+        }
+        if let Some(tracking_number) = &evidence.tracking_number {
+            let text = match &evidence.shipping_carrier {
+                Some(carrier) => format!("{} {}", carrier, tracking_number),
+                None => tracking_number.clone(),
+            };
+            texts.push(("TRACKING_NUMBER", text));
+        }
+        texts
+    }
+
     fn map_money(&self, money: &UnifiedMoney) -> Money {
         Money {
             amount: money.amount,
@@ -161,10 +229,16 @@ impl PaymentProvider for SquareProvider {
             PaymentFeature::PaymentMethods,
             PaymentFeature::Webhooks,
             PaymentFeature::CustomerManagement,
+            PaymentFeature::Disputes,
+            PaymentFeature::DisputeManagement,
         ]
     }
 
     async fn create_customer(&self, customer: &UnifiedCustomer) -> Result<UnifiedCustomer> {
+        self.create_customer_idempotent(customer, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_customer_idempotent(&self, customer: &UnifiedCustomer, idempotency_key: &str) -> Result<UnifiedCustomer> {
         let client = self.client.lock().await;
         
         // Split the name if provided
@@ -184,7 +258,7 @@ impl PaymentProvider for SquareProvider {
         };
         
         let request = CreateCustomerRequest {
-            idempotency_key: Some(uuid::Uuid::new_v4().to_string()),
+            idempotency_key: Some(idempotency_key.to_string()),
             given_name,
             family_name,
             company_name: None,
@@ -297,6 +371,10 @@ impl PaymentProvider for SquareProvider {
     }
 
     async fn create_charge(&self, charge: &UnifiedCharge) -> Result<UnifiedCharge> {
+        self.create_charge_idempotent(charge, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_charge_idempotent(&self, charge: &UnifiedCharge, idempotency_key: &str) -> Result<UnifiedCharge> {
         let client = self.client.lock().await;
         
         // For Square, we need a source_id (payment source like card nonce or customer card on file)
@@ -309,7 +387,7 @@ impl PaymentProvider for SquareProvider {
         
         let request = CreatePaymentRequest {
             source_id: source_id.clone(),
-            idempotency_key: uuid::Uuid::new_v4().to_string(),
+            idempotency_key: idempotency_key.to_string(),
             amount_money: self.map_money(&charge.amount),
             tip_money: None,
             app_fee_money: None,
@@ -367,6 +445,10 @@ impl PaymentProvider for SquareProvider {
     }
 
     async fn create_refund(&self, refund: &UnifiedRefund) -> Result<UnifiedRefund> {
+        self.create_refund_idempotent(refund, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_refund_idempotent(&self, refund: &UnifiedRefund, idempotency_key: &str) -> Result<UnifiedRefund> {
         let client = self.client.lock().await;
         
         let amount_money = if let Some(amount) = &refund.amount {
@@ -380,7 +462,7 @@ impl PaymentProvider for SquareProvider {
         };
         
         let request = RefundPaymentRequest {
-            idempotency_key: uuid::Uuid::new_v4().to_string(),
+            idempotency_key: idempotency_key.to_string(),
             amount_money,
             app_fee_money: None,
             payment_id: refund.charge_id.clone(),
@@ -440,6 +522,64 @@ impl PaymentProvider for SquareProvider {
         ))
     }
 
+    async fn list_disputes(&self, limit: Option<u32>) -> Result<Vec<UnifiedDispute>> {
+        let mut page = PageRequest::new();
+        if let Some(limit) = limit {
+            page = page.max_items(limit as usize);
+        }
+
+        let client = self.client.lock().await;
+        let disputes: Vec<Dispute> = Dispute::list_stream(&client, None, page).try_collect().await?;
+        Ok(disputes.iter().map(|d| self.map_dispute(d)).collect())
+    }
+
+    async fn get_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let client = self.client.lock().await;
+        let dispute = Dispute::async_get(&client, dispute_id).await?;
+        Ok(self.map_dispute(&dispute))
+    }
+
+    async fn accept_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let client = self.client.lock().await;
+        let dispute = Dispute::async_accept(&client, dispute_id).await?;
+        Ok(self.map_dispute(&dispute))
+    }
+
+    /// Each upload's idempotency key is derived from the dispute ID and the
+    /// uploaded content, so resubmitting after a timeout doesn't attach the
+    /// same evidence twice.
+    async fn submit_dispute_evidence(&self, dispute_id: &str, evidence: &DisputeEvidence) -> Result<UnifiedDispute> {
+        let client = self.client.lock().await;
+
+        for (evidence_type, evidence_text) in self.map_evidence_texts(evidence) {
+            let request = CreateDisputeEvidenceTextRequest {
+                idempotency_key: evidence_idempotency_key(
+                    dispute_id,
+                    &[b"text", evidence_type.as_bytes(), evidence_text.as_bytes()],
+                ),
+                evidence_type: Some(evidence_type.to_string()),
+                evidence_text,
+            };
+            SquareDisputeEvidence::async_create_text(&client, dispute_id, &request).await?;
+        }
+
+        for file in &evidence.files {
+            let evidence_type = self.map_evidence_kind(file.kind);
+            let request = CreateDisputeEvidenceFileRequest {
+                idempotency_key: evidence_idempotency_key(
+                    dispute_id,
+                    &[b"file", evidence_type.as_bytes(), file.filename.as_bytes(), &file.content],
+                ),
+                evidence_type: Some(evidence_type.to_string()),
+                content_type: None,
+            };
+            SquareDisputeEvidence::async_create_file(&client, dispute_id, &request, &file.filename, &file.content).await?;
+        }
+
+        let dispute = Dispute::async_submit_evidence(&client, dispute_id).await?;
+        Ok(self.map_dispute(&dispute))
+    }
+
     async fn verify_webhook(&self, _payload: &[u8], _signature: &str, _secret: &str) -> Result<bool> {
         // Square webhook verification would go here
         // For now, return unsupported
@@ -447,4 +587,83 @@ impl PaymentProvider for SquareProvider {
             "Square webhook verification not yet implemented.".to_string()
         ))
     }
-}
\ No newline at end of file
+}
+
+/// Idempotency key of one evidence upload to a dispute, hashed from the
+/// dispute and the uploaded content. Resubmitting the same evidence reuses
+/// the key, and different evidence gets a new one. Square allows at most 45
+/// characters, so the 32 hex digits of the first 16 digest bytes are used.
+fn evidence_idempotency_key(dispute_id: &str, parts: &[&[u8]]) -> String {
+    let mut hasher = Sha256::new();
+    for part in std::iter::once(dispute_id.as_bytes()).chain(parts.iter().copied()) {
+        // Length-prefix each part so shifting bytes between parts changes the key
+        hasher.update((part.len() as u64).to_be_bytes());
+        hasher.update(part);
+    }
+    hex::encode(&hasher.finalize()[..16])
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use super::super::disputes::DisputedPayment;
+
+    fn provider() -> SquareProvider {
+        SquareProvider::new("test_token".to_string(), Environment::Sandbox).unwrap()
+    }
+
+    #[test]
+    fn test_map_dispute() {
+        let dispute = Dispute {
+            id: Some("dispute_123".to_string()),
+            amount_money: Some(Money { amount: 2500, currency: "USD".to_string() }),
+            reason: Some("NOT_AS_DESCRIBED".to_string()),
+            state: Some("EVIDENCE_REQUIRED".to_string()),
+            due_at: Some("2024-01-15T00:00:00Z".to_string()),
+            disputed_payment: Some(DisputedPayment { payment_id: Some("payment_123".to_string()) }),
+            card_brand: Some("VISA".to_string()),
+            created_at: None,
+            updated_at: None,
+            brand_dispute_id: None,
+            location_id: None,
+            version: Some(1),
+        };
+
+        let unified = provider().map_dispute(&dispute);
+        assert_eq!(unified.id, "dispute_123");
+        assert_eq!(unified.charge_id.as_deref(), Some("payment_123"));
+        assert_eq!(unified.amount.amount, 2500);
+        assert_eq!(unified.amount.currency, "usd");
+        assert_eq!(unified.status, DisputeStatus::NeedsResponse);
+        assert_eq!(unified.evidence_due_by, Some(1705276800));
+    }
+
+    #[test]
+    fn test_map_evidence_texts() {
+        let evidence = DisputeEvidence {
+            explanation: Some("Customer signed for the parcel".to_string()),
+            shipping_carrier: Some("USPS".to_string()),
+            tracking_number: Some("9400".to_string()),
+            files: vec![],
+        };
+
+        assert_eq!(
+            provider().map_evidence_texts(&evidence),
+            vec![
+                ("REBUTTAL_EXPLANATION", "Customer signed for the parcel".to_string()),
+                ("TRACKING_NUMBER", "USPS 9400".to_string()),
+            ]
+        );
+    }
+
+    #[test]
+    fn test_evidence_idempotency_keys_follow_content() {
+        let key = evidence_idempotency_key("dispute_123", &[b"text", b"GENERIC_EVIDENCE", b"Delivered"]);
+        assert_eq!(key, evidence_idempotency_key("dispute_123", &[b"text", b"GENERIC_EVIDENCE", b"Delivered"]));
+        assert!(key.len() <= 45);
+
+        assert_ne!(key, evidence_idempotency_key("dispute_123", &[b"text", b"GENERIC_EVIDENCE", b"Refunded"]));
+        assert_ne!(key, evidence_idempotency_key("dispute_456", &[b"text", b"GENERIC_EVIDENCE", b"Delivered"]));
+        assert_ne!(key, evidence_idempotency_key("dispute_123", &[b"text", b"GENERIC_EVIDENCEDelivered", b""]));
+    }
+}
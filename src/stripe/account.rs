@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
 use super::Auth;
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
 
 /// Represents a Stripe Account (Connect account)
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -352,10 +356,11 @@ impl Account {
     pub fn create(auth: &Auth, params: CreateAccountParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post("https://api.stripe.com/v1/accounts")
+            .post(auth.url("accounts"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let account: Account = response.json()?;
         Ok(account)
@@ -365,10 +370,11 @@ impl Account {
     pub async fn create_async(auth: &Auth, params: CreateAccountParams) -> Result<Self> {
         let client = get_shared_client();
         let response = client
-            .post("https://api.stripe.com/v1/accounts")
+            .post(auth.url("accounts"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let account: Account = response.json().await?;
@@ -379,9 +385,9 @@ impl Account {
     pub fn retrieve(auth: &Auth, account_id: &str) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .get(auth.url(&format!("accounts/{}", account_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let account: Account = response.json()?;
         Ok(account)
@@ -391,9 +397,9 @@ impl Account {
     pub async fn retrieve_async(auth: &Auth, account_id: &str) -> Result<Self> {
         let client = get_shared_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .get(auth.url(&format!("accounts/{}", account_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let account: Account = response.json().await?;
@@ -404,10 +410,11 @@ impl Account {
     pub fn update(auth: &Auth, account_id: &str, params: serde_json::Value) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .post(auth.url(&format!("accounts/{}", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .json(&params)
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let account: Account = response.json()?;
         Ok(account)
@@ -417,10 +424,11 @@ impl Account {
     pub async fn update_async(auth: &Auth, account_id: &str, params: serde_json::Value) -> Result<Self> {
         let client = get_shared_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .post(auth.url(&format!("accounts/{}", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .json(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let account: Account = response.json().await?;
@@ -431,9 +439,9 @@ impl Account {
     pub fn delete(auth: &Auth, account_id: &str) -> Result<DeletedAccount> {
         let client = get_shared_blocking_client();
         let response = client
-            .delete(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .delete(auth.url(&format!("accounts/{}", account_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let deleted: DeletedAccount = response.json()?;
         Ok(deleted)
@@ -443,19 +451,29 @@ impl Account {
     pub async fn delete_async(auth: &Auth, account_id: &str) -> Result<DeletedAccount> {
         let client = get_shared_client();
         let response = client
-            .delete(&format!("https://api.stripe.com/v1/accounts/{}", account_id))
+            .delete(auth.url(&format!("accounts/{}", account_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let deleted: DeletedAccount = response.json().await?;
         Ok(deleted)
     }
 
+    /// Iterate over all connected accounts, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "accounts", page)
+    }
+
+    /// Stream all connected accounts, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "accounts", page)
+    }
+
     /// List all accounts
     pub fn list(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
         let client = get_shared_blocking_client();
-        let mut url = "https://api.stripe.com/v1/accounts".to_string();
+        let mut url = auth.url("accounts");
         if let Some(limit) = limit {
             url = format!("{}?limit={}", url, limit);
         }
@@ -463,7 +481,7 @@ impl Account {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         #[derive(Deserialize)]
         struct AccountList {
@@ -477,7 +495,7 @@ impl Account {
     /// List all accounts (async)
     pub async fn list_async(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
         let client = get_shared_client();
-        let mut url = "https://api.stripe.com/v1/accounts".to_string();
+        let mut url = auth.url("accounts");
         if let Some(limit) = limit {
             url = format!("{}?limit={}", url, limit);
         }
@@ -485,7 +503,7 @@ impl Account {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         #[derive(Deserialize)]
@@ -505,10 +523,11 @@ impl Account {
         });
         
         let response = client
-            .post(&format!("https://api.stripe.com/v1/accounts/{}/reject", account_id))
+            .post(auth.url(&format!("accounts/{}/reject", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .json(&params)
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let account: Account = response.json()?;
         Ok(account)
@@ -522,10 +541,11 @@ impl Account {
         });
         
         let response = client
-            .post(&format!("https://api.stripe.com/v1/accounts/{}/reject", account_id))
+            .post(auth.url(&format!("accounts/{}/reject", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .json(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let account: Account = response.json().await?;
@@ -0,0 +1,106 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::stripe::form;
+use super::Auth;
+
+/// What an account link lets the connected account do
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountLinkType {
+    /// Provide the information Stripe needs to enable payments and payouts
+    AccountOnboarding,
+    /// Update information already provided; Custom accounts only
+    AccountUpdate,
+}
+
+/// Which requirements the onboarding flow collects
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AccountLinkCollect {
+    CurrentlyDue,
+    EventuallyDue,
+}
+
+/// A single-use URL taking a connected account through Stripe-hosted onboarding
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AccountLink {
+    /// String representing the object's type (always "account_link")
+    pub object: String,
+    pub created: i64,
+    /// Time after which the link can no longer be used (Unix timestamp)
+    pub expires_at: i64,
+    /// The URL to redirect the account holder to
+    pub url: String,
+}
+
+/// Parameters for creating an account link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateAccountLinkParams {
+    /// ID of the connected account
+    pub account: String,
+    /// Where to send the user when the link has expired or was already
+    /// visited; create a fresh link from there
+    pub refresh_url: String,
+    /// Where to send the user when they leave or complete the flow; this
+    /// does not mean every requirement was provided
+    pub return_url: String,
+    #[serde(rename = "type")]
+    pub type_field: AccountLinkType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collect: Option<AccountLinkCollect>,
+}
+
+impl CreateAccountLinkParams {
+    /// An onboarding link for `account`
+    pub fn onboarding(account: &str, refresh_url: &str, return_url: &str) -> Self {
+        Self {
+            account: account.to_string(),
+            refresh_url: refresh_url.to_string(),
+            return_url: return_url.to_string(),
+            type_field: AccountLinkType::AccountOnboarding,
+            collect: None,
+        }
+    }
+}
+
+impl AccountLink {
+    /// Create an account link
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::account_link::{AccountLink, CreateAccountLinkParams};
+    ///
+    /// let params = CreateAccountLinkParams::onboarding(
+    ///     "acct_123",
+    ///     "https://example.com/connect/refresh",
+    ///     "https://example.com/connect/done",
+    /// );
+    /// let link = AccountLink::create(&auth, params)?;
+    /// // Redirect the account holder to link.url
+    /// ```
+    pub fn create(auth: &Auth, params: CreateAccountLinkParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("account_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create an account link (async)
+    pub async fn create_async(auth: &Auth, params: CreateAccountLinkParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("account_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
@@ -0,0 +1,172 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// The fee a platform collected on a charge made through a connected account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationFee {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "application_fee")
+    pub object: String,
+    /// The connected account the fee was taken from
+    pub account: String,
+    pub amount: i64,
+    pub amount_refunded: i64,
+    /// ID of the Connect application that earned the fee
+    pub application: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_transaction: Option<String>,
+    /// The charge the fee was taken from
+    pub charge: String,
+    pub created: i64,
+    pub currency: String,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub originating_transaction: Option<String>,
+    /// Whether the whole fee has been refunded
+    pub refunded: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refunds: Option<ApplicationFeeRefundList>,
+}
+
+/// A page of refunds of an application fee
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationFeeRefundList {
+    pub object: String,
+    pub data: Vec<ApplicationFeeRefund>,
+    pub has_more: bool,
+    pub url: String,
+}
+
+/// Part or all of an application fee returned to the connected account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ApplicationFeeRefund {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "fee_refund")
+    pub object: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub balance_transaction: Option<String>,
+    pub created: i64,
+    pub currency: String,
+    /// The application fee that was refunded
+    pub fee: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for refunding an application fee
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateApplicationFeeRefundParams {
+    /// Amount to refund in the smallest currency unit, defaults to the rest of the fee
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing application fees
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListApplicationFeesParams {
+    /// Only fees taken from this charge
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub charge: Option<String>,
+}
+
+impl ApplicationFee {
+    /// Retrieve an application fee by ID
+    pub fn retrieve(auth: &Auth, fee_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("application_fees/{}", fee_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve an application fee by ID (async)
+    pub async fn retrieve_async(auth: &Auth, fee_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("application_fees/{}", fee_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Refund all or part of an application fee to the connected account
+    pub fn refund(auth: &Auth, fee_id: &str, params: CreateApplicationFeeRefundParams) -> Result<ApplicationFeeRefund> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("application_fees/{}/refunds", fee_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Refund all or part of an application fee (async)
+    pub async fn refund_async(
+        auth: &Auth,
+        fee_id: &str,
+        params: CreateApplicationFeeRefundParams,
+    ) -> Result<ApplicationFeeRefund> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("application_fees/{}/refunds", fee_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List application fees matching `filters`, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListApplicationFeesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List application fees matching `filters` (async)
+    pub async fn list_async(auth: &Auth, filters: &ListApplicationFeesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over application fees matching `filters`, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListApplicationFeesParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream application fees matching `filters`, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListApplicationFeesParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Iterate over the refunds of an application fee, fetching further pages as needed
+    pub fn refunds_iter(auth: &Auth, fee_id: &str, page: PageRequest) -> Paginator<ApplicationFeeRefund> {
+        pagination::list_iter(auth.clone(), &format!("application_fees/{}/refunds", fee_id), page)
+    }
+
+    /// Stream the refunds of an application fee, fetching further pages as needed
+    pub fn refunds_stream(auth: &Auth, fee_id: &str, page: PageRequest) -> PageStream<ApplicationFeeRefund> {
+        pagination::list_stream(auth.clone(), &format!("application_fees/{}/refunds", fee_id), page)
+    }
+
+    fn list_path(filters: &ListApplicationFeesParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "application_fees".to_string(),
+            query => format!("application_fees?{}", query),
+        }
+    }
+}
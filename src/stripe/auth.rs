@@ -1,14 +1,296 @@
+use std::sync::{Arc, Mutex};
+use async_trait::async_trait;
 use serde::{Deserialize, Serialize};
+use crate::config::api::{STRIPE_BASE_URL, STRIPE_FILES_URL};
+use crate::config::http::HEADER_STRIPE_ACCOUNT;
+use crate::error::{PayupError, Result};
+use crate::http_client::{default_transport, Transport};
 
-/// Stores the Stripe API client + secret.
+/// Credentials and API location used for every Stripe request.
+///
+/// `base_url` defaults to [`STRIPE_BASE_URL`]; point it at a local
+/// stand-in (e.g. stripe-mock) with [`Auth::with_base_url`].
+///
+/// Mutating requests are sent with an `Idempotency-Key` header. Set one with
+/// [`Auth::with_idempotency_key`] to make a retried call safe across process
+/// restarts or network timeouts; it is sent with the next mutating request
+/// only, and every other request gets a freshly generated key.
+///
+/// Requests go through the shared [`Transport`] unless another is set with
+/// [`Auth::with_transport`].
+///
+/// A Connect platform acts on a connected account's behalf by sending its
+/// ID in the `Stripe-Account` header; [`Auth::with_stripe_account`] adds it
+/// to every request made with the returned `Auth`. The account is kept
+/// when an `Auth` is serialized and routed again when it is deserialized.
 #[derive(Serialize, Deserialize, Debug, Clone)]
+#[serde(try_from = "SerializedAuth")]
 pub struct Auth {
     pub client: String,
     pub secret: String,
+    #[serde(default = "default_base_url")]
+    pub base_url: String,
+    /// Key for the next mutating request, shared with clones so that it is
+    /// sent at most once
+    #[serde(skip)]
+    pub(crate) idempotency_key: Arc<Mutex<Option<String>>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    stripe_account: Option<String>,
+    #[serde(skip, default = "default_transport")]
+    transport: Arc<dyn Transport>,
+    /// `transport` wrapped to send the `Stripe-Account` header, when set
+    #[serde(skip)]
+    account_transport: Option<Arc<ConnectedAccountTransport>>,
+}
+
+/// The serialized fields of [`Auth`]. Deserializing goes through
+/// [`Auth::with_stripe_account`], so a malformed account ID is rejected
+/// rather than dropped.
+#[derive(Deserialize)]
+struct SerializedAuth {
+    client: String,
+    secret: String,
+    #[serde(default = "default_base_url")]
+    base_url: String,
+    #[serde(default)]
+    stripe_account: Option<String>,
+}
+
+impl TryFrom<SerializedAuth> for Auth {
+    type Error = PayupError;
+
+    fn try_from(fields: SerializedAuth) -> Result<Self> {
+        let auth = Auth::new(fields.client, fields.secret).with_base_url(fields.base_url);
+        match fields.stripe_account {
+            Some(account_id) => auth.with_stripe_account(account_id),
+            None => Ok(auth),
+        }
+    }
+}
+
+/// Adds the `Stripe-Account` header to every request before handing it on.
+#[derive(Debug)]
+struct ConnectedAccountTransport {
+    inner: Arc<dyn Transport>,
+    account: reqwest::header::HeaderValue,
+}
+
+#[async_trait]
+impl Transport for ConnectedAccountTransport {
+    async fn execute(&self, mut request: reqwest::Request) -> std::result::Result<reqwest::Response, reqwest::Error> {
+        request.headers_mut().insert(HEADER_STRIPE_ACCOUNT, self.account.clone());
+        self.inner.execute(request).await
+    }
+
+    fn execute_blocking(
+        &self,
+        mut request: reqwest::blocking::Request,
+    ) -> std::result::Result<reqwest::blocking::Response, reqwest::Error> {
+        request.headers_mut().insert(HEADER_STRIPE_ACCOUNT, self.account.clone());
+        self.inner.execute_blocking(request)
+    }
+}
+
+fn default_base_url() -> String {
+    STRIPE_BASE_URL.to_string()
 }
 
 impl Auth {
     pub fn new(client: String, secret: String) -> Self {
-        Auth { client, secret }
+        Auth {
+            client,
+            secret,
+            base_url: default_base_url(),
+            idempotency_key: Arc::default(),
+            stripe_account: None,
+            transport: default_transport(),
+            account_transport: None,
+        }
+    }
+
+    /// Overrides the API base URL, e.g. `http://localhost:12111/v1`.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.base_url = base_url.into();
+        self
+    }
+
+    /// Sends requests made with this `Auth` through `transport`.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.transport = transport;
+        if let Some(routed) = self.account_transport.take() {
+            self.route_account(routed.account.clone());
+        }
+        self
+    }
+
+    pub fn transport(&self) -> &dyn Transport {
+        match &self.account_transport {
+            Some(transport) => &**transport,
+            None => &*self.transport,
+        }
+    }
+
+    /// Makes requests on behalf of the connected account `account_id`
+    /// (e.g. `acct_123`), replacing any account set before.
+    ///
+    /// Returns `PayupError::ValidationError` for an ID that can't be sent as
+    /// a header value, rather than falling back to the platform account.
+    pub fn with_stripe_account(mut self, account_id: impl Into<String>) -> Result<Self> {
+        let account_id = account_id.into();
+        let account = reqwest::header::HeaderValue::from_str(&account_id).map_err(|_| {
+            PayupError::ValidationError(format!("Invalid Stripe account ID: {:?}", account_id))
+        })?;
+        self.stripe_account = Some(account_id);
+        self.route_account(account);
+        Ok(self)
+    }
+
+    /// Returns a copy acting on behalf of the connected account `account_id`.
+    pub fn for_account(&self, account_id: &str) -> Result<Self> {
+        self.clone().with_stripe_account(account_id)
+    }
+
+    /// The connected account requests are made on behalf of, if any.
+    pub fn stripe_account(&self) -> Option<&str> {
+        self.stripe_account.as_deref()
+    }
+
+    fn route_account(&mut self, account: reqwest::header::HeaderValue) {
+        self.account_transport = Some(Arc::new(ConnectedAccountTransport {
+            inner: self.transport.clone(),
+            account,
+        }));
+    }
+
+    /// Uses `key` as the `Idempotency-Key` of the next mutating request made
+    /// with the returned `Auth` or any clone of it.
+    ///
+    /// The key is used once: later requests get fresh keys, so an `Auth`
+    /// kept around after the call can't replay it on an unrelated request.
+    /// Retries of that one request, including the client's own, resend it.
+    pub fn with_idempotency_key(mut self, key: impl Into<String>) -> Self {
+        self.idempotency_key = Arc::new(Mutex::new(Some(key.into())));
+        self
     }
-}
\ No newline at end of file
+
+    /// Takes the caller-supplied idempotency key, or generates a fresh one
+    /// once it has been used.
+    pub fn next_idempotency_key(&self) -> String {
+        self.idempotency_key
+            .lock()
+            .unwrap_or_else(|poisoned| poisoned.into_inner())
+            .take()
+            .unwrap_or_else(|| uuid::Uuid::new_v4().to_string())
+    }
+
+    /// Joins `path` (e.g. `"customers/cus_123"`) onto the configured base URL.
+    pub fn url(&self, path: &str) -> String {
+        format!(
+            "{}/{}",
+            self.base_url.trim_end_matches('/'),
+            path.trim_start_matches('/')
+        )
+    }
+
+    /// Joins `path` onto the file host.
+    ///
+    /// Stripe only accepts uploads, and serves downloads such as quote PDFs,
+    /// on `files.stripe.com`. A custom `base_url` is used as-is, since local
+    /// stand-ins serve files from the same host.
+    pub fn files_url(&self, path: &str) -> String {
+        if self.base_url.trim_end_matches('/') == STRIPE_BASE_URL.trim_end_matches('/') {
+            format!("{}{}", STRIPE_FILES_URL, path.trim_start_matches('/'))
+        } else {
+            self.url(path)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_default_base_url() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string());
+        assert_eq!(auth.url("customers"), "https://api.stripe.com/v1/customers");
+        assert_eq!(auth.url("/charges/ch_123"), "https://api.stripe.com/v1/charges/ch_123");
+    }
+
+    #[test]
+    fn test_custom_base_url() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string())
+            .with_base_url("http://localhost:12111/v1/");
+        assert_eq!(auth.url("payment_intents"), "http://localhost:12111/v1/payment_intents");
+    }
+
+    #[test]
+    fn test_files_url() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string());
+        assert_eq!(auth.files_url("files"), "https://files.stripe.com/v1/files");
+
+        let local = auth.with_base_url("http://localhost:12111/v1");
+        assert_eq!(local.files_url("files"), "http://localhost:12111/v1/files");
+    }
+
+    #[test]
+    fn test_idempotency_key() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string());
+        assert_ne!(auth.next_idempotency_key(), auth.next_idempotency_key());
+
+        let keyed = auth.clone().with_idempotency_key("order-42");
+        let copy = keyed.clone();
+        assert_eq!(copy.next_idempotency_key(), "order-42");
+        // Used up by the clone, so neither copy sends it again
+        assert_ne!(keyed.next_idempotency_key(), "order-42");
+        assert_ne!(copy.next_idempotency_key(), "order-42");
+        // Setting a key on a copy leaves the original alone
+        assert_ne!(auth.next_idempotency_key(), "order-42");
+    }
+
+    #[test]
+    fn test_stripe_account() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string());
+        assert_eq!(auth.stripe_account(), None);
+
+        let connected = auth.for_account("acct_123").unwrap();
+        assert_eq!(connected.stripe_account(), Some("acct_123"));
+        assert_eq!(auth.stripe_account(), None);
+
+        let switched = connected.with_stripe_account("acct_456").unwrap();
+        assert_eq!(switched.stripe_account(), Some("acct_456"));
+    }
+
+    #[test]
+    fn test_invalid_stripe_account_is_rejected() {
+        let auth = Auth::new("sk_test".to_string(), "sk_test".to_string());
+        assert!(matches!(
+            auth.for_account("acct_1\r\nX-Injected: 1"),
+            Err(PayupError::ValidationError(_))
+        ));
+
+        let result = serde_json::from_str::<Auth>(r#"{"client":"a","secret":"b","stripe_account":"acct\n1"}"#);
+        assert!(result.is_err());
+    }
+
+    #[test]
+    fn test_stripe_account_survives_serialization() {
+        let connected = Auth::new("sk_test".to_string(), "sk_test".to_string())
+            .for_account("acct_123")
+            .unwrap();
+        let json = serde_json::to_string(&connected).unwrap();
+
+        let restored: Auth = serde_json::from_str(&json).unwrap();
+        assert_eq!(restored.stripe_account(), Some("acct_123"));
+        assert!(restored.account_transport.is_some());
+
+        let platform = serde_json::to_string(&Auth::new("sk_test".to_string(), "sk_test".to_string())).unwrap();
+        assert!(!platform.contains("stripe_account"));
+    }
+
+    #[test]
+    fn test_base_url_defaults_when_deserialized() {
+        let auth: Auth = serde_json::from_str(r#"{"client":"a","secret":"b"}"#).unwrap();
+        assert_eq!(auth.base_url, STRIPE_BASE_URL);
+    }
+}
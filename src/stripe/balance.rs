@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 
+use futures::TryStreamExt;
+
 use crate::stripe::auth::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
 
 /// Represents your Stripe balance.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -29,12 +34,12 @@ impl Balance {
     /// // Fetch balance
     /// let balance = payup::stripe::Balance::async_get(auth).await;
     /// ```ignore
-    pub async fn async_get(creds: Auth) -> Result<Self, reqwest::Error> {
-        let url = "https://api.stripe.com/v1/balance";
+    pub async fn async_get(creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url("balance");
         let request = get_shared_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
         let json = request.json::<Self>().await?;
         Ok(json)
@@ -55,12 +60,12 @@ impl Balance {
     /// // Fetch balance
     /// let balance = payup::stripe::Balance::get(auth);
     /// ```ignore
-    pub fn get(creds: Auth) -> Result<Self, reqwest::Error> {
-        let url = "https://api.stripe.com/v1/balance";
+    pub fn get(creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url("balance");
         let request = get_shared_blocking_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
         let json = request.json::<Self>()?;
         Ok(json)
     }
@@ -109,17 +114,56 @@ impl BalanceTransaction {
     /// // Retrieve the balance transaction with the given ID.
     /// let balance_transaction = payup::stripe::BalanceTransaction::async_get(auth, "txn_test123".to_string()).await;
     /// ```ignore
-    pub async fn async_get(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/balance_transactions/{}", id);
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("balance_transactions/{}", id));
         let request = get_shared_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
         let json = request.json::<Self>().await?;
         Ok(json)
     }
 
+    /// Iterates over all balance transactions, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of balance transactions
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the balance transactions 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::BalanceTransaction::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "balance_transactions", page)
+    }
+
+    /// Asynchronously streams all balance transactions, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::BalanceTransaction::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "balance_transactions", page)
+    }
+
     /// Asynchronously lists all balance transactions
     ///
     /// # Arguments
@@ -135,20 +179,8 @@ impl BalanceTransaction {
     /// // List all balance transactions.
     /// let balance_transactions = payup::stripe::BalanceTransaction::async_list(auth).await;
     /// ```ignore
-    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, reqwest::Error> {
-        let mut objects = Vec::new();
-        let mut has_more = true;
-        let mut starting_after = None;
-
-        while has_more {
-            let json = Self::list_chunk_async(creds.clone(), starting_after.clone()).await?;
-            for json_object in json.data {
-                objects.push(json_object);
-            }
-            has_more = json.has_more;
-            starting_after = objects.last().map(|obj| obj.id.clone());
-        }
-        Ok(objects)
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
     }
 
     /// Retrieves the balance transaction with the given ID.
@@ -167,12 +199,12 @@ impl BalanceTransaction {
     /// // Retrieve the balance transaction with the given ID.
     /// let balance_transaction = payup::stripe::BalanceTransaction::get(auth, "txn_test123".to_string());
     /// ```ignore
-    pub fn get(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/balance_transactions/{}", id);
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("balance_transactions/{}", id));
         let request = get_shared_blocking_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
         let json = request.json::<Self>()?;
         Ok(json)
     }
@@ -192,61 +224,8 @@ impl BalanceTransaction {
     /// // List all balance transactions.
     /// let balance_transactions = payup::stripe::BalanceTransaction::list(auth);
     /// ```ignore
-    pub fn list(creds: Auth) -> Result<Vec<Self>, reqwest::Error> {
-        let mut objects = Vec::new();
-        let mut has_more = true;
-        let mut starting_after = None;
-
-        while has_more {
-            let json = Self::list_chunk(creds.clone(), starting_after.clone())?;
-            for json_object in json.data {
-                objects.push(json_object);
-            }
-            has_more = json.has_more;
-            starting_after = objects.last().map(|obj| obj.id.clone());
-        }
-        Ok(objects)
-    }
-
-    fn list_chunk(
-        creds: Auth,
-        starting_after: Option<String>,
-    ) -> Result<BalanceTransactions, reqwest::Error> {
-        let url = match starting_after {
-            Some(ref id) => format!(
-                "https://api.stripe.com/v1/balance_transactions?starting_after={}",
-                id
-            ),
-            None => "https://api.stripe.com/v1/balance_transactions".to_string(),
-        };
-
-        let request = get_shared_blocking_client()
-            .get(url)
-            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
-        let json = request.json::<BalanceTransactions>()?;
-        Ok(json)
-    }
-
-    async fn list_chunk_async(
-        creds: Auth,
-        starting_after: Option<String>,
-    ) -> Result<BalanceTransactions, reqwest::Error> {
-        let url = match starting_after {
-            Some(ref id) => format!(
-                "https://api.stripe.com/v1/balance_transactions?starting_after={}",
-                id
-            ),
-            None => "https://api.stripe.com/v1/balance_transactions".to_string(),
-        };
-
-        let request = get_shared_client()
-            .get(url)
-            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
-            .await?;
-        let json = request.json::<BalanceTransactions>().await?;
-        Ok(json)
+    pub fn list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_iter(creds, PageRequest::new()).collect()
     }
 }
 
@@ -0,0 +1,350 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// Payload key Stripe reads the customer ID from unless a meter maps it elsewhere
+pub const DEFAULT_CUSTOMER_KEY: &str = "stripe_customer_id";
+/// Payload key Stripe reads the usage value from unless a meter maps it elsewhere
+pub const DEFAULT_VALUE_KEY: &str = "value";
+
+/// How a meter combines the values of its events over a billing period
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterAggregation {
+    Sum,
+    Count,
+    Last,
+}
+
+/// Status of a meter
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum MeterStatus {
+    Active,
+    Inactive,
+}
+
+/// A billing meter, which turns meter events into usage for metered prices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Meter {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "billing.meter")
+    pub object: String,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    pub customer_mapping: MeterCustomerMapping,
+    pub default_aggregation: MeterDefaultAggregation,
+    pub display_name: String,
+    /// The `event_name` meter events must carry to count towards this meter
+    pub event_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub event_time_window: Option<String>,
+    pub livemode: bool,
+    pub status: MeterStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub updated: Option<i64>,
+    pub value_settings: MeterValueSettings,
+}
+
+/// Where a meter finds the customer in an event's payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterCustomerMapping {
+    pub event_payload_key: String,
+    /// Always `by_id`
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterDefaultAggregation {
+    pub formula: MeterAggregation,
+}
+
+/// Where a meter finds the usage value in an event's payload
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterValueSettings {
+    pub event_payload_key: String,
+}
+
+/// Parameters for creating a meter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMeterParams {
+    pub default_aggregation: MeterDefaultAggregation,
+    pub display_name: String,
+    pub event_name: String,
+    /// Defaults to reading the customer from `stripe_customer_id`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_mapping: Option<MeterCustomerMapping>,
+    /// Defaults to reading the value from `value`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_settings: Option<MeterValueSettings>,
+}
+
+impl CreateMeterParams {
+    /// A meter named `display_name` that aggregates events called `event_name`
+    /// with `formula`, using the default payload keys
+    pub fn new(display_name: &str, event_name: &str, formula: MeterAggregation) -> Self {
+        Self {
+            default_aggregation: MeterDefaultAggregation { formula },
+            display_name: display_name.to_string(),
+            event_name: event_name.to_string(),
+            customer_mapping: None,
+            value_settings: None,
+        }
+    }
+}
+
+/// A usage event reported to a meter
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterEvent {
+    /// String representing the object's type (always "billing.meter_event")
+    pub object: String,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    pub event_name: String,
+    /// Unique per event; Stripe drops later events with the same identifier
+    pub identifier: String,
+    pub livemode: bool,
+    pub payload: HashMap<String, String>,
+    /// The time the usage occurred (Unix timestamp)
+    pub timestamp: i64,
+}
+
+/// Parameters for reporting a meter event
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateMeterEventParams {
+    pub event_name: String,
+    /// The customer and value, under the keys the meter maps
+    pub payload: HashMap<String, String>,
+    /// Defaults to a random identifier
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub identifier: Option<String>,
+    /// When the usage occurred (Unix timestamp), defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+}
+
+impl CreateMeterEventParams {
+    /// An event reporting `value` for `customer_id`, using the default payload keys
+    pub fn new(event_name: &str, customer_id: &str, value: u64) -> Self {
+        let payload = HashMap::from([
+            (DEFAULT_CUSTOMER_KEY.to_string(), customer_id.to_string()),
+            (DEFAULT_VALUE_KEY.to_string(), value.to_string()),
+        ]);
+        Self {
+            event_name: event_name.to_string(),
+            payload,
+            identifier: None,
+            timestamp: None,
+        }
+    }
+}
+
+/// A customer's aggregated usage on a meter over a time window
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterEventSummary {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "billing.meter_event_summary")
+    pub object: String,
+    pub aggregated_value: f64,
+    pub end_time: i64,
+    pub livemode: bool,
+    pub meter: String,
+    pub start_time: i64,
+}
+
+/// Which customer and time range to summarize a meter for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct MeterEventSummaryParams {
+    pub customer: String,
+    /// Start of the range (Unix timestamp, inclusive)
+    pub start_time: i64,
+    /// End of the range (Unix timestamp, exclusive)
+    pub end_time: i64,
+    /// Split the range into `hour` or `day` windows instead of one summary
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value_grouping_window: Option<String>,
+}
+
+impl Meter {
+    /// Create a new meter
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::billing_meter::{CreateMeterParams, Meter, MeterAggregation};
+    ///
+    /// let meter = Meter::create(&auth, CreateMeterParams::new("API requests", "api_requests", MeterAggregation::Sum))?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateMeterParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("billing/meters"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new meter (async)
+    pub async fn create_async(auth: &Auth, params: CreateMeterParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("billing/meters"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a meter by ID
+    pub fn retrieve(auth: &Auth, meter_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("billing/meters/{}", meter_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a meter by ID (async)
+    pub async fn retrieve_async(auth: &Auth, meter_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("billing/meters/{}", meter_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Stop a meter from accepting events
+    pub fn deactivate(auth: &Auth, meter_id: &str) -> Result<Self> {
+        Self::set_active(auth, meter_id, "deactivate")
+    }
+
+    /// Stop a meter from accepting events (async)
+    pub async fn deactivate_async(auth: &Auth, meter_id: &str) -> Result<Self> {
+        Self::set_active_async(auth, meter_id, "deactivate").await
+    }
+
+    /// Let a deactivated meter accept events again
+    pub fn reactivate(auth: &Auth, meter_id: &str) -> Result<Self> {
+        Self::set_active(auth, meter_id, "reactivate")
+    }
+
+    /// Let a deactivated meter accept events again (async)
+    pub async fn reactivate_async(auth: &Auth, meter_id: &str) -> Result<Self> {
+        Self::set_active_async(auth, meter_id, "reactivate").await
+    }
+
+    fn set_active(auth: &Auth, meter_id: &str, action: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("billing/meters/{}/{}", meter_id, action)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    async fn set_active_async(auth: &Auth, meter_id: &str, action: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("billing/meters/{}/{}", meter_id, action)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List meters, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List meters (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over all meters, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "billing/meters", page)
+    }
+
+    /// Stream all meters, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "billing/meters", page)
+    }
+
+    /// Iterate over a customer's usage summaries on a meter, fetching further pages as needed
+    pub fn event_summaries_iter(
+        auth: &Auth,
+        meter_id: &str,
+        params: &MeterEventSummaryParams,
+        page: PageRequest,
+    ) -> Paginator<MeterEventSummary> {
+        pagination::list_iter(auth.clone(), &Self::event_summaries_path(meter_id, params), page)
+    }
+
+    /// Stream a customer's usage summaries on a meter, fetching further pages as needed
+    pub fn event_summaries_stream(
+        auth: &Auth,
+        meter_id: &str,
+        params: &MeterEventSummaryParams,
+        page: PageRequest,
+    ) -> PageStream<MeterEventSummary> {
+        pagination::list_stream(auth.clone(), &Self::event_summaries_path(meter_id, params), page)
+    }
+
+    fn event_summaries_path(meter_id: &str, params: &MeterEventSummaryParams) -> String {
+        format!("billing/meters/{}/event_summaries?{}", meter_id, form::to_query(params))
+    }
+}
+
+impl MeterEvent {
+    /// Report usage to the meter listening for `params.event_name`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::billing_meter::{CreateMeterEventParams, MeterEvent};
+    ///
+    /// MeterEvent::create(&auth, CreateMeterEventParams::new("api_requests", "cus_123", 25))?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateMeterEventParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("billing/meter_events"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Report usage to the meter listening for `params.event_name` (async)
+    pub async fn create_async(auth: &Auth, params: CreateMeterEventParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("billing/meter_events"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
@@ -0,0 +1,544 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::coupon::DiscountItem;
+use super::subscription::SubscriptionItemParams;
+use super::subscription_item::ProrationBehavior;
+use super::Auth;
+
+/// A customer's session in the billing portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingPortalSession {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "billing_portal.session")
+    pub object: String,
+    /// The configuration the session uses
+    pub configuration: String,
+    pub created: i64,
+    /// ID of the customer the session is for
+    pub customer: String,
+    /// The flow the session opens with, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flow: Option<PortalFlow>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    /// Where the customer goes when they leave the portal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_url: Option<String>,
+    /// The short-lived URL to send the customer to
+    pub url: String,
+}
+
+/// The task a portal session sends the customer straight to
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortalFlowType {
+    PaymentMethodUpdate,
+    SubscriptionCancel,
+    SubscriptionUpdate,
+    /// Confirm a plan change chosen outside the portal
+    SubscriptionUpdateConfirm,
+}
+
+/// What happens once the customer completes a flow
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortalAfterCompletionType {
+    HostedConfirmation,
+    PortalHomepage,
+    Redirect,
+}
+
+/// What happens once the customer completes a flow
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalAfterCompletion {
+    #[serde(rename = "type")]
+    pub type_field: PortalAfterCompletionType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosted_confirmation: Option<PortalHostedConfirmation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<PortalRedirect>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalHostedConfirmation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalRedirect {
+    pub return_url: String,
+}
+
+/// The subscription a cancel or update flow is for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalFlowSubscription {
+    pub subscription: String,
+}
+
+/// A flow as returned on a session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalFlow {
+    #[serde(rename = "type")]
+    pub type_field: PortalFlowType,
+    pub after_completion: PortalAfterCompletion,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_cancel: Option<PortalFlowSubscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_update: Option<PortalFlowSubscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_update_confirm: Option<serde_json::Value>,
+}
+
+/// A plan change for the customer to confirm
+#[derive(Debug, Clone, Serialize)]
+pub struct PortalSubscriptionUpdateConfirm {
+    pub subscription: String,
+    /// The subscription's items after the change; Stripe takes a single item
+    pub items: Vec<SubscriptionItemParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discounts: Option<Vec<DiscountItem>>,
+}
+
+/// A flow to open a portal session with
+#[derive(Debug, Clone, Serialize)]
+pub struct PortalFlowParams {
+    #[serde(rename = "type")]
+    pub type_field: PortalFlowType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_completion: Option<PortalAfterCompletion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_cancel: Option<PortalFlowSubscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_update: Option<PortalFlowSubscription>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_update_confirm: Option<PortalSubscriptionUpdateConfirm>,
+}
+
+impl PortalFlowParams {
+    fn new(type_field: PortalFlowType) -> Self {
+        Self {
+            type_field,
+            after_completion: None,
+            subscription_cancel: None,
+            subscription_update: None,
+            subscription_update_confirm: None,
+        }
+    }
+
+    /// Let the customer replace their default payment method
+    pub fn payment_method_update() -> Self {
+        Self::new(PortalFlowType::PaymentMethodUpdate)
+    }
+
+    /// Let the customer cancel `subscription_id`
+    pub fn subscription_cancel(subscription_id: &str) -> Self {
+        Self {
+            subscription_cancel: Some(PortalFlowSubscription { subscription: subscription_id.to_string() }),
+            ..Self::new(PortalFlowType::SubscriptionCancel)
+        }
+    }
+
+    /// Let the customer pick a new plan for `subscription_id`
+    pub fn subscription_update(subscription_id: &str) -> Self {
+        Self {
+            subscription_update: Some(PortalFlowSubscription { subscription: subscription_id.to_string() }),
+            ..Self::new(PortalFlowType::SubscriptionUpdate)
+        }
+    }
+
+    /// Ask the customer to confirm `item`, e.g. `SubscriptionItemParams::quantity`
+    /// or a price swap on an existing item, as the new state of `subscription_id`
+    pub fn subscription_update_confirm(subscription_id: &str, item: SubscriptionItemParams) -> Self {
+        Self {
+            subscription_update_confirm: Some(PortalSubscriptionUpdateConfirm {
+                subscription: subscription_id.to_string(),
+                items: vec![item],
+                discounts: None,
+            }),
+            ..Self::new(PortalFlowType::SubscriptionUpdateConfirm)
+        }
+    }
+
+    /// Send the customer to `return_url` once the flow is done
+    pub fn redirect_to(mut self, return_url: &str) -> Self {
+        self.after_completion = Some(PortalAfterCompletion {
+            type_field: PortalAfterCompletionType::Redirect,
+            hosted_confirmation: None,
+            redirect: Some(PortalRedirect { return_url: return_url.to_string() }),
+        });
+        self
+    }
+}
+
+/// Parameters for creating a portal session
+#[derive(Debug, Clone, Serialize)]
+pub struct CreateBillingPortalSessionParams {
+    /// ID of the customer to open the portal for
+    pub customer: String,
+    /// Configuration to use instead of the default one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flow_data: Option<PortalFlowParams>,
+    /// IETF language tag, or "auto" to use the browser's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub locale: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    /// Where the customer goes when they leave the portal, defaulting to the
+    /// configuration's `default_return_url`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_url: Option<String>,
+}
+
+impl CreateBillingPortalSessionParams {
+    /// A session for `customer_id` returning to `return_url`
+    pub fn new(customer_id: &str, return_url: &str) -> Self {
+        Self {
+            customer: customer_id.to_string(),
+            configuration: None,
+            flow_data: None,
+            locale: None,
+            on_behalf_of: None,
+            return_url: Some(return_url.to_string()),
+        }
+    }
+
+    /// Open the session with `flow` instead of the portal's home page
+    pub fn flow(mut self, flow: PortalFlowParams) -> Self {
+        self.flow_data = Some(flow);
+        self
+    }
+}
+
+impl BillingPortalSession {
+    /// Create a portal session and send the customer to its `url`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::billing_portal::{BillingPortalSession, CreateBillingPortalSessionParams, PortalFlowParams};
+    ///
+    /// let params = CreateBillingPortalSessionParams::new("cus_123", "https://example.com/account")
+    ///     .flow(PortalFlowParams::subscription_cancel("sub_123"));
+    /// let session = BillingPortalSession::create(&auth, params)?;
+    /// // Redirect the customer to session.url
+    /// ```
+    pub fn create(auth: &Auth, params: CreateBillingPortalSessionParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("billing_portal/sessions"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a portal session (async)
+    pub async fn create_async(auth: &Auth, params: CreateBillingPortalSessionParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("billing_portal/sessions"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+/// What customers can see and do in the portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct BillingPortalConfiguration {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "billing_portal.configuration")
+    pub object: String,
+    pub active: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    pub business_profile: PortalBusinessProfile,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_return_url: Option<String>,
+    pub features: PortalFeatures,
+    /// Whether sessions use this configuration unless given another
+    pub is_default: bool,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_page: Option<PortalLoginPage>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    pub updated: i64,
+}
+
+/// Business details shown in the portal
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortalBusinessProfile {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub headline: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub privacy_policy_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub terms_of_service_url: Option<String>,
+}
+
+/// A shareable link where customers log in to the portal by email
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalLoginPage {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// The portal's features; when creating or updating a configuration, only
+/// the features set are sent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PortalFeatures {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_update: Option<PortalCustomerUpdate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_history: Option<PortalFeature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method_update: Option<PortalFeature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_cancel: Option<PortalSubscriptionCancel>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_update: Option<PortalSubscriptionUpdate>,
+}
+
+/// A feature that can only be switched on or off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalFeature {
+    pub enabled: bool,
+}
+
+/// Which customer details customers can change
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalCustomerUpdate {
+    pub enabled: bool,
+    /// Any of "address", "email", "name", "phone", "shipping" and "tax_id"
+    #[serde(default)]
+    pub allowed_updates: Vec<String>,
+}
+
+/// When a subscription cancelled in the portal ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PortalCancelMode {
+    AtPeriodEnd,
+    Immediately,
+}
+
+/// How customers cancel subscriptions in the portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalSubscriptionCancel {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<PortalCancelMode>,
+    /// Prorations for immediate cancellations
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation_reason: Option<PortalCancellationReason>,
+}
+
+/// Whether customers are asked why they cancel
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalCancellationReason {
+    pub enabled: bool,
+    /// Any of "too_expensive", "missing_features", "switched_service",
+    /// "unused", "customer_service", "too_complex", "low_quality" and "other"
+    #[serde(default)]
+    pub options: Vec<String>,
+}
+
+/// How customers change plans in the portal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalSubscriptionUpdate {
+    pub enabled: bool,
+    /// Any of "price", "quantity" and "promotion_code"
+    #[serde(default)]
+    pub default_allowed_updates: Vec<String>,
+    /// The products, and their prices, customers can switch between
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub products: Vec<PortalProduct>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+}
+
+/// A product customers can switch to, limited to some of its prices
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PortalProduct {
+    pub product: String,
+    pub prices: Vec<String>,
+}
+
+/// Parameters for creating a portal configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateBillingPortalConfigurationParams {
+    pub features: PortalFeatures,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_profile: Option<PortalBusinessProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_return_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_page: Option<PortalFeature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for updating a portal configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateBillingPortalConfigurationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub business_profile: Option<PortalBusinessProfile>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_return_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub features: Option<PortalFeatures>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub login_page: Option<PortalFeature>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing portal configurations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListBillingPortalConfigurationsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_default: Option<bool>,
+}
+
+impl BillingPortalConfiguration {
+    /// Create a portal configuration
+    pub fn create(auth: &Auth, params: CreateBillingPortalConfigurationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("billing_portal/configurations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a portal configuration (async)
+    pub async fn create_async(auth: &Auth, params: CreateBillingPortalConfigurationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("billing_portal/configurations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a portal configuration by ID
+    pub fn retrieve(auth: &Auth, configuration_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("billing_portal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a portal configuration by ID (async)
+    pub async fn retrieve_async(auth: &Auth, configuration_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("billing_portal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a portal configuration
+    pub fn update(
+        auth: &Auth,
+        configuration_id: &str,
+        params: UpdateBillingPortalConfigurationParams,
+    ) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("billing_portal/configurations/{}", configuration_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a portal configuration (async)
+    pub async fn update_async(
+        auth: &Auth,
+        configuration_id: &str,
+        params: UpdateBillingPortalConfigurationParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("billing_portal/configurations/{}", configuration_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List portal configurations matching `filters`, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListBillingPortalConfigurationsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List portal configurations matching `filters` (async)
+    pub async fn list_async(
+        auth: &Auth,
+        filters: &ListBillingPortalConfigurationsParams,
+        page: PageRequest,
+    ) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over portal configurations matching `filters`, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListBillingPortalConfigurationsParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream portal configurations matching `filters`, fetching further pages as needed
+    pub fn list_stream(
+        auth: &Auth,
+        filters: &ListBillingPortalConfigurationsParams,
+        page: PageRequest,
+    ) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListBillingPortalConfigurationsParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "billing_portal/configurations".to_string(),
+            query => format!("billing_portal/configurations?{}", query),
+        }
+    }
+}
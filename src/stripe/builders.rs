@@ -1,4 +1,5 @@
 use std::collections::HashMap;
+use crate::stripe::invoice::AutomaticTax;
 use crate::stripe::{
     Charge, Shipping, ShippingAddress, BillingDetails, Address,
     Event, EventData, EventRequest,
@@ -6,7 +7,8 @@ use crate::stripe::{
     Plan, Price, CreatePlanParams, CreatePriceParams, UpdatePlanParams, UpdatePriceParams,
     BillingScheme, Interval, AggregateUsage, TiersMode, UsageType, PriceType, TaxBehavior, Recurring,
     PaymentIntent, CreatePaymentIntentParams, UpdatePaymentIntentParams, ConfirmPaymentIntentParams,
-    CaptureMethod, ConfirmationMethod, SetupFutureUsage, ShippingDetails, Address as PaymentIntentAddress,
+    CaptureMethod, ConfirmationMethod, SetupFutureUsage, ShippingDetails, Address as PaymentIntentAddress, TransferData,
+    CouponDuration, CouponAppliesTo, CreateCouponParams, CreatePromotionCodeParams, PromotionCodeRestrictions,
 };
 
 /// Builder for creating a Charge
@@ -212,6 +214,7 @@ impl PaymentIntentBuilder {
             params: CreatePaymentIntentParams {
                 amount,
                 currency: currency.into(),
+                application_fee_amount: None,
                 automatic_payment_methods: None,
                 capture_method: None,
                 confirm: None,
@@ -301,6 +304,34 @@ impl PaymentIntentBuilder {
         self
     }
 
+    /// Sets the fee (in cents) the platform keeps from a Connect charge
+    pub fn application_fee_amount(mut self, amount: i64) -> Self {
+        self.params.application_fee_amount = Some(amount);
+        self
+    }
+
+    /// Makes the connected account the merchant of record
+    pub fn on_behalf_of(mut self, account: impl Into<String>) -> Self {
+        self.params.on_behalf_of = Some(account.into());
+        self
+    }
+
+    /// Makes a destination charge, transferring the amount less any
+    /// application fee to the connected account
+    pub fn transfer_destination(mut self, account: impl Into<String>) -> Self {
+        self.params.transfer_data = Some(TransferData {
+            amount: None,
+            destination: account.into(),
+        });
+        self
+    }
+
+    /// Sets the transfer group, for matching separate transfers to this payment
+    pub fn transfer_group(mut self, group: impl Into<String>) -> Self {
+        self.params.transfer_group = Some(group.into());
+        self
+    }
+
     /// Builds the CreatePaymentIntentParams
     pub fn build(self) -> CreatePaymentIntentParams {
         self.params
@@ -324,6 +355,7 @@ impl InvoiceBuilder {
                 metadata: None,
                 subscription: None,
                 account_tax_ids: None,
+                automatic_tax: None,
                 application_fee_amount: None,
                 custom_fields: None,
                 days_until_due: None,
@@ -400,6 +432,24 @@ impl InvoiceBuilder {
         self
     }
 
+    /// Enables or disables automatic tax calculation with Stripe Tax
+    pub fn automatic_tax(mut self, enabled: bool) -> Self {
+        self.params.automatic_tax = Some(AutomaticTax { enabled, status: None });
+        self
+    }
+
+    /// Sets the tax rates applied to line items without tax rates of their own
+    pub fn default_tax_rates(mut self, tax_rates: Vec<String>) -> Self {
+        self.params.default_tax_rates = Some(tax_rates);
+        self
+    }
+
+    /// Sets the account tax IDs shown on the invoice
+    pub fn account_tax_ids(mut self, tax_ids: Vec<String>) -> Self {
+        self.params.account_tax_ids = Some(tax_ids);
+        self
+    }
+
     /// Builds the CreateInvoiceParams
     pub fn build(self) -> CreateInvoiceParams {
         self.params
@@ -567,7 +617,8 @@ impl PriceBuilder {
         self
     }
 
-    /// Sets the tax behavior
+    /// Sets whether the unit amount includes tax; automatic tax needs this
+    /// unless the account has a default tax behavior
     pub fn tax_behavior(mut self, behavior: TaxBehavior) -> Self {
         self.params.tax_behavior = Some(behavior);
         self
@@ -597,6 +648,175 @@ impl PriceBuilder {
     }
 }
 
+/// Builder for creating Coupon parameters
+pub struct CouponBuilder {
+    params: CreateCouponParams,
+}
+
+impl CouponBuilder {
+    fn new(duration: CouponDuration) -> Self {
+        CouponBuilder {
+            params: CreateCouponParams {
+                duration,
+                id: None,
+                amount_off: None,
+                currency: None,
+                percent_off: None,
+                duration_in_months: None,
+                applies_to: None,
+                max_redemptions: None,
+                redeem_by: None,
+                name: None,
+                metadata: None,
+            },
+        }
+    }
+
+    /// Creates a CouponBuilder taking `percent` percent off
+    pub fn percent_off(percent: f64, duration: CouponDuration) -> Self {
+        let mut builder = Self::new(duration);
+        builder.params.percent_off = Some(percent);
+        builder
+    }
+
+    /// Creates a CouponBuilder taking a fixed amount (in cents) off
+    pub fn amount_off(amount: i64, currency: impl Into<String>, duration: CouponDuration) -> Self {
+        let mut builder = Self::new(duration);
+        builder.params.amount_off = Some(amount);
+        builder.params.currency = Some(currency.into());
+        builder
+    }
+
+    /// Sets a custom coupon ID
+    pub fn id(mut self, id: impl Into<String>) -> Self {
+        self.params.id = Some(id.into());
+        self
+    }
+
+    /// Sets the number of months a repeating coupon applies for
+    pub fn duration_in_months(mut self, months: i64) -> Self {
+        self.params.duration_in_months = Some(months);
+        self
+    }
+
+    /// Sets how many times the coupon can be redeemed in total
+    pub fn max_redemptions(mut self, max: u64) -> Self {
+        self.params.max_redemptions = Some(max);
+        self
+    }
+
+    /// Sets the last time the coupon can be redeemed (Unix timestamp)
+    pub fn redeem_by(mut self, timestamp: i64) -> Self {
+        self.params.redeem_by = Some(timestamp);
+        self
+    }
+
+    /// Limits the coupon to the given products
+    pub fn applies_to_products(mut self, products: Vec<String>) -> Self {
+        self.params.applies_to = Some(CouponAppliesTo { products });
+        self
+    }
+
+    /// Sets the name shown to customers
+    pub fn name(mut self, name: impl Into<String>) -> Self {
+        self.params.name = Some(name.into());
+        self
+    }
+
+    /// Sets metadata
+    pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.params.metadata = Some(metadata);
+        self
+    }
+
+    /// Builds the CreateCouponParams
+    pub fn build(self) -> CreateCouponParams {
+        self.params
+    }
+}
+
+/// Builder for creating PromotionCode parameters
+pub struct PromotionCodeBuilder {
+    params: CreatePromotionCodeParams,
+}
+
+impl PromotionCodeBuilder {
+    /// Creates a new PromotionCodeBuilder redeeming `coupon`
+    pub fn new(coupon: impl Into<String>) -> Self {
+        PromotionCodeBuilder {
+            params: CreatePromotionCodeParams {
+                coupon: coupon.into(),
+                code: None,
+                active: None,
+                customer: None,
+                expires_at: None,
+                max_redemptions: None,
+                metadata: None,
+                restrictions: None,
+            },
+        }
+    }
+
+    /// Sets the customer-facing code
+    pub fn code(mut self, code: impl Into<String>) -> Self {
+        self.params.code = Some(code.into());
+        self
+    }
+
+    /// Sets whether the code can be redeemed
+    pub fn active(mut self, active: bool) -> Self {
+        self.params.active = Some(active);
+        self
+    }
+
+    /// Limits redemption to one customer
+    pub fn customer(mut self, customer: impl Into<String>) -> Self {
+        self.params.customer = Some(customer.into());
+        self
+    }
+
+    /// Sets when the code expires (Unix timestamp)
+    pub fn expires_at(mut self, timestamp: i64) -> Self {
+        self.params.expires_at = Some(timestamp);
+        self
+    }
+
+    /// Sets how many times the code can be redeemed
+    pub fn max_redemptions(mut self, max: u64) -> Self {
+        self.params.max_redemptions = Some(max);
+        self
+    }
+
+    /// Limits redemption to customers without a previous payment
+    pub fn first_time_transaction(mut self, first_time: bool) -> Self {
+        self.restrictions().first_time_transaction = first_time;
+        self
+    }
+
+    /// Sets the minimum order amount (in cents) the code applies to
+    pub fn minimum_amount(mut self, amount: i64, currency: impl Into<String>) -> Self {
+        let restrictions = self.restrictions();
+        restrictions.minimum_amount = Some(amount);
+        restrictions.minimum_amount_currency = Some(currency.into());
+        self
+    }
+
+    /// Sets metadata
+    pub fn metadata(mut self, metadata: HashMap<String, String>) -> Self {
+        self.params.metadata = Some(metadata);
+        self
+    }
+
+    /// Builds the CreatePromotionCodeParams
+    pub fn build(self) -> CreatePromotionCodeParams {
+        self.params
+    }
+
+    fn restrictions(&mut self) -> &mut PromotionCodeRestrictions {
+        self.params.restrictions.get_or_insert_with(PromotionCodeRestrictions::default)
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -660,6 +880,19 @@ mod tests {
         assert_eq!(params.capture_method, Some(CaptureMethod::Manual));
     }
 
+    #[test]
+    fn test_payment_intent_builder_destination_charge() {
+        let params = PaymentIntentBuilder::new(10000, "usd")
+            .application_fee_amount(1230)
+            .on_behalf_of("acct_123")
+            .transfer_destination("acct_123")
+            .build();
+
+        assert_eq!(params.application_fee_amount, Some(1230));
+        assert_eq!(params.on_behalf_of, Some("acct_123".to_string()));
+        assert_eq!(params.transfer_data.unwrap().destination, "acct_123");
+    }
+
     #[test]
     fn test_invoice_builder() {
         let params = InvoiceBuilder::new("cust_123")
@@ -674,6 +907,18 @@ mod tests {
         assert_eq!(params.days_until_due, Some(30));
     }
 
+    #[test]
+    fn test_invoice_builder_automatic_tax() {
+        let params = InvoiceBuilder::new("cust_123")
+            .automatic_tax(true)
+            .account_tax_ids(vec!["txi_123".to_string()])
+            .build();
+
+        assert!(params.automatic_tax.unwrap().enabled);
+        assert_eq!(params.account_tax_ids, Some(vec!["txi_123".to_string()]));
+        assert_eq!(params.default_tax_rates, None);
+    }
+
     #[test]
     fn test_plan_builder() {
         let params = PlanBuilder::new("usd", Interval::Month, "prod_123")
@@ -708,4 +953,37 @@ mod tests {
         assert_eq!(params.tax_behavior, Some(TaxBehavior::Inclusive));
         assert!(params.recurring.is_some());
     }
+
+    #[test]
+    fn test_coupon_builder() {
+        let params = CouponBuilder::amount_off(500, "usd", CouponDuration::Repeating)
+            .duration_in_months(3)
+            .max_redemptions(100)
+            .applies_to_products(vec!["prod_123".to_string()])
+            .build();
+
+        assert_eq!(params.duration, CouponDuration::Repeating);
+        assert_eq!(params.amount_off, Some(500));
+        assert_eq!(params.currency, Some("usd".to_string()));
+        assert_eq!(params.percent_off, None);
+        assert_eq!(params.duration_in_months, Some(3));
+        assert_eq!(params.max_redemptions, Some(100));
+        assert_eq!(params.applies_to.unwrap().products, vec!["prod_123".to_string()]);
+    }
+
+    #[test]
+    fn test_promotion_code_builder() {
+        let params = PromotionCodeBuilder::new("co_123")
+            .code("SPRING25")
+            .first_time_transaction(true)
+            .minimum_amount(5000, "usd")
+            .build();
+
+        assert_eq!(params.coupon, "co_123");
+        assert_eq!(params.code, Some("SPRING25".to_string()));
+        let restrictions = params.restrictions.unwrap();
+        assert!(restrictions.first_time_transaction);
+        assert_eq!(restrictions.minimum_amount, Some(5000));
+        assert_eq!(restrictions.minimum_amount_currency, Some("usd".to_string()));
+    }
 }
\ No newline at end of file
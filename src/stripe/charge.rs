@@ -1,7 +1,14 @@
 use serde::{Deserialize, Serialize};
 
+use futures::TryStreamExt;
+
 use crate::stripe::auth::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::search::SearchQuery;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
 
 /// Shipping information for charges
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -126,6 +133,7 @@ impl Card {
 pub struct Charge {
     pub id: Option<String>,
     pub object: Option<String>,
+    #[serde(default, deserialize_with = "amount_as_string")]
     pub amount: Option<String>,
     #[serde(rename = "amount")]
     pub stripe_amount: Option<i64>,
@@ -142,6 +150,7 @@ pub struct Charge {
     pub currency: Option<String>,
     pub description: Option<String>,
     pub disputed: Option<bool>,
+    /// Fraud reports on the charge, by Stripe and by you
     #[serde(rename = "fraud_details")]
     pub fraud_details: Option<FraudDetails>,
     pub livemode: Option<bool>,
@@ -167,7 +176,8 @@ pub struct Charge {
     // #[serde(rename = "on_behalf_of")]
     // pub on_behalf_of: Value,
     // pub order: Value,
-    // pub outcome: Value,
+    /// Radar's risk assessment and whether the charge was authorized
+    pub outcome: Option<ChargeOutcome>,
     // #[serde(rename = "payment_intent")]
     // pub payment_intent: Value,
     #[serde(rename = "receipt_email")]
@@ -175,7 +185,8 @@ pub struct Charge {
     pub source: Option<String>,
     // #[serde(rename = "receipt_number")]
     // pub receipt_number: Value,
-    // pub review: Value,
+    /// The Radar review the charge is held in, if any
+    pub review: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub shipping: Option<Shipping>,
     // #[serde(rename = "source_transfer")]
@@ -195,6 +206,19 @@ pub struct Charge {
     // pub application_fee_amount: Value,
 }
 
+/// Reads `amount`, an integer in Stripe's responses, into the string field
+/// sent with requests
+fn amount_as_string<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(amount)) => Some(amount),
+        Some(serde_json::Value::Number(amount)) => Some(amount.to_string()),
+        _ => None,
+    })
+}
+
 impl Charge {
     /// Returns an empty Charge object
     ///
@@ -226,6 +250,8 @@ impl Charge {
             description: None,
             disputed: None,
             fraud_details: None,
+            outcome: None,
+            review: None,
             livemode: None,
             paid: None,
             payment_method: None,
@@ -269,16 +295,17 @@ impl Charge {
     /// let captured_charge = charge.async_capture(auth.clone()).await?;
     /// ```ignore
     pub async fn async_capture(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
-        let url = format!(
-            "https://api.stripe.com/v1/charges/{}/capture",
+        let url = creds.url(&format!(
+            "charges/{}/capture",
             self.id.clone().ok_or_else(|| crate::error::PayupError::ValidationError("Charge ID is required for capture".to_string()))?
-        );
+        ));
 
         let request = get_shared_client()
             .post(url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_capture_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<Self>().await?;
@@ -301,17 +328,56 @@ impl Charge {
     /// // Fetch customer using id
     /// let charge = payup::stripe::Charge::async_get(auth, "ch_test123".to_string()).await?;
     /// ```ignore
-    pub async fn async_get(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/charges/{}", id);
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("charges/{}", id));
         let request = get_shared_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
         let json = request.json::<Self>().await?;
         Ok(json)
     }
 
+    /// Iterates over all charges, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of charges
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the charges 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::Charge::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "charges", page)
+    }
+
+    /// Asynchronously streams all charges, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::Charge::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "charges", page)
+    }
+
     /// Asynchronously returns all stripe charges.
     ///
     /// # Arguments
@@ -327,22 +393,56 @@ impl Charge {
     /// // Fetch all customers from stripe
     /// let charges = payup::stripe::Charge::async_list(auth).await?;
     /// ```ignore
-    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, reqwest::Error> {
-        let mut objects = Vec::new();
-        let mut has_more = true;
-        let mut starting_after = None;
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
+    }
+
+    /// Returns an iterator over the charges matching `query`, requesting further pages as it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `query` - The search query, see payup::stripe::SearchQuery
+    /// * `page` - Page size, a saved `next_page` token as `starting_after` and an optional cap on the number of charges
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let query = payup::stripe::SearchQuery::new(payup::stripe::SearchClause::metadata("order_id").eq("6735"));
+    /// for object in payup::stripe::Charge::search_iter(auth, &query, payup::builders::PageRequest::new()) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn search_iter(creds: Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(creds, "charges/search", query, page)
+    }
 
-        while has_more {
-            let json = Self::list_chunk_async(creds.clone(), starting_after.clone()).await?;
-            for json_object in json.data {
-                objects.push(json_object);
-            }
-            has_more = json.has_more;
-            if let Some(last) = objects.last() {
-                starting_after = last.id.clone();
-            }
-        }
-        Ok(objects)
+    /// Asynchronously streams the charges matching `query`, requesting further pages as the stream is consumed.
+    pub fn search_stream(creds: Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(creds, "charges/search", query, page)
+    }
+
+    /// Asynchronously returns all charges matching `query`.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `query` - The search query, see payup::stripe::SearchQuery
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let query = payup::stripe::SearchQuery::new(payup::stripe::SearchClause::metadata("order_id").eq("6735"));
+    /// let charges = payup::stripe::Charge::async_search(auth, &query).await?;
+    /// ```ignore
+    pub async fn async_search(creds: Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_stream(creds, query, PageRequest::new()).try_collect().await
     }
 
     /// Asynchronously POSTs a new Charge to the stripe api
@@ -362,12 +462,13 @@ impl Charge {
     ///
     /// charge = charge.async_post(auth.clone()).await?;
     /// ```ignore
-    pub async fn async_post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_client()
-            .post("https://api.stripe.com/v1/charges")
+            .post(creds.url("charges"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<Self>().await?;
@@ -396,13 +497,14 @@ impl Charge {
     /// ```ignore
     pub async fn async_update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_client()
-            .post(format!(
-                "https://api.stripe.com/v1/charges/{}",
+            .post(creds.url(&format!(
+                "charges/{}",
                 self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Charge ID is required for update".to_string()))?
-            ))
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<Self>().await?;
@@ -436,16 +538,17 @@ impl Charge {
     /// let captured_charge = charge.capture(auth.clone())?;
     /// ```ignore
     pub fn capture(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
-        let url = format!(
-            "https://api.stripe.com/v1/charges/{}/capture",
+        let url = creds.url(&format!(
+            "charges/{}/capture",
             self.id.clone().unwrap()
-        );
+        ));
 
         let request = get_shared_blocking_client()
             .post(url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_capture_params())
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
         let json = request.json::<Self>()?;
         Ok(json)
@@ -467,12 +570,12 @@ impl Charge {
     /// // Fetch customer using id
     /// let charge = payup::stripe::Charge::get(auth, "ch_test123".to_string());
     /// ```ignore
-    pub fn get(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/charges/{}", id);
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("charges/{}", id));
         let request = get_shared_blocking_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
         let json = request.json::<Self>()?;
         Ok(json)
     }
@@ -492,22 +595,28 @@ impl Charge {
     /// // Fetch all customers from stripe
     /// let charges = payup::stripe::Charge::list(auth)?;
     /// ```ignore
-    pub fn list(creds: Auth) -> Result<Vec<Self>, reqwest::Error> {
-        let mut objects = Vec::new();
-        let mut has_more = true;
-        let mut starting_after = None;
+    pub fn list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_iter(creds, PageRequest::new()).collect()
+    }
 
-        while has_more {
-            let json = Self::list_chunk(creds.clone(), starting_after.clone())?;
-            for json_object in json.data {
-                objects.push(json_object);
-            }
-            has_more = json.has_more;
-            if let Some(last) = objects.last() {
-                starting_after = last.id.clone();
-            }
-        }
-        Ok(objects)
+    /// Returns all charges matching `query`.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `query` - The search query, see payup::stripe::SearchQuery
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let query = payup::stripe::SearchQuery::new(payup::stripe::SearchClause::field("amount").gt(1000));
+    /// let charges = payup::stripe::Charge::search(auth, &query)?;
+    /// ```ignore
+    pub fn search(creds: Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_iter(creds, query, PageRequest::new()).collect()
     }
 
     /// POSTs a new Charge to the stripe api
@@ -527,12 +636,13 @@ impl Charge {
     ///
     /// charge = charge.post(auth.clone())?;
     /// ```ignore
-    pub fn post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_blocking_client()
-            .post("https://api.stripe.com/v1/charges")
+            .post(creds.url("charges"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
         let json = request.json::<Self>()?;
         Ok(json)
@@ -560,60 +670,19 @@ impl Charge {
     /// ```ignore
     pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_blocking_client()
-            .post(format!(
-                "https://api.stripe.com/v1/charges/{}",
+            .post(creds.url(&format!(
+                "charges/{}",
                 self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Charge ID is required for update".to_string()))?
-            ))
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
         let json = request.json::<Self>()?;
         Ok(json)
     }
 
-    fn list_chunk(creds: Auth, starting_after: Option<String>) -> Result<Charges, reqwest::Error> {
-        let mut url = "https://api.stripe.com/v1/charges".to_string();
-
-        if starting_after.is_some() {
-            url = format!(
-                "https://api.stripe.com/v1/charges?starting_after={}",
-                starting_after.expect("starting_after should be Some at this point")
-            );
-        }
-
-        let request = get_shared_blocking_client()
-            .get(url)
-            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
-
-        let json = request.json::<Charges>()?;
-        Ok(json)
-    }
-
-    async fn list_chunk_async(
-        creds: Auth,
-        starting_after: Option<String>,
-    ) -> Result<Charges, reqwest::Error> {
-        let mut url = "https://api.stripe.com/v1/charges".to_string();
-
-        if starting_after.is_some() {
-            url = format!(
-                "https://api.stripe.com/v1/charges?starting_after={}",
-                starting_after.expect("starting_after should be Some at this point")
-            );
-        }
-
-        let request = get_shared_client()
-            .get(url)
-            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
-            .await?;
-
-        let json = request.json::<Charges>().await?;
-        Ok(json)
-    }
-
     fn to_capture_params(&self) -> Vec<(&str, &str)> {
         let mut params = vec![];
 
@@ -700,10 +769,40 @@ pub struct PaymentMethodDetails {
     pub type_field: Option<String>,
 }
 
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FraudDetails {
+    /// "fraudulent" once Stripe has flagged the charge
+    pub stripe_report: Option<String>,
+    /// "safe" or "fraudulent" once you've reported the charge
+    pub user_report: Option<String>,
+}
+
+impl FraudDetails {
+    /// Whether Stripe or you have reported the charge as fraudulent
+    pub fn is_fraudulent(&self) -> bool {
+        self.stripe_report.as_deref() == Some("fraudulent") || self.user_report.as_deref() == Some("fraudulent")
+    }
+}
+
 #[derive(Serialize, Deserialize, Debug, Clone)]
-#[serde(rename_all = "camelCase")]
-#[doc(hidden)]
-pub struct FraudDetails {}
+pub struct ChargeOutcome {
+    /// "approved_by_network", "declined_by_network", "not_sent_to_network"
+    /// or "reversed_after_approval"
+    pub network_status: Option<String>,
+    /// Why the charge was blocked or sent to review, e.g. "highest_risk_level"
+    /// or "rule"
+    pub reason: Option<String>,
+    /// "normal", "elevated", "highest", "not_assessed" or "unknown"
+    pub risk_level: Option<String>,
+    /// From 0 to 99, higher meaning riskier
+    pub risk_score: Option<u32>,
+    /// ID of the Radar rule that matched, if one did
+    pub rule: Option<String>,
+    pub seller_message: Option<String>,
+    /// "authorized", "manual_review", "issuer_declined", "blocked" or "invalid"
+    #[serde(rename = "type")]
+    pub type_field: Option<String>,
+}
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 #[serde(rename_all = "camelCase")]
@@ -0,0 +1,432 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use crate::stripe_ext::product::Product;
+use super::coupon::Discount;
+use super::plan::{Interval, Price, TaxBehavior};
+use super::Auth;
+
+/// A Checkout Session is a customer's session as they pay through a Stripe-hosted payment page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Session {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "checkout.session")
+    pub object: String,
+    /// Total of all items before discounts or taxes are applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_subtotal: Option<i64>,
+    /// Total of all items after discounts and taxes are applied
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_total: Option<i64>,
+    /// The URL the customer will be directed to if they decide to cancel payment
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_url: Option<String>,
+    /// A unique string to reference the session, e.g. an order or cart ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference_id: Option<String>,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    /// Three-letter ISO currency code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// The ID of the customer for this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    /// The email address the customer entered, or the one prefilled on create
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_email: Option<String>,
+    /// The time at which the session will expire (Unix timestamp)
+    pub expires_at: i64,
+    /// The line items purchased, only present when expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<LineItemList>,
+    /// Has the value true if the object exists in live mode
+    pub livemode: bool,
+    /// Set of key-value pairs that you can attach to an object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// The mode of the session
+    pub mode: SessionMode,
+    /// The ID of the PaymentIntent for sessions in payment mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_intent: Option<String>,
+    /// The ID of the Payment Link that created this session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_link: Option<String>,
+    /// Whether the payment has been collected
+    pub payment_status: SessionPaymentStatus,
+    /// The ID of the SetupIntent for sessions in setup mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub setup_intent: Option<String>,
+    /// The status of the session
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<SessionStatus>,
+    /// The ID of the subscription for sessions in subscription mode
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+    /// The URL the customer will be directed to after the payment or subscription creation is successful
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_url: Option<String>,
+    /// The URL of the hosted payment page, only present while the session is open
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub url: Option<String>,
+}
+
+/// Checkout session mode
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionMode {
+    Payment,
+    Setup,
+    Subscription,
+}
+
+/// Checkout session status
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionStatus {
+    Open,
+    Complete,
+    Expired,
+}
+
+/// Whether a checkout session has been paid
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+#[serde(rename_all = "snake_case")]
+pub enum SessionPaymentStatus {
+    Paid,
+    Unpaid,
+    NoPaymentRequired,
+}
+
+/// A line item purchased through a Checkout Session or Payment Link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItem {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "item")
+    pub object: String,
+    /// Total before any discounts or taxes are applied
+    pub amount_subtotal: i64,
+    /// Total after discounts and taxes
+    pub amount_total: i64,
+    /// Three-letter ISO currency code
+    pub currency: String,
+    /// Defaults to the product name
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The price used to generate the line item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+    /// The quantity of products being purchased
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    /// The discounts applied to the line item, only present when expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discounts: Option<Vec<LineItemDiscount>>,
+}
+
+/// A discount applied to a line item and how much it took off
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItemDiscount {
+    pub amount: i64,
+    pub discount: Discount,
+}
+
+/// List of line items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LineItemList {
+    pub object: String,
+    pub data: Vec<LineItem>,
+    pub has_more: bool,
+    pub url: String,
+}
+
+/// Parameters for creating a checkout session
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSessionParams {
+    /// The mode of the session
+    pub mode: SessionMode,
+    /// The items the customer is purchasing
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<SessionLineItemParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub success_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancel_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_reference_id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_email: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_promotion_codes: Option<bool>,
+    /// The time at which the session will expire (Unix timestamp), 30 minutes to 24 hours after creation
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method_types: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateSessionParams {
+    /// Parameters for a session in `mode` selling `line_items`
+    pub fn new(mode: SessionMode, line_items: Vec<SessionLineItemParams>) -> Self {
+        Self {
+            mode,
+            line_items,
+            success_url: None,
+            cancel_url: None,
+            client_reference_id: None,
+            customer: None,
+            customer_email: None,
+            allow_promotion_codes: None,
+            expires_at: None,
+            payment_method_types: None,
+            metadata: None,
+        }
+    }
+}
+
+/// A line item to sell, given either an existing price or inline price data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SessionLineItemParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_data: Option<PriceData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustable_quantity: Option<AdjustableQuantity>,
+}
+
+impl SessionLineItemParams {
+    /// Sells `quantity` of an existing price
+    pub fn price(price: &Price, quantity: u64) -> Self {
+        Self {
+            price: Some(price.id.clone()),
+            price_data: None,
+            quantity: Some(quantity),
+            adjustable_quantity: None,
+        }
+    }
+
+    /// Sells `quantity` of a price created inline for this session
+    pub fn price_data(price_data: PriceData, quantity: u64) -> Self {
+        Self {
+            price: None,
+            price_data: Some(price_data),
+            quantity: Some(quantity),
+            adjustable_quantity: None,
+        }
+    }
+}
+
+/// Lets the customer change the quantity of a line item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AdjustableQuantity {
+    pub enabled: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum: Option<u64>,
+}
+
+/// Inline price data, for a product that exists or one created with the price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceData {
+    /// Three-letter ISO currency code
+    pub currency: String,
+    /// The unit amount in cents
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_amount: Option<i64>,
+    /// The ID of an existing product
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    /// A product to create along with the price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product_data: Option<ProductData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurring: Option<PriceDataRecurring>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_behavior: Option<TaxBehavior>,
+}
+
+impl PriceData {
+    /// `unit_amount` in `currency` for an existing product
+    pub fn for_product(product: &Product, currency: &str, unit_amount: i64) -> Self {
+        Self {
+            currency: currency.to_string(),
+            unit_amount: Some(unit_amount),
+            product: product.id.clone(),
+            product_data: if product.id.is_none() { Some(ProductData::from(product)) } else { None },
+            recurring: None,
+            tax_behavior: None,
+        }
+    }
+}
+
+/// Recurring settings of inline price data
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PriceDataRecurring {
+    pub interval: Interval,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval_count: Option<u32>,
+}
+
+/// A product created inline with its price
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProductData {
+    pub name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub images: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_code: Option<String>,
+}
+
+impl From<&Product> for ProductData {
+    fn from(product: &Product) -> Self {
+        Self {
+            name: product.name.clone(),
+            description: product.description.clone(),
+            images: product.images.clone(),
+            metadata: product.metadata.clone(),
+            tax_code: product.tax_code.clone(),
+        }
+    }
+}
+
+impl Session {
+    /// Create a new checkout session
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::checkout::{CreateSessionParams, Session, SessionLineItemParams, SessionMode};
+    ///
+    /// let mut params = CreateSessionParams::new(SessionMode::Payment, vec![SessionLineItemParams::price(&price, 2)]);
+    /// params.success_url = Some("https://example.com/success".to_string());
+    /// let session = Session::create(&auth, params)?;
+    /// // Redirect the customer to session.url
+    /// ```
+    pub fn create(auth: &Auth, params: CreateSessionParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("checkout/sessions"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new checkout session (async)
+    pub async fn create_async(auth: &Auth, params: CreateSessionParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("checkout/sessions"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a checkout session by ID
+    pub fn retrieve(auth: &Auth, session_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("checkout/sessions/{}", session_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a checkout session by ID (async)
+    pub async fn retrieve_async(auth: &Auth, session_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("checkout/sessions/{}", session_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Expire an open checkout session so the customer can no longer pay through it
+    pub fn expire(auth: &Auth, session_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("checkout/sessions/{}/expire", session_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Expire an open checkout session (async)
+    pub async fn expire_async(auth: &Auth, session_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("checkout/sessions/{}/expire", session_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List checkout sessions, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List checkout sessions (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over all checkout sessions, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "checkout/sessions", page)
+    }
+
+    /// Stream all checkout sessions, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "checkout/sessions", page)
+    }
+
+    /// List the line items of a checkout session
+    pub fn list_line_items(auth: &Auth, session_id: &str, page: PageRequest) -> Result<Vec<LineItem>> {
+        Self::list_line_items_iter(auth, session_id, page).collect()
+    }
+
+    /// List the line items of a checkout session (async)
+    pub async fn list_line_items_async(auth: &Auth, session_id: &str, page: PageRequest) -> Result<Vec<LineItem>> {
+        Self::list_line_items_stream(auth, session_id, page).try_collect().await
+    }
+
+    /// Iterate over the line items of a checkout session, fetching further pages as needed
+    pub fn list_line_items_iter(auth: &Auth, session_id: &str, page: PageRequest) -> Paginator<LineItem> {
+        pagination::list_iter(auth.clone(), &format!("checkout/sessions/{}/line_items", session_id), page)
+    }
+
+    /// Stream the line items of a checkout session, fetching further pages as needed
+    pub fn list_line_items_stream(auth: &Auth, session_id: &str, page: PageRequest) -> PageStream<LineItem> {
+        pagination::list_stream(auth.clone(), &format!("checkout/sessions/{}/line_items", session_id), page)
+    }
+}
@@ -0,0 +1,312 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// How long a coupon's discount applies once redeemed
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CouponDuration {
+    /// Only the first invoice is discounted
+    Once,
+    /// Every invoice for `duration_in_months`
+    Repeating,
+    /// Every invoice for as long as the discount stays applied
+    Forever,
+}
+
+/// A discount that can be applied to customers, subscriptions and invoices,
+/// directly or through a promotion code
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Coupon {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "coupon")
+    pub object: String,
+    /// Amount in the smallest currency unit taken off the subtotal
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_off: Option<i64>,
+    /// Products the coupon is limited to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applies_to: Option<CouponAppliesTo>,
+    /// Time at which the object was created (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub created: Option<i64>,
+    /// Currency of `amount_off`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    pub duration: CouponDuration,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_in_months: Option<i64>,
+    pub livemode: bool,
+    /// How many times the coupon can be redeemed in total
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_redemptions: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Name displayed to customers on invoices and receipts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent_off: Option<f64>,
+    /// Last time the coupon can be redeemed (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redeem_by: Option<i64>,
+    #[serde(default)]
+    pub times_redeemed: u64,
+    /// Whether the coupon can still be redeemed
+    pub valid: bool,
+}
+
+/// Products a coupon applies to
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CouponAppliesTo {
+    pub products: Vec<String>,
+}
+
+/// A deleted coupon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedCoupon {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+/// A coupon or promotion code applied to a customer, subscription or invoice
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Discount {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon: Option<Coupon>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_item: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+}
+
+/// The result of removing a discount
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedDiscount {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon: Option<Coupon>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+}
+
+/// A discount to apply: a coupon, a promotion code or an existing discount
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DiscountItem {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discount: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub promotion_code: Option<String>,
+}
+
+impl DiscountItem {
+    /// Apply the coupon with ID `coupon_id`
+    pub fn coupon(coupon_id: &str) -> Self {
+        Self {
+            coupon: Some(coupon_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Apply the promotion code with ID `promotion_code_id` (not the customer-facing code)
+    pub fn promotion_code(promotion_code_id: &str) -> Self {
+        Self {
+            promotion_code: Some(promotion_code_id.to_string()),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameters for creating a coupon, most easily built with
+/// [`CouponBuilder`](crate::stripe::builders::CouponBuilder)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCouponParams {
+    pub duration: CouponDuration,
+    /// Custom ID, generated by Stripe when left empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_off: Option<i64>,
+    /// Required with `amount_off`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percent_off: Option<f64>,
+    /// Required when `duration` is `repeating`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub duration_in_months: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub applies_to: Option<CouponAppliesTo>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_redemptions: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redeem_by: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for updating a coupon; its discount cannot change once created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateCouponParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Coupon {
+    /// Create a new coupon
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::builders::CouponBuilder;
+    /// use payup::stripe::coupon::{Coupon, CouponDuration};
+    ///
+    /// let params = CouponBuilder::percent_off(25.0, CouponDuration::Repeating)
+    ///     .duration_in_months(3)
+    ///     .max_redemptions(100)
+    ///     .build();
+    /// let coupon = Coupon::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateCouponParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("coupons"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new coupon (async)
+    pub async fn create_async(auth: &Auth, params: CreateCouponParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("coupons"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a coupon by ID
+    pub fn retrieve(auth: &Auth, coupon_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("coupons/{}", coupon_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a coupon by ID (async)
+    pub async fn retrieve_async(auth: &Auth, coupon_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("coupons/{}", coupon_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a coupon's name or metadata
+    pub fn update(auth: &Auth, coupon_id: &str, params: UpdateCouponParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("coupons/{}", coupon_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a coupon's name or metadata (async)
+    pub async fn update_async(auth: &Auth, coupon_id: &str, params: UpdateCouponParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("coupons/{}", coupon_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete a coupon so it can no longer be redeemed; discounts already
+    /// applied keep working
+    pub fn delete(auth: &Auth, coupon_id: &str) -> Result<DeletedCoupon> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("coupons/{}", coupon_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Delete a coupon (async)
+    pub async fn delete_async(auth: &Auth, coupon_id: &str) -> Result<DeletedCoupon> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("coupons/{}", coupon_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List coupons, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List coupons (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over all coupons, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "coupons", page)
+    }
+
+    /// Stream all coupons, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "coupons", page)
+    }
+}
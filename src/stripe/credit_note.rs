@@ -0,0 +1,476 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::invoice::TaxAmount;
+use super::tax_rate::TaxRate;
+use super::Auth;
+
+/// A credit against a finalized invoice, reducing what the customer owes or
+/// returning money as a refund or customer balance credit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditNote {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "credit_note")
+    pub object: String,
+    /// Total credited, including tax, in the smallest currency unit
+    pub amount: i64,
+    #[serde(default)]
+    pub amount_shipping: i64,
+    pub created: i64,
+    pub currency: String,
+    pub customer: String,
+    /// The balance transaction crediting the customer, if any of the amount
+    /// went to their balance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_balance_transaction: Option<String>,
+    #[serde(default)]
+    pub discount_amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_at: Option<i64>,
+    /// The invoice credited
+    pub invoice: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub lines: Option<CreditNoteLineItemList>,
+    pub livemode: bool,
+    /// Customer-facing text shown on the credit note PDF
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Customer-facing number, derived from the invoice number
+    pub number: String,
+    /// Amount settled outside of Stripe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_of_band_amount: Option<i64>,
+    /// Link to download the credit note PDF
+    pub pdf: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<CreditNoteReason>,
+    /// The refund issued for the credit note, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund: Option<String>,
+    pub status: CreditNoteStatus,
+    pub subtotal: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subtotal_excluding_tax: Option<i64>,
+    #[serde(default)]
+    pub tax_amounts: Vec<TaxAmount>,
+    pub total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_excluding_tax: Option<i64>,
+    /// Whether the invoice was paid when the credit note was issued
+    #[serde(rename = "type")]
+    pub type_field: CreditNoteType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub voided_at: Option<i64>,
+}
+
+/// Why a credit note was issued
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditNoteReason {
+    Duplicate,
+    Fraudulent,
+    OrderChange,
+    ProductUnsatisfactory,
+}
+
+/// Credit note status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditNoteStatus {
+    Issued,
+    Void,
+}
+
+/// Whether a credit note was issued before or after its invoice was paid
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditNoteType {
+    PrePayment,
+    PostPayment,
+}
+
+/// Credit note line item list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditNoteLineItemList {
+    pub object: String,
+    pub data: Vec<CreditNoteLineItem>,
+    pub has_more: bool,
+    pub url: String,
+}
+
+/// What a credit note line credits
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum CreditNoteLineType {
+    /// A line of the credited invoice
+    InvoiceLineItem,
+    /// A free-form line, e.g. a goodwill credit
+    CustomLineItem,
+}
+
+/// A line of a credit note
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditNoteLineItem {
+    pub id: String,
+    /// String representing the object's type (always "credit_note_line_item")
+    pub object: String,
+    pub amount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(default)]
+    pub discount_amount: i64,
+    /// The invoice line credited, for lines of type `invoice_line_item`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_line_item: Option<String>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(default)]
+    pub tax_amounts: Vec<TaxAmount>,
+    #[serde(default)]
+    pub tax_rates: Vec<TaxRate>,
+    #[serde(rename = "type")]
+    pub type_field: CreditNoteLineType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_amount: Option<i64>,
+}
+
+/// A line to credit
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreditNoteLineParams {
+    #[serde(rename = "type")]
+    pub type_field: CreditNoteLineType,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_line_item: Option<String>,
+    /// Amount to credit, instead of a quantity
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub unit_amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Tax rates for custom lines
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_rates: Option<Vec<String>>,
+}
+
+impl CreditNoteLineParams {
+    fn invoice_line(line_item_id: &str) -> Self {
+        Self {
+            type_field: CreditNoteLineType::InvoiceLineItem,
+            invoice_line_item: Some(line_item_id.to_string()),
+            amount: None,
+            quantity: None,
+            unit_amount: None,
+            description: None,
+            tax_rates: None,
+        }
+    }
+
+    /// Credit `quantity` units of an invoice line
+    pub fn quantity(line_item_id: &str, quantity: u64) -> Self {
+        Self {
+            quantity: Some(quantity),
+            ..Self::invoice_line(line_item_id)
+        }
+    }
+
+    /// Credit `amount` of an invoice line
+    pub fn amount(line_item_id: &str, amount: i64) -> Self {
+        Self {
+            amount: Some(amount),
+            ..Self::invoice_line(line_item_id)
+        }
+    }
+
+    /// Credit a line not on the invoice
+    pub fn custom(description: &str, quantity: u64, unit_amount: i64) -> Self {
+        Self {
+            type_field: CreditNoteLineType::CustomLineItem,
+            invoice_line_item: None,
+            amount: None,
+            quantity: Some(quantity),
+            unit_amount: Some(unit_amount),
+            description: Some(description.to_string()),
+            tax_rates: None,
+        }
+    }
+}
+
+/// Parameters for previewing or issuing a credit note.
+///
+/// The credit is the sum of `lines`, or `amount` when there are none; it is
+/// split between `credit_amount`, `refund_amount` and `out_of_band_amount`,
+/// or for paid invoices goes to the customer's balance when none are set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCreditNoteParams {
+    /// ID of the finalized invoice to credit
+    pub invoice: String,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub lines: Vec<CreditNoteLineParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    /// Amount credited to the customer's balance
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_amount: Option<i64>,
+    /// Amount refunded to the customer
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund_amount: Option<i64>,
+    /// Amount settled outside of Stripe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub out_of_band_amount: Option<i64>,
+    /// An existing refund to link the credit note to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub refund: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reason: Option<CreditNoteReason>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateCreditNoteParams {
+    pub fn new(invoice_id: &str) -> Self {
+        Self {
+            invoice: invoice_id.to_string(),
+            lines: Vec::new(),
+            amount: None,
+            credit_amount: None,
+            refund_amount: None,
+            out_of_band_amount: None,
+            refund: None,
+            reason: None,
+            memo: None,
+            effective_at: None,
+            metadata: None,
+        }
+    }
+
+    /// Credit `line` as well
+    pub fn line(mut self, line: CreditNoteLineParams) -> Self {
+        self.lines.push(line);
+        self
+    }
+}
+
+/// Parameters for updating a credit note
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateCreditNoteParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memo: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing credit notes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListCreditNotesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+}
+
+impl CreditNote {
+    /// Preview a credit note without issuing it, e.g. to show its totals
+    /// before confirming
+    pub fn preview(auth: &Auth, params: &CreateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("credit_notes/preview?{}", form::to_query(params))))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Preview a credit note without issuing it (async)
+    pub async fn preview_async(auth: &Auth, params: &CreateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("credit_notes/preview?{}", form::to_query(params))))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Iterate over the lines a credit note would have, fetching further pages as needed
+    pub fn preview_lines_iter(auth: &Auth, params: &CreateCreditNoteParams, page: PageRequest) -> Paginator<CreditNoteLineItem> {
+        pagination::list_iter(auth.clone(), &format!("credit_notes/preview/lines?{}", form::to_query(params)), page)
+    }
+
+    /// Stream the lines a credit note would have, fetching further pages as needed
+    pub fn preview_lines_stream(
+        auth: &Auth,
+        params: &CreateCreditNoteParams,
+        page: PageRequest,
+    ) -> PageStream<CreditNoteLineItem> {
+        pagination::list_stream(auth.clone(), &format!("credit_notes/preview/lines?{}", form::to_query(params)), page)
+    }
+
+    /// Issue a credit note against a finalized invoice
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::credit_note::{CreateCreditNoteParams, CreditNote, CreditNoteLineParams, CreditNoteReason};
+    ///
+    /// let mut params = CreateCreditNoteParams::new("in_123")
+    ///     .line(CreditNoteLineParams::quantity("il_123", 1));
+    /// params.reason = Some(CreditNoteReason::ProductUnsatisfactory);
+    /// let credit_note = CreditNote::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("credit_notes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Issue a credit note against a finalized invoice (async)
+    pub async fn create_async(auth: &Auth, params: CreateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("credit_notes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a credit note by ID
+    pub fn retrieve(auth: &Auth, credit_note_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("credit_notes/{}", credit_note_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a credit note by ID (async)
+    pub async fn retrieve_async(auth: &Auth, credit_note_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("credit_notes/{}", credit_note_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a credit note's memo or metadata
+    pub fn update(auth: &Auth, credit_note_id: &str, params: UpdateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("credit_notes/{}", credit_note_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a credit note's memo or metadata (async)
+    pub async fn update_async(auth: &Auth, credit_note_id: &str, params: UpdateCreditNoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("credit_notes/{}", credit_note_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Void a credit note, reversing its effect on the invoice; refunds
+    /// already issued are not reversed
+    pub fn void(auth: &Auth, credit_note_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("credit_notes/{}/void", credit_note_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Void a credit note (async)
+    pub async fn void_async(auth: &Auth, credit_note_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("credit_notes/{}/void", credit_note_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List credit notes matching `filters`, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListCreditNotesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List credit notes matching `filters` (async)
+    pub async fn list_async(auth: &Auth, filters: &ListCreditNotesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over credit notes matching `filters`, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListCreditNotesParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream credit notes matching `filters`, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListCreditNotesParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListCreditNotesParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "credit_notes".to_string(),
+            query => format!("credit_notes?{}", query),
+        }
+    }
+
+    /// List a credit note's lines, walking every page or up to `page.max_items`
+    pub fn lines(auth: &Auth, credit_note_id: &str, page: PageRequest) -> Result<Vec<CreditNoteLineItem>> {
+        Self::lines_iter(auth, credit_note_id, page).collect()
+    }
+
+    /// List a credit note's lines (async)
+    pub async fn lines_async(auth: &Auth, credit_note_id: &str, page: PageRequest) -> Result<Vec<CreditNoteLineItem>> {
+        Self::lines_stream(auth, credit_note_id, page).try_collect().await
+    }
+
+    /// Iterate over a credit note's lines, fetching further pages as needed
+    pub fn lines_iter(auth: &Auth, credit_note_id: &str, page: PageRequest) -> Paginator<CreditNoteLineItem> {
+        pagination::list_iter(auth.clone(), &format!("credit_notes/{}/lines", credit_note_id), page)
+    }
+
+    /// Stream a credit note's lines, fetching further pages as needed
+    pub fn lines_stream(auth: &Auth, credit_note_id: &str, page: PageRequest) -> PageStream<CreditNoteLineItem> {
+        pagination::list_stream(auth.clone(), &format!("credit_notes/{}/lines", credit_note_id), page)
+    }
+}
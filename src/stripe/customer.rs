@@ -1,7 +1,17 @@
 use serde::{Deserialize, Serialize};
 
+use futures::TryStreamExt;
+
 use crate::stripe::auth::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::search::SearchQuery;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::stripe::coupon::{DeletedDiscount, Discount, DiscountItem};
+use crate::stripe::billing_portal::{BillingPortalSession, CreateBillingPortalSessionParams};
+use crate::stripe::customer_balance_transaction::{CreateCustomerBalanceTransactionParams, CustomerBalanceTransaction};
 
 /// Represents a customer of your business.
 #[derive(Serialize, Deserialize, Debug, Clone)]
@@ -18,7 +28,7 @@ pub struct Customer {
     pub payment_method: Option<String>,
     pub delinquent: Option<bool>,
     pub description: Option<String>,
-    // pub discount: Value,
+    pub discount: Option<Discount>,
     pub email: Option<String>,
     #[serde(rename = "invoice_prefix")]
     pub invoice_prefix: Option<String>,
@@ -61,6 +71,7 @@ impl Customer {
             payment_method: None,
             delinquent: None,
             description: None,
+            discount: None,
             email: None,
             invoice_prefix: None,
             livemode: None,
@@ -71,6 +82,83 @@ impl Customer {
         };
     }
 
+    /// Asynchronously applies a coupon or promotion code to a stripe Customer
+    ///
+    /// The discount applies to every recurring charge of the customer's
+    /// subscriptions that don't carry a discount of their own.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - A string representing an existing stripe customer_id
+    /// * `discount` - The coupon or promotion code to apply
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let discount = payup::stripe::DiscountItem::promotion_code("promo_test123");
+    /// let customer = payup::stripe::Customer::async_apply_discount(auth, "cust_test123".to_string(), discount).await?;
+    /// ```ignore
+    pub async fn async_apply_discount(
+        creds: Auth,
+        id: String,
+        discount: DiscountItem,
+    ) -> Result<Self, crate::error::PayupError> {
+        let mut params = vec![];
+        if let Some(coupon) = &discount.coupon {
+            params.push(("coupon", coupon.as_str()));
+        }
+        if let Some(promotion_code) = &discount.promotion_code {
+            params.push(("promotion_code", promotion_code.as_str()));
+        }
+        if params.is_empty() {
+            return Err(crate::error::PayupError::ValidationError(
+                "A coupon or promotion code is required to apply a discount".to_string(),
+            ));
+        }
+
+        let request = get_shared_client()
+            .post(creds.url(&format!("customers/{}", id)))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&params)
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Asynchronously adjust a Customer's balance, which is applied to their next invoices.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - A string representing an existing stripe customer_id
+    /// * `amount` - Negative to credit the customer, positive to debit them
+    /// * `currency` - Three-letter ISO currency code, e.g. "usd"
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Credit the customer $5.00
+    /// let transaction = payup::stripe::Customer::async_adjust_balance(auth, "cust_test123".to_string(), -500, "usd".to_string()).await?;
+    /// ```ignore
+    pub async fn async_adjust_balance(
+        creds: Auth,
+        id: String,
+        amount: i64,
+        currency: String,
+    ) -> Result<CustomerBalanceTransaction, crate::error::PayupError> {
+        CustomerBalanceTransaction::create_async(&creds, &id, CreateCustomerBalanceTransactionParams::new(amount, &currency)).await
+    }
+
     /// Asynchronously destroy a stripe Customer
     ///
     /// # Arguments
@@ -87,19 +175,71 @@ impl Customer {
     /// // Fetch customer using id
     /// let customer = payup::stripe::Customer::async_delete(auth, "cust_test123".to_string()).await?;
     /// ```ignore
-    pub async fn async_delete(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/customers/{}", id);
+    pub async fn async_delete(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("customers/{}", id));
 
         let request = get_shared_client()
             .delete(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<Self>().await?;
         Ok(json)
     }
 
+    /// Asynchronously removes the discount currently applied to a stripe Customer
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - A string representing an existing stripe customer_id
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let removed = payup::stripe::Customer::async_delete_discount(auth, "cust_test123".to_string()).await?;
+    /// ```ignore
+    pub async fn async_delete_discount(creds: Auth, id: String) -> Result<DeletedDiscount, crate::error::PayupError> {
+        let request = get_shared_client()
+            .delete(creds.url(&format!("customers/{}/discount", id)))
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<DeletedDiscount>().await?;
+        Ok(json)
+    }
+
+    /// Asynchronously open a billing portal session where the customer can
+    /// manage their own subscriptions, payment methods and invoices.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - A string representing an existing stripe customer_id
+    /// * `return_url` - Where the customer goes when they leave the portal
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let session = payup::stripe::Customer::async_portal_session(auth, "cust_test123".to_string(), "https://example.com/account".to_string()).await?;
+    /// // Redirect the customer to session.url
+    /// ```ignore
+    pub async fn async_portal_session(
+        creds: Auth,
+        id: String,
+        return_url: String,
+    ) -> Result<BillingPortalSession, crate::error::PayupError> {
+        BillingPortalSession::create_async(&creds, CreateBillingPortalSessionParams::new(&id, &return_url)).await
+    }
+
     /// Asynchronously lookup a stripe Customer using customer_id
     ///
     /// # Arguments
@@ -116,12 +256,12 @@ impl Customer {
     /// // Fetch customer using id
     /// let customer = payup::stripe::Customer::async_get(auth, "cust_test123".to_string()).await?;
     /// ```ignore
-    pub async fn async_get(creds: Auth, id: String) -> Result<Self, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/customers/{}", id);
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("customers/{}", id));
         let request = get_shared_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
         let json = request.json::<Self>().await?;
         Ok(json)
@@ -145,7 +285,7 @@ impl Customer {
     pub async fn async_invoices(
         creds: Auth,
         customer_id: String,
-    ) -> Result<Vec<crate::stripe::response::Invoice>, reqwest::Error> {
+    ) -> Result<Vec<crate::stripe::response::Invoice>, crate::error::PayupError> {
         let mut objects: Vec<crate::stripe::response::Invoice> = Vec::new();
 
         let mut has_more = true;
@@ -165,6 +305,45 @@ impl Customer {
         Ok(objects)
     }
 
+    /// Iterates over all customers, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of customers
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the customers 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::Customer::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "customers", page)
+    }
+
+    /// Asynchronously streams all customers, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::Customer::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "customers", page)
+    }
+
     /// Asynchronously returns all stripe customers owned by the account.
     ///
     /// # Arguments
@@ -180,22 +359,56 @@ impl Customer {
     /// // Fetch all customers from stripe
     /// let customers = payup::stripe::Customer::async_list(auth).await?;
     /// ```ignore
-    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, reqwest::Error> {
-        let mut objects = Vec::new();
-        let mut has_more = true;
-        let mut starting_after = None;
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
+    }
 
-        while has_more {
-            let json = Self::list_chunk_async(creds.clone(), starting_after.clone()).await?;
-            for json_object in json.data {
-                objects.push(json_object);
-            }
-            has_more = json.has_more;
-            if let Some(last) = objects.last() {
-                starting_after = last.id.clone();
-            }
-        }
-        Ok(objects)
+    /// Returns an iterator over the customers matching `query`, requesting further pages as it is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `query` - The search query, see payup::stripe::SearchQuery
+    /// * `page` - Page size, a saved `next_page` token as `starting_after` and an optional cap on the number of customers
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let query = payup::stripe::SearchQuery::new(payup::stripe::SearchClause::field("email").eq("jenny@example.com"));
+    /// for object in payup::stripe::Customer::search_iter(auth, &query, payup::builders::PageRequest::new()) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn search_iter(creds: Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(creds, "customers/search", query, page)
+    }
+
+    /// Asynchronously streams the customers matching `query`, requesting further pages as the stream is consumed.
+    pub fn search_stream(creds: Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(creds, "customers/search", query, page)
+    }
+
+    /// Asynchronously returns all customers matching `query`.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `query` - The search query, see payup::stripe::SearchQuery
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let query = payup::stripe::SearchQuery::new(payup::stripe::SearchClause::field("email").eq("jenny@example.com"));
+    /// let customers = payup::stripe::Customer::async_search(auth, &query).await?;
+    /// ```ignore
+    pub async fn async_search(creds: Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_stream(creds, query, PageRequest::new()).try_collect().await
     }
 
     /// Asynchronously returns all PaymentMethods belonging to the customer_id
@@ -218,7 +431,7 @@ impl Customer {
         creds: Auth,
         customer_id: String,
         method_type: String,
-    ) -> Result<Vec<crate::stripe::response::PaymentMethod>, reqwest::Error> {
+    ) -> Result<Vec<crate::stripe::response::PaymentMethod>, crate::error::PayupError> {
         let mut objects: Vec<crate::stripe::response::PaymentMethod> = Vec::new();
 
         let mut has_more = true;
@@ -263,12 +476,13 @@ impl Customer {
     /// // Post customer to stripe and update the local cust variable
     /// let customer = cust.async_post(auth).await?;
     /// ```ignore
-    pub async fn async_post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_client()
-            .post("https://api.stripe.com/v1/customers")
+            .post(creds.url("customers"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await;
         match request {
             Ok(req) => {
@@ -311,13 +525,14 @@ impl Customer {
     /// ```ignore
     pub async fn async_update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let request = get_shared_client()
-            .post(format!(
-                "https://api.stripe.com/v1/customers/{}",
+            .post(creds.url(&format!(
+                "customers/{}",
                 self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Customer ID is required for update".to_string()))?
-            ))
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<Self>().await?;
@@ -331,73 +546,50 @@ impl Customer {
         creds: Auth,
         customer_id: String,
         starting_after: Option<String>,
-    ) -> Result<crate::stripe::response::Invoices, reqwest::Error> {
+    ) -> Result<crate::stripe::response::Invoices, crate::error::PayupError> {
         let url = if let Some(ref after) = starting_after {
-            format!(
-                "https://api.stripe.com/v1/invoices?customer={}&starting_after={}",
+            creds.url(&format!(
+                "invoices?customer={}&starting_after={}",
                 customer_id, after
-            )
+            ))
         } else {
-            format!(
-                "https://api.stripe.com/v1/invoices?customer={}",
+            creds.url(&format!(
+                "invoices?customer={}",
                 customer_id
-            )
+            ))
         };
 
         let request = get_shared_blocking_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
         let json = request.json::<crate::stripe::response::Invoices>()?;
         Ok(json)
     }
 
-    async fn list_chunk_async(
-        creds: Auth,
-        starting_after: Option<String>,
-    ) -> Result<Customers, reqwest::Error> {
-        let mut url = "https://api.stripe.com/v1/customers".to_string();
-
-        if let Some(ref after) = starting_after {
-            url = format!(
-                "https://api.stripe.com/v1/customers?starting_after={}",
-                after
-            );
-        }
-
-        let request = get_shared_client()
-            .get(url)
-            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
-            .await?;
-
-        let json = request.json::<Customers>().await?;
-        Ok(json)
-    }
-
     async fn get_payment_methods_chunk_async(
         creds: Auth,
         customer_id: String,
         method_type: String,
         starting_after: Option<String>,
-    ) -> Result<crate::stripe::response::PaymentMethods, reqwest::Error> {
+    ) -> Result<crate::stripe::response::PaymentMethods, crate::error::PayupError> {
         let url = if let Some(ref after) = starting_after {
-            format!(
-                "https://api.stripe.com/v1/customers/{}/payment_methods?type={}&starting_after={}",
+            creds.url(&format!(
+                "customers/{}/payment_methods?type={}&starting_after={}",
                 customer_id, method_type, after
-            )
+            ))
         } else {
-            format!(
-                "https://api.stripe.com/v1/customers/{}/payment_methods?type={}",
+            creds.url(&format!(
+                "customers/{}/payment_methods?type={}",
                 customer_id, method_type
-            )
+            ))
         };
 
         let request = get_shared_client()
             .get(url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request
@@ -429,6 +621,9 @@ impl Customer {
             Some(phone) => params.push(("phone", phone.as_str())),
             None => {}
         }
+        if let Some(tax_exempt) = &self.tax_exempt {
+            params.push(("tax_exempt", tax_exempt.as_str()));
+        }
         return params;
     }
 }
@@ -0,0 +1,194 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// A change to a customer's balance, which is applied to their next invoices.
+///
+/// Negative amounts credit the customer, positive amounts are owed by them.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CustomerBalanceTransaction {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "customer_balance_transaction")
+    pub object: String,
+    pub amount: i64,
+    pub created: i64,
+    /// The credit note that made the transaction, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub credit_note: Option<String>,
+    pub currency: String,
+    pub customer: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The customer's balance after the transaction
+    pub ending_balance: i64,
+    /// The invoice the transaction was applied to, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// What made the transaction, e.g. "adjustment", "credit_note",
+    /// "applied_to_invoice" or "invoice_overpaid"
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+/// Parameters for adjusting a customer's balance
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateCustomerBalanceTransactionParams {
+    /// Negative to credit the customer, positive to debit them
+    pub amount: i64,
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateCustomerBalanceTransactionParams {
+    pub fn new(amount: i64, currency: &str) -> Self {
+        Self {
+            amount,
+            currency: currency.to_string(),
+            description: None,
+            metadata: None,
+        }
+    }
+}
+
+/// Parameters for updating a customer balance transaction
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateCustomerBalanceTransactionParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CustomerBalanceTransaction {
+    /// Adjust a customer's balance
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::customer_balance_transaction::{
+    ///     CreateCustomerBalanceTransactionParams, CustomerBalanceTransaction,
+    /// };
+    ///
+    /// // Credit the customer $5.00 towards their next invoice
+    /// let mut params = CreateCustomerBalanceTransactionParams::new(-500, "usd");
+    /// params.description = Some("Goodwill credit".to_string());
+    /// let transaction = CustomerBalanceTransaction::create(&auth, "cus_123", params)?;
+    /// ```
+    pub fn create(auth: &Auth, customer_id: &str, params: CreateCustomerBalanceTransactionParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("customers/{}/balance_transactions", customer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Adjust a customer's balance (async)
+    pub async fn create_async(
+        auth: &Auth,
+        customer_id: &str,
+        params: CreateCustomerBalanceTransactionParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("customers/{}/balance_transactions", customer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve one of a customer's balance transactions
+    pub fn retrieve(auth: &Auth, customer_id: &str, transaction_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("customers/{}/balance_transactions/{}", customer_id, transaction_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve one of a customer's balance transactions (async)
+    pub async fn retrieve_async(auth: &Auth, customer_id: &str, transaction_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("customers/{}/balance_transactions/{}", customer_id, transaction_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a balance transaction's description or metadata; its amount
+    /// can't be changed
+    pub fn update(
+        auth: &Auth,
+        customer_id: &str,
+        transaction_id: &str,
+        params: UpdateCustomerBalanceTransactionParams,
+    ) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("customers/{}/balance_transactions/{}", customer_id, transaction_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a balance transaction's description or metadata (async)
+    pub async fn update_async(
+        auth: &Auth,
+        customer_id: &str,
+        transaction_id: &str,
+        params: UpdateCustomerBalanceTransactionParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("customers/{}/balance_transactions/{}", customer_id, transaction_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List a customer's balance transactions, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, customer_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, customer_id, page).collect()
+    }
+
+    /// List a customer's balance transactions (async)
+    pub async fn list_async(auth: &Auth, customer_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, customer_id, page).try_collect().await
+    }
+
+    /// Iterate over a customer's balance transactions, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, customer_id: &str, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &format!("customers/{}/balance_transactions", customer_id), page)
+    }
+
+    /// Stream a customer's balance transactions, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, customer_id: &str, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &format!("customers/{}/balance_transactions", customer_id), page)
+    }
+}
@@ -0,0 +1,422 @@
+use serde::{Deserialize, Serialize};
+
+use futures::TryStreamExt;
+
+use crate::stripe::auth::Auth;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+
+/// A dispute occurs when a customer questions your charge with their card issuer.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Dispute {
+    pub id: Option<String>,
+    pub object: Option<String>,
+    pub amount: Option<i64>,
+    pub charge: Option<String>,
+    pub created: Option<i64>,
+    pub currency: Option<String>,
+    pub evidence: Option<Evidence>,
+    pub evidence_details: Option<EvidenceDetails>,
+    pub is_charge_refundable: Option<bool>,
+    pub livemode: Option<bool>,
+    /// Set to `true` before calling `update` to submit the evidence to the bank immediately
+    pub submit: Option<bool>,
+    pub payment_intent: Option<String>,
+    pub reason: Option<String>,
+    pub status: Option<String>,
+}
+
+impl Dispute {
+    /// Returns an empty Dispute object
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut dispute = payup::stripe::Dispute::new();
+    /// dispute.id = Some("dp_test123".to_string());
+    /// ```ignore
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asynchronously close a dispute.
+    /// Closing the dispute for a charge indicates that you do not have any evidence to submit and are essentially dismissing the dispute, acknowledging it as lost.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut dispute = payup::stripe::Dispute::new();
+    /// dispute.id = Some("dp_test123".to_string());
+    ///
+    /// dispute = dispute.async_close(auth.clone()).await?;
+    /// ```ignore
+    pub async fn async_close(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_client()
+            .post(creds.url(&format!(
+                "disputes/{}/close",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Dispute ID is required for close".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Asynchronously retrieves the dispute with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the dispute you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch dispute using id
+    /// let dispute = payup::stripe::Dispute::async_get(auth, "dp_test123".to_string()).await?;
+    /// ```ignore
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("disputes/{}", id));
+        let request = get_shared_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Iterates over all disputes, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of disputes
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the disputes 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::Dispute::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "disputes", page)
+    }
+
+    /// Asynchronously streams all disputes, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::Dispute::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "disputes", page)
+    }
+
+    /// Asynchronously returns all stripe disputes.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all disputes from stripe
+    /// let disputes = payup::stripe::Dispute::async_list(auth).await?;
+    /// ```ignore
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
+    }
+
+    /// Asynchronously POSTs an update to an existing Dispute
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Upload a receipt and reference it from the evidence
+    /// let mut receipt = payup::stripe::File::new();
+    /// receipt.file = Some(std::fs::read("receipt.pdf")?);
+    /// receipt.filename = Some("receipt.pdf".to_string());
+    /// receipt.purpose = Some("dispute_evidence".to_string());
+    /// receipt = receipt.async_post(auth.clone()).await?;
+    ///
+    /// let mut evidence = payup::stripe::Evidence::new();
+    /// evidence.receipt = receipt.id;
+    /// evidence.customer_name = Some("Jane Doe".to_string());
+    ///
+    /// let mut dispute = payup::stripe::Dispute::new();
+    /// dispute.id = Some("dp_test123".to_string());
+    /// dispute.evidence = Some(evidence);
+    ///
+    /// // Submit the evidence to the bank
+    /// dispute.submit = Some(true);
+    ///
+    /// // Update the dispute
+    /// dispute = dispute.async_update(auth.clone()).await?;
+    /// ```ignore
+    pub async fn async_update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_client()
+            .post(creds.url(&format!(
+                "disputes/{}",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Dispute ID is required for update".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_params())
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Close a dispute.
+    /// Closing the dispute for a charge indicates that you do not have any evidence to submit and are essentially dismissing the dispute, acknowledging it as lost.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut dispute = payup::stripe::Dispute::new();
+    /// dispute.id = Some("dp_test123".to_string());
+    ///
+    /// dispute = dispute.close(auth.clone())?;
+    /// ```ignore
+    pub fn close(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_blocking_client()
+            .post(creds.url(&format!(
+                "disputes/{}/close",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Dispute ID is required for close".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    /// Retrieves the dispute with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the dispute you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch dispute using id
+    /// let dispute = payup::stripe::Dispute::get(auth, "dp_test123".to_string())?;
+    /// ```ignore
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("disputes/{}", id));
+        let request = get_shared_blocking_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    /// Returns all stripe disputes.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all disputes from stripe
+    /// let disputes = payup::stripe::Dispute::list(auth)?;
+    /// ```ignore
+    pub fn list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_iter(creds, PageRequest::new()).collect()
+    }
+
+    /// POSTs an update to an existing Dispute
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Create some evidence to update the dispute with
+    /// let mut evidence = payup::stripe::Evidence::new();
+    /// evidence.billing_address = Some("123 Main St".to_string());
+    /// evidence.cancellation_policy = Some("file_test123".to_string());
+    ///
+    /// let mut dispute = payup::stripe::Dispute::new();
+    /// dispute.id = Some("dp_test123".to_string());
+    /// dispute.evidence = Some(evidence);
+    ///
+    /// // Submit the evidence to the bank
+    /// dispute.submit = Some(true);
+    ///
+    /// // Update the dispute
+    /// dispute = dispute.update(auth.clone())?;
+    /// ```ignore
+    pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_blocking_client()
+            .post(creds.url(&format!(
+                "disputes/{}",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("Dispute ID is required for update".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_params())
+            .send_limited("stripe", creds.transport())?;
+
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    fn to_params(&self) -> Vec<(String, String)> {
+        let mut params = vec![];
+        if let Some(evidence) = &self.evidence {
+            params.extend(evidence.to_params());
+        }
+        if let Some(submit) = self.submit {
+            params.push(("submit".to_string(), submit.to_string()));
+        }
+        params
+    }
+}
+
+/// Evidence to help resolve a dispute.
+///
+/// File fields (`receipt`, `service_documentation`, …) take the ID of a
+/// [`File`](crate::stripe::File) uploaded with purpose `dispute_evidence`.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct Evidence {
+    pub access_activity_log: Option<String>,
+    pub billing_address: Option<String>,
+    pub cancellation_policy: Option<String>,
+    pub cancellation_policy_disclosure: Option<String>,
+    pub cancellation_rebuttal: Option<String>,
+    pub customer_communication: Option<String>,
+    pub customer_email_address: Option<String>,
+    pub customer_name: Option<String>,
+    pub customer_purchase_ip: Option<String>,
+    pub customer_signature: Option<String>,
+    pub duplicate_charge_documentation: Option<String>,
+    pub duplicate_charge_explanation: Option<String>,
+    pub duplicate_charge_id: Option<String>,
+    pub product_description: Option<String>,
+    pub receipt: Option<String>,
+    pub refund_policy: Option<String>,
+    pub refund_policy_disclosure: Option<String>,
+    pub refund_refusal_explanation: Option<String>,
+    pub service_date: Option<String>,
+    pub service_documentation: Option<String>,
+    pub shipping_address: Option<String>,
+    pub shipping_carrier: Option<String>,
+    pub shipping_date: Option<String>,
+    pub shipping_documentation: Option<String>,
+    pub shipping_tracking_number: Option<String>,
+    pub uncategorized_file: Option<String>,
+    pub uncategorized_text: Option<String>,
+}
+
+impl Evidence {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Form-encodes the populated fields as `evidence[field]` pairs
+    pub fn to_params(&self) -> Vec<(String, String)> {
+        let value = serde_json::to_value(self).unwrap_or_default();
+        value
+            .as_object()
+            .map(|fields| {
+                fields
+                    .iter()
+                    .filter_map(|(key, value)| {
+                        value.as_str().map(|v| (format!("evidence[{}]", key), v.to_string()))
+                    })
+                    .collect()
+            })
+            .unwrap_or_default()
+    }
+}
+
+/// Information about the evidence submission for a dispute
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct EvidenceDetails {
+    /// Date by which evidence must be submitted (Unix timestamp)
+    pub due_by: Option<i64>,
+    pub has_evidence: bool,
+    pub past_due: bool,
+    pub submission_count: i64,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Disputes {
+    pub object: String,
+    pub url: String,
+    pub has_more: bool,
+    pub data: Vec<Dispute>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_evidence_params() {
+        let mut evidence = Evidence::new();
+        evidence.receipt = Some("file_123".to_string());
+        evidence.customer_name = Some("Jane Doe".to_string());
+
+        let mut dispute = Dispute::new();
+        dispute.evidence = Some(evidence);
+        dispute.submit = Some(true);
+
+        let params = dispute.to_params();
+        assert_eq!(params.len(), 3);
+        assert!(params.contains(&("evidence[receipt]".to_string(), "file_123".to_string())));
+        assert!(params.contains(&("evidence[customer_name]".to_string(), "Jane Doe".to_string())));
+        assert!(params.contains(&("submit".to_string(), "true".to_string())));
+    }
+}
@@ -1,7 +1,11 @@
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::stripe::auth::Auth;
-use crate::http_client::get_shared_client;
+use crate::http_client::{get_shared_client, SendVia};
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::webhooks::StripeEventObject;
 
 /// Event data wrapper - contains the actual object that triggered the event
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -74,17 +78,43 @@ impl Event {
     /// println!("Event type: {}", event.type_field);
     /// ```
     pub async fn get(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/events/{}", id);
+        let url = auth.url(&format!("events/{}", id));
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let event = response.json::<Self>().await?;
         Ok(event)
     }
 
+    /// Iterate over all events matching `params`, fetching further pages as needed.
+    /// Paging is controlled by `page`; any limit or cursor in `params` is ignored.
+    pub fn list_iter(auth: &Auth, params: Option<ListEventsParams>, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(params), page)
+    }
+
+    /// Stream all events matching `params`, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, params: Option<ListEventsParams>, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(params), page)
+    }
+
+    fn list_path(params: Option<ListEventsParams>) -> String {
+        let filters = ListEventsParams {
+            limit: None,
+            starting_after: None,
+            ending_before: None,
+            ..params.unwrap_or_default()
+        }
+        .to_query_string();
+        if filters.is_empty() {
+            "events".to_string()
+        } else {
+            format!("events?{}", filters)
+        }
+    }
+
     /// Lists events, returning the most recent events first
     ///
     /// # Arguments
@@ -104,7 +134,7 @@ impl Event {
     /// }
     /// ```
     pub async fn list(auth: &Auth, params: Option<ListEventsParams>) -> Result<EventList, crate::error::PayupError> {
-        let mut url = "https://api.stripe.com/v1/events".to_string();
+        let mut url = auth.url("events");
         
         if let Some(params) = params {
             let query = params.to_query_string();
@@ -117,7 +147,7 @@ impl Event {
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let events = response.json::<EventList>().await?;
@@ -137,7 +167,7 @@ impl Event {
     /// let event = Event::get(&auth, "evt_charge_succeeded").await?;
     /// if event.type_field == "charge.succeeded" {
     ///     let charge: Charge = event.extract_object()?;
-    ///     println!("Charge amount: {}", charge.amount.unwrap_or(0));
+    ///     println!("Charge amount: {}", charge.stripe_amount.unwrap_or(0));
     /// }
     /// ```
     pub fn extract_object<T>(&self) -> Result<T, crate::error::PayupError>
@@ -145,7 +175,24 @@ impl Event {
         T: for<'de> Deserialize<'de>,
     {
         serde_json::from_value(self.data.object.clone())
-            .map_err(|e| crate::error::PayupError::SerializationError(e.to_string()))
+            .map_err(crate::error::PayupError::SerializationError)
+    }
+
+    /// Types the event's data.object by its `object` field, as webhook
+    /// events are, instead of naming the type up front
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use payup::stripe::{Event, StripeEventObject};
+    ///
+    /// let event = Event::get(&auth, "evt_charge_succeeded").await?;
+    /// if let StripeEventObject::Charge(charge) = event.typed_object() {
+    ///     println!("Charge amount: {}", charge.stripe_amount.unwrap_or(0));
+    /// }
+    /// ```
+    pub fn typed_object(&self) -> StripeEventObject {
+        StripeEventObject::from_value(self.data.object.clone())
     }
 
     /// Gets the previous attributes for update events
@@ -159,7 +206,7 @@ impl Event {
         match &self.data.previous_attributes {
             Some(attrs) => {
                 let result = serde_json::from_value(attrs.clone())
-                    .map_err(|e| crate::error::PayupError::SerializationError(e.to_string()))?;
+                    .map_err(crate::error::PayupError::SerializationError)?;
                 Ok(Some(result))
             }
             None => Ok(None),
@@ -320,6 +367,12 @@ pub mod event_types {
     pub const CHARGE_DISPUTE_UPDATED: &str = "charge.dispute.updated";
     pub const CHARGE_REFUND_UPDATED: &str = "charge.refund.updated";
 
+    // Checkout events
+    pub const CHECKOUT_SESSION_ASYNC_PAYMENT_FAILED: &str = "checkout.session.async_payment_failed";
+    pub const CHECKOUT_SESSION_ASYNC_PAYMENT_SUCCEEDED: &str = "checkout.session.async_payment_succeeded";
+    pub const CHECKOUT_SESSION_COMPLETED: &str = "checkout.session.completed";
+    pub const CHECKOUT_SESSION_EXPIRED: &str = "checkout.session.expired";
+
     // Customer events
     pub const CUSTOMER_CREATED: &str = "customer.created";
     pub const CUSTOMER_DELETED: &str = "customer.deleted";
@@ -397,6 +450,21 @@ pub mod event_types {
     pub const PRODUCT_DELETED: &str = "product.deleted";
     pub const PRODUCT_UPDATED: &str = "product.updated";
 
+    // Radar events
+    pub const RADAR_EARLY_FRAUD_WARNING_CREATED: &str = "radar.early_fraud_warning.created";
+    pub const RADAR_EARLY_FRAUD_WARNING_UPDATED: &str = "radar.early_fraud_warning.updated";
+
+    // Review events
+    pub const REVIEW_CLOSED: &str = "review.closed";
+    pub const REVIEW_OPENED: &str = "review.opened";
+
+    // Setup Intent events
+    pub const SETUP_INTENT_CANCELED: &str = "setup_intent.canceled";
+    pub const SETUP_INTENT_CREATED: &str = "setup_intent.created";
+    pub const SETUP_INTENT_REQUIRES_ACTION: &str = "setup_intent.requires_action";
+    pub const SETUP_INTENT_SETUP_FAILED: &str = "setup_intent.setup_failed";
+    pub const SETUP_INTENT_SUCCEEDED: &str = "setup_intent.succeeded";
+
     // Subscription Schedule events
     pub const SUBSCRIPTION_SCHEDULE_ABORTED: &str = "subscription_schedule.aborted";
     pub const SUBSCRIPTION_SCHEDULE_CANCELED: &str = "subscription_schedule.canceled";
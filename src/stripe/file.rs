@@ -0,0 +1,267 @@
+use serde::{Deserialize, Serialize};
+
+use futures::TryStreamExt;
+
+use crate::stripe::auth::Auth;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::file_link::FileLinks;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+
+/// A file hosted on Stripe's servers, such as dispute evidence or an identity document.
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct File {
+    pub id: Option<String>,
+    pub object: Option<String>,
+    pub created: Option<i64>,
+    pub expires_at: Option<i64>,
+    pub filename: Option<String>,
+    pub links: Option<FileLinks>,
+    /// The purpose of the uploaded file.
+    /// Possible enum values: account_requirement, additional_verification, business_icon, business_logo, customer_signature, dispute_evidence, identity_document, pci_document, tax_document_user_upload
+    pub purpose: Option<String>,
+    pub size: Option<i64>,
+    pub title: Option<String>,
+    #[serde(rename = "type")]
+    pub type_field: Option<String>,
+    pub url: Option<String>,
+    /// Contents to upload; never returned by the API
+    #[serde(skip)]
+    pub file: Option<Vec<u8>>,
+}
+
+impl File {
+    /// Returns an empty File object
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut file = payup::stripe::File::new();
+    /// file.title = Some("Title".to_string());
+    /// ```ignore
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asynchronously retrieves a file with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the file you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch file using id
+    /// let file = payup::stripe::File::async_get(auth, "file_test123".to_string()).await?;
+    /// ```ignore
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("files/{}", id));
+        let request = get_shared_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Iterates over all files, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of files
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the files 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::File::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "files", page)
+    }
+
+    /// Asynchronously streams all files, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::File::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "files", page)
+    }
+
+    /// Asynchronously returns all stripe files.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all files from stripe
+    /// let files = payup::stripe::File::async_list(auth).await?;
+    /// ```ignore
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
+    }
+
+    /// Asynchronously uploads a new File to the stripe api as `multipart/form-data`
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file = payup::stripe::File::new();
+    /// file.file = Some(std::fs::read("receipt.pdf")?);
+    /// file.filename = Some("receipt.pdf".to_string());
+    /// file.purpose = Some("dispute_evidence".to_string());
+    ///
+    /// file = file.async_post(auth.clone()).await?;
+    /// ```ignore
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let (purpose, contents) = self.upload_parts()?;
+        let part = reqwest::multipart::Part::bytes(contents).file_name(self.upload_filename());
+        let form = reqwest::multipart::Form::new()
+            .text("purpose", purpose)
+            .part("file", part);
+
+        let request = get_shared_client()
+            .post(creds.files_url("files"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .multipart(form)
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Retrieves the file with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the file you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch file using id
+    /// let file = payup::stripe::File::get(auth, "file_test123".to_string())?;
+    /// ```ignore
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("files/{}", id));
+        let request = get_shared_blocking_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    /// Returns all stripe files.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all files from stripe
+    /// let files = payup::stripe::File::list(auth)?;
+    /// ```ignore
+    pub fn list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_iter(creds, PageRequest::new()).collect()
+    }
+
+    /// Uploads a new File to the stripe api as `multipart/form-data`
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file = payup::stripe::File::new();
+    /// file.file = Some(std::fs::read("receipt.pdf")?);
+    /// file.filename = Some("receipt.pdf".to_string());
+    /// file.purpose = Some("dispute_evidence".to_string());
+    ///
+    /// file = file.post(auth.clone())?;
+    /// ```ignore
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let (purpose, contents) = self.upload_parts()?;
+        let part = reqwest::blocking::multipart::Part::bytes(contents).file_name(self.upload_filename());
+        let form = reqwest::blocking::multipart::Form::new()
+            .text("purpose", purpose)
+            .part("file", part);
+
+        let request = get_shared_blocking_client()
+            .post(creds.files_url("files"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .multipart(form)
+            .send_limited("stripe", creds.transport())?;
+
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    fn upload_parts(&self) -> Result<(String, Vec<u8>), crate::error::PayupError> {
+        let purpose = self.purpose.clone().ok_or_else(|| {
+            crate::error::PayupError::ValidationError("File purpose is required for upload".to_string())
+        })?;
+        let contents = self.file.clone().ok_or_else(|| {
+            crate::error::PayupError::ValidationError("File contents are required for upload".to_string())
+        })?;
+        Ok((purpose, contents))
+    }
+
+    /// Stripe rejects file parts without a filename
+    fn upload_filename(&self) -> String {
+        self.filename.clone().unwrap_or_else(|| "upload".to_string())
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Files {
+    pub object: String,
+    pub url: String,
+    pub has_more: bool,
+    pub data: Vec<File>,
+}
@@ -0,0 +1,305 @@
+use serde::{Deserialize, Serialize};
+
+use futures::TryStreamExt;
+
+use crate::stripe::auth::Auth;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+
+/// To share the contents of a File object with non-Stripe users, you can create a FileLink
+#[derive(Serialize, Deserialize, Debug, Clone, Default)]
+pub struct FileLink {
+    pub id: Option<String>,
+    pub object: Option<String>,
+    pub created: Option<i64>,
+    pub expired: Option<bool>,
+    pub expires_at: Option<i64>,
+    /// Expiry to send on create/update: a Unix timestamp, or `"now"` to expire the link immediately
+    #[serde(skip)]
+    pub link_expires_at: Option<String>,
+    pub file: Option<String>,
+    pub livemode: Option<bool>,
+    pub url: Option<String>,
+}
+
+impl FileLink {
+    /// Returns an empty FileLink object
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// let mut file_link = payup::stripe::FileLink::new();
+    /// file_link.file = Some("file_test123".to_string());
+    /// ```ignore
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Asynchronously retrieves the file link with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the file link you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch file link using id
+    /// let file_link = payup::stripe::FileLink::async_get(auth, "link_test123".to_string()).await?;
+    /// ```ignore
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("file_links/{}", id));
+        let request = get_shared_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Iterates over all file links, requesting further pages as the iterator is consumed.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `page` - Page size, starting cursor and an optional cap on the number of file links
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Walk the file links 100 at a time, stopping after 10,000
+    /// let page = payup::builders::PageRequest::new().limit(100).max_items(10_000);
+    /// for object in payup::stripe::FileLink::list_iter(auth, page) {
+    ///     let object = object?;
+    /// }
+    /// ```ignore
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "file_links", page)
+    }
+
+    /// Asynchronously streams all file links, requesting further pages as the stream is consumed.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    ///
+    /// let mut objects = payup::stripe::FileLink::list_stream(auth, payup::builders::PageRequest::new());
+    /// while let Some(object) = objects.try_next().await? {
+    ///     println!("{:?}", object.id);
+    /// }
+    /// ```ignore
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "file_links", page)
+    }
+
+    /// Asynchronously returns all stripe file links.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all file links from stripe
+    /// let file_links = payup::stripe::FileLink::async_list(auth).await?;
+    /// ```ignore
+    pub async fn async_list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_stream(creds, PageRequest::new()).try_collect().await
+    }
+
+    /// Asynchronously POSTs a new FileLink to the stripe api
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file_link = payup::stripe::FileLink::new();
+    /// file_link.file = Some("file_test123".to_string());
+    ///
+    /// file_link = file_link.async_post(auth.clone()).await?;
+    /// ```ignore
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_client()
+            .post(creds.url("file_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_params())
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Asynchronously POSTs an update to an existing FileLink
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file_link = payup::stripe::FileLink::new();
+    /// file_link.id = Some("link_test123".to_string());
+    /// file_link.link_expires_at = Some("now".to_string());
+    ///
+    /// file_link = file_link.async_update(auth.clone()).await?;
+    /// ```ignore
+    pub async fn async_update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_client()
+            .post(creds.url(&format!(
+                "file_links/{}",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("FileLink ID is required for update".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_update_params())
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Retrieves the file link with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the file link you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch file link using id
+    /// let file_link = payup::stripe::FileLink::get(auth, "link_test123".to_string())?;
+    /// ```ignore
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("file_links/{}", id));
+        let request = get_shared_blocking_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    /// Returns all stripe file links.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch all file links from stripe
+    /// let file_links = payup::stripe::FileLink::list(auth)?;
+    /// ```ignore
+    pub fn list(creds: Auth) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::list_iter(creds, PageRequest::new()).collect()
+    }
+
+    /// POSTs a new FileLink to the stripe api
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file_link = payup::stripe::FileLink::new();
+    /// file_link.file = Some("file_test123".to_string());
+    ///
+    /// file_link = file_link.post(auth.clone())?;
+    /// ```ignore
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_blocking_client()
+            .post(creds.url("file_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_params())
+            .send_limited("stripe", creds.transport())?;
+
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    /// POSTs an update to an existing FileLink
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// let mut file_link = payup::stripe::FileLink::new();
+    /// file_link.id = Some("link_test123".to_string());
+    /// file_link.link_expires_at = Some("now".to_string());
+    ///
+    /// file_link = file_link.update(auth.clone())?;
+    /// ```ignore
+    pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
+        let request = get_shared_blocking_client()
+            .post(creds.url(&format!(
+                "file_links/{}",
+                self.clone().id.ok_or_else(|| crate::error::PayupError::ValidationError("FileLink ID is required for update".to_string()))?
+            )))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .form(&self.to_update_params())
+            .send_limited("stripe", creds.transport())?;
+
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+
+    fn to_params(&self) -> Vec<(&str, &str)> {
+        let mut params = self.to_update_params();
+        if let Some(file) = &self.file {
+            params.push(("file", file.as_str()));
+        }
+        params
+    }
+
+    /// The file of an existing link cannot be changed, only its expiry
+    fn to_update_params(&self) -> Vec<(&str, &str)> {
+        let mut params = vec![];
+        if let Some(link_expires_at) = &self.link_expires_at {
+            params.push(("expires_at", link_expires_at.as_str()));
+        }
+        params
+    }
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct FileLinks {
+    pub object: String,
+    pub url: String,
+    pub has_more: bool,
+    pub data: Vec<FileLink>,
+}
@@ -0,0 +1,92 @@
+//! Form encoding for Stripe request parameters.
+//!
+//! Stripe takes nested parameters in bracket notation, e.g.
+//! `line_items[0][price_data][currency]=usd`, which `serde_urlencoded` (and
+//! so `RequestBuilder::form`) cannot produce from nested structs.
+
+use serde::Serialize;
+use serde_json::Value;
+
+/// Flattens `params` into `(key, value)` pairs in Stripe's bracket notation.
+/// `None` fields are left out; anything that is not a struct or map yields no pairs.
+pub(crate) fn to_form<T: Serialize>(params: &T) -> Vec<(String, String)> {
+    let mut pairs = Vec::new();
+    if let Ok(Value::Object(fields)) = serde_json::to_value(params) {
+        for (key, value) in fields {
+            flatten(key, value, &mut pairs);
+        }
+    }
+    pairs
+}
+
+/// Encodes `params` as a URL query string in the same notation, for list
+/// filters that have to travel in a `pagination::list_iter` path.
+pub(crate) fn to_query<T: Serialize>(params: &T) -> String {
+    url::form_urlencoded::Serializer::new(String::new())
+        .extend_pairs(to_form(params))
+        .finish()
+}
+
+fn flatten(key: String, value: Value, pairs: &mut Vec<(String, String)>) {
+    match value {
+        Value::Null => {}
+        Value::Bool(b) => pairs.push((key, b.to_string())),
+        Value::Number(n) => pairs.push((key, n.to_string())),
+        Value::String(s) => pairs.push((key, s)),
+        Value::Array(items) => {
+            for (index, item) in items.into_iter().enumerate() {
+                flatten(format!("{}[{}]", key, index), item, pairs);
+            }
+        }
+        Value::Object(fields) => {
+            for (field, item) in fields {
+                flatten(format!("{}[{}]", key, field), item, pairs);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::collections::HashMap;
+
+    #[derive(Serialize)]
+    struct Item {
+        price: String,
+        quantity: u64,
+    }
+
+    #[derive(Serialize)]
+    struct Params {
+        mode: &'static str,
+        line_items: Vec<Item>,
+        metadata: HashMap<&'static str, &'static str>,
+        expires_at: Option<i64>,
+        allow_promotion_codes: bool,
+    }
+
+    #[test]
+    fn test_to_form_uses_bracket_notation() {
+        let params = Params {
+            mode: "payment",
+            line_items: vec![
+                Item { price: "price_1".to_string(), quantity: 2 },
+                Item { price: "price_2".to_string(), quantity: 1 },
+            ],
+            metadata: HashMap::from([("order", "42")]),
+            expires_at: None,
+            allow_promotion_codes: true,
+        };
+
+        let pairs = to_form(&params);
+        let pair = |k: &str, v: &str| (k.to_string(), v.to_string());
+        assert_eq!(pairs.len(), 7);
+        assert!(pairs.contains(&pair("mode", "payment")));
+        assert!(pairs.contains(&pair("line_items[0][price]", "price_1")));
+        assert!(pairs.contains(&pair("line_items[0][quantity]", "2")));
+        assert!(pairs.contains(&pair("line_items[1][price]", "price_2")));
+        assert!(pairs.contains(&pair("metadata[order]", "42")));
+        assert!(pairs.contains(&pair("allow_promotion_codes", "true")));
+    }
+}
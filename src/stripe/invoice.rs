@@ -1,12 +1,22 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
+use futures::TryStreamExt;
 use crate::stripe::auth::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use crate::stripe::search::SearchQuery;
+use crate::stripe::subscription_item::ProrationBehavior;
+pub use crate::stripe::coupon::{Coupon, Discount, DiscountItem};
+pub use crate::stripe::tax_rate::TaxRate;
 
 /// Represents an invoice issued to a customer
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct Invoice {
-    /// Unique identifier for the object
+    /// Unique identifier for the object, empty for upcoming invoice previews
+    #[serde(default)]
     pub id: String,
     /// String representing the object's type (always "invoice")
     pub object: String,
@@ -207,7 +217,7 @@ pub enum BillingReason {
 }
 
 /// Collection method for the invoice
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CollectionMethod {
     ChargeAutomatically,
@@ -270,7 +280,8 @@ pub struct Shipping {
     pub tracking_number: Option<String>,
 }
 
-/// Tax ID
+/// A tax ID as recorded on an invoice; see [`crate::stripe::tax_id::TaxId`]
+/// for managing a customer's tax IDs
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaxId {
     #[serde(rename = "type")]
@@ -278,84 +289,6 @@ pub struct TaxId {
     pub value: String,
 }
 
-/// Tax rate
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct TaxRate {
-    pub id: String,
-    pub active: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub country: Option<String>,
-    pub created: i64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub description: Option<String>,
-    pub display_name: String,
-    pub inclusive: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub jurisdiction: Option<String>,
-    pub livemode: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<HashMap<String, String>>,
-    pub percentage: f64,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub state: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub tax_type: Option<String>,
-}
-
-/// Discount
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Discount {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub coupon: Option<Coupon>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub customer: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub end: Option<i64>,
-    pub id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub invoice: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub invoice_item: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub promotion_code: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub start: Option<i64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub subscription: Option<String>,
-}
-
-/// Discount item
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct DiscountItem {
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub coupon: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub discount: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub promotion_code: Option<String>,
-}
-
-/// Coupon
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct Coupon {
-    pub id: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub amount_off: Option<i64>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub currency: Option<String>,
-    pub duration: String,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub duration_in_months: Option<i64>,
-    pub livemode: bool,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub metadata: Option<HashMap<String, String>>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub name: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
-    pub percent_off: Option<f64>,
-    pub valid: bool,
-}
-
 /// Tax amount
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TaxAmount {
@@ -395,9 +328,9 @@ pub struct InvoiceLineItem {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<HashMap<String, String>>,
     pub period: Period,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, deserialize_with = "id_or_object", skip_serializing_if = "Option::is_none")]
     pub plan: Option<String>,
-    #[serde(skip_serializing_if = "Option::is_none")]
+    #[serde(default, deserialize_with = "id_or_object", skip_serializing_if = "Option::is_none")]
     pub price: Option<String>,
     pub proration: bool,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -418,6 +351,19 @@ pub struct InvoiceLineItem {
     pub unit_amount_excluding_tax: Option<String>,
 }
 
+/// Reads an ID that Stripe sends either on its own or, as on line items,
+/// inside the full object
+fn id_or_object<'de, D>(deserializer: D) -> Result<Option<String>, D::Error>
+where
+    D: serde::Deserializer<'de>,
+{
+    Ok(match Option::<serde_json::Value>::deserialize(deserializer)? {
+        Some(serde_json::Value::String(id)) => Some(id),
+        Some(serde_json::Value::Object(object)) => object.get("id").and_then(|id| id.as_str()).map(str::to_string),
+        _ => None,
+    })
+}
+
 /// Discount amount
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct DiscountAmount {
@@ -450,24 +396,58 @@ pub struct CreditedItems {
 impl Invoice {
     /// Retrieves an invoice by ID
     pub async fn get(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}", id);
+        let url = auth.url(&format!("invoices/{}", id));
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
         Ok(invoice)
     }
 
+    /// Iterate over all invoices, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "invoices", page)
+    }
+
+    /// Stream all invoices, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "invoices", page)
+    }
+
+    /// Iterate over invoices matching `query`, fetching further pages as needed
+    pub fn search_iter(auth: &Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(auth.clone(), "invoices/search", query, page)
+    }
+
+    /// Stream invoices matching `query`, fetching further pages as needed
+    pub fn search_stream(auth: &Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(auth.clone(), "invoices/search", query, page)
+    }
+
+    /// Searches for invoices, walking every page of results
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::{Invoice, SearchClause, SearchQuery};
+    ///
+    /// let query = SearchQuery::new(SearchClause::field("customer").eq("cus_123"))
+    ///     .and(SearchClause::field("status").eq("open"));
+    /// let invoices = Invoice::search(&auth, &query).await?;
+    /// ```
+    pub async fn search(auth: &Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_stream(auth, query, PageRequest::new()).try_collect().await
+    }
+
     /// Lists all invoices
     pub async fn list(auth: &Auth) -> Result<InvoiceList, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/invoices";
+        let url = auth.url("invoices");
         let response = get_shared_client()
             .get(url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoices = response.json::<InvoiceList>().await?;
@@ -476,12 +456,13 @@ impl Invoice {
 
     /// Creates a new invoice
     pub async fn create(auth: &Auth, params: CreateInvoiceParams) -> Result<Self, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/invoices";
+        let url = auth.url("invoices");
         let response = get_shared_client()
             .post(url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .form(&params)
-            .send()
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -490,12 +471,13 @@ impl Invoice {
 
     /// Updates an invoice
     pub async fn update(auth: &Auth, id: &str, params: UpdateInvoiceParams) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}", id);
+        let url = auth.url(&format!("invoices/{}", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .form(&params)
-            .send()
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -504,11 +486,12 @@ impl Invoice {
 
     /// Finalizes an invoice
     pub async fn finalize(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}/finalize", id);
+        let url = auth.url(&format!("invoices/{}/finalize", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -517,11 +500,12 @@ impl Invoice {
 
     /// Pays an invoice
     pub async fn pay(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}/pay", id);
+        let url = auth.url(&format!("invoices/{}/pay", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -530,11 +514,12 @@ impl Invoice {
 
     /// Sends an invoice for manual payment
     pub async fn send(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}/send", id);
+        let url = auth.url(&format!("invoices/{}/send", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -543,11 +528,12 @@ impl Invoice {
 
     /// Voids an invoice
     pub async fn void(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}/void", id);
+        let url = auth.url(&format!("invoices/{}/void", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -556,11 +542,42 @@ impl Invoice {
 
     /// Marks an invoice as uncollectible
     pub async fn mark_uncollectible(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}/mark_uncollectible", id);
+        let url = auth.url(&format!("invoices/{}/mark_uncollectible", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
+            .await?;
+        
+        let invoice = response.json::<Self>().await?;
+        Ok(invoice)
+    }
+
+    /// Previews the next invoice for a customer or subscription without
+    /// creating it. Pass `subscription_items` and proration settings to see
+    /// what a plan change would cost before applying it.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::invoice::{Invoice, UpcomingInvoiceParams, UpcomingInvoiceItemParams};
+    ///
+    /// let params = UpcomingInvoiceParams {
+    ///     subscription: Some("sub_123".to_string()),
+    ///     subscription_items: Some(vec![UpcomingInvoiceItemParams::swap("si_123", "price_pro")]),
+    ///     subscription_proration_date: Some(proration_date),
+    ///     ..Default::default()
+    /// };
+    /// let preview = Invoice::upcoming(&auth, params).await?;
+    /// let prorations: i64 = preview.lines.iter().flat_map(|l| &l.data).filter(|l| l.proration).map(|l| l.amount).sum();
+    /// ```
+    pub async fn upcoming(auth: &Auth, params: UpcomingInvoiceParams) -> Result<Self, crate::error::PayupError> {
+        let url = auth.url("invoices/upcoming");
+        let response = get_shared_client()
+            .get(&url)
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .query(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let invoice = response.json::<Self>().await?;
@@ -569,11 +586,11 @@ impl Invoice {
 
     /// Deletes a draft invoice
     pub async fn delete(auth: &Auth, id: &str) -> Result<DeletedInvoice, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/invoices/{}", id);
+        let url = auth.url(&format!("invoices/{}", id));
         let response = get_shared_client()
             .delete(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let result = response.json::<DeletedInvoice>().await?;
@@ -614,6 +631,9 @@ pub struct CreateInvoiceParams {
     pub subscription: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account_tax_ids: Option<Vec<String>>,
+    /// Calculate tax with Stripe Tax from the customer's location and tax IDs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_tax: Option<AutomaticTax>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub application_fee_amount: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -653,6 +673,9 @@ pub struct UpdateInvoiceParams {
     pub metadata: Option<HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub account_tax_ids: Option<Vec<String>>,
+    /// Calculate tax with Stripe Tax from the customer's location and tax IDs
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_tax: Option<AutomaticTax>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub application_fee_amount: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
@@ -679,6 +702,67 @@ pub struct UpdateInvoiceParams {
     pub transfer_data: Option<TransferData>,
 }
 
+/// Parameters for previewing an upcoming invoice
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UpcomingInvoiceParams {
+    /// Customer to preview; required unless `subscription` is set
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    /// Subscription to preview, defaulting to the customer's only subscription
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+    /// Preview the next invoice of a subscription schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub schedule: Option<String>,
+    /// Items the subscription would have, as changes to its current ones
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_items: Option<Vec<UpcomingInvoiceItemParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_cancel_at_period_end: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_proration_behavior: Option<ProrationBehavior>,
+    /// Calculate prorations as though the change happened at this time (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_proration_date: Option<i64>,
+    /// Preview a different trial end (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_trial_end: Option<i64>,
+}
+
+/// A subscription item change to preview
+#[derive(Debug, Clone, Serialize, Deserialize, Default)]
+pub struct UpcomingInvoiceItemParams {
+    /// ID of an existing subscription item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub deleted: Option<bool>,
+}
+
+impl UpcomingInvoiceItemParams {
+    /// Move an existing item to a different price
+    pub fn swap(item_id: &str, price: &str) -> Self {
+        Self {
+            id: Some(item_id.to_string()),
+            price: Some(price.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Change the quantity of an existing item
+    pub fn quantity(item_id: &str, quantity: u64) -> Self {
+        Self {
+            id: Some(item_id.to_string()),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+}
+
 /// Transfer data for invoices
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct TransferData {
@@ -0,0 +1,39 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use super::Auth;
+
+/// A single-use URL signing an Express account holder into their dashboard
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct LoginLink {
+    /// String representing the object's type (always "login_link")
+    pub object: String,
+    pub created: i64,
+    pub url: String,
+}
+
+impl LoginLink {
+    /// Create a login link for the Express account `account_id`
+    pub fn create(auth: &Auth, account_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("accounts/{}/login_links", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a login link for the Express account `account_id` (async)
+    pub async fn create_async(auth: &Auth, account_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("accounts/{}/login_links", account_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
@@ -0,0 +1,109 @@
+use serde::{Deserialize, Serialize};
+
+use crate::stripe::auth::Auth;
+use crate::stripe::charge::SepaDebit;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+
+/// A Mandate is a record of the permission a customer has given you to debit their payment method.
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Mandate {
+    pub id: String,
+    pub object: String,
+    pub customer_acceptance: CustomerAcceptance,
+    pub livemode: bool,
+    /// Present when the mandate covers a single payment
+    pub single_use: Option<SingleUse>,
+    pub payment_method: String,
+    pub payment_method_details: MandatePaymentMethodDetails,
+    /// One of `active`, `inactive` or `pending`
+    pub status: String,
+    /// Either `multi_use` or `single_use`
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+impl Mandate {
+    /// Asynchronously retrieves a Mandate with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the Mandate you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch mandate using id
+    /// let mandate = payup::stripe::Mandate::async_get(auth, "mandate_test123".to_string()).await?;
+    /// ```ignore
+    pub async fn async_get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("mandates/{}", id));
+        let request = get_shared_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+        let json = request.json::<Self>().await?;
+        Ok(json)
+    }
+
+    /// Retrieves a Mandate with the given ID.
+    ///
+    /// # Arguments
+    ///
+    /// * `auth` - payup::stripe::Auth::new(client, secret)
+    /// * `id` - The id of the Mandate you want to retrieve.
+    ///
+    /// # Examples
+    ///
+    /// ```ignore
+    /// // Create the Authentication refererence
+    /// let auth = payup::stripe::Auth::new("test_key".to_string(), "test_secret".to_string());
+    ///
+    /// // Fetch mandate using id
+    /// let mandate = payup::stripe::Mandate::get(auth, "mandate_test123".to_string())?;
+    /// ```ignore
+    pub fn get(creds: Auth, id: String) -> Result<Self, crate::error::PayupError> {
+        let url = creds.url(&format!("mandates/{}", id));
+        let request = get_shared_blocking_client()
+            .get(url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        let json = request.json::<Self>()?;
+        Ok(json)
+    }
+}
+
+/// Details about the customer's acceptance of the mandate
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct CustomerAcceptance {
+    pub accepted_at: Option<i64>,
+    /// Present when `type_field` is `online`
+    pub online: Option<Online>,
+    /// Either `online` or `offline`
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct Online {
+    pub ip_address: Option<String>,
+    pub user_agent: Option<String>,
+}
+
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct SingleUse {
+    pub amount: i64,
+    pub currency: String,
+}
+
+/// Payment method specific mandate information
+#[derive(Serialize, Deserialize, Debug, Clone)]
+pub struct MandatePaymentMethodDetails {
+    pub sepa_debit: Option<SepaDebit>,
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
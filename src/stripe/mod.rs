@@ -4,33 +4,85 @@ pub mod provider;
 
 // Core modules
 pub mod account;
+pub mod account_link;
+pub mod application_fee;
 pub mod auth;
 pub mod balance;
+pub mod billing_meter;
+pub mod billing_portal;
 pub mod builders;
 pub mod charge;
+pub mod checkout;
+pub mod coupon;
+pub mod credit_note;
 pub mod customer;
+pub mod customer_balance_transaction;
+pub mod dispute;
 pub mod event;
+pub mod file;
+pub mod file_link;
+pub(crate) mod form;
 pub mod invoice;
+pub mod login_link;
+pub mod mandate;
+pub mod pagination;
 pub mod payment_intent;
+pub mod payment_link;
 pub mod payment_method;
 pub mod plan;
+pub mod promotion_code;
+pub mod quote;
+pub mod radar;
+pub mod search;
+pub mod setup_intent;
 pub mod subscription;
+pub mod subscription_item;
+pub mod subscription_schedule;
+pub mod tax;
+pub mod tax_id;
+pub mod tax_rate;
+pub mod terminal;
 pub mod transfer;
+pub mod usage_batcher;
+pub mod usage_record;
 
 // Re-exports for backward compatibility
 pub use account::{Account, CreateAccountParams, BusinessProfile, Capabilities, Requirements, AccountSettings};
+pub use account_link::{AccountLink, AccountLinkType, AccountLinkCollect, CreateAccountLinkParams};
+pub use application_fee::{
+    ApplicationFee, ApplicationFeeRefund, ApplicationFeeRefundList, CreateApplicationFeeRefundParams,
+    ListApplicationFeesParams
+};
 pub use auth::Auth;
 pub use balance::{Balance, BalanceTransaction, BalanceAvailable, BalancePending, BalanceTransactions, FeeDetail, BalanceSourceTypes};
-pub use charge::{Charge, Card, Charges, PaymentMethodDetails, FraudDetails, BillingDetails, Address, Refunds, SepaDebit, Shipping, ShippingAddress};
+pub use charge::{Charge, Card, Charges, PaymentMethodDetails, FraudDetails, ChargeOutcome, BillingDetails, Address, Refunds, SepaDebit, Shipping, ShippingAddress};
 pub use event::{Event, EventData, EventRequest, EventList, ListEventsParams, EventTimeFilter, event_types};
-pub use invoice::{Invoice, InvoiceList, CreateInvoiceParams, UpdateInvoiceParams, InvoiceStatus, BillingReason, CollectionMethod, InvoiceLineItem, InvoiceLineItemList};
+pub use invoice::{Invoice, InvoiceList, CreateInvoiceParams, UpdateInvoiceParams, InvoiceStatus, BillingReason, CollectionMethod, InvoiceLineItem, InvoiceLineItemList, UpcomingInvoiceParams, UpcomingInvoiceItemParams};
+pub use checkout::{Session as CheckoutSession, CreateSessionParams, SessionLineItemParams, SessionMode, SessionStatus, LineItem, LineItemDiscount, PriceData, ProductData};
+pub use coupon::{
+    Coupon, CouponDuration, CouponAppliesTo, DeletedCoupon, Discount, DeletedDiscount, DiscountItem,
+    CreateCouponParams, UpdateCouponParams
+};
+pub use credit_note::{
+    CreditNote, CreditNoteLineItem, CreditNoteLineItemList, CreditNoteLineParams, CreditNoteLineType, CreditNoteReason,
+    CreditNoteStatus, CreditNoteType, CreateCreditNoteParams, UpdateCreditNoteParams, ListCreditNotesParams,
+};
 pub use customer::{Customer, Customers};
+pub use customer_balance_transaction::{
+    CustomerBalanceTransaction, CreateCustomerBalanceTransactionParams, UpdateCustomerBalanceTransactionParams,
+};
+pub use dispute::{Dispute, Disputes, Evidence, EvidenceDetails};
+pub use file::{File, Files};
+pub use file_link::{FileLink, FileLinks};
+pub use login_link::LoginLink;
+pub use mandate::{Mandate, CustomerAcceptance, MandatePaymentMethodDetails};
 pub use payment_intent::{
     PaymentIntent, PaymentIntentStatus, ConfirmationMethod, CaptureMethod, SetupFutureUsage,
     CreatePaymentIntentParams, UpdatePaymentIntentParams, ConfirmPaymentIntentParams,
     CapturePaymentIntentParams, CancelPaymentIntentParams, AutomaticPaymentMethods,
-    ShippingDetails, Address as PaymentIntentAddress, PaymentMethodOptions, TransferData
+    ShippingDetails, Address as PaymentIntentAddress, PaymentMethodOptions, CardPresentOptions, TransferData
 };
+pub use payment_link::{PaymentLink, CreatePaymentLinkParams, UpdatePaymentLinkParams, PaymentLinkLineItemParams};
 pub use payment_method::{
     PaymentMethod, PaymentMethodType as StripePaymentMethodType, 
     CreatePaymentMethodParams, CreateCardParams, BillingDetails as PaymentMethodBillingDetails,
@@ -42,11 +94,61 @@ pub use plan::{
     BillingScheme, Interval, AggregateUsage, TiersMode, UsageType, PriceType, TaxBehavior,
     TransformUsage, TransformQuantity, RoundingMode, Recurring, PlanTier, PriceTier
 };
-pub use subscription::Subscription;
+pub use promotion_code::{
+    PromotionCode, PromotionCodeRestrictions, CreatePromotionCodeParams, UpdatePromotionCodeParams,
+    ListPromotionCodesParams
+};
+pub use quote::{
+    Quote, QuoteStatus, QuoteLineItemList, QuoteLineItemParams, CreateQuoteParams, UpdateQuoteParams,
+    FinalizeQuoteParams, ListQuotesParams, PdfStream,
+};
+pub use radar::{
+    Review, ReviewOpenedReason, ValueList, ValueListItem, ValueListItemType, DeletedValueListObject,
+    CreateValueListParams, UpdateValueListParams, ListValueListsParams, ListValueListItemsParams,
+    EarlyFraudWarning, EarlyFraudWarningType, ListEarlyFraudWarningsParams,
+};
+pub use search::{SearchQuery, SearchClause, SearchField, SearchOperator, SearchValue, SearchResult};
+pub use setup_intent::{
+    SetupIntent, SetupIntentStatus, SetupIntentCancellationReason,
+    CreateSetupIntentParams, ConfirmSetupIntentParams, CancelSetupIntentParams
+};
+pub use subscription::{Subscription, SubscriptionItemParams};
+pub use subscription_item::{
+    SubscriptionItem, DeletedSubscriptionItem, ProrationBehavior, CreateSubscriptionItemParams,
+    UpdateSubscriptionItemParams, DeleteSubscriptionItemParams
+};
+pub use subscription_schedule::{
+    SubscriptionSchedule, SubscriptionScheduleStatus, EndBehavior, SchedulePhase, SchedulePhaseItem,
+    ScheduleStartDate, PhaseParams, PhaseItemParams, CreateSubscriptionScheduleParams,
+    UpdateSubscriptionScheduleParams, CancelSubscriptionScheduleParams
+};
+pub use billing_portal::{
+    BillingPortalSession, BillingPortalConfiguration, CreateBillingPortalSessionParams, PortalFlowParams,
+    CreateBillingPortalConfigurationParams, UpdateBillingPortalConfigurationParams, ListBillingPortalConfigurationsParams,
+    PortalFeatures,
+};
+pub use billing_meter::{
+    Meter, MeterEvent, MeterEventSummary, MeterAggregation, MeterStatus, CreateMeterParams,
+    CreateMeterEventParams, MeterEventSummaryParams
+};
+pub use tax::{
+    TaxCalculation, TaxTransaction, TaxLineItem, TaxBreakdown, TaxRateDetails, TaxCustomerDetails,
+    TaxAddressSource, TaxShippingCost, TaxTransactionType, TaxReversalMode, CreateTaxCalculationParams,
+    TaxLineItemParams, TaxShippingCostParams, CreateTaxTransactionParams, ReverseTaxTransactionParams,
+    ReversalLineItemParams
+};
+pub use tax_id::{TaxId, TaxIdVerification, TaxIdVerificationStatus, DeletedTaxId, CreateTaxIdParams};
+pub use tax_rate::{TaxRate, CreateTaxRateParams, UpdateTaxRateParams, ListTaxRatesParams};
+pub use terminal::{
+    ConnectionToken as TerminalConnectionToken, Location as TerminalLocation, Reader as TerminalReader,
+    Configuration as TerminalConfiguration,
+};
+pub use usage_batcher::{UsageBatcher, UsageTarget};
+pub use usage_record::{UsageRecord, UsageRecordSummary, UsageAction, CreateUsageRecordParams};
 pub use transfer::{Transfer, TransferReversal, TransferReversalList, CreateTransferParams, UpdateTransferParams, CreateReversalParams};
-pub use webhooks::{StripeWebhookHandler, WebhookEvent, WebhookEventType, WebhookEventData, WebhookRequest};
+pub use webhooks::{StripeWebhookHandler, StripeEventObject, WebhookEvent, WebhookEventType, WebhookEventData, WebhookRequest};
 pub use provider::StripeProvider;
 pub use builders::{
     ChargeBuilder, ShippingBuilder, ShippingAddressBuilder,
-    PaymentIntentBuilder, InvoiceBuilder, PlanBuilder, PriceBuilder
+    PaymentIntentBuilder, InvoiceBuilder, PlanBuilder, PriceBuilder, CouponBuilder, PromotionCodeBuilder
 };
\ No newline at end of file
@@ -0,0 +1,200 @@
+//! Auto-pagination over Stripe list endpoints.
+//!
+//! Every Stripe list answers with `{"has_more": ..., "data": [...]}` and is
+//! paged with the ID of an object on the current page as cursor, so one
+//! implementation serves every resource. Search results instead carry an
+//! opaque `next_page` token, sent back as `page`.
+
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use serde_json::Value;
+
+use crate::builders::PageRequest;
+use crate::error::{PayupError, Result};
+use crate::http_client::{get_shared_blocking_client, get_shared_client, SendVia, SendViaBlocking};
+use crate::pagination::{page_stream, Page, PageStream, Paginator};
+use crate::stripe::auth::Auth;
+use crate::stripe::search::SearchQuery;
+
+#[derive(Deserialize)]
+struct ListPage {
+    has_more: bool,
+    data: Vec<Value>,
+}
+
+/// Where and which way to walk a list.
+///
+/// Stripe lists are newest first: `starting_after` pages towards older
+/// objects with the last ID of each page, `ending_before` towards newer ones
+/// with the first.
+#[derive(Clone)]
+struct Walk {
+    url: String,
+    page_size: Option<usize>,
+    backwards: bool,
+}
+
+impl Walk {
+    fn new(creds: &Auth, path: &str, page: &PageRequest) -> (Self, Option<String>) {
+        let backwards = page.ending_before.is_some();
+        let walk = Walk {
+            url: creds.url(path),
+            page_size: page.page_size(),
+            backwards,
+        };
+        let start = if backwards { page.ending_before.clone() } else { page.starting_after.clone() };
+        (walk, start)
+    }
+
+    fn query(&self, cursor: Option<String>) -> Vec<(&'static str, String)> {
+        let mut query = Vec::new();
+        if let Some(page_size) = self.page_size {
+            query.push(("limit", page_size.to_string()));
+        }
+        if let Some(cursor) = cursor {
+            query.push((if self.backwards { "ending_before" } else { "starting_after" }, cursor));
+        }
+        query
+    }
+
+    fn page<T: DeserializeOwned>(&self, list: ListPage) -> Result<Page<T>> {
+        let next_cursor = if list.has_more {
+            let edge = if self.backwards { list.data.first() } else { list.data.last() };
+            edge.and_then(|object| object["id"].as_str()).map(String::from)
+        } else {
+            None
+        };
+        let items = list
+            .data
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<Vec<T>, _>>()?;
+        Ok(Page { items, next_cursor })
+    }
+}
+
+/// Blocking iterator over the list at `path`, which may carry filters as a
+/// query string (e.g. `"refunds?charge=ch_123"`).
+pub(crate) fn list_iter<T>(creds: Auth, path: &str, page: PageRequest) -> Paginator<T>
+where
+    T: DeserializeOwned,
+{
+    let (walk, start) = Walk::new(&creds, path, &page);
+    Paginator::new(start, page.max_items, move |cursor| {
+        let response = get_shared_blocking_client()
+            .get(&walk.url)
+            .query(&walk.query(cursor))
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        walk.page(response.json::<ListPage>()?)
+    })
+}
+
+/// Async counterpart of [`list_iter`].
+pub(crate) fn list_stream<T>(creds: Auth, path: &str, page: PageRequest) -> PageStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let (walk, start) = Walk::new(&creds, path, &page);
+    page_stream(start, page.max_items, move |cursor| {
+        let creds = creds.clone();
+        let walk = walk.clone();
+        async move {
+            let response = get_shared_client()
+                .get(&walk.url)
+                .query(&walk.query(cursor))
+                .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+                .send_limited("stripe", creds.transport())
+                .await?;
+            walk.page(response.json::<ListPage>().await?)
+        }
+    })
+}
+
+#[derive(Deserialize)]
+struct SearchPage {
+    data: Vec<Value>,
+    next_page: Option<String>,
+}
+
+/// A search to walk, one `next_page` token at a time.
+#[derive(Clone)]
+struct Search {
+    url: String,
+    query: String,
+    problem: Option<&'static str>,
+    page_size: Option<usize>,
+}
+
+impl Search {
+    fn new(creds: &Auth, path: &str, query: &SearchQuery, page: &PageRequest) -> Self {
+        Search {
+            url: creds.url(path),
+            query: query.to_string(),
+            problem: query.problem(),
+            page_size: page.page_size(),
+        }
+    }
+
+    fn query(&self, token: Option<String>) -> Result<Vec<(&'static str, String)>> {
+        if let Some(problem) = self.problem {
+            return Err(PayupError::ValidationError(problem.to_string()));
+        }
+        let mut query = vec![("query", self.query.clone())];
+        if let Some(page_size) = self.page_size {
+            query.push(("limit", page_size.to_string()));
+        }
+        if let Some(token) = token {
+            query.push(("page", token));
+        }
+        Ok(query)
+    }
+
+    fn page<T: DeserializeOwned>(&self, results: SearchPage) -> Result<Page<T>> {
+        let items = results
+            .data
+            .into_iter()
+            .map(serde_json::from_value)
+            .collect::<std::result::Result<Vec<T>, _>>()?;
+        Ok(Page { items, next_cursor: results.next_page })
+    }
+}
+
+/// Blocking iterator over the results of searching `path` (e.g.
+/// `"customers/search"`). `page.starting_after` resumes from a saved
+/// `next_page` token; an invalid query is yielded as the only item.
+pub(crate) fn search_iter<T>(creds: Auth, path: &str, query: &SearchQuery, page: PageRequest) -> Paginator<T>
+where
+    T: DeserializeOwned,
+{
+    let search = Search::new(&creds, path, query, &page);
+    Paginator::new(page.starting_after, page.max_items, move |token| {
+        let response = get_shared_blocking_client()
+            .get(&search.url)
+            .query(&search.query(token)?)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())?;
+        search.page(response.json::<SearchPage>()?)
+    })
+}
+
+/// Async counterpart of [`search_iter`].
+pub(crate) fn search_stream<T>(creds: Auth, path: &str, query: &SearchQuery, page: PageRequest) -> PageStream<T>
+where
+    T: DeserializeOwned + Send + 'static,
+{
+    let search = Search::new(&creds, path, query, &page);
+    page_stream(page.starting_after, page.max_items, move |token| {
+        let creds = creds.clone();
+        let search = search.clone();
+        async move {
+            let response = get_shared_client()
+                .get(&search.url)
+                .query(&search.query(token)?)
+                .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+                .send_limited("stripe", creds.transport())
+                .await?;
+            search.page(response.json::<SearchPage>().await?)
+        }
+    })
+}
@@ -1,8 +1,13 @@
 use serde::{Deserialize, Serialize};
+use futures::TryStreamExt;
 use crate::error::Result;
-use crate::rate_limiter::get_rate_limiter;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
 use super::Auth;
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use crate::stripe::search::SearchQuery;
 
 /// Status of a payment intent
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -26,7 +31,7 @@ pub enum ConfirmationMethod {
 }
 
 /// Capture method for payment intents
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum CaptureMethod {
     Automatic,
@@ -99,11 +104,21 @@ pub struct RedirectToUrl {
     pub url: String,
 }
 
+impl NextAction {
+    /// The URL to send the customer to, when the action is a redirect
+    pub fn redirect_url(&self) -> Option<&str> {
+        self.redirect_to_url.as_ref().map(|redirect| redirect.url.as_str())
+    }
+}
+
 /// Payment method options
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
 pub struct PaymentMethodOptions {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub card: Option<CardOptions>,
+    /// Options for cards read in person by a Terminal reader
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_present: Option<CardPresentOptions>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub us_bank_account: Option<UsBankAccountOptions>,
 }
@@ -117,6 +132,19 @@ pub struct CardOptions {
     pub setup_future_usage: Option<SetupFutureUsage>,
 }
 
+/// Card present payment method options
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CardPresentOptions {
+    /// Ask the issuer for an authorization that stays capturable for longer
+    /// than the usual two days
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_extended_authorization: Option<bool>,
+    /// Ask the issuer to allow increasing the authorized amount later, e.g.
+    /// to add a tip after the fact
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub request_incremental_authorization_support: Option<bool>,
+}
+
 /// US Bank Account payment method options
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct UsBankAccountOptions {
@@ -220,11 +248,17 @@ pub struct TransferData {
 pub struct CreatePaymentIntentParams {
     pub amount: i64,
     pub currency: String,
+    /// Fee in the smallest currency unit kept by the platform from a charge
+    /// made on behalf of, or transferred to, a connected account
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_fee_amount: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub automatic_payment_methods: Option<AutomaticPaymentMethods>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub capture_method: Option<CaptureMethod>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub confirmation_method: Option<ConfirmationMethod>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer: Option<String>,
@@ -233,6 +267,10 @@ pub struct CreatePaymentIntentParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub metadata: Option<std::collections::HashMap<String, String>>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub off_session: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_method: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub payment_method_options: Option<PaymentMethodOptions>,
@@ -260,6 +298,8 @@ pub struct UpdatePaymentIntentParams {
     #[serde(skip_serializing_if = "Option::is_none")]
     pub amount: Option<i64>,
     #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_fee_amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
     pub currency: Option<String>,
     #[serde(skip_serializing_if = "Option::is_none")]
     pub customer: Option<String>,
@@ -362,10 +402,11 @@ impl PaymentIntent {
     pub fn create(auth: &Auth, params: CreatePaymentIntentParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post("https://api.stripe.com/v1/payment_intents")
+            .post(auth.url("payment_intents"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -373,23 +414,17 @@ impl PaymentIntent {
 
     /// Create a new payment intent (async)
     pub async fn create_async(auth: &Auth, params: CreatePaymentIntentParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url("payment_intents"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post("https://api.stripe.com/v1/payment_intents")
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
     }
 
     /// Retrieve a payment intent by ID
@@ -404,9 +439,9 @@ impl PaymentIntent {
     pub fn retrieve(auth: &Auth, payment_intent_id: &str) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/payment_intents/{}", payment_intent_id))
+            .get(auth.url(&format!("payment_intents/{}", payment_intent_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -414,23 +449,15 @@ impl PaymentIntent {
 
     /// Retrieve a payment intent by ID (async)
     pub async fn retrieve_async(auth: &Auth, payment_intent_id: &str) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_intent_id = payment_intent_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .get(auth.url(&format!("payment_intents/{}", payment_intent_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_intent_id = payment_intent_id.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .get(&format!("https://api.stripe.com/v1/payment_intents/{}", payment_intent_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
     }
 
     /// Update a payment intent
@@ -450,10 +477,11 @@ impl PaymentIntent {
     pub fn update(auth: &Auth, payment_intent_id: &str, params: UpdatePaymentIntentParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_intents/{}", payment_intent_id))
+            .post(auth.url(&format!("payment_intents/{}", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -461,25 +489,17 @@ impl PaymentIntent {
 
     /// Update a payment intent (async)
     pub async fn update_async(auth: &Auth, payment_intent_id: &str, params: UpdatePaymentIntentParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_intent_id = payment_intent_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("payment_intents/{}", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_intent_id = payment_intent_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_intents/{}", payment_intent_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
     }
 
     /// Confirm a payment intent
@@ -501,10 +521,11 @@ impl PaymentIntent {
     pub fn confirm(auth: &Auth, payment_intent_id: &str, params: ConfirmPaymentIntentParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_intents/{}/confirm", payment_intent_id))
+            .post(auth.url(&format!("payment_intents/{}/confirm", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -512,25 +533,17 @@ impl PaymentIntent {
 
     /// Confirm a payment intent (async)
     pub async fn confirm_async(auth: &Auth, payment_intent_id: &str, params: ConfirmPaymentIntentParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_intent_id = payment_intent_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("payment_intents/{}/confirm", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_intent_id = payment_intent_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_intents/{}/confirm", payment_intent_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
     }
 
     /// Capture a payment intent
@@ -552,10 +565,11 @@ impl PaymentIntent {
     pub fn capture(auth: &Auth, payment_intent_id: &str, params: CapturePaymentIntentParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_intents/{}/capture", payment_intent_id))
+            .post(auth.url(&format!("payment_intents/{}/capture", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -563,25 +577,17 @@ impl PaymentIntent {
 
     /// Capture a payment intent (async)
     pub async fn capture_async(auth: &Auth, payment_intent_id: &str, params: CapturePaymentIntentParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_intent_id = payment_intent_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("payment_intents/{}/capture", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_intent_id = payment_intent_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_intents/{}/capture", payment_intent_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
     }
 
     /// Cancel a payment intent
@@ -599,10 +605,11 @@ impl PaymentIntent {
     pub fn cancel(auth: &Auth, payment_intent_id: &str, params: CancelPaymentIntentParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_intents/{}/cancel", payment_intent_id))
+            .post(auth.url(&format!("payment_intents/{}/cancel", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let payment_intent: PaymentIntent = response.json()?;
         Ok(payment_intent)
@@ -610,25 +617,56 @@ impl PaymentIntent {
 
     /// Cancel a payment intent (async)
     pub async fn cancel_async(auth: &Auth, payment_intent_id: &str, params: CancelPaymentIntentParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_intent_id = payment_intent_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("payment_intents/{}/cancel", payment_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_intent_id = payment_intent_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_intents/{}/cancel", payment_intent_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_intent: PaymentIntent = response.json().await?;
-                Ok(payment_intent)
-            }
-        }).await
+        let payment_intent: PaymentIntent = response.json().await?;
+        Ok(payment_intent)
+    }
+
+    /// Iterate over all payment intents, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "payment_intents", page)
+    }
+
+    /// Stream all payment intents, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "payment_intents", page)
+    }
+
+    /// Search payment intents, walking every page of results or up to `page.max_items`
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::{PaymentIntent, SearchClause, SearchQuery};
+    ///
+    /// let query = SearchQuery::new(SearchClause::field("status").eq("succeeded"))
+    ///     .and(SearchClause::metadata("order_id").eq("6735"));
+    /// let payment_intents = PaymentIntent::search(&auth, &query, PageRequest::new())?;
+    /// ```
+    pub fn search(auth: &Auth, query: &SearchQuery, page: PageRequest) -> Result<Vec<Self>> {
+        Self::search_iter(auth, query, page).collect()
+    }
+
+    /// Search payment intents (async)
+    pub async fn search_async(auth: &Auth, query: &SearchQuery, page: PageRequest) -> Result<Vec<Self>> {
+        Self::search_stream(auth, query, page).try_collect().await
+    }
+
+    /// Iterate over payment intents matching `query`, fetching further pages as needed
+    pub fn search_iter(auth: &Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(auth.clone(), "payment_intents/search", query, page)
+    }
+
+    /// Stream payment intents matching `query`, fetching further pages as needed
+    pub fn search_stream(auth: &Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(auth.clone(), "payment_intents/search", query, page)
     }
 
     /// List all payment intents
@@ -642,7 +680,7 @@ impl PaymentIntent {
     /// ```
     pub fn list(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
         let client = get_shared_blocking_client();
-        let mut url = "https://api.stripe.com/v1/payment_intents".to_string();
+        let mut url = auth.url("payment_intents");
         if let Some(limit) = limit {
             url = format!("{}?limit={}", url, limit);
         }
@@ -650,7 +688,7 @@ impl PaymentIntent {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         #[derive(Deserialize)]
         struct PaymentIntentList {
@@ -663,29 +701,38 @@ impl PaymentIntent {
 
     /// List all payment intents (async)
     pub async fn list_async(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
-        let rate_limiter = get_rate_limiter();
+        let client = get_shared_client();
+        let mut url = auth.url("payment_intents");
+        if let Some(limit) = limit {
+            url = format!("{}?limit={}", url, limit);
+        }
         
-        rate_limiter.execute_with_retry_async("stripe", move || async move {
-            let client = get_shared_client();
-            let mut url = "https://api.stripe.com/v1/payment_intents".to_string();
-            if let Some(limit) = limit {
-                url = format!("{}?limit={}", url, limit);
-            }
-            
-            let response = client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", auth.secret))
-                .send()
-                .await?;
-            
-            #[derive(Deserialize)]
-            struct PaymentIntentList {
-                data: Vec<PaymentIntent>,
-            }
-            
-            let list: PaymentIntentList = response.json().await?;
-            Ok(list.data)
-        }).await
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+        
+        #[derive(Deserialize)]
+        struct PaymentIntentList {
+            data: Vec<PaymentIntent>,
+        }
+        
+        let list: PaymentIntentList = response.json().await?;
+        Ok(list.data)
+    }
+}
+
+impl CreatePaymentIntentParams {
+    /// Parameters for a payment collected in person by a Terminal reader,
+    /// which is then handed to `terminal::Reader::process_payment_intent`
+    pub fn card_present(amount: i64, currency: &str) -> Self {
+        Self {
+            amount,
+            currency: currency.to_string(),
+            payment_method_types: Some(vec!["card_present".to_string()]),
+            ..Default::default()
+        }
     }
 }
 
@@ -693,6 +740,7 @@ impl Default for UpdatePaymentIntentParams {
     fn default() -> Self {
         Self {
             amount: None,
+            application_fee_amount: None,
             currency: None,
             customer: None,
             description: None,
@@ -716,12 +764,16 @@ impl Default for CreatePaymentIntentParams {
         Self {
             amount: 0,
             currency: String::new(),
+            application_fee_amount: None,
             automatic_payment_methods: None,
             capture_method: None,
+            confirm: None,
             confirmation_method: None,
             customer: None,
             description: None,
             metadata: None,
+            off_session: None,
+            on_behalf_of: None,
             payment_method: None,
             payment_method_options: None,
             payment_method_types: None,
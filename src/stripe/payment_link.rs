@@ -0,0 +1,259 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::checkout::{AdjustableQuantity, LineItem, LineItemList};
+use super::plan::Price;
+use super::Auth;
+
+/// A shareable URL that takes customers to a hosted payment page
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentLink {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "payment_link")
+    pub object: String,
+    /// Whether the payment link's URL is active
+    pub active: bool,
+    /// Behavior after the purchase is complete
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_completion: Option<AfterCompletion>,
+    /// Whether user redeemable promotion codes are enabled
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_promotion_codes: Option<bool>,
+    /// Three-letter ISO currency code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    /// The line items representing what is being sold, only present when expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<LineItemList>,
+    /// Has the value true if the object exists in live mode
+    pub livemode: bool,
+    /// Set of key-value pairs that you can attach to an object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// The public URL that can be shared with customers
+    pub url: String,
+}
+
+/// What happens after a payment link purchase completes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AfterCompletion {
+    /// Either `hosted_confirmation` or `redirect`
+    #[serde(rename = "type")]
+    pub type_field: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hosted_confirmation: Option<HostedConfirmation>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub redirect: Option<AfterCompletionRedirect>,
+}
+
+impl AfterCompletion {
+    /// Redirect the customer to `url` after they pay
+    pub fn redirect(url: &str) -> Self {
+        Self {
+            type_field: "redirect".to_string(),
+            hosted_confirmation: None,
+            redirect: Some(AfterCompletionRedirect { url: url.to_string() }),
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct HostedConfirmation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub custom_message: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AfterCompletionRedirect {
+    pub url: String,
+}
+
+/// Parameters for creating a payment link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePaymentLinkParams {
+    /// The items being sold
+    pub line_items: Vec<PaymentLinkLineItemParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_completion: Option<AfterCompletion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_promotion_codes: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreatePaymentLinkParams {
+    /// Parameters for a link selling `line_items`
+    pub fn new(line_items: Vec<PaymentLinkLineItemParams>) -> Self {
+        Self {
+            line_items,
+            after_completion: None,
+            allow_promotion_codes: None,
+            metadata: None,
+        }
+    }
+}
+
+/// A price to sell through a payment link
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PaymentLinkLineItemParams {
+    pub price: String,
+    pub quantity: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustable_quantity: Option<AdjustableQuantity>,
+}
+
+impl PaymentLinkLineItemParams {
+    /// Sells `quantity` of an existing price
+    pub fn price(price: &Price, quantity: u64) -> Self {
+        Self {
+            price: price.id.clone(),
+            quantity,
+            adjustable_quantity: None,
+        }
+    }
+}
+
+/// Parameters for updating a payment link
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePaymentLinkParams {
+    /// Deactivating a link stops it from accepting new payments
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub after_completion: Option<AfterCompletion>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub allow_promotion_codes: Option<bool>,
+    /// Quantity changes to existing line items, by line item ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<Vec<UpdatePaymentLinkLineItemParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UpdatePaymentLinkLineItemParams {
+    pub id: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub adjustable_quantity: Option<AdjustableQuantity>,
+}
+
+impl PaymentLink {
+    /// Create a new payment link
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::payment_link::{CreatePaymentLinkParams, PaymentLink, PaymentLinkLineItemParams};
+    ///
+    /// let params = CreatePaymentLinkParams::new(vec![PaymentLinkLineItemParams::price(&price, 1)]);
+    /// let link = PaymentLink::create(&auth, params)?;
+    /// println!("Share {}", link.url);
+    /// ```
+    pub fn create(auth: &Auth, params: CreatePaymentLinkParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("payment_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new payment link (async)
+    pub async fn create_async(auth: &Auth, params: CreatePaymentLinkParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("payment_links"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a payment link by ID
+    pub fn retrieve(auth: &Auth, link_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("payment_links/{}", link_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a payment link by ID (async)
+    pub async fn retrieve_async(auth: &Auth, link_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("payment_links/{}", link_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a payment link
+    pub fn update(auth: &Auth, link_id: &str, params: UpdatePaymentLinkParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("payment_links/{}", link_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a payment link (async)
+    pub async fn update_async(auth: &Auth, link_id: &str, params: UpdatePaymentLinkParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("payment_links/{}", link_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List payment links, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List payment links (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over all payment links, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "payment_links", page)
+    }
+
+    /// Stream all payment links, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "payment_links", page)
+    }
+
+    /// Iterate over the line items of a payment link, fetching further pages as needed
+    pub fn list_line_items_iter(auth: &Auth, link_id: &str, page: PageRequest) -> Paginator<LineItem> {
+        pagination::list_iter(auth.clone(), &format!("payment_links/{}/line_items", link_id), page)
+    }
+
+    /// Stream the line items of a payment link, fetching further pages as needed
+    pub fn list_line_items_stream(auth: &Auth, link_id: &str, page: PageRequest) -> PageStream<LineItem> {
+        pagination::list_stream(auth.clone(), &format!("payment_links/{}/line_items", link_id), page)
+    }
+}
@@ -1,8 +1,9 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
-use crate::rate_limiter::get_rate_limiter;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
 use super::Auth;
 use std::collections::HashMap;
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
 
 /// Type of payment method
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -321,12 +322,13 @@ impl PaymentMethod {
 
     /// Create a new payment method
     pub fn create(auth: &Auth, params: CreatePaymentMethodParams) -> Result<Self> {
-        let client = reqwest::blocking::Client::new();
+        let client = get_shared_blocking_client();
         let response = client
-            .post("https://api.stripe.com/v1/payment_methods")
+            .post(auth.url("payment_methods"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .form(&params)
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let payment_method: PaymentMethod = response.json()?;
         Ok(payment_method)
@@ -334,32 +336,26 @@ impl PaymentMethod {
 
     /// Create a new payment method (async)
     pub async fn create_async(auth: &Auth, params: CreatePaymentMethodParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url("payment_methods"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&params)
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let params = params.clone();
-            async move {
-                let client = reqwest::Client::new();
-                let response = client
-                    .post("https://api.stripe.com/v1/payment_methods")
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_method: PaymentMethod = response.json().await?;
-                Ok(payment_method)
-            }
-        }).await
+        let payment_method: PaymentMethod = response.json().await?;
+        Ok(payment_method)
     }
 
     /// Retrieve a payment method by ID
     pub fn retrieve(auth: &Auth, payment_method_id: &str) -> Result<Self> {
-        let client = reqwest::blocking::Client::new();
+        let client = get_shared_blocking_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/payment_methods/{}", payment_method_id))
+            .get(auth.url(&format!("payment_methods/{}", payment_method_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let payment_method: PaymentMethod = response.json()?;
         Ok(payment_method)
@@ -367,37 +363,30 @@ impl PaymentMethod {
 
     /// Retrieve a payment method by ID (async)
     pub async fn retrieve_async(auth: &Auth, payment_method_id: &str) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_method_id = payment_method_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .get(auth.url(&format!("payment_methods/{}", payment_method_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_method_id = payment_method_id.clone();
-            async move {
-                let client = reqwest::Client::new();
-                let response = client
-                    .get(&format!("https://api.stripe.com/v1/payment_methods/{}", payment_method_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                let payment_method: PaymentMethod = response.json().await?;
-                Ok(payment_method)
-            }
-        }).await
+        let payment_method: PaymentMethod = response.json().await?;
+        Ok(payment_method)
     }
 
     /// Attach a payment method to a customer
     pub fn attach(auth: &Auth, payment_method_id: &str, customer_id: &str) -> Result<Self> {
-        let client = reqwest::blocking::Client::new();
+        let client = get_shared_blocking_client();
         let params = AttachPaymentMethodParams {
             customer: customer_id.to_string(),
         };
         
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_methods/{}/attach", payment_method_id))
+            .post(auth.url(&format!("payment_methods/{}/attach", payment_method_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
             .form(&params)
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let payment_method: PaymentMethod = response.json()?;
         Ok(payment_method)
@@ -405,39 +394,31 @@ impl PaymentMethod {
 
     /// Attach a payment method to a customer (async)
     pub async fn attach_async(auth: &Auth, payment_method_id: &str, customer_id: &str) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_method_id = payment_method_id.to_string();
-        let customer_id = customer_id.to_string();
+        let client = get_shared_client();
+        let params = AttachPaymentMethodParams {
+            customer: customer_id.to_string(),
+        };
+        
+        let response = client
+            .post(auth.url(&format!("payment_methods/{}/attach", payment_method_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&params)
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_method_id = payment_method_id.clone();
-            let customer_id = customer_id.clone();
-            async move {
-                let client = reqwest::Client::new();
-                let params = AttachPaymentMethodParams {
-                    customer: customer_id,
-                };
-                
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_methods/{}/attach", payment_method_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let payment_method: PaymentMethod = response.json().await?;
-                Ok(payment_method)
-            }
-        }).await
+        let payment_method: PaymentMethod = response.json().await?;
+        Ok(payment_method)
     }
 
     /// Detach a payment method from a customer
     pub fn detach(auth: &Auth, payment_method_id: &str) -> Result<Self> {
-        let client = reqwest::blocking::Client::new();
+        let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/payment_methods/{}/detach", payment_method_id))
+            .post(auth.url(&format!("payment_methods/{}/detach", payment_method_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let payment_method: PaymentMethod = response.json()?;
         Ok(payment_method)
@@ -445,22 +426,15 @@ impl PaymentMethod {
 
     /// Detach a payment method from a customer (async)
     pub async fn detach_async(auth: &Auth, payment_method_id: &str) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let payment_method_id = payment_method_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("payment_methods/{}/detach", payment_method_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let payment_method_id = payment_method_id.clone();
-            async move {
-                let client = reqwest::Client::new();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/payment_methods/{}/detach", payment_method_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                let payment_method: PaymentMethod = response.json().await?;
-                Ok(payment_method)
-            }
-        }).await
+        let payment_method: PaymentMethod = response.json().await?;
+        Ok(payment_method)
     }
 }
\ No newline at end of file
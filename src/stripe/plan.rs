@@ -1,7 +1,12 @@
 use serde::{Deserialize, Serialize};
 use std::collections::HashMap;
 use crate::stripe::auth::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::search::{SearchQuery, SearchResult};
 
 /// Represents a subscription plan
 /// 
@@ -128,7 +133,7 @@ pub enum BillingScheme {
 }
 
 /// Billing interval
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum Interval {
     Day,
@@ -172,7 +177,7 @@ pub enum PriceType {
 }
 
 /// Tax behavior
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
 #[serde(rename_all = "snake_case")]
 pub enum TaxBehavior {
     Exclusive,
@@ -267,12 +272,13 @@ pub struct PriceTier {
 impl Plan {
     /// Creates a new plan
     pub async fn create(auth: &Auth, params: CreatePlanParams) -> Result<Self, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/plans";
+        let url = auth.url("plans");
         let response = get_shared_client()
             .post(url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
             .form(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let plan = response.json::<Self>().await?;
@@ -281,11 +287,11 @@ impl Plan {
 
     /// Retrieves a plan by ID
     pub async fn get(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/plans/{}", id);
+        let url = auth.url(&format!("plans/{}", id));
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let plan = response.json::<Self>().await?;
@@ -294,12 +300,13 @@ impl Plan {
 
     /// Updates a plan
     pub async fn update(auth: &Auth, id: &str, params: UpdatePlanParams) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/plans/{}", id);
+        let url = auth.url(&format!("plans/{}", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
             .form(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let plan = response.json::<Self>().await?;
@@ -308,24 +315,34 @@ impl Plan {
 
     /// Deletes a plan
     pub async fn delete(auth: &Auth, id: &str) -> Result<DeletedPlan, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/plans/{}", id);
+        let url = auth.url(&format!("plans/{}", id));
         let response = get_shared_client()
             .delete(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let result = response.json::<DeletedPlan>().await?;
         Ok(result)
     }
 
+    /// Iterate over all plans, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "plans", page)
+    }
+
+    /// Stream all plans, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "plans", page)
+    }
+
     /// Lists all plans
     pub async fn list(auth: &Auth) -> Result<PlanList, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/plans";
+        let url = auth.url("plans");
         let response = get_shared_client()
             .get(url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let plans = response.json::<PlanList>().await?;
@@ -336,12 +353,13 @@ impl Plan {
 impl Price {
     /// Creates a new price
     pub async fn create(auth: &Auth, params: CreatePriceParams) -> Result<Self, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/prices";
+        let url = auth.url("prices");
         let response = get_shared_client()
             .post(url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
             .form(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let price = response.json::<Self>().await?;
@@ -350,11 +368,11 @@ impl Price {
 
     /// Retrieves a price by ID
     pub async fn get(auth: &Auth, id: &str) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/prices/{}", id);
+        let url = auth.url(&format!("prices/{}", id));
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let price = response.json::<Self>().await?;
@@ -363,38 +381,59 @@ impl Price {
 
     /// Updates a price
     pub async fn update(auth: &Auth, id: &str, params: UpdatePriceParams) -> Result<Self, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/prices/{}", id);
+        let url = auth.url(&format!("prices/{}", id));
         let response = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .basic_auth(&auth.client, Some(&auth.secret))
             .form(&params)
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let price = response.json::<Self>().await?;
         Ok(price)
     }
 
+    /// Iterate over all prices, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "prices", page)
+    }
+
+    /// Stream all prices, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "prices", page)
+    }
+
     /// Lists all prices
     pub async fn list(auth: &Auth) -> Result<PriceList, crate::error::PayupError> {
-        let url = "https://api.stripe.com/v1/prices";
+        let url = auth.url("prices");
         let response = get_shared_client()
             .get(url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let prices = response.json::<PriceList>().await?;
         Ok(prices)
     }
 
-    /// Searches for prices
+    /// Iterate over prices matching `query`, fetching further pages as needed
+    pub fn search_iter(auth: &Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(auth.clone(), "prices/search", query, page)
+    }
+
+    /// Stream prices matching `query`, fetching further pages as needed
+    pub fn search_stream(auth: &Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(auth.clone(), "prices/search", query, page)
+    }
+
+    /// Searches for prices, returning the first page of results
     pub async fn search(auth: &Auth, query: &str) -> Result<PriceSearchResult, crate::error::PayupError> {
-        let url = format!("https://api.stripe.com/v1/prices/search?query={}", urlencoding::encode(query));
+        let url = auth.url(&format!("prices/search?query={}", urlencoding::encode(query)));
         let response = get_shared_client()
             .get(&url)
             .basic_auth(&auth.client, Some(&auth.secret))
-            .send()
+            .send_limited("stripe", auth.transport())
             .await?;
         
         let result = response.json::<PriceSearchResult>().await?;
@@ -421,14 +460,7 @@ pub struct PriceList {
 }
 
 /// Response from searching prices
-#[derive(Debug, Clone, Serialize, Deserialize)]
-pub struct PriceSearchResult {
-    pub object: String,
-    pub url: String,
-    pub has_more: bool,
-    pub data: Vec<Price>,
-    pub next_page: Option<String>,
-}
+pub type PriceSearchResult = SearchResult<Price>;
 
 /// Response from deleting a plan
 #[derive(Debug, Clone, Serialize, Deserialize)]
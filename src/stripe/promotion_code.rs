@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::coupon::Coupon;
+use super::Auth;
+
+/// A customer-facing code that redeems a coupon
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PromotionCode {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "promotion_code")
+    pub object: String,
+    /// Whether the code can currently be redeemed
+    pub active: bool,
+    /// The code customers enter, unique among active codes
+    pub code: String,
+    /// The coupon the code redeems
+    pub coupon: Coupon,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    /// The only customer allowed to redeem the code, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_redemptions: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(default)]
+    pub restrictions: PromotionCodeRestrictions,
+    #[serde(default)]
+    pub times_redeemed: u64,
+}
+
+/// Conditions an order has to meet for a promotion code to apply
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PromotionCodeRestrictions {
+    /// Only customers who have never paid before can redeem the code
+    #[serde(default)]
+    pub first_time_transaction: bool,
+    /// Minimum order amount in the smallest currency unit
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_amount: Option<i64>,
+    /// Currency of `minimum_amount`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub minimum_amount_currency: Option<String>,
+}
+
+/// Parameters for creating a promotion code, most easily built with
+/// [`PromotionCodeBuilder`](crate::stripe::builders::PromotionCodeBuilder)
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreatePromotionCodeParams {
+    /// ID of the coupon the code redeems
+    pub coupon: String,
+    /// The customer-facing code, generated by Stripe when left empty
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_redemptions: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restrictions: Option<PromotionCodeRestrictions>,
+}
+
+/// Parameters for updating a promotion code
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdatePromotionCodeParams {
+    /// Deactivating a code stops new redemptions; it cannot be reactivated
+    /// if its coupon is no longer valid
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing promotion codes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListPromotionCodesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    /// Only the code with this exact customer-facing value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub coupon: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+}
+
+impl PromotionCode {
+    /// Create a new promotion code
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::builders::PromotionCodeBuilder;
+    /// use payup::stripe::promotion_code::PromotionCode;
+    ///
+    /// let params = PromotionCodeBuilder::new(&coupon.id)
+    ///     .code("SPRING25")
+    ///     .first_time_transaction(true)
+    ///     .minimum_amount(5000, "usd")
+    ///     .build();
+    /// let promotion_code = PromotionCode::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreatePromotionCodeParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("promotion_codes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new promotion code (async)
+    pub async fn create_async(auth: &Auth, params: CreatePromotionCodeParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("promotion_codes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a promotion code by ID
+    pub fn retrieve(auth: &Auth, promotion_code_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("promotion_codes/{}", promotion_code_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a promotion code by ID (async)
+    pub async fn retrieve_async(auth: &Auth, promotion_code_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("promotion_codes/{}", promotion_code_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a promotion code
+    pub fn update(auth: &Auth, promotion_code_id: &str, params: UpdatePromotionCodeParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("promotion_codes/{}", promotion_code_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a promotion code (async)
+    pub async fn update_async(
+        auth: &Auth,
+        promotion_code_id: &str,
+        params: UpdatePromotionCodeParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("promotion_codes/{}", promotion_code_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Look up the active promotion code a customer typed in, if there is one
+    pub fn find_by_code(auth: &Auth, code: &str) -> Result<Option<Self>> {
+        Self::list_iter(auth, &Self::active_code(code), PageRequest::new().limit(1))
+            .next()
+            .transpose()
+    }
+
+    /// Look up the active promotion code a customer typed in (async)
+    pub async fn find_by_code_async(auth: &Auth, code: &str) -> Result<Option<Self>> {
+        Self::list_stream(auth, &Self::active_code(code), PageRequest::new().limit(1))
+            .try_next()
+            .await
+    }
+
+    fn active_code(code: &str) -> ListPromotionCodesParams {
+        ListPromotionCodesParams {
+            active: Some(true),
+            code: Some(code.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// List promotion codes matching `filters`, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListPromotionCodesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List promotion codes matching `filters` (async)
+    pub async fn list_async(auth: &Auth, filters: &ListPromotionCodesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over promotion codes matching `filters`, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListPromotionCodesParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream promotion codes matching `filters`, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListPromotionCodesParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListPromotionCodesParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "promotion_codes".to_string(),
+            query => format!("promotion_codes?{}", query),
+        }
+    }
+}
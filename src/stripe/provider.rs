@@ -1,41 +1,57 @@
 use async_trait::async_trait;
+use futures::TryStreamExt;
 use std::collections::HashMap;
+use crate::builders::PageRequest;
 use crate::error::{PayupError, Result};
 use crate::payment_provider::{
     PaymentProvider, PaymentFeature, Customer as UnifiedCustomer, PaymentMethod as UnifiedPaymentMethod,
     PaymentMethodType, CardDetails, BankAccountDetails, Charge as UnifiedCharge, ChargeStatus,
     Refund as UnifiedRefund, RefundStatus, RefundReason, Subscription as UnifiedSubscription,
-    SubscriptionStatus, Money
+    SubscriptionStatus, Money, Dispute as UnifiedDispute, DisputeStatus, DisputeEvidence,
+    DisputeEvidenceKind
 };
 use super::{
-    Auth, Customer, Customers, Charge, PaymentIntent, PaymentIntentStatus,
-    PaymentMethod, StripePaymentMethodType, CreatePaymentMethodParams, CreateCardParams,
-    PaymentMethodBillingDetails, StripeCardDetails, PaymentMethodAddress,
+    Auth, Customer, Dispute, Evidence, File, Customers, Charge, PaymentIntent, PaymentIntentStatus,
+    PaymentMethod, StripePaymentMethodType, PaymentMethodBillingDetails, StripeCardDetails, PaymentMethodAddress,
     Subscription, CreatePaymentIntentParams, UpdatePaymentIntentParams,
-    ConfirmPaymentIntentParams, CapturePaymentIntentParams
+    ConfirmPaymentIntentParams, CapturePaymentIntentParams, SetupFutureUsage,
+    SetupIntent, SetupIntentStatus, CreateSetupIntentParams
 };
 use crate::stripe_ext::refund::Refund as StripeRefund;
+use crate::http_client::{ReqwestTransport, Transport};
 use reqwest::Client;
+use std::sync::Arc;
 use serde_json::json;
 
 pub struct StripeProvider {
     auth: Auth,
-    client: Client,
 }
 
 impl StripeProvider {
     pub fn new(api_key: String) -> Self {
         Self {
             auth: Auth::new(api_key.clone(), api_key),
-            client: Client::new(),
         }
     }
 
     pub fn with_client(api_key: String, client: Client) -> Self {
-        Self {
-            auth: Auth::new(api_key.clone(), api_key),
-            client,
-        }
+        Self::new(api_key).with_transport(Arc::new(ReqwestTransport::new(client)))
+    }
+
+    /// Sends every request through `transport` instead of the shared one.
+    pub fn with_transport(mut self, transport: Arc<dyn Transport>) -> Self {
+        self.auth = self.auth.with_transport(transport);
+        self
+    }
+
+    /// Routes every request through `base_url` instead of the live Stripe API.
+    pub fn with_base_url(mut self, base_url: impl Into<String>) -> Self {
+        self.auth = self.auth.with_base_url(base_url);
+        self
+    }
+
+    pub fn base_url(&self) -> &str {
+        &self.auth.base_url
     }
 
     fn map_customer_to_unified(&self, customer: &Customer) -> UnifiedCustomer {
@@ -59,6 +75,7 @@ impl StripeProvider {
             payment_method: None,
             delinquent: None,
             description: None,
+            discount: None,
             email: customer.email.clone(),
             invoice_prefix: None,
             livemode: None,
@@ -149,7 +166,9 @@ impl StripeProvider {
         UnifiedCharge {
             id: charge.id.clone(),
             amount: Money {
-                amount: charge.amount.as_ref().and_then(|a| a.parse().ok()).unwrap_or(0),
+                amount: charge.stripe_amount
+                    .or_else(|| charge.amount.as_ref().and_then(|a| a.parse().ok()))
+                    .unwrap_or(0),
                 currency: charge.currency.clone().unwrap_or_else(|| "usd".to_string()),
             },
             customer_id: charge.customer.clone(),
@@ -161,6 +180,65 @@ impl StripeProvider {
         }
     }
 
+    fn map_dispute_to_unified(&self, dispute: &Dispute) -> UnifiedDispute {
+        // `warning_*` statuses belong to inquiries, which close without a
+        // chargeback; `prevented` and `charge_refunded` also end without one
+        let status = match dispute.status.as_deref().unwrap_or_default() {
+            "needs_response" | "warning_needs_response" => DisputeStatus::NeedsResponse,
+            "under_review" | "warning_under_review" => DisputeStatus::UnderReview,
+            "won" => DisputeStatus::Won,
+            "lost" => DisputeStatus::Lost,
+            "warning_closed" | "prevented" | "charge_refunded" => DisputeStatus::Closed,
+            other => DisputeStatus::Other(other.to_string()),
+        };
+
+        UnifiedDispute {
+            id: dispute.id.clone().unwrap_or_default(),
+            charge_id: dispute.charge.clone(),
+            amount: Money {
+                amount: dispute.amount.unwrap_or(0),
+                currency: dispute.currency.clone().unwrap_or_else(|| "usd".to_string()),
+            },
+            status,
+            reason: dispute.reason.clone(),
+            evidence_due_by: dispute.evidence_details.as_ref().and_then(|d| d.due_by),
+            created_at: dispute.created,
+        }
+    }
+
+    /// Uploads each evidence file and files it under the matching Stripe evidence field
+    async fn map_evidence_to_stripe(&self, evidence: &DisputeEvidence) -> Result<Evidence> {
+        let mut stripe_evidence = Evidence::new();
+        stripe_evidence.uncategorized_text = evidence.explanation.clone();
+        stripe_evidence.shipping_carrier = evidence.shipping_carrier.clone();
+        stripe_evidence.shipping_tracking_number = evidence.tracking_number.clone();
+
+        for evidence_file in &evidence.files {
+            let field = match evidence_file.kind {
+                DisputeEvidenceKind::Receipt => &mut stripe_evidence.receipt,
+                DisputeEvidenceKind::ProofOfDelivery => &mut stripe_evidence.shipping_documentation,
+                DisputeEvidenceKind::CustomerCommunication => &mut stripe_evidence.customer_communication,
+                DisputeEvidenceKind::RefundPolicy => &mut stripe_evidence.refund_policy,
+                DisputeEvidenceKind::ServiceDocumentation => &mut stripe_evidence.service_documentation,
+                DisputeEvidenceKind::Other => &mut stripe_evidence.uncategorized_file,
+            };
+            if field.is_some() {
+                return Err(PayupError::ValidationError(format!(
+                    "Stripe accepts a single {:?} evidence file per dispute",
+                    evidence_file.kind
+                )));
+            }
+
+            let mut file = File::new();
+            file.file = Some(evidence_file.content.clone());
+            file.filename = Some(evidence_file.filename.clone());
+            file.purpose = Some("dispute_evidence".to_string());
+            *field = file.async_post(self.auth.clone()).await?.id;
+        }
+
+        Ok(stripe_evidence)
+    }
+
     fn map_refund_to_unified(&self, refund: &StripeRefund) -> UnifiedRefund {
         let status = match refund.status.as_ref() {
             Some(crate::stripe_ext::refund::RefundStatus::Pending) => RefundStatus::Pending,
@@ -226,23 +304,6 @@ impl StripeProvider {
             cancel_at_period_end: sub.cancel_at_period_end.unwrap_or(false),
         }
     }
-
-    async fn stripe_error_to_payup(&self, error: reqwest::Error) -> PayupError {
-        if error.is_timeout() {
-            return PayupError::TimeoutError("Stripe API request timed out".to_string());
-        }
-        
-        if let Some(status) = error.status() {
-            if status.as_u16() == 429 {
-                return PayupError::RateLimitError { retry_after: None };
-            }
-            if status.is_server_error() {
-                return PayupError::ServerError(status.as_u16());
-            }
-        }
-
-        PayupError::NetworkError(error)
-    }
 }
 
 #[async_trait]
@@ -270,14 +331,20 @@ impl PaymentProvider for StripeProvider {
             PaymentFeature::Webhooks,
             PaymentFeature::Subscriptions,
             PaymentFeature::CustomerManagement,
+            PaymentFeature::Disputes,
             PaymentFeature::DisputeManagement,
             PaymentFeature::Invoicing,
         ]
     }
 
     async fn create_customer(&self, customer: &UnifiedCustomer) -> Result<UnifiedCustomer> {
+        self.create_customer_idempotent(customer, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_customer_idempotent(&self, customer: &UnifiedCustomer, idempotency_key: &str) -> Result<UnifiedCustomer> {
+        let auth = self.auth.clone().with_idempotency_key(idempotency_key);
         let stripe_customer = self.map_unified_to_customer(customer);
-        let created = stripe_customer.async_post(self.auth.clone()).await?;
+        let created = stripe_customer.async_post(auth).await?;
         Ok(self.map_customer_to_unified(&created))
     }
 
@@ -310,46 +377,54 @@ impl PaymentProvider for StripeProvider {
             .collect())
     }
 
+    /// Saves a payment method through a SetupIntent so that any
+    /// authentication it needs runs up front. Raw card details are never sent:
+    /// pass a `pm_...` ID tokenized client-side in `payment_method.id`, or
+    /// leave it empty to get an `ActionRequired` error whose client secret
+    /// lets Stripe.js collect the card.
     async fn create_payment_method(&self, payment_method: &UnifiedPaymentMethod) -> Result<UnifiedPaymentMethod> {
         // Map unified payment method to Stripe payment method type
         let payment_method_type = match &payment_method.method_type {
-            PaymentMethodType::Card => StripePaymentMethodType::Card,
-            PaymentMethodType::BankAccount => StripePaymentMethodType::SepaDebit,
-            PaymentMethodType::PayPal => StripePaymentMethodType::Paypal,
-            PaymentMethodType::ApplePay => StripePaymentMethodType::Card, // Apple Pay uses card
-            PaymentMethodType::GooglePay => StripePaymentMethodType::Card, // Google Pay uses card
+            PaymentMethodType::Card => "card",
+            PaymentMethodType::BankAccount => "sepa_debit",
+            PaymentMethodType::PayPal => "paypal",
+            PaymentMethodType::ApplePay => "card", // Apple Pay uses card
+            PaymentMethodType::GooglePay => "card", // Google Pay uses card
             _ => return Err(PayupError::UnsupportedOperation(
                 format!("Payment method type {:?} not supported by Stripe", payment_method.method_type)
             )),
         };
 
-        // Build parameters based on payment method type
-        let mut params = CreatePaymentMethodParams {
-            payment_method_type,
-            billing_details: None,
-            card: None,
-            metadata: None,
+        let params = CreateSetupIntentParams {
+            confirm: payment_method.id.as_ref().map(|_| true),
+            payment_method: payment_method.id.clone(),
+            payment_method_types: Some(vec![payment_method_type.to_string()]),
+            usage: Some(SetupFutureUsage::OffSession),
+            ..Default::default()
         };
+        let setup_intent = SetupIntent::create_async(&self.auth, params).await?;
 
-        // Add card details if it's a card payment method
-        if let Some(card) = &payment_method.card {
-            if let (Some(number), exp_month, exp_year) = (
-                card.number.as_ref(),
-                card.exp_month.parse::<i32>().ok(),
-                card.exp_year.parse::<i32>().ok(),
-            ) {
-                params.card = Some(CreateCardParams {
-                    number: number.clone(),
-                    exp_month: exp_month.unwrap_or(1),
-                    exp_year: exp_year.unwrap_or(2025),
-                    cvc: card.cvv.clone(),
-                });
+        match (&setup_intent.status, &setup_intent.payment_method) {
+            (SetupIntentStatus::Succeeded, Some(payment_method_id)) => {
+                let saved = PaymentMethod::retrieve_async(&self.auth, payment_method_id).await?;
+                Ok(self.map_payment_method_to_unified(&saved))
             }
+            (SetupIntentStatus::Canceled, _) => Err(PayupError::ApiError {
+                code: "setup_intent_canceled".to_string(),
+                message: format!("SetupIntent {} was canceled", setup_intent.id),
+                provider: "stripe".to_string(),
+            }),
+            _ => Err(PayupError::ActionRequired {
+                provider: "stripe".to_string(),
+                intent_id: setup_intent.id.clone(),
+                client_secret: setup_intent.client_secret.clone(),
+                redirect_url: setup_intent
+                    .next_action
+                    .as_ref()
+                    .and_then(|action| action.redirect_url())
+                    .map(str::to_string),
+            }),
         }
-
-        // Create the payment method using Stripe API
-        let created = PaymentMethod::create_async(&self.auth, params).await?;
-        Ok(self.map_payment_method_to_unified(&created))
     }
 
     async fn get_payment_method(&self, payment_method_id: &str) -> Result<UnifiedPaymentMethod> {
@@ -368,6 +443,11 @@ impl PaymentProvider for StripeProvider {
     }
 
     async fn create_charge(&self, charge: &UnifiedCharge) -> Result<UnifiedCharge> {
+        self.create_charge_idempotent(charge, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_charge_idempotent(&self, charge: &UnifiedCharge, idempotency_key: &str) -> Result<UnifiedCharge> {
+        let auth = self.auth.clone().with_idempotency_key(idempotency_key);
         // Use PaymentIntent for creating charges (modern Stripe approach)
         let params = CreatePaymentIntentParams {
             amount: charge.amount.amount,
@@ -379,7 +459,7 @@ impl PaymentProvider for StripeProvider {
             ..Default::default()
         };
 
-        let pi = PaymentIntent::create_async(&self.auth, params).await?;
+        let pi = PaymentIntent::create_async(&auth, params).await?;
         Ok(self.map_payment_intent_to_charge(&pi))
     }
 
@@ -396,6 +476,11 @@ impl PaymentProvider for StripeProvider {
     }
 
     async fn capture_charge(&self, charge_id: &str, amount: Option<Money>) -> Result<UnifiedCharge> {
+        self.capture_charge_idempotent(charge_id, amount, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn capture_charge_idempotent(&self, charge_id: &str, amount: Option<Money>, idempotency_key: &str) -> Result<UnifiedCharge> {
+        let auth = self.auth.clone().with_idempotency_key(idempotency_key);
         if charge_id.starts_with("pi_") {
             let params = CapturePaymentIntentParams {
                 amount_to_capture: amount.map(|m| m.amount),
@@ -404,7 +489,7 @@ impl PaymentProvider for StripeProvider {
                 statement_descriptor_suffix: None,
                 transfer_data: None,
             };
-            let pi = PaymentIntent::capture_async(&self.auth, charge_id, params).await?;
+            let pi = PaymentIntent::capture_async(&auth, charge_id, params).await?;
             Ok(self.map_payment_intent_to_charge(&pi))
         } else {
             // Fall back to legacy Charge API - need to get charge first then capture
@@ -412,7 +497,7 @@ impl PaymentProvider for StripeProvider {
             if let Some(amt) = amount {
                 charge.amount = Some(amt.amount.to_string());
             }
-            let captured = charge.async_capture(self.auth.clone()).await?;
+            let captured = charge.async_capture(auth).await?;
             Ok(self.map_charge_to_unified(&captured))
         }
     }
@@ -428,6 +513,11 @@ impl PaymentProvider for StripeProvider {
     }
 
     async fn create_refund(&self, refund: &UnifiedRefund) -> Result<UnifiedRefund> {
+        self.create_refund_idempotent(refund, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_refund_idempotent(&self, refund: &UnifiedRefund, idempotency_key: &str) -> Result<UnifiedRefund> {
+        let auth = self.auth.clone().with_idempotency_key(idempotency_key);
         let mut stripe_refund = StripeRefund::new();
         stripe_refund.charge = Some(refund.charge_id.clone());
         stripe_refund.amount = refund.amount.as_ref().map(|m| m.amount);
@@ -439,7 +529,7 @@ impl PaymentProvider for StripeProvider {
         });
         stripe_refund.metadata = refund.metadata.clone();
         
-        let created = stripe_refund.async_post(self.auth.clone()).await?;
+        let created = stripe_refund.async_post(auth).await?;
         Ok(self.map_refund_to_unified(&created))
     }
 
@@ -461,6 +551,11 @@ impl PaymentProvider for StripeProvider {
     }
 
     async fn create_subscription(&self, subscription: &UnifiedSubscription) -> Result<UnifiedSubscription> {
+        self.create_subscription_idempotent(subscription, &uuid::Uuid::new_v4().to_string()).await
+    }
+
+    async fn create_subscription_idempotent(&self, subscription: &UnifiedSubscription, idempotency_key: &str) -> Result<UnifiedSubscription> {
+        let auth = self.auth.clone().with_idempotency_key(idempotency_key);
         let mut stripe_sub = Subscription::new();
         stripe_sub.customer = Some(subscription.customer_id.clone());
         
@@ -479,12 +574,7 @@ impl PaymentProvider for StripeProvider {
         // Set cancel_at_period_end if needed
         stripe_sub.cancel_at_period_end = Some(subscription.cancel_at_period_end);
         
-        let stripe_response = stripe_sub.async_post(self.auth.clone()).await
-            .map_err(|e| PayupError::ApiError {
-                code: "subscription_create_error".to_string(),
-                message: format!("Failed to create subscription: {}", e),
-                provider: "stripe".to_string(),
-            })?;
+        let stripe_response = stripe_sub.async_post(auth).await?;
         
         Ok(self.map_subscription_to_unified(&stripe_response))
     }
@@ -493,12 +583,7 @@ impl PaymentProvider for StripeProvider {
         let stripe_response = Subscription::async_get(
             self.auth.clone(),
             subscription_id.to_string()
-        ).await
-            .map_err(|e| PayupError::ApiError {
-                code: "subscription_get_error".to_string(),
-                message: format!("Failed to get subscription: {}", e),
-                provider: "stripe".to_string(),
-            })?;
+        ).await?;
         
         Ok(self.map_subscription_to_unified(&stripe_response))
     }
@@ -523,12 +608,7 @@ impl PaymentProvider for StripeProvider {
         // Update cancel_at_period_end
         stripe_sub.cancel_at_period_end = Some(subscription.cancel_at_period_end);
         
-        let stripe_response = stripe_sub.async_update(self.auth.clone()).await
-            .map_err(|e| PayupError::ApiError {
-                code: "subscription_update_error".to_string(),
-                message: format!("Failed to update subscription: {}", e),
-                provider: "stripe".to_string(),
-            })?;
+        let stripe_response = stripe_sub.async_update(self.auth.clone()).await?;
         
         Ok(self.map_subscription_to_unified(&stripe_response))
     }
@@ -538,12 +618,7 @@ impl PaymentProvider for StripeProvider {
             self.auth.clone(),
             subscription_id.to_string(),
             at_period_end
-        ).await
-            .map_err(|e| PayupError::ApiError {
-                code: "subscription_cancel_error".to_string(),
-                message: format!("Failed to cancel subscription: {}", e),
-                provider: "stripe".to_string(),
-            })?;
+        ).await?;
         
         Ok(self.map_subscription_to_unified(&stripe_response))
     }
@@ -553,12 +628,7 @@ impl PaymentProvider for StripeProvider {
             self.auth.clone(),
             customer_id.map(String::from),
             limit.map(|l| l as i32)
-        ).await
-            .map_err(|e| PayupError::ApiError {
-                code: "subscription_list_error".to_string(),
-                message: format!("Failed to list subscriptions: {}", e),
-                provider: "stripe".to_string(),
-            })?;
+        ).await?;
         
         Ok(stripe_response.data
             .iter()
@@ -566,6 +636,39 @@ impl PaymentProvider for StripeProvider {
             .collect())
     }
 
+    async fn list_disputes(&self, limit: Option<u32>) -> Result<Vec<UnifiedDispute>> {
+        let mut page = PageRequest::new();
+        if let Some(limit) = limit {
+            page = page.max_items(limit as usize);
+        }
+
+        let disputes: Vec<Dispute> = Dispute::list_stream(self.auth.clone(), page).try_collect().await?;
+        Ok(disputes.iter().map(|d| self.map_dispute_to_unified(d)).collect())
+    }
+
+    async fn get_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let dispute = Dispute::async_get(self.auth.clone(), dispute_id.to_string()).await?;
+        Ok(self.map_dispute_to_unified(&dispute))
+    }
+
+    async fn accept_dispute(&self, dispute_id: &str) -> Result<UnifiedDispute> {
+        let mut dispute = Dispute::new();
+        dispute.id = Some(dispute_id.to_string());
+
+        let closed = dispute.async_close(self.auth.clone()).await?;
+        Ok(self.map_dispute_to_unified(&closed))
+    }
+
+    async fn submit_dispute_evidence(&self, dispute_id: &str, evidence: &DisputeEvidence) -> Result<UnifiedDispute> {
+        let mut dispute = Dispute::new();
+        dispute.id = Some(dispute_id.to_string());
+        dispute.evidence = Some(self.map_evidence_to_stripe(evidence).await?);
+        dispute.submit = Some(true);
+
+        let updated = dispute.async_update(self.auth.clone()).await?;
+        Ok(self.map_dispute_to_unified(&updated))
+    }
+
     async fn verify_webhook(&self, payload: &[u8], signature: &str, secret: &str) -> Result<bool> {
         use super::webhooks::StripeWebhookHandler;
         
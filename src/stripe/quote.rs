@@ -0,0 +1,600 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use std::io::Write;
+use std::pin::Pin;
+use futures::{Stream, TryStreamExt};
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::checkout::{LineItem, LineItemDiscount, PriceData};
+use super::coupon::DiscountItem;
+use super::invoice::{AutomaticTax, CollectionMethod, Invoice};
+use super::response::Subscription;
+use super::Auth;
+
+/// The quote PDF, a chunk at a time
+pub type PdfStream = Pin<Box<dyn Stream<Item = Result<Vec<u8>>> + Send>>;
+
+/// A quote sent to a customer, which turns into an invoice or a subscription
+/// once they accept it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Quote {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "quote")
+    pub object: String,
+    /// Total before any discounts or taxes are applied
+    pub amount_subtotal: i64,
+    /// Total after discounts and taxes are applied
+    pub amount_total: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_fee_amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application_fee_percent: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_tax: Option<AutomaticTax>,
+    pub collection_method: CollectionMethod,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub computed: Option<QuoteComputed>,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub currency: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// IDs of the discounts applied to the quote
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub discounts: Vec<String>,
+    /// When the quote can no longer be accepted (Unix timestamp)
+    pub expires_at: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    /// The invoice created when a quote with only one-time prices is accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_settings: Option<QuoteInvoiceSettings>,
+    /// The line items, only present when expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<QuoteLineItemList>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Assigned when the quote is finalized
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    pub status: QuoteStatus,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status_transitions: Option<QuoteStatusTransitions>,
+    /// The subscription created when a quote with recurring prices is accepted
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_data: Option<QuoteSubscriptionData>,
+    /// The schedule created instead of a subscription when the quote starts
+    /// in the future
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_schedule: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_details: Option<QuoteTotalDetails>,
+}
+
+/// Where a quote is in its lifecycle
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum QuoteStatus {
+    /// Still editable, and not yet visible to the customer
+    Draft,
+    /// Finalized and waiting for the customer
+    Open,
+    Accepted,
+    Canceled,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteStatusTransitions {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub accepted_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canceled_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub finalized_at: Option<i64>,
+}
+
+/// Totals Stripe works out for the quote once accepted
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteComputed {
+    /// What the customer will pay each period, when the quote has recurring prices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub recurring: Option<QuoteComputedRecurring>,
+    /// What the customer will pay on acceptance
+    pub upfront: QuoteComputedUpfront,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteComputedRecurring {
+    pub amount_subtotal: i64,
+    pub amount_total: i64,
+    /// "day", "week", "month" or "year"
+    pub interval: String,
+    pub interval_count: u64,
+    pub total_details: QuoteTotalDetails,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteComputedUpfront {
+    pub amount_subtotal: i64,
+    pub amount_total: i64,
+    pub total_details: QuoteTotalDetails,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteTotalDetails {
+    pub amount_discount: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_shipping: Option<i64>,
+    pub amount_tax: i64,
+    /// Each discount and what it took off, only present when expanded
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub breakdown: Option<QuoteTotalBreakdown>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteTotalBreakdown {
+    pub discounts: Vec<LineItemDiscount>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuoteInvoiceSettings {
+    /// Only used with the `send_invoice` collection method
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub days_until_due: Option<u64>,
+}
+
+/// Settings for the subscription created when the quote is accepted
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuoteSubscriptionData {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// When the subscription starts (Unix timestamp); a subscription schedule
+    /// is created instead when this is in the future
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_date: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial_period_days: Option<u64>,
+}
+
+/// A page of a quote's line items; the items are the same objects Checkout
+/// returns, each carrying its full `Price`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct QuoteLineItemList {
+    pub object: String,
+    pub data: Vec<LineItem>,
+    pub has_more: bool,
+    pub url: String,
+}
+
+/// A line item to quote, from an existing price or one described inline
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct QuoteLineItemParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price_data: Option<PriceData>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discounts: Option<Vec<DiscountItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_rates: Option<Vec<String>>,
+}
+
+impl QuoteLineItemParams {
+    /// Quote `quantity` of the price with ID `price_id`
+    pub fn price(price_id: &str, quantity: u64) -> Self {
+        Self {
+            price: Some(price_id.to_string()),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+
+    /// Quote `quantity` of a price described inline
+    pub fn price_data(price_data: PriceData, quantity: u64) -> Self {
+        Self {
+            price_data: Some(price_data),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+}
+
+/// Parameters for creating a quote
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateQuoteParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub line_items: Vec<QuoteLineItemParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_method: Option<CollectionMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_tax: Option<AutomaticTax>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_tax_rates: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discounts: Option<Vec<DiscountItem>>,
+    /// Defaults to 30 days after the quote is created
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_settings: Option<QuoteInvoiceSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_data: Option<QuoteSubscriptionData>,
+}
+
+impl CreateQuoteParams {
+    pub fn new(customer_id: &str) -> Self {
+        Self {
+            customer: Some(customer_id.to_string()),
+            ..Default::default()
+        }
+    }
+
+    /// Add a line item to the quote
+    pub fn line_item(mut self, line_item: QuoteLineItemParams) -> Self {
+        self.line_items.push(line_item);
+        self
+    }
+
+    /// Apply a coupon or promotion code to the whole quote
+    pub fn discount(mut self, discount: DiscountItem) -> Self {
+        self.discounts.get_or_insert_with(Vec::new).push(discount);
+        self
+    }
+}
+
+/// Parameters for updating a draft quote; `line_items` replaces the quote's
+/// line items when set
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateQuoteParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<Vec<QuoteLineItemParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub collection_method: Option<CollectionMethod>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_tax: Option<AutomaticTax>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub default_tax_rates: Option<Vec<String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub discounts: Option<Vec<DiscountItem>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub footer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub header: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_settings: Option<QuoteInvoiceSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription_data: Option<QuoteSubscriptionData>,
+}
+
+/// Parameters for finalizing a quote
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct FinalizeQuoteParams {
+    /// Overrides the expiry set on the draft
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+}
+
+/// Filters for listing quotes
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListQuotesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<QuoteStatus>,
+}
+
+impl Quote {
+    /// Create a draft quote
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::coupon::DiscountItem;
+    /// use payup::stripe::quote::{CreateQuoteParams, FinalizeQuoteParams, Quote, QuoteLineItemParams};
+    ///
+    /// let params = CreateQuoteParams::new("cus_123")
+    ///     .line_item(QuoteLineItemParams::price("price_seats", 25))
+    ///     .discount(DiscountItem::coupon("ANNUAL10"));
+    /// let quote = Quote::create(&auth, params)?;
+    /// let quote = Quote::finalize(&auth, &quote.id, FinalizeQuoteParams::default())?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateQuoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("quotes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a draft quote (async)
+    pub async fn create_async(auth: &Auth, params: CreateQuoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("quotes"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a quote
+    pub fn retrieve(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("quotes/{}", quote_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a quote (async)
+    pub async fn retrieve_async(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("quotes/{}", quote_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a draft quote
+    pub fn update(auth: &Auth, quote_id: &str, params: UpdateQuoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("quotes/{}", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a draft quote (async)
+    pub async fn update_async(auth: &Auth, quote_id: &str, params: UpdateQuoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("quotes/{}", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Finalize a draft quote so it can be sent to the customer
+    pub fn finalize(auth: &Auth, quote_id: &str, params: FinalizeQuoteParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("quotes/{}/finalize", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Finalize a draft quote (async)
+    pub async fn finalize_async(auth: &Auth, quote_id: &str, params: FinalizeQuoteParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("quotes/{}/finalize", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Accept an open quote on the customer's behalf, creating its invoice,
+    /// subscription or subscription schedule
+    pub fn accept(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("quotes/{}/accept", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Accept an open quote (async)
+    pub async fn accept_async(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("quotes/{}/accept", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Cancel a draft or open quote
+    pub fn cancel(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("quotes/{}/cancel", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Cancel a draft or open quote (async)
+    pub async fn cancel_async(auth: &Auth, quote_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("quotes/{}/cancel", quote_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List quotes, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListQuotesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List quotes (async)
+    pub async fn list_async(auth: &Auth, filters: &ListQuotesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over quotes, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListQuotesParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream quotes, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListQuotesParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListQuotesParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "quotes".to_string(),
+            query => format!("quotes?{}", query),
+        }
+    }
+
+    /// List a quote's line items, walking every page or up to `page.max_items`
+    pub fn line_items(auth: &Auth, quote_id: &str, page: PageRequest) -> Result<Vec<LineItem>> {
+        Self::line_items_iter(auth, quote_id, page).collect()
+    }
+
+    /// List a quote's line items (async)
+    pub async fn line_items_async(auth: &Auth, quote_id: &str, page: PageRequest) -> Result<Vec<LineItem>> {
+        Self::line_items_stream(auth, quote_id, page).try_collect().await
+    }
+
+    /// Iterate over a quote's line items, fetching further pages as needed
+    pub fn line_items_iter(auth: &Auth, quote_id: &str, page: PageRequest) -> Paginator<LineItem> {
+        pagination::list_iter(auth.clone(), &format!("quotes/{}/line_items", quote_id), page)
+    }
+
+    /// Stream a quote's line items, fetching further pages as needed
+    pub fn line_items_stream(auth: &Auth, quote_id: &str, page: PageRequest) -> PageStream<LineItem> {
+        pagination::list_stream(auth.clone(), &format!("quotes/{}/line_items", quote_id), page)
+    }
+
+    /// Iterate over the line items the customer pays on acceptance, which
+    /// leaves out the recurring part of a subscription quote
+    pub fn computed_upfront_line_items_iter(auth: &Auth, quote_id: &str, page: PageRequest) -> Paginator<LineItem> {
+        pagination::list_iter(auth.clone(), &format!("quotes/{}/computed_upfront_line_items", quote_id), page)
+    }
+
+    /// Stream the line items the customer pays on acceptance
+    pub fn computed_upfront_line_items_stream(auth: &Auth, quote_id: &str, page: PageRequest) -> PageStream<LineItem> {
+        pagination::list_stream(auth.clone(), &format!("quotes/{}/computed_upfront_line_items", quote_id), page)
+    }
+
+    /// Download the quote's PDF into `writer`, returning the number of bytes
+    /// written. The PDF is copied as it arrives rather than held in memory.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::quote::Quote;
+    ///
+    /// let mut file = std::fs::File::create("quote.pdf")?;
+    /// Quote::pdf(&auth, "qt_123", &mut file)?;
+    /// ```
+    pub fn pdf<W: Write>(auth: &Auth, quote_id: &str, writer: &mut W) -> Result<u64> {
+        let mut response = get_shared_blocking_client()
+            .get(auth.files_url(&format!("quotes/{}/pdf", quote_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.copy_to(writer)?)
+    }
+
+    /// Download the quote's PDF as a stream of chunks (async)
+    ///
+    /// # Example
+    /// ```ignore
+    /// use futures::TryStreamExt;
+    /// use payup::stripe::quote::Quote;
+    /// use tokio::io::AsyncWriteExt;
+    ///
+    /// let mut pdf = Quote::pdf_stream(&auth, "qt_123").await?;
+    /// let mut file = tokio::fs::File::create("quote.pdf").await?;
+    /// while let Some(chunk) = pdf.try_next().await? {
+    ///     file.write_all(&chunk).await?;
+    /// }
+    /// ```
+    pub async fn pdf_stream(auth: &Auth, quote_id: &str) -> Result<PdfStream> {
+        let response = get_shared_client()
+            .get(auth.files_url(&format!("quotes/{}/pdf", quote_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(Box::pin(futures::stream::try_unfold(response, |mut response| async move {
+            Ok(response.chunk().await?.map(|chunk| (chunk.to_vec(), response)))
+        })))
+    }
+
+    /// The invoice an accepted quote created, if it created one
+    pub async fn invoice_async(&self, auth: &Auth) -> Result<Option<Invoice>> {
+        match &self.invoice {
+            Some(invoice_id) => Ok(Some(Invoice::get(auth, invoice_id).await?)),
+            None => Ok(None),
+        }
+    }
+
+    /// The subscription an accepted quote created, if it created one
+    pub async fn subscription_async(&self, auth: &Auth) -> Result<Option<Subscription>> {
+        match &self.subscription {
+            Some(subscription_id) => Ok(Some(
+                super::subscription::Subscription::async_get(auth.clone(), subscription_id.clone()).await?,
+            )),
+            None => Ok(None),
+        }
+    }
+}
@@ -0,0 +1,613 @@
+//! Radar, Stripe's fraud screening: reviews of charges held by a rule, the
+//! value lists that block and allow rules refer to, and the early fraud
+//! warnings card issuers send ahead of a dispute.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// A charge or PaymentIntent Radar held for someone to look at
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Review {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "review")
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_zip: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub charge: Option<String>,
+    /// Why the review was closed: "approved", "refunded", "refunded_as_fraud",
+    /// "disputed", "redacted" or "acknowledged"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub closed_reason: Option<String>,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address_location: Option<ReviewLocation>,
+    pub livemode: bool,
+    /// Whether the review still needs a decision
+    pub open: bool,
+    pub opened_reason: ReviewOpenedReason,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_intent: Option<String>,
+    /// The opened reason while open, the closed reason once closed
+    pub reason: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub session: Option<ReviewSession>,
+}
+
+/// Why a review was opened
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReviewOpenedReason {
+    /// A Radar rule placed the payment in review
+    Rule,
+    /// Someone placed the payment in review from the Dashboard
+    Manual,
+}
+
+/// Where the payment's IP address is, as far as Stripe can tell
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewLocation {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub city: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub longitude: Option<f64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub region: Option<String>,
+}
+
+/// The browser session the payment came from
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReviewSession {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub browser: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub platform: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub version: Option<String>,
+}
+
+impl Review {
+    /// Retrieve a review
+    pub fn retrieve(auth: &Auth, review_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("reviews/{}", review_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a review (async)
+    pub async fn retrieve_async(auth: &Auth, review_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("reviews/{}", review_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Approve an open review, letting the payment through
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::builders::PageRequest;
+    /// use payup::stripe::radar::Review;
+    ///
+    /// for review in Review::list_iter(&auth, PageRequest::new()) {
+    ///     let review = review?;
+    ///     if review.open && trusted(&review) {
+    ///         Review::approve(&auth, &review.id)?;
+    ///     }
+    /// }
+    /// ```
+    pub fn approve(auth: &Auth, review_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("reviews/{}/approve", review_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Approve an open review (async)
+    pub async fn approve_async(auth: &Auth, review_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("reviews/{}/approve", review_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List open reviews, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List open reviews (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over open reviews, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "reviews", page)
+    }
+
+    /// Stream open reviews, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "reviews", page)
+    }
+}
+
+/// A list of values that Radar rules can block or allow, e.g.
+/// `Block if :email: in @blocked_emails`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueList {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "radar.value_list")
+    pub object: String,
+    /// The name rules refer to the list by, without the `@`
+    pub alias: String,
+    pub created: i64,
+    /// Who created the list
+    pub created_by: String,
+    pub item_type: ValueListItemType,
+    /// The first items in the list
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub list_items: Option<ValueListItemList>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    pub name: String,
+}
+
+/// What kind of value a list holds
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ValueListItemType {
+    CardBin,
+    CardFingerprint,
+    CaseSensitiveString,
+    Country,
+    CustomerId,
+    Email,
+    IpAddress,
+    SepaDebitFingerprint,
+    /// The default, compared case-insensitively
+    String,
+    UsBankAccountFingerprint,
+}
+
+/// One value in a value list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueListItem {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "radar.value_list_item")
+    pub object: String,
+    pub created: i64,
+    /// Who added the item
+    pub created_by: String,
+    pub livemode: bool,
+    pub value: String,
+    /// ID of the list the item belongs to
+    pub value_list: String,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ValueListItemList {
+    pub object: String,
+    pub data: Vec<ValueListItem>,
+    pub has_more: bool,
+    pub url: String,
+}
+
+/// The result of deleting a value list or one of its items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedValueListObject {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+/// Parameters for creating a value list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateValueListParams {
+    pub alias: String,
+    pub name: String,
+    /// Defaults to `string`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub item_type: Option<ValueListItemType>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateValueListParams {
+    pub fn new(alias: &str, name: &str, item_type: ValueListItemType) -> Self {
+        Self {
+            alias: alias.to_string(),
+            name: name.to_string(),
+            item_type: Some(item_type),
+            metadata: None,
+        }
+    }
+}
+
+/// Parameters for updating a value list; its item type can't be changed
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateValueListParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing value lists
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListValueListsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub alias: Option<String>,
+    /// Only lists holding this value
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub contains: Option<String>,
+}
+
+/// Filters for listing the items of a value list
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ListValueListItemsParams {
+    pub value_list: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+impl ListValueListItemsParams {
+    pub fn new(value_list_id: &str) -> Self {
+        Self {
+            value_list: value_list_id.to_string(),
+            value: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+struct CreateValueListItemParams<'a> {
+    value_list: &'a str,
+    value: &'a str,
+}
+
+impl ValueList {
+    /// Create a value list
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::radar::{CreateValueListParams, ValueList, ValueListItem, ValueListItemType};
+    ///
+    /// let params = CreateValueListParams::new("blocked_emails", "Blocked emails", ValueListItemType::Email);
+    /// let list = ValueList::create(&auth, params)?;
+    /// ValueListItem::create(&auth, &list.id, "fraudster@example.com")?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateValueListParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("radar/value_lists"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a value list (async)
+    pub async fn create_async(auth: &Auth, params: CreateValueListParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("radar/value_lists"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a value list
+    pub fn retrieve(auth: &Auth, value_list_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a value list (async)
+    pub async fn retrieve_async(auth: &Auth, value_list_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a value list
+    pub fn update(auth: &Auth, value_list_id: &str, params: UpdateValueListParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a value list (async)
+    pub async fn update_async(auth: &Auth, value_list_id: &str, params: UpdateValueListParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete a value list; it can't be deleted while a rule refers to it
+    pub fn delete(auth: &Auth, value_list_id: &str) -> Result<DeletedValueListObject> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Delete a value list (async)
+    pub async fn delete_async(auth: &Auth, value_list_id: &str) -> Result<DeletedValueListObject> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("radar/value_lists/{}", value_list_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List value lists, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListValueListsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List value lists (async)
+    pub async fn list_async(auth: &Auth, filters: &ListValueListsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over value lists, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListValueListsParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream value lists, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListValueListsParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListValueListsParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "radar/value_lists".to_string(),
+            query => format!("radar/value_lists?{}", query),
+        }
+    }
+}
+
+impl ValueListItem {
+    /// Add `value` to the value list with ID `value_list_id`
+    pub fn create(auth: &Auth, value_list_id: &str, value: &str) -> Result<Self> {
+        let params = CreateValueListItemParams { value_list: value_list_id, value };
+        let response = get_shared_blocking_client()
+            .post(auth.url("radar/value_list_items"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Add a value to a value list (async)
+    pub async fn create_async(auth: &Auth, value_list_id: &str, value: &str) -> Result<Self> {
+        let params = CreateValueListItemParams { value_list: value_list_id, value };
+        let response = get_shared_client()
+            .post(auth.url("radar/value_list_items"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a value list item
+    pub fn retrieve(auth: &Auth, item_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("radar/value_list_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a value list item (async)
+    pub async fn retrieve_async(auth: &Auth, item_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("radar/value_list_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Remove an item from its value list
+    pub fn delete(auth: &Auth, item_id: &str) -> Result<DeletedValueListObject> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("radar/value_list_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Remove an item from its value list (async)
+    pub async fn delete_async(auth: &Auth, item_id: &str) -> Result<DeletedValueListObject> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("radar/value_list_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List a value list's items, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListValueListItemsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List a value list's items (async)
+    pub async fn list_async(auth: &Auth, filters: &ListValueListItemsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over a value list's items, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListValueListItemsParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &format!("radar/value_list_items?{}", form::to_query(filters)), page)
+    }
+
+    /// Stream a value list's items, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListValueListItemsParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &format!("radar/value_list_items?{}", form::to_query(filters)), page)
+    }
+}
+
+/// A card issuer's warning that a charge is likely fraudulent, usually sent
+/// before the cardholder disputes it
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct EarlyFraudWarning {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "radar.early_fraud_warning")
+    pub object: String,
+    /// Whether refunding the charge would still head off a dispute
+    pub actionable: bool,
+    pub charge: String,
+    pub created: i64,
+    pub fraud_type: EarlyFraudWarningType,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_intent: Option<String>,
+}
+
+/// The kind of fraud the issuer reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EarlyFraudWarningType {
+    CardNeverReceived,
+    FraudulentCardApplication,
+    MadeWithCounterfeitCard,
+    MadeWithLostCard,
+    MadeWithStolenCard,
+    Misc,
+    UnauthorizedUseOfCard,
+}
+
+/// Filters for listing early fraud warnings
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListEarlyFraudWarningsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub charge: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_intent: Option<String>,
+}
+
+impl EarlyFraudWarning {
+    /// Retrieve an early fraud warning
+    pub fn retrieve(auth: &Auth, warning_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("radar/early_fraud_warnings/{}", warning_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve an early fraud warning (async)
+    pub async fn retrieve_async(auth: &Auth, warning_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("radar/early_fraud_warnings/{}", warning_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List early fraud warnings, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListEarlyFraudWarningsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List early fraud warnings (async)
+    pub async fn list_async(auth: &Auth, filters: &ListEarlyFraudWarningsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over early fraud warnings, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListEarlyFraudWarningsParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream early fraud warnings, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListEarlyFraudWarningsParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListEarlyFraudWarningsParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "radar/early_fraud_warnings".to_string(),
+            query => format!("radar/early_fraud_warnings?{}", query),
+        }
+    }
+}
@@ -203,6 +203,8 @@ pub struct Subscription {
     pub days_until_due: Option<i64>,
     #[serde(rename = "default_payment_method")]
     pub default_payment_method: Option<String>,
+    /// IDs of the discounts applied to the subscription
+    pub discounts: Option<Vec<String>>,
     #[serde(rename = "ended_at")]
     pub ended_at: Option<i64>,
     pub items: Option<SubscriptionItems>,
@@ -0,0 +1,330 @@
+//! Queries for Stripe's search endpoints.
+//!
+//! A query is a list of clauses such as `email:'jenny@example.com'` or
+//! `metadata['order_id']:'6735'`, joined with either `AND` or `OR`; Stripe
+//! does not allow both in one query, nor more than ten clauses.
+
+use std::fmt;
+
+use serde::{Deserialize, Serialize};
+
+use crate::error::{PayupError, Result};
+
+/// Most clauses Stripe accepts in one query
+const MAX_CLAUSES: usize = 10;
+
+/// How a clause compares a field with its value
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum SearchOperator {
+    /// `field:value`, an exact match (case insensitive for strings)
+    Equals,
+    /// `field~value`, a substring match of at least three characters
+    Contains,
+    GreaterThan,
+    GreaterThanOrEqual,
+    LessThan,
+    LessThanOrEqual,
+}
+
+impl SearchOperator {
+    fn as_str(&self) -> &'static str {
+        match self {
+            Self::Equals => ":",
+            Self::Contains => "~",
+            Self::GreaterThan => ">",
+            Self::GreaterThanOrEqual => ">=",
+            Self::LessThan => "<",
+            Self::LessThanOrEqual => "<=",
+        }
+    }
+}
+
+/// The value a clause compares against
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum SearchValue {
+    /// Quoted in the query, with quotes and backslashes escaped
+    String(String),
+    Number(i64),
+    /// Matches objects where the field is not set
+    Null,
+}
+
+impl From<&str> for SearchValue {
+    fn from(value: &str) -> Self {
+        Self::String(value.to_string())
+    }
+}
+
+impl From<String> for SearchValue {
+    fn from(value: String) -> Self {
+        Self::String(value)
+    }
+}
+
+impl From<i64> for SearchValue {
+    fn from(value: i64) -> Self {
+        Self::Number(value)
+    }
+}
+
+impl fmt::Display for SearchValue {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Self::String(value) => write!(f, "'{}'", escape(value)),
+            Self::Number(value) => write!(f, "{}", value),
+            Self::Null => f.write_str("null"),
+        }
+    }
+}
+
+fn escape(value: &str) -> String {
+    value.replace('\\', "\\\\").replace('\'', "\\'")
+}
+
+/// A field to build a clause on
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchField(String);
+
+impl SearchField {
+    pub fn eq(self, value: impl Into<SearchValue>) -> SearchClause {
+        SearchClause::new(self, SearchOperator::Equals, value)
+    }
+
+    pub fn contains(self, value: impl Into<String>) -> SearchClause {
+        SearchClause::new(self, SearchOperator::Contains, value.into())
+    }
+
+    pub fn gt(self, value: i64) -> SearchClause {
+        SearchClause::new(self, SearchOperator::GreaterThan, value)
+    }
+
+    pub fn gte(self, value: i64) -> SearchClause {
+        SearchClause::new(self, SearchOperator::GreaterThanOrEqual, value)
+    }
+
+    pub fn lt(self, value: i64) -> SearchClause {
+        SearchClause::new(self, SearchOperator::LessThan, value)
+    }
+
+    pub fn lte(self, value: i64) -> SearchClause {
+        SearchClause::new(self, SearchOperator::LessThanOrEqual, value)
+    }
+
+    /// Match objects where the field is not set
+    pub fn is_null(self) -> SearchClause {
+        SearchClause::new(self, SearchOperator::Equals, SearchValue::Null)
+    }
+}
+
+/// One `field operator value` comparison
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchClause {
+    field: SearchField,
+    operator: SearchOperator,
+    value: SearchValue,
+    negated: bool,
+}
+
+impl SearchClause {
+    pub fn new(field: SearchField, operator: SearchOperator, value: impl Into<SearchValue>) -> Self {
+        Self {
+            field,
+            operator,
+            value: value.into(),
+            negated: false,
+        }
+    }
+
+    /// A top-level field of the resource, e.g. `email` or `status`
+    pub fn field(name: &str) -> SearchField {
+        SearchField(name.to_string())
+    }
+
+    /// A metadata key, e.g. `metadata['order_id']`
+    pub fn metadata(key: &str) -> SearchField {
+        SearchField(format!("metadata['{}']", escape(key)))
+    }
+}
+
+/// `!clause` matches objects the clause would otherwise exclude
+impl std::ops::Not for SearchClause {
+    type Output = Self;
+
+    fn not(mut self) -> Self {
+        self.negated = !self.negated;
+        self
+    }
+}
+
+impl fmt::Display for SearchClause {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.negated {
+            f.write_str("-")?;
+        }
+        write!(f, "{}{}{}", self.field.0, self.operator.as_str(), self.value)
+    }
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+enum Join {
+    And,
+    Or,
+}
+
+/// A search query, built from clauses or given as a raw query string
+///
+/// # Example
+/// ```ignore
+/// use payup::stripe::search::{SearchClause, SearchQuery};
+///
+/// let query = SearchQuery::new(SearchClause::field("status").eq("active"))
+///     .and(SearchClause::metadata("order_id").eq("6735"));
+/// assert_eq!(query.build()?, "status:'active' AND metadata['order_id']:'6735'");
+/// ```
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SearchQuery {
+    clauses: Vec<SearchClause>,
+    join: Option<Join>,
+    mixed: bool,
+    raw: Option<String>,
+}
+
+impl SearchQuery {
+    pub fn new(clause: SearchClause) -> Self {
+        Self {
+            clauses: vec![clause],
+            join: None,
+            mixed: false,
+            raw: None,
+        }
+    }
+
+    /// A query string written by hand, sent as is
+    pub fn raw(query: &str) -> Self {
+        Self {
+            clauses: Vec::new(),
+            join: None,
+            mixed: false,
+            raw: Some(query.to_string()),
+        }
+    }
+
+    /// Also require `clause`
+    pub fn and(self, clause: SearchClause) -> Self {
+        self.join(Join::And, clause)
+    }
+
+    /// Also accept objects matching `clause`
+    pub fn or(self, clause: SearchClause) -> Self {
+        self.join(Join::Or, clause)
+    }
+
+    fn join(mut self, join: Join, clause: SearchClause) -> Self {
+        self.mixed |= self.join.is_some_and(|current| current != join);
+        self.join = Some(join);
+        self.clauses.push(clause);
+        self
+    }
+
+    /// Why Stripe would reject the query, if it would
+    pub(crate) fn problem(&self) -> Option<&'static str> {
+        if self.raw.as_deref().is_some_and(|raw| raw.trim().is_empty()) {
+            Some("A search query can't be empty")
+        } else if self.mixed {
+            Some("A search query can't combine AND and OR")
+        } else if self.clauses.len() > MAX_CLAUSES {
+            Some("A search query can't have more than 10 clauses")
+        } else {
+            None
+        }
+    }
+
+    /// The query string to send, or why Stripe would reject it
+    pub fn build(&self) -> Result<String> {
+        match self.problem() {
+            Some(problem) => Err(PayupError::ValidationError(problem.to_string())),
+            None => Ok(self.to_string()),
+        }
+    }
+}
+
+impl fmt::Display for SearchQuery {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if let Some(raw) = &self.raw {
+            return f.write_str(raw);
+        }
+        let separator = match self.join {
+            Some(Join::Or) => " OR ",
+            _ => " AND ",
+        };
+        for (i, clause) in self.clauses.iter().enumerate() {
+            if i > 0 {
+                f.write_str(separator)?;
+            }
+            write!(f, "{}", clause)?;
+        }
+        Ok(())
+    }
+}
+
+impl From<SearchClause> for SearchQuery {
+    fn from(clause: SearchClause) -> Self {
+        Self::new(clause)
+    }
+}
+
+/// One page of search results
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SearchResult<T> {
+    pub object: String,
+    pub url: String,
+    pub has_more: bool,
+    pub data: Vec<T>,
+    /// Token for the following page, sent as `page`
+    pub next_page: Option<String>,
+    /// Number of matching objects, when requested with `expand[]=total_count`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub total_count: Option<u64>,
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_query_rendering() {
+        let query = SearchQuery::new(SearchClause::field("status").eq("active"))
+            .and(SearchClause::metadata("order_id").eq("6735"))
+            .and(SearchClause::field("amount").gte(1000))
+            .and(!SearchClause::field("currency").eq("usd"));
+
+        assert_eq!(
+            query.build().unwrap(),
+            "status:'active' AND metadata['order_id']:'6735' AND amount>=1000 AND -currency:'usd'"
+        );
+    }
+
+    #[test]
+    fn test_query_escaping_and_or() {
+        let query = SearchQuery::new(SearchClause::field("name").contains("O'Brien"))
+            .or(SearchClause::field("email").is_null());
+
+        assert_eq!(query.build().unwrap(), r"name~'O\'Brien' OR email:null");
+    }
+
+    #[test]
+    fn test_query_validation() {
+        let mixed = SearchQuery::new(SearchClause::field("a").eq("1"))
+            .and(SearchClause::field("b").eq("2"))
+            .or(SearchClause::field("c").eq("3"));
+        assert!(mixed.build().is_err());
+
+        let too_long = (0..10).fold(SearchQuery::new(SearchClause::field("a").eq(0)), |query, i| {
+            query.and(SearchClause::field("a").eq(i + 1))
+        });
+        assert!(too_long.build().is_err());
+
+        assert!(SearchQuery::raw(" ").build().is_err());
+        assert_eq!(SearchQuery::raw("email:'a@b.co'").build().unwrap(), "email:'a@b.co'");
+    }
+}
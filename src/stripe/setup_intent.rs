@@ -0,0 +1,269 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::payment_intent::{AutomaticPaymentMethods, NextAction, SetupFutureUsage};
+use super::Auth;
+
+/// Status of a setup intent
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupIntentStatus {
+    RequiresPaymentMethod,
+    RequiresConfirmation,
+    RequiresAction,
+    Processing,
+    Canceled,
+    Succeeded,
+}
+
+/// Why a setup intent was canceled
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SetupIntentCancellationReason {
+    Abandoned,
+    RequestedByCustomer,
+    Duplicate,
+}
+
+/// Saves a customer's payment method for later payments, running any
+/// authentication (such as 3D Secure) the card needs up front
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SetupIntent {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "setup_intent")
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub application: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation_reason: Option<SetupIntentCancellationReason>,
+    /// Secret used by Stripe.js or the mobile SDKs to confirm the intent client-side
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub client_secret: Option<String>,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// The error encountered in the previous setup attempt
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub last_setup_error: Option<serde_json::Value>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub latest_attempt: Option<String>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mandate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// What the customer has to do next, when `status` is `requires_action`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub next_action: Option<NextAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    /// ID of the payment method being saved
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method_options: Option<serde_json::Value>,
+    #[serde(default)]
+    pub payment_method_types: Vec<String>,
+    pub status: SetupIntentStatus,
+    /// How the saved payment method will be used, defaults to `off_session`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SetupFutureUsage>,
+}
+
+/// Parameters for creating a setup intent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateSetupIntentParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub automatic_payment_methods: Option<AutomaticPaymentMethods>,
+    /// Confirm the intent immediately, requires `payment_method`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub confirm: Option<bool>,
+    /// Customer the payment method is attached to once the setup succeeds
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub on_behalf_of: Option<String>,
+    /// A payment method ID, e.g. one tokenized client-side by Stripe.js
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method_types: Option<Vec<String>>,
+    /// Where to send the customer after a redirect-based authentication, only used with `confirm`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_url: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub usage: Option<SetupFutureUsage>,
+}
+
+/// Parameters for confirming a setup intent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfirmSetupIntentParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub payment_method: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub return_url: Option<String>,
+}
+
+/// Parameters for canceling a setup intent
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CancelSetupIntentParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cancellation_reason: Option<SetupIntentCancellationReason>,
+}
+
+impl SetupIntent {
+    /// Whether the customer has to act, see `next_action`
+    pub fn requires_action(&self) -> bool {
+        self.status == SetupIntentStatus::RequiresAction
+    }
+
+    /// Create a new setup intent
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::setup_intent::{CreateSetupIntentParams, SetupIntent};
+    ///
+    /// let params = CreateSetupIntentParams {
+    ///     customer: Some("cus_123".to_string()),
+    ///     payment_method_types: Some(vec!["card".to_string()]),
+    ///     ..Default::default()
+    /// };
+    /// let intent = SetupIntent::create(&auth, params)?;
+    /// // Hand intent.client_secret to Stripe.js to collect and authenticate the card
+    /// ```
+    pub fn create(auth: &Auth, params: CreateSetupIntentParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("setup_intents"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new setup intent (async)
+    pub async fn create_async(auth: &Auth, params: CreateSetupIntentParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("setup_intents"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a setup intent by ID
+    pub fn retrieve(auth: &Auth, setup_intent_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("setup_intents/{}", setup_intent_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a setup intent by ID (async)
+    pub async fn retrieve_async(auth: &Auth, setup_intent_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("setup_intents/{}", setup_intent_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Confirm a setup intent; check `requires_action` on the result
+    pub fn confirm(auth: &Auth, setup_intent_id: &str, params: ConfirmSetupIntentParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("setup_intents/{}/confirm", setup_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Confirm a setup intent (async)
+    pub async fn confirm_async(auth: &Auth, setup_intent_id: &str, params: ConfirmSetupIntentParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("setup_intents/{}/confirm", setup_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Cancel a setup intent that has not succeeded yet
+    pub fn cancel(auth: &Auth, setup_intent_id: &str, params: CancelSetupIntentParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("setup_intents/{}/cancel", setup_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Cancel a setup intent (async)
+    pub async fn cancel_async(auth: &Auth, setup_intent_id: &str, params: CancelSetupIntentParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("setup_intents/{}/cancel", setup_intent_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List setup intents, optionally only those of one customer, walking
+    /// every page or up to `page.max_items`
+    pub fn list(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, customer_id, page).collect()
+    }
+
+    /// List setup intents (async)
+    pub async fn list_async(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, customer_id, page).try_collect().await
+    }
+
+    /// Iterate over setup intents, optionally only those of one customer, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(customer_id), page)
+    }
+
+    /// Stream setup intents, optionally only those of one customer, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(customer_id), page)
+    }
+
+    fn list_path(customer_id: Option<&str>) -> String {
+        match customer_id {
+            Some(customer) => format!("setup_intents?customer={}", customer),
+            None => "setup_intents".to_string(),
+        }
+    }
+}
@@ -1,5 +1,14 @@
 use serde::{Deserialize, Serialize};
+use futures::TryStreamExt;
 use crate::stripe::Auth;
+use crate::http_client::{get_shared_client, SendVia};
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::search::SearchQuery;
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::stripe::subscription_item::ProrationBehavior;
+use crate::stripe::coupon::{DeletedDiscount, DiscountItem};
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Subscription {
@@ -23,6 +32,61 @@ pub struct Subscription {
     pub start_date: Option<i64>,
     pub status: Option<String>,
     pub price_items: Option<Vec<String>>,
+    /// Items with per-item quantity, or changes to existing items by ID;
+    /// sent instead of `price_items` when set
+    #[serde(skip)]
+    pub items: Option<Vec<SubscriptionItemParams>>,
+    /// How to prorate item changes made by `update`
+    #[serde(skip)]
+    pub proration_behavior: Option<ProrationBehavior>,
+    /// Coupons, promotion codes or existing discounts to apply; an empty
+    /// list removes every discount on `update`
+    #[serde(skip)]
+    pub discounts: Option<Vec<DiscountItem>>,
+}
+
+/// An item to add to, change on or remove from a subscription
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct SubscriptionItemParams {
+    /// ID of an existing subscription item to change or remove
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    /// Remove the item given by `id`
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub deleted: bool,
+}
+
+impl SubscriptionItemParams {
+    /// Add `quantity` of a price
+    pub fn price(price: &str, quantity: u64) -> Self {
+        Self {
+            price: Some(price.to_string()),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+
+    /// Change the quantity of an existing item
+    pub fn quantity(item_id: &str, quantity: u64) -> Self {
+        Self {
+            id: Some(item_id.to_string()),
+            quantity: Some(quantity),
+            ..Default::default()
+        }
+    }
+
+    /// Remove an existing item
+    pub fn delete(item_id: &str) -> Self {
+        Self {
+            id: Some(item_id.to_string()),
+            deleted: true,
+            ..Default::default()
+        }
+    }
 }
 
 impl Subscription {
@@ -48,15 +112,32 @@ impl Subscription {
             quantity: None,
             start_date: None,
             status: None,
+            items: None,
+            proration_behavior: None,
+            discounts: None,
         }
     }
 
+    /// Replace the discounts applied to an existing subscription
+    pub async fn async_apply_discount(
+        creds: Auth,
+        id: String,
+        discounts: Vec<DiscountItem>,
+    ) -> Result<crate::stripe::response::Subscription, crate::error::PayupError> {
+        let subscription = Subscription {
+            id: Some(id),
+            discounts: Some(discounts),
+            ..Subscription::new()
+        };
+        subscription.async_update(creds).await
+    }
+
     pub async fn async_cancel(
         creds: Auth,
         id: String,
         cancel_at_period_end: bool,
-    ) -> Result<crate::stripe::response::Subscription, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/subscriptions/{}", id);
+    ) -> Result<crate::stripe::response::Subscription, crate::error::PayupError> {
+        let url = creds.url(&format!("subscriptions/{}", id));
         
         let params = if cancel_at_period_end {
             vec![("cancel_at_period_end", "true")]
@@ -64,27 +145,44 @@ impl Subscription {
             vec![]
         };
 
-        let request = reqwest::Client::new()
+        let request = get_shared_client()
             .delete(&url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&params)
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<crate::stripe::response::Subscription>().await?;
         Ok(json)
     }
 
+    /// Remove the discount applied directly to a subscription
+    pub async fn async_delete_discount(
+        creds: Auth,
+        id: String,
+    ) -> Result<DeletedDiscount, crate::error::PayupError> {
+        let url = creds.url(&format!("subscriptions/{}/discount", id));
+
+        let request = get_shared_client()
+            .delete(&url)
+            .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
+            .send_limited("stripe", creds.transport())
+            .await?;
+
+        let json = request.json::<DeletedDiscount>().await?;
+        Ok(json)
+    }
+
     pub async fn async_get(
         creds: Auth,
         id: String,
-    ) -> Result<crate::stripe::response::Subscription, reqwest::Error> {
-        let url = format!("https://api.stripe.com/v1/subscriptions/{}", id);
+    ) -> Result<crate::stripe::response::Subscription, crate::error::PayupError> {
+        let url = creds.url(&format!("subscriptions/{}", id));
 
-        let request = reqwest::Client::new()
+        let request = get_shared_client()
             .get(&url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<crate::stripe::response::Subscription>().await?;
@@ -94,17 +192,18 @@ impl Subscription {
     pub async fn async_update(
         &self,
         creds: Auth,
-    ) -> Result<crate::stripe::response::Subscription, reqwest::Error> {
-        let url = format!(
-            "https://api.stripe.com/v1/subscriptions/{}",
+    ) -> Result<crate::stripe::response::Subscription, crate::error::PayupError> {
+        let url = creds.url(&format!(
+            "subscriptions/{}",
             self.id.as_ref().unwrap()
-        );
+        ));
 
-        let request = reqwest::Client::new()
+        let request = get_shared_client()
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<crate::stripe::response::Subscription>().await?;
@@ -114,24 +213,79 @@ impl Subscription {
     pub async fn async_post(
         &self,
         creds: Auth,
-    ) -> Result<crate::stripe::response::Subscription, reqwest::Error> {
-        let request = reqwest::Client::new()
-            .post("https://api.stripe.com/v1/subscriptions")
+    ) -> Result<crate::stripe::response::Subscription, crate::error::PayupError> {
+        let request = get_shared_client()
+            .post(creds.url("subscriptions"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
             .form(&self.to_params())
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<crate::stripe::response::Subscription>().await?;
         Ok(json)
     }
 
+    /// Iterate over all subscriptions, optionally only those of one customer,
+    /// fetching further pages as needed
+    pub fn list_iter(
+        creds: Auth,
+        customer_id: Option<String>,
+        page: PageRequest,
+    ) -> Paginator<crate::stripe::response::Subscription> {
+        pagination::list_iter(creds, &Self::list_path(customer_id), page)
+    }
+
+    /// Stream all subscriptions, optionally only those of one customer,
+    /// fetching further pages as needed
+    pub fn list_stream(
+        creds: Auth,
+        customer_id: Option<String>,
+        page: PageRequest,
+    ) -> PageStream<crate::stripe::response::Subscription> {
+        pagination::list_stream(creds, &Self::list_path(customer_id), page)
+    }
+
+    /// Iterate over subscriptions matching `query`, fetching further pages as needed
+    pub fn search_iter(
+        creds: Auth,
+        query: &SearchQuery,
+        page: PageRequest,
+    ) -> Paginator<crate::stripe::response::Subscription> {
+        pagination::search_iter(creds, "subscriptions/search", query, page)
+    }
+
+    /// Stream subscriptions matching `query`, fetching further pages as needed
+    pub fn search_stream(
+        creds: Auth,
+        query: &SearchQuery,
+        page: PageRequest,
+    ) -> PageStream<crate::stripe::response::Subscription> {
+        pagination::search_stream(creds, "subscriptions/search", query, page)
+    }
+
+    /// Asynchronously return all subscriptions matching `query`, e.g.
+    /// `SearchClause::field("status").eq("past_due")`
+    pub async fn async_search(
+        creds: Auth,
+        query: &SearchQuery,
+    ) -> Result<Vec<crate::stripe::response::Subscription>, crate::error::PayupError> {
+        Self::search_stream(creds, query, PageRequest::new()).try_collect().await
+    }
+
+    fn list_path(customer_id: Option<String>) -> String {
+        match customer_id {
+            Some(customer) => format!("subscriptions?customer={}", customer),
+            None => "subscriptions".to_string(),
+        }
+    }
+
     pub async fn async_list(
         creds: Auth,
         customer_id: Option<String>,
         limit: Option<i32>,
-    ) -> Result<crate::stripe::response::Subscriptions, reqwest::Error> {
-        let mut url = "https://api.stripe.com/v1/subscriptions".to_string();
+    ) -> Result<crate::stripe::response::Subscriptions, crate::error::PayupError> {
+        let mut url = creds.url("subscriptions");
         let mut params = vec![];
         
         if let Some(customer) = customer_id {
@@ -147,10 +301,10 @@ impl Subscription {
             url.push_str(&params.join("&"));
         }
 
-        let request = reqwest::Client::new()
+        let request = get_shared_client()
             .get(&url)
             .basic_auth(creds.client.as_str(), Some(creds.secret.as_str()))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
         let json = request.json::<crate::stripe::response::Subscriptions>().await?;
@@ -180,7 +334,32 @@ impl Subscription {
             params.push(("days_until_due", days_until_due.to_string()));
         }
 
-        if let Some(price_items) = &self.price_items {
+        if let Some(proration_behavior) = &self.proration_behavior {
+            let value = match proration_behavior {
+                ProrationBehavior::CreateProrations => "create_prorations",
+                ProrationBehavior::None => "none",
+                ProrationBehavior::AlwaysInvoice => "always_invoice",
+            };
+            params.push(("proration_behavior", value.to_string()));
+        }
+
+        if let Some(items) = &self.items {
+            for (i, item) in items.iter().enumerate().take(20) {
+                let key = |field: &str| -> &str { Box::leak(format!("items[{}][{}]", i, field).into_boxed_str()) };
+                if let Some(id) = &item.id {
+                    params.push((key("id"), id.clone()));
+                }
+                if let Some(price) = &item.price {
+                    params.push((key("price"), price.clone()));
+                }
+                if let Some(quantity) = item.quantity {
+                    params.push((key("quantity"), quantity.to_string()));
+                }
+                if item.deleted {
+                    params.push((key("deleted"), "true".to_string()));
+                }
+            }
+        } else if let Some(price_items) = &self.price_items {
             for (i, item) in price_items.iter().enumerate() {
                 if i < 20 {
                     params.push((
@@ -191,6 +370,24 @@ impl Subscription {
             }
         }
 
+        if let Some(discounts) = &self.discounts {
+            if discounts.is_empty() {
+                params.push(("discounts", String::new()));
+            }
+            for (i, discount) in discounts.iter().enumerate() {
+                let key = |field: &str| -> &str { Box::leak(format!("discounts[{}][{}]", i, field).into_boxed_str()) };
+                if let Some(coupon) = &discount.coupon {
+                    params.push((key("coupon"), coupon.clone()));
+                }
+                if let Some(discount_id) = &discount.discount {
+                    params.push((key("discount"), discount_id.clone()));
+                }
+                if let Some(promotion_code) = &discount.promotion_code {
+                    params.push((key("promotion_code"), promotion_code.clone()));
+                }
+            }
+        }
+
         params
     }
 }
@@ -0,0 +1,243 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::plan::Price;
+use super::Auth;
+
+/// How Stripe handles prorations when a subscription's items change
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ProrationBehavior {
+    /// Create proration invoice items, billed on the next invoice (the default)
+    CreateProrations,
+    /// Don't prorate the change
+    None,
+    /// Create prorations and invoice them immediately
+    AlwaysInvoice,
+}
+
+/// A price and quantity on a subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionItem {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "subscription_item")
+    pub object: String,
+    /// Usage thresholds that trigger an invoice early
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub billing_thresholds: Option<serde_json::Value>,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// The price the customer is subscribed to
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<Price>,
+    /// The quantity of the price, absent for metered prices
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    /// The subscription this item belongs to
+    pub subscription: String,
+}
+
+/// A deleted subscription item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedSubscriptionItem {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+/// Parameters for adding an item to a subscription
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateSubscriptionItemParams {
+    /// The subscription to add the item to
+    pub subscription: String,
+    /// The price to subscribe to
+    pub price: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    /// Calculate prorations as though the change happened at this time (Unix timestamp)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_date: Option<i64>,
+}
+
+impl CreateSubscriptionItemParams {
+    /// Parameters adding `quantity` of `price` to `subscription`
+    pub fn new(subscription: &str, price: &str, quantity: u64) -> Self {
+        Self {
+            subscription: subscription.to_string(),
+            price: price.to_string(),
+            quantity: Some(quantity),
+            metadata: None,
+            proration_behavior: None,
+            proration_date: None,
+        }
+    }
+}
+
+/// Parameters for updating a subscription item
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateSubscriptionItemParams {
+    /// Swap the item to a different price
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub price: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_date: Option<i64>,
+}
+
+/// Parameters for removing an item from a subscription
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeleteSubscriptionItemParams {
+    /// Delete all usage for a metered item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub clear_usage: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_date: Option<i64>,
+}
+
+impl SubscriptionItem {
+    /// Add an item to an existing subscription
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::subscription_item::{CreateSubscriptionItemParams, SubscriptionItem};
+    ///
+    /// let params = CreateSubscriptionItemParams::new("sub_123", "price_seats", 5);
+    /// let item = SubscriptionItem::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateSubscriptionItemParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("subscription_items"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Add an item to an existing subscription (async)
+    pub async fn create_async(auth: &Auth, params: CreateSubscriptionItemParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("subscription_items"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a subscription item by ID
+    pub fn retrieve(auth: &Auth, item_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("subscription_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a subscription item by ID (async)
+    pub async fn retrieve_async(auth: &Auth, item_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("subscription_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Change the price or quantity of a subscription item
+    pub fn update(auth: &Auth, item_id: &str, params: UpdateSubscriptionItemParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("subscription_items/{}", item_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Change the price or quantity of a subscription item (async)
+    pub async fn update_async(auth: &Auth, item_id: &str, params: UpdateSubscriptionItemParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("subscription_items/{}", item_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Remove an item from its subscription
+    pub fn delete(auth: &Auth, item_id: &str, params: DeleteSubscriptionItemParams) -> Result<DeletedSubscriptionItem> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("subscription_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .query(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Remove an item from its subscription (async)
+    pub async fn delete_async(
+        auth: &Auth,
+        item_id: &str,
+        params: DeleteSubscriptionItemParams,
+    ) -> Result<DeletedSubscriptionItem> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("subscription_items/{}", item_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .query(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List the items of a subscription, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, subscription_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, subscription_id, page).collect()
+    }
+
+    /// List the items of a subscription (async)
+    pub async fn list_async(auth: &Auth, subscription_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, subscription_id, page).try_collect().await
+    }
+
+    /// Iterate over the items of a subscription, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, subscription_id: &str, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &format!("subscription_items?subscription={}", subscription_id), page)
+    }
+
+    /// Stream the items of a subscription, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, subscription_id: &str, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &format!("subscription_items?subscription={}", subscription_id), page)
+    }
+}
@@ -0,0 +1,375 @@
+use serde::{Deserialize, Serialize, Serializer};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::subscription_item::ProrationBehavior;
+use super::Auth;
+
+/// What happens to the subscription once a schedule's last phase ends
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum EndBehavior {
+    /// Keep the subscription running on the last phase's items
+    Release,
+    /// Cancel the subscription
+    Cancel,
+}
+
+/// Status of a subscription schedule
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum SubscriptionScheduleStatus {
+    NotStarted,
+    Active,
+    Completed,
+    Released,
+    Canceled,
+}
+
+/// Changes to a subscription planned ahead of time as a series of phases
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SubscriptionSchedule {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "subscription_schedule")
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub canceled_at: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub completed_at: Option<i64>,
+    /// Time at which the object was created (Unix timestamp)
+    pub created: i64,
+    /// The phase currently in effect, if the schedule has started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub current_phase: Option<CurrentPhase>,
+    /// ID of the customer who owns the schedule
+    pub customer: String,
+    pub end_behavior: EndBehavior,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// The schedule's phases, in order
+    #[serde(default)]
+    pub phases: Vec<SchedulePhase>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub released_at: Option<i64>,
+    /// The subscription the schedule let go of when it was released
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub released_subscription: Option<String>,
+    pub status: SubscriptionScheduleStatus,
+    /// ID of the subscription managed by the schedule
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subscription: Option<String>,
+}
+
+/// Start and end of the phase currently in effect
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CurrentPhase {
+    pub start_date: i64,
+    pub end_date: i64,
+}
+
+/// A period of a schedule during which the subscription has a fixed set of items
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulePhase {
+    pub start_date: i64,
+    pub end_date: i64,
+    #[serde(default)]
+    pub items: Vec<SchedulePhaseItem>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial_end: Option<i64>,
+}
+
+/// A price and quantity within a schedule phase
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SchedulePhaseItem {
+    /// ID of the price
+    pub price: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+}
+
+/// When a new schedule starts
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ScheduleStartDate {
+    Now,
+    At(i64),
+}
+
+impl Serialize for ScheduleStartDate {
+    fn serialize<S: Serializer>(&self, serializer: S) -> std::result::Result<S::Ok, S::Error> {
+        match self {
+            ScheduleStartDate::Now => serializer.serialize_str("now"),
+            ScheduleStartDate::At(timestamp) => serializer.serialize_i64(*timestamp),
+        }
+    }
+}
+
+/// A phase to create or replace on a schedule
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct PhaseParams {
+    /// The items the subscription has during this phase
+    pub items: Vec<PhaseItemParams>,
+    /// Required on the first phase when updating a schedule that has started
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<i64>,
+    /// When the phase ends; set either this or `iterations`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_date: Option<i64>,
+    /// How many billing periods the phase lasts
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub iterations: Option<u32>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub trial_end: Option<i64>,
+}
+
+/// A price and quantity within a phase being created
+#[derive(Debug, Clone, Serialize)]
+pub struct PhaseItemParams {
+    pub price: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+}
+
+impl PhaseItemParams {
+    /// `quantity` of the price with ID `price`
+    pub fn new(price: &str, quantity: u64) -> Self {
+        Self {
+            price: price.to_string(),
+            quantity: Some(quantity),
+        }
+    }
+}
+
+/// Parameters for creating a subscription schedule
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CreateSubscriptionScheduleParams {
+    /// Customer to create a new subscription for; not used with `from_subscription`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    /// Put an existing subscription under a schedule, taking its items as the first phase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub from_subscription: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_date: Option<ScheduleStartDate>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_behavior: Option<EndBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phases: Option<Vec<PhaseParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for updating a subscription schedule
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct UpdateSubscriptionScheduleParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end_behavior: Option<EndBehavior>,
+    /// Replaces all phases; past and current phases have to be included unchanged
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub phases: Option<Vec<PhaseParams>>,
+    /// How to prorate if the current phase changes
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub proration_behavior: Option<ProrationBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for canceling a subscription schedule and its subscription
+#[derive(Debug, Clone, Default, Serialize)]
+pub struct CancelSubscriptionScheduleParams {
+    /// Invoice pending metered usage and prorations right away
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice_now: Option<bool>,
+    /// Credit unused time on the current phase
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub prorate: Option<bool>,
+}
+
+impl SubscriptionSchedule {
+    /// Create a new subscription schedule
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::subscription_schedule::*;
+    ///
+    /// // One month on the starter price, then move to pro for good
+    /// let params = CreateSubscriptionScheduleParams {
+    ///     customer: Some("cus_123".to_string()),
+    ///     start_date: Some(ScheduleStartDate::Now),
+    ///     end_behavior: Some(EndBehavior::Release),
+    ///     phases: Some(vec![
+    ///         PhaseParams { items: vec![PhaseItemParams::new("price_starter", 1)], iterations: Some(1), ..Default::default() },
+    ///         PhaseParams { items: vec![PhaseItemParams::new("price_pro", 1)], ..Default::default() },
+    ///     ]),
+    ///     ..Default::default()
+    /// };
+    /// let schedule = SubscriptionSchedule::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateSubscriptionScheduleParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("subscription_schedules"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new subscription schedule (async)
+    pub async fn create_async(auth: &Auth, params: CreateSubscriptionScheduleParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("subscription_schedules"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a subscription schedule by ID
+    pub fn retrieve(auth: &Auth, schedule_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("subscription_schedules/{}", schedule_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a subscription schedule by ID (async)
+    pub async fn retrieve_async(auth: &Auth, schedule_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("subscription_schedules/{}", schedule_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a subscription schedule
+    pub fn update(auth: &Auth, schedule_id: &str, params: UpdateSubscriptionScheduleParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("subscription_schedules/{}", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a subscription schedule (async)
+    pub async fn update_async(
+        auth: &Auth,
+        schedule_id: &str,
+        params: UpdateSubscriptionScheduleParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("subscription_schedules/{}", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Cancel a schedule, canceling its subscription too
+    pub fn cancel(auth: &Auth, schedule_id: &str, params: CancelSubscriptionScheduleParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("subscription_schedules/{}/cancel", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Cancel a schedule, canceling its subscription too (async)
+    pub async fn cancel_async(
+        auth: &Auth,
+        schedule_id: &str,
+        params: CancelSubscriptionScheduleParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("subscription_schedules/{}/cancel", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Release a schedule, leaving its subscription running as it is
+    pub fn release(auth: &Auth, schedule_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("subscription_schedules/{}/release", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Release a schedule, leaving its subscription running as it is (async)
+    pub async fn release_async(auth: &Auth, schedule_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("subscription_schedules/{}/release", schedule_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List subscription schedules, optionally only those of one customer,
+    /// walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, customer_id, page).collect()
+    }
+
+    /// List subscription schedules (async)
+    pub async fn list_async(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, customer_id, page).try_collect().await
+    }
+
+    /// Iterate over subscription schedules, optionally only those of one customer, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(customer_id), page)
+    }
+
+    /// Stream subscription schedules, optionally only those of one customer, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, customer_id: Option<&str>, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(customer_id), page)
+    }
+
+    fn list_path(customer_id: Option<&str>) -> String {
+        match customer_id {
+            Some(customer) => format!("subscription_schedules?customer={}", customer),
+            None => "subscription_schedules".to_string(),
+        }
+    }
+}
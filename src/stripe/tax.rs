@@ -0,0 +1,496 @@
+//! Stripe Tax calculations and transactions.
+//!
+//! A [`TaxCalculation`] works out the tax owed on a set of line items for a
+//! customer's location and tax IDs, applying reverse charges and thresholds as
+//! registered in the dashboard. Once the order is paid, record it as a
+//! [`TaxTransaction`] so it shows up in tax reports, and reverse it on refunds.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::invoice::{self, Address};
+use super::plan::TaxBehavior;
+use super::Auth;
+
+/// Which of the customer's addresses tax is based on
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxAddressSource {
+    Billing,
+    Shipping,
+}
+
+/// Whether a transaction records a sale or reverses one
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxTransactionType {
+    Transaction,
+    Reversal,
+}
+
+/// How much of a transaction a reversal undoes
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxReversalMode {
+    Full,
+    Partial,
+}
+
+/// Where and to whom a sale is made, when not taken from a Stripe customer
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxCustomerDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address_source: Option<TaxAddressSource>,
+    /// Used to locate the customer when no address is given
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    /// The customer's tax IDs, e.g. a VAT number making a B2B sale reverse charged
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub tax_ids: Vec<invoice::TaxId>,
+    /// "customer_exempt" or "reverse_charge" to override the customer's taxability
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub taxability_override: Option<String>,
+}
+
+/// The tax on one jurisdiction's share of a calculation
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxBreakdown {
+    /// Tax amount in the smallest currency unit
+    pub amount: i64,
+    pub inclusive: bool,
+    pub tax_rate_details: TaxRateDetails,
+    /// Why the amount is or isn't taxed, e.g. "standard_rated" or "reverse_charge"
+    pub taxability_reason: String,
+    /// The amount the tax was calculated on
+    pub taxable_amount: i64,
+}
+
+/// The rate applied in a tax breakdown
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRateDetails {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    /// The rate as a decimal string, e.g. "19.0"
+    pub percentage_decimal: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_type: Option<String>,
+}
+
+/// Shipping costs as taxed in a calculation or transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxShippingCost {
+    pub amount: i64,
+    pub amount_tax: i64,
+    pub tax_behavior: TaxBehavior,
+    pub tax_code: String,
+}
+
+/// A line item of a calculation or transaction, with the tax it carries
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxLineItem {
+    /// Unique identifier for the object
+    pub id: String,
+    /// "tax.calculation_line_item" or "tax.transaction_line_item"
+    pub object: String,
+    pub amount: i64,
+    pub amount_tax: i64,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    pub quantity: u64,
+    /// Your own ID for the line item
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reference: Option<String>,
+    pub tax_behavior: TaxBehavior,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_breakdown: Option<Vec<TaxBreakdown>>,
+    pub tax_code: String,
+}
+
+/// The tax owed on an order, valid for 90 days
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxCalculation {
+    /// Unique identifier for the object
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub id: Option<String>,
+    /// String representing the object's type (always "tax.calculation")
+    pub object: String,
+    /// Total of the line items and shipping, including tax
+    pub amount_total: i64,
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    pub customer_details: TaxCustomerDetails,
+    /// When the calculation can no longer be turned into a transaction
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub expires_at: Option<i64>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_cost: Option<TaxShippingCost>,
+    /// Tax added on top of the amounts
+    pub tax_amount_exclusive: i64,
+    /// Tax already included in the amounts
+    pub tax_amount_inclusive: i64,
+    #[serde(default)]
+    pub tax_breakdown: Vec<TaxBreakdown>,
+    pub tax_date: i64,
+}
+
+/// A line item to calculate tax for
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxLineItemParams {
+    /// Amount in the smallest currency unit, for the whole quantity
+    pub amount: i64,
+    /// Your own ID for the line item, unique within the calculation
+    pub reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub product: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_behavior: Option<TaxBehavior>,
+    /// Stripe tax code, defaulting to the product's or the account's
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_code: Option<String>,
+}
+
+impl TaxLineItemParams {
+    pub fn new(amount: i64, reference: &str) -> Self {
+        Self {
+            amount,
+            reference: reference.to_string(),
+            product: None,
+            quantity: None,
+            tax_behavior: None,
+            tax_code: None,
+        }
+    }
+}
+
+/// Shipping costs to calculate tax for
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TaxShippingCostParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount: Option<i64>,
+    /// A shipping rate to take the amount and tax code from
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_rate: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_behavior: Option<TaxBehavior>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_code: Option<String>,
+}
+
+/// Parameters for calculating tax; set either `customer` or `customer_details`
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaxCalculationParams {
+    pub currency: String,
+    pub line_items: Vec<TaxLineItemParams>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer_details: Option<TaxCustomerDetails>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_cost: Option<TaxShippingCostParams>,
+    /// Calculate with the rates in force at this time (Unix timestamp), defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_date: Option<i64>,
+}
+
+impl CreateTaxCalculationParams {
+    /// A calculation in `currency` for a Stripe customer's saved address and tax IDs
+    pub fn for_customer(currency: &str, customer_id: &str, line_items: Vec<TaxLineItemParams>) -> Self {
+        Self {
+            currency: currency.to_string(),
+            line_items,
+            customer: Some(customer_id.to_string()),
+            customer_details: None,
+            shipping_cost: None,
+            tax_date: None,
+        }
+    }
+
+    /// A calculation in `currency` for a customer described by `details`
+    pub fn for_details(currency: &str, details: TaxCustomerDetails, line_items: Vec<TaxLineItemParams>) -> Self {
+        Self {
+            currency: currency.to_string(),
+            line_items,
+            customer: None,
+            customer_details: Some(details),
+            shipping_cost: None,
+            tax_date: None,
+        }
+    }
+}
+
+/// The transaction a reversal undoes
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxTransactionReversal {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub original_transaction: Option<String>,
+}
+
+/// A sale or refund recorded for tax reporting
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxTransaction {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "tax.transaction")
+    pub object: String,
+    pub created: i64,
+    pub currency: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    pub customer_details: TaxCustomerDetails,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    /// Your own ID for the transaction, e.g. the order number
+    pub reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reversal: Option<TaxTransactionReversal>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shipping_cost: Option<TaxShippingCost>,
+    pub tax_date: i64,
+    #[serde(rename = "type")]
+    pub type_field: TaxTransactionType,
+}
+
+/// Parameters for recording a calculation as a transaction
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaxTransactionParams {
+    /// ID of the calculation to record
+    pub calculation: String,
+    /// Your own ID for the transaction, unique across transactions
+    pub reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateTaxTransactionParams {
+    pub fn new(calculation_id: &str, reference: &str) -> Self {
+        Self {
+            calculation: calculation_id.to_string(),
+            reference: reference.to_string(),
+            metadata: None,
+        }
+    }
+}
+
+/// A line item to reverse in a partial reversal
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReversalLineItemParams {
+    /// Amount to reverse, as a negative number
+    pub amount: i64,
+    /// Tax to reverse, as a negative number
+    pub amount_tax: i64,
+    /// ID of the line item in the original transaction
+    pub original_line_item: String,
+    /// Your own ID for the reversed line item
+    pub reference: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub quantity: Option<u64>,
+}
+
+/// Parameters for reversing a transaction, e.g. after a refund
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReverseTaxTransactionParams {
+    pub mode: TaxReversalMode,
+    pub original_transaction: String,
+    /// Your own ID for the reversal, unique across transactions
+    pub reference: String,
+    /// Reverse this amount (negative, including tax) spread over the whole
+    /// transaction instead of listing line items
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub flat_amount: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub line_items: Option<Vec<ReversalLineItemParams>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl ReverseTaxTransactionParams {
+    /// Undo the whole of `transaction_id`
+    pub fn full(transaction_id: &str, reference: &str) -> Self {
+        Self {
+            mode: TaxReversalMode::Full,
+            original_transaction: transaction_id.to_string(),
+            reference: reference.to_string(),
+            flat_amount: None,
+            line_items: None,
+            metadata: None,
+        }
+    }
+
+    /// Undo `flat_amount` (negative, including tax) of `transaction_id`
+    pub fn flat(transaction_id: &str, reference: &str, flat_amount: i64) -> Self {
+        Self {
+            mode: TaxReversalMode::Partial,
+            flat_amount: Some(flat_amount),
+            ..Self::full(transaction_id, reference)
+        }
+    }
+}
+
+impl TaxCalculation {
+    /// Calculate the tax on an order
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::tax::{CreateTaxCalculationParams, TaxCalculation, TaxLineItemParams};
+    ///
+    /// let params = CreateTaxCalculationParams::for_customer(
+    ///     "eur",
+    ///     "cus_123",
+    ///     vec![TaxLineItemParams::new(10000, "order-42-line-1")],
+    /// );
+    /// let calculation = TaxCalculation::create(&auth, params)?;
+    /// let total = calculation.amount_total;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateTaxCalculationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("tax/calculations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Calculate the tax on an order (async)
+    pub async fn create_async(auth: &Auth, params: CreateTaxCalculationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("tax/calculations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List a calculation's line items, walking every page or up to `page.max_items`
+    pub fn line_items(auth: &Auth, calculation_id: &str, page: PageRequest) -> Result<Vec<TaxLineItem>> {
+        Self::line_items_iter(auth, calculation_id, page).collect()
+    }
+
+    /// List a calculation's line items (async)
+    pub async fn line_items_async(auth: &Auth, calculation_id: &str, page: PageRequest) -> Result<Vec<TaxLineItem>> {
+        Self::line_items_stream(auth, calculation_id, page).try_collect().await
+    }
+
+    /// Iterate over a calculation's line items, fetching further pages as needed
+    pub fn line_items_iter(auth: &Auth, calculation_id: &str, page: PageRequest) -> Paginator<TaxLineItem> {
+        pagination::list_iter(auth.clone(), &format!("tax/calculations/{}/line_items", calculation_id), page)
+    }
+
+    /// Stream a calculation's line items, fetching further pages as needed
+    pub fn line_items_stream(auth: &Auth, calculation_id: &str, page: PageRequest) -> PageStream<TaxLineItem> {
+        pagination::list_stream(auth.clone(), &format!("tax/calculations/{}/line_items", calculation_id), page)
+    }
+}
+
+impl TaxTransaction {
+    /// Record a calculation as a transaction once the order is paid
+    pub fn create_from_calculation(auth: &Auth, params: CreateTaxTransactionParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("tax/transactions/create_from_calculation"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Record a calculation as a transaction once the order is paid (async)
+    pub async fn create_from_calculation_async(auth: &Auth, params: CreateTaxTransactionParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("tax/transactions/create_from_calculation"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Reverse all or part of a transaction, e.g. after a refund
+    pub fn create_reversal(auth: &Auth, params: ReverseTaxTransactionParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("tax/transactions/create_reversal"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Reverse all or part of a transaction (async)
+    pub async fn create_reversal_async(auth: &Auth, params: ReverseTaxTransactionParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("tax/transactions/create_reversal"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a transaction by ID
+    pub fn retrieve(auth: &Auth, transaction_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("tax/transactions/{}", transaction_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a transaction by ID (async)
+    pub async fn retrieve_async(auth: &Auth, transaction_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("tax/transactions/{}", transaction_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List a transaction's line items, walking every page or up to `page.max_items`
+    pub fn line_items(auth: &Auth, transaction_id: &str, page: PageRequest) -> Result<Vec<TaxLineItem>> {
+        Self::line_items_iter(auth, transaction_id, page).collect()
+    }
+
+    /// List a transaction's line items (async)
+    pub async fn line_items_async(auth: &Auth, transaction_id: &str, page: PageRequest) -> Result<Vec<TaxLineItem>> {
+        Self::line_items_stream(auth, transaction_id, page).try_collect().await
+    }
+
+    /// Iterate over a transaction's line items, fetching further pages as needed
+    pub fn line_items_iter(auth: &Auth, transaction_id: &str, page: PageRequest) -> Paginator<TaxLineItem> {
+        pagination::list_iter(auth.clone(), &format!("tax/transactions/{}/line_items", transaction_id), page)
+    }
+
+    /// Stream a transaction's line items, fetching further pages as needed
+    pub fn line_items_stream(auth: &Auth, transaction_id: &str, page: PageRequest) -> PageStream<TaxLineItem> {
+        pagination::list_stream(auth.clone(), &format!("tax/transactions/{}/line_items", transaction_id), page)
+    }
+}
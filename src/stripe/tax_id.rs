@@ -0,0 +1,177 @@
+use serde::{Deserialize, Serialize};
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// A customer's tax ID, such as an EU VAT number, shown on their invoices
+/// and used by automatic tax to decide on reverse charges
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxId {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "tax_id")
+    pub object: String,
+    /// Two-letter country code of the tax ID
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub customer: Option<String>,
+    pub livemode: bool,
+    /// The kind of tax ID, e.g. "eu_vat", "gb_vat" or "us_ein"
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub value: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verification: Option<TaxIdVerification>,
+}
+
+/// Result of checking a tax ID against the issuing authority
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxIdVerification {
+    pub status: TaxIdVerificationStatus,
+    /// Address on file with the authority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_address: Option<String>,
+    /// Name on file with the authority
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verified_name: Option<String>,
+}
+
+/// Tax ID verification status
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum TaxIdVerificationStatus {
+    Pending,
+    Verified,
+    Unverified,
+    /// The authority offers no verification for this kind of tax ID
+    Unavailable,
+}
+
+/// A deleted tax ID
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedTaxId {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+/// Parameters for adding a tax ID to a customer
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaxIdParams {
+    /// The kind of tax ID, e.g. "eu_vat"
+    #[serde(rename = "type")]
+    pub type_field: String,
+    pub value: String,
+}
+
+impl CreateTaxIdParams {
+    pub fn new(type_field: &str, value: &str) -> Self {
+        Self {
+            type_field: type_field.to_string(),
+            value: value.to_string(),
+        }
+    }
+}
+
+impl TaxId {
+    /// Add a tax ID to a customer
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::tax_id::{CreateTaxIdParams, TaxId};
+    ///
+    /// let tax_id = TaxId::create(&auth, "cus_123", CreateTaxIdParams::new("eu_vat", "DE123456789"))?;
+    /// ```
+    pub fn create(auth: &Auth, customer_id: &str, params: CreateTaxIdParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("customers/{}/tax_ids", customer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Add a tax ID to a customer (async)
+    pub async fn create_async(auth: &Auth, customer_id: &str, params: CreateTaxIdParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("customers/{}/tax_ids", customer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve one of a customer's tax IDs
+    pub fn retrieve(auth: &Auth, customer_id: &str, tax_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("customers/{}/tax_ids/{}", customer_id, tax_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve one of a customer's tax IDs (async)
+    pub async fn retrieve_async(auth: &Auth, customer_id: &str, tax_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("customers/{}/tax_ids/{}", customer_id, tax_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Remove a tax ID from a customer
+    pub fn delete(auth: &Auth, customer_id: &str, tax_id: &str) -> Result<DeletedTaxId> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("customers/{}/tax_ids/{}", customer_id, tax_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Remove a tax ID from a customer (async)
+    pub async fn delete_async(auth: &Auth, customer_id: &str, tax_id: &str) -> Result<DeletedTaxId> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("customers/{}/tax_ids/{}", customer_id, tax_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List a customer's tax IDs, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, customer_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, customer_id, page).collect()
+    }
+
+    /// List a customer's tax IDs (async)
+    pub async fn list_async(auth: &Auth, customer_id: &str, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, customer_id, page).try_collect().await
+    }
+
+    /// Iterate over a customer's tax IDs, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, customer_id: &str, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &format!("customers/{}/tax_ids", customer_id), page)
+    }
+
+    /// Stream a customer's tax IDs, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, customer_id: &str, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &format!("customers/{}/tax_ids", customer_id), page)
+    }
+}
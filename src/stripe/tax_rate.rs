@@ -0,0 +1,229 @@
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// A fixed tax percentage applied to invoices, subscriptions and Checkout
+/// line items when automatic tax is not used
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct TaxRate {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "tax_rate")
+    #[serde(default)]
+    pub object: String,
+    /// Inactive rates can't be applied to new objects
+    pub active: bool,
+    /// Two-letter country code (ISO 3166-1 alpha-2)
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    pub created: i64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    /// Name shown to customers, e.g. "VAT"
+    pub display_name: String,
+    /// The percentage actually charged, which is zero for some
+    /// jurisdiction-dependent rates
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub effective_percentage: Option<f64>,
+    /// Whether the tax is included in the amount it applies to
+    pub inclusive: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    pub percentage: f64,
+    /// ISO 3166-2 subdivision code, without the country prefix
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    /// The kind of tax, e.g. "vat", "gst" or "sales_tax"
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_type: Option<String>,
+}
+
+/// Parameters for creating a tax rate
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateTaxRateParams {
+    pub display_name: String,
+    pub inclusive: bool,
+    pub percentage: f64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_type: Option<String>,
+}
+
+impl CreateTaxRateParams {
+    /// A rate of `percentage` percent shown to customers as `display_name`
+    pub fn new(display_name: &str, percentage: f64, inclusive: bool) -> Self {
+        Self {
+            display_name: display_name.to_string(),
+            inclusive,
+            percentage,
+            active: None,
+            country: None,
+            description: None,
+            jurisdiction: None,
+            metadata: None,
+            state: None,
+            tax_type: None,
+        }
+    }
+}
+
+/// Parameters for updating a tax rate; its percentage cannot change once created
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateTaxRateParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub country: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub description: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub jurisdiction: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub state: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tax_type: Option<String>,
+}
+
+/// Filters for listing tax rates
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListTaxRatesParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub active: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub inclusive: Option<bool>,
+}
+
+impl TaxRate {
+    /// Create a new tax rate
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::tax_rate::{CreateTaxRateParams, TaxRate};
+    ///
+    /// let mut params = CreateTaxRateParams::new("VAT", 19.0, false);
+    /// params.country = Some("DE".to_string());
+    /// params.tax_type = Some("vat".to_string());
+    /// let rate = TaxRate::create(&auth, params)?;
+    /// ```
+    pub fn create(auth: &Auth, params: CreateTaxRateParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("tax_rates"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a new tax rate (async)
+    pub async fn create_async(auth: &Auth, params: CreateTaxRateParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("tax_rates"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a tax rate by ID
+    pub fn retrieve(auth: &Auth, tax_rate_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("tax_rates/{}", tax_rate_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a tax rate by ID (async)
+    pub async fn retrieve_async(auth: &Auth, tax_rate_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("tax_rates/{}", tax_rate_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a tax rate; tax rates can't be deleted, only deactivated
+    pub fn update(auth: &Auth, tax_rate_id: &str, params: UpdateTaxRateParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("tax_rates/{}", tax_rate_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a tax rate (async)
+    pub async fn update_async(auth: &Auth, tax_rate_id: &str, params: UpdateTaxRateParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("tax_rates/{}", tax_rate_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List tax rates matching `filters`, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListTaxRatesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List tax rates matching `filters` (async)
+    pub async fn list_async(auth: &Auth, filters: &ListTaxRatesParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over tax rates matching `filters`, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListTaxRatesParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream tax rates matching `filters`, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListTaxRatesParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListTaxRatesParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "tax_rates".to_string(),
+            query => format!("tax_rates?{}", query),
+        }
+    }
+}
@@ -0,0 +1,838 @@
+//! Stripe Terminal, for taking card payments in person.
+//!
+//! A payment on a reader goes through the usual PaymentIntent flow: create
+//! the PaymentIntent with `card_present` as its payment method type, hand it
+//! to the reader with [`Reader::process_payment_intent`], then capture it
+//! once the reader reports success. In test mode,
+//! [`Reader::present_payment_method`] stands in for the customer tapping
+//! their card.
+
+use serde::{Deserialize, Serialize};
+use std::collections::HashMap;
+use futures::TryStreamExt;
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::invoice::Address;
+use super::Auth;
+
+/// A short-lived secret the Terminal SDK on a device uses to talk to Stripe
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ConnectionToken {
+    /// String representing the object's type (always "terminal.connection_token")
+    pub object: String,
+    /// The location the token is limited to, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    /// Handed to the SDK; never store or log it
+    pub secret: String,
+}
+
+/// Parameters for creating a connection token
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct CreateConnectionTokenParams {
+    /// Only let the SDK connect to readers at this location
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+}
+
+impl ConnectionToken {
+    /// Create a connection token for the Terminal SDK to fetch from your back end
+    pub fn create(auth: &Auth, params: CreateConnectionTokenParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("terminal/connection_tokens"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a connection token (async)
+    pub async fn create_async(auth: &Auth, params: CreateConnectionTokenParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("terminal/connection_tokens"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+/// The result of deleting a location, reader or configuration
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct DeletedTerminalObject {
+    pub id: String,
+    pub object: String,
+    pub deleted: bool,
+}
+
+/// A physical place readers are registered to, e.g. a store
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Location {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "terminal.location")
+    pub object: String,
+    pub address: Address,
+    /// The configuration readers at the location use instead of the account default
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration_overrides: Option<String>,
+    pub display_name: String,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Parameters for creating a location
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateLocationParams {
+    pub address: Address,
+    pub display_name: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration_overrides: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateLocationParams {
+    pub fn new(display_name: &str, address: Address) -> Self {
+        Self {
+            address,
+            display_name: display_name.to_string(),
+            configuration_overrides: None,
+            metadata: None,
+        }
+    }
+}
+
+/// Parameters for updating a location
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateLocationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub address: Option<Address>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub configuration_overrides: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub display_name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl Location {
+    /// Create a location
+    pub fn create(auth: &Auth, params: CreateLocationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("terminal/locations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a location (async)
+    pub async fn create_async(auth: &Auth, params: CreateLocationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("terminal/locations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a location
+    pub fn retrieve(auth: &Auth, location_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("terminal/locations/{}", location_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a location (async)
+    pub async fn retrieve_async(auth: &Auth, location_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("terminal/locations/{}", location_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a location
+    pub fn update(auth: &Auth, location_id: &str, params: UpdateLocationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("terminal/locations/{}", location_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a location (async)
+    pub async fn update_async(auth: &Auth, location_id: &str, params: UpdateLocationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("terminal/locations/{}", location_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete a location
+    pub fn delete(auth: &Auth, location_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("terminal/locations/{}", location_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Delete a location (async)
+    pub async fn delete_async(auth: &Auth, location_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("terminal/locations/{}", location_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List locations, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, page).collect()
+    }
+
+    /// List locations (async)
+    pub async fn list_async(auth: &Auth, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, page).try_collect().await
+    }
+
+    /// Iterate over locations, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "terminal/locations", page)
+    }
+
+    /// Stream locations, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "terminal/locations", page)
+    }
+}
+
+/// A card reader registered to the account
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Reader {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "terminal.reader")
+    pub object: String,
+    /// What the reader is doing or last did, for server-driven readers
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<ReaderAction>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_sw_version: Option<String>,
+    /// e.g. "bbpos_wisepos_e", "stripe_s700" or "simulated_wisepos_e"
+    pub device_type: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ip_address: Option<String>,
+    pub label: String,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+    pub serial_number: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReaderStatus>,
+}
+
+/// Whether a reader is connected to Stripe
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReaderStatus {
+    Online,
+    Offline,
+}
+
+/// An action sent to a reader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderAction {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_code: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_message: Option<String>,
+    /// Set when the action is a `process_payment_intent`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_payment_intent: Option<ReaderProcessPaymentIntent>,
+    pub status: ReaderActionStatus,
+    /// e.g. "process_payment_intent", "process_setup_intent" or "refund_payment"
+    #[serde(rename = "type")]
+    pub type_field: String,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum ReaderActionStatus {
+    InProgress,
+    Succeeded,
+    Failed,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ReaderProcessPaymentIntent {
+    pub payment_intent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_config: Option<ProcessConfig>,
+}
+
+/// How the reader collects the payment
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessConfig {
+    /// Skip the tipping screen, even if the reader's configuration has one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub skip_tipping: Option<bool>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tipping: Option<ProcessTipping>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ProcessTipping {
+    /// Tips are suggested as a share of this amount rather than the full
+    /// PaymentIntent amount, e.g. to leave tax out
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_eligible: Option<i64>,
+}
+
+/// Parameters for registering a reader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateReaderParams {
+    /// The code the reader shows, e.g. "simulated-wpe" for a simulated reader
+    pub registration_code: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+impl CreateReaderParams {
+    pub fn new(registration_code: &str, location_id: &str) -> Self {
+        Self {
+            registration_code: registration_code.to_string(),
+            label: None,
+            location: Some(location_id.to_string()),
+            metadata: None,
+        }
+    }
+}
+
+/// Parameters for updating a reader
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct UpdateReaderParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub label: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub metadata: Option<HashMap<String, String>>,
+}
+
+/// Filters for listing readers
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListReadersParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub device_type: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub location: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub serial_number: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub status: Option<ReaderStatus>,
+}
+
+/// Parameters for handing a PaymentIntent to a reader
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct ProcessPaymentIntentParams {
+    pub payment_intent: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub process_config: Option<ProcessConfig>,
+}
+
+impl ProcessPaymentIntentParams {
+    pub fn new(payment_intent_id: &str) -> Self {
+        Self {
+            payment_intent: payment_intent_id.to_string(),
+            process_config: None,
+        }
+    }
+}
+
+/// The card a simulated reader pretends was presented
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct PresentPaymentMethodParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub amount_tip: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub card_present: Option<PresentedCard>,
+    /// "card_present" (the default) or "interac_present"
+    #[serde(rename = "type", skip_serializing_if = "Option::is_none")]
+    pub type_field: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct PresentedCard {
+    /// A test card number, e.g. "4000000000000002" to simulate a decline
+    pub number: String,
+}
+
+impl PresentPaymentMethodParams {
+    /// Present the test card `number`
+    pub fn card(number: &str) -> Self {
+        Self {
+            card_present: Some(PresentedCard { number: number.to_string() }),
+            ..Default::default()
+        }
+    }
+}
+
+impl Reader {
+    /// Register a reader to a location
+    pub fn create(auth: &Auth, params: CreateReaderParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("terminal/readers"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Register a reader to a location (async)
+    pub async fn create_async(auth: &Auth, params: CreateReaderParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("terminal/readers"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a reader, e.g. to poll its action's status
+    pub fn retrieve(auth: &Auth, reader_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a reader (async)
+    pub async fn retrieve_async(auth: &Auth, reader_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a reader
+    pub fn update(auth: &Auth, reader_id: &str, params: UpdateReaderParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a reader (async)
+    pub async fn update_async(auth: &Auth, reader_id: &str, params: UpdateReaderParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete a reader
+    pub fn delete(auth: &Auth, reader_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Delete a reader (async)
+    pub async fn delete_async(auth: &Auth, reader_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("terminal/readers/{}", reader_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List readers, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListReadersParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List readers (async)
+    pub async fn list_async(auth: &Auth, filters: &ListReadersParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over readers, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListReadersParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream readers, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListReadersParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListReadersParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "terminal/readers".to_string(),
+            query => format!("terminal/readers?{}", query),
+        }
+    }
+
+    /// Have the reader collect payment for a `card_present` PaymentIntent.
+    /// The call returns straight away with the action in progress; retrieve
+    /// the reader, or listen for `terminal.reader.action_succeeded`, to learn
+    /// how it went.
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::payment_intent::{CreatePaymentIntentParams, PaymentIntent};
+    /// use payup::stripe::terminal::{PresentPaymentMethodParams, ProcessPaymentIntentParams, Reader};
+    ///
+    /// let params = CreatePaymentIntentParams::card_present(1299, "usd");
+    /// let payment_intent = PaymentIntent::create(&auth, params)?;
+    /// Reader::process_payment_intent(&auth, "tmr_123", ProcessPaymentIntentParams::new(&payment_intent.id))?;
+    ///
+    /// // In test mode, tap a simulated card
+    /// let reader = Reader::present_payment_method(&auth, "tmr_123", PresentPaymentMethodParams::default())?;
+    /// ```
+    pub fn process_payment_intent(auth: &Auth, reader_id: &str, params: ProcessPaymentIntentParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("terminal/readers/{}/process_payment_intent", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Have the reader collect payment for a PaymentIntent (async)
+    pub async fn process_payment_intent_async(
+        auth: &Auth,
+        reader_id: &str,
+        params: ProcessPaymentIntentParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("terminal/readers/{}/process_payment_intent", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Cancel the reader's current action, returning it to its idle screen
+    pub fn cancel_action(auth: &Auth, reader_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("terminal/readers/{}/cancel_action", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Cancel the reader's current action (async)
+    pub async fn cancel_action_async(auth: &Auth, reader_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("terminal/readers/{}/cancel_action", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Test mode only: have a simulated reader act as though a card was
+    /// presented for its current action
+    pub fn present_payment_method(auth: &Auth, reader_id: &str, params: PresentPaymentMethodParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("test_helpers/terminal/readers/{}/present_payment_method", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Test mode only: present a card to a simulated reader (async)
+    pub async fn present_payment_method_async(
+        auth: &Auth,
+        reader_id: &str,
+        params: PresentPaymentMethodParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("test_helpers/terminal/readers/{}/present_payment_method", reader_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+/// Settings for readers: splash screens, tipping and offline payments
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Configuration {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "terminal.configuration")
+    pub object: String,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbpos_wisepos_e: Option<DeviceSettings>,
+    /// Whether readers without a location-level configuration use this one
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_account_default: Option<bool>,
+    pub livemode: bool,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<OfflineSettings>,
+    /// Tip suggestions by lowercase currency code
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tipping: Option<HashMap<String, TippingSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifone_p400: Option<DeviceSettings>,
+}
+
+/// Settings for one model of reader
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct DeviceSettings {
+    /// ID of a File with purpose `terminal_reader_splashscreen`
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub splashscreen: Option<String>,
+}
+
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct OfflineSettings {
+    /// Let readers keep taking payments while they can't reach Stripe
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub enabled: Option<bool>,
+}
+
+/// Tips offered for one currency. Below `smart_tip_threshold` the reader
+/// offers `fixed_amounts`, otherwise `percentages`.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct TippingSettings {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub fixed_amounts: Option<Vec<i64>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub percentages: Option<Vec<u32>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub smart_tip_threshold: Option<i64>,
+}
+
+/// Parameters for creating or updating a configuration
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ConfigurationParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bbpos_wisepos_e: Option<DeviceSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub offline: Option<OfflineSettings>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tipping: Option<HashMap<String, TippingSettings>>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub verifone_p400: Option<DeviceSettings>,
+}
+
+impl ConfigurationParams {
+    /// Offer `tipping` on payments in `currency`
+    pub fn tipping(mut self, currency: &str, tipping: TippingSettings) -> Self {
+        self.tipping.get_or_insert_with(HashMap::new).insert(currency.to_lowercase(), tipping);
+        self
+    }
+}
+
+/// Filters for listing configurations
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct ListConfigurationsParams {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub is_account_default: Option<bool>,
+}
+
+impl Configuration {
+    /// Create a configuration, to apply to a location with its
+    /// `configuration_overrides`
+    pub fn create(auth: &Auth, params: ConfigurationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url("terminal/configurations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Create a configuration (async)
+    pub async fn create_async(auth: &Auth, params: ConfigurationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url("terminal/configurations"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Retrieve a configuration
+    pub fn retrieve(auth: &Auth, configuration_id: &str) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .get(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Retrieve a configuration (async)
+    pub async fn retrieve_async(auth: &Auth, configuration_id: &str) -> Result<Self> {
+        let response = get_shared_client()
+            .get(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Update a configuration
+    pub fn update(auth: &Auth, configuration_id: &str, params: ConfigurationParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Update a configuration (async)
+    pub async fn update_async(auth: &Auth, configuration_id: &str, params: ConfigurationParams) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// Delete a configuration
+    pub fn delete(auth: &Auth, configuration_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_blocking_client()
+            .delete(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Delete a configuration (async)
+    pub async fn delete_async(auth: &Auth, configuration_id: &str) -> Result<DeletedTerminalObject> {
+        let response = get_shared_client()
+            .delete(auth.url(&format!("terminal/configurations/{}", configuration_id)))
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+
+    /// List configurations, walking every page or up to `page.max_items`
+    pub fn list(auth: &Auth, filters: &ListConfigurationsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_iter(auth, filters, page).collect()
+    }
+
+    /// List configurations (async)
+    pub async fn list_async(auth: &Auth, filters: &ListConfigurationsParams, page: PageRequest) -> Result<Vec<Self>> {
+        Self::list_stream(auth, filters, page).try_collect().await
+    }
+
+    /// Iterate over configurations, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, filters: &ListConfigurationsParams, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    /// Stream configurations, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, filters: &ListConfigurationsParams, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), &Self::list_path(filters), page)
+    }
+
+    fn list_path(filters: &ListConfigurationsParams) -> String {
+        match form::to_query(filters) {
+            query if query.is_empty() => "terminal/configurations".to_string(),
+            query => format!("terminal/configurations?{}", query),
+        }
+    }
+}
@@ -1,8 +1,11 @@
 use serde::{Deserialize, Serialize};
 use crate::error::Result;
-use crate::rate_limiter::get_rate_limiter;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
 use super::Auth;
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
 
 /// Represents a Stripe Transfer object for moving funds between Stripe accounts
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -110,10 +113,11 @@ impl Transfer {
     pub fn create(auth: &Auth, params: CreateTransferParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post("https://api.stripe.com/v1/transfers")
+            .post(auth.url("transfers"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let transfer: Transfer = response.json()?;
         Ok(transfer)
@@ -121,29 +125,26 @@ impl Transfer {
 
     /// Create a new transfer (async)
     pub async fn create_async(auth: &Auth, params: CreateTransferParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url("transfers"))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", || async {
-            let client = get_shared_client();
-            let response = client
-                .post("https://api.stripe.com/v1/transfers")
-                .header("Authorization", format!("Bearer {}", auth.secret))
-                .form(&params)
-                .send()
-                .await?;
-            
-            let transfer: Transfer = response.json().await?;
-            Ok(transfer)
-        }).await
+        let transfer: Transfer = response.json().await?;
+        Ok(transfer)
     }
 
     /// Retrieve a transfer by ID
     pub fn retrieve(auth: &Auth, transfer_id: &str) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/transfers/{}", transfer_id))
+            .get(auth.url(&format!("transfers/{}", transfer_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let transfer: Transfer = response.json()?;
         Ok(transfer)
@@ -151,33 +152,26 @@ impl Transfer {
 
     /// Retrieve a transfer by ID (async)
     pub async fn retrieve_async(auth: &Auth, transfer_id: &str) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let transfer_id = transfer_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .get(auth.url(&format!("transfers/{}", transfer_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let transfer_id = transfer_id.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .get(&format!("https://api.stripe.com/v1/transfers/{}", transfer_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                let transfer: Transfer = response.json().await?;
-                Ok(transfer)
-            }
-        }).await
+        let transfer: Transfer = response.json().await?;
+        Ok(transfer)
     }
 
     /// Update a transfer
     pub fn update(auth: &Auth, transfer_id: &str, params: UpdateTransferParams) -> Result<Self> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/transfers/{}", transfer_id))
+            .post(auth.url(&format!("transfers/{}", transfer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let transfer: Transfer = response.json()?;
         Ok(transfer)
@@ -185,31 +179,33 @@ impl Transfer {
 
     /// Update a transfer (async)
     pub async fn update_async(auth: &Auth, transfer_id: &str, params: UpdateTransferParams) -> Result<Self> {
-        let rate_limiter = get_rate_limiter();
-        let transfer_id = transfer_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("transfers/{}", transfer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let transfer_id = transfer_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/transfers/{}", transfer_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let transfer: Transfer = response.json().await?;
-                Ok(transfer)
-            }
-        }).await
+        let transfer: Transfer = response.json().await?;
+        Ok(transfer)
+    }
+
+    /// Iterate over all transfers, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(auth.clone(), "transfers", page)
+    }
+
+    /// Stream all transfers, fetching further pages as needed
+    pub fn list_stream(auth: &Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(auth.clone(), "transfers", page)
     }
 
     /// List all transfers
     pub fn list(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
         let client = get_shared_blocking_client();
-        let mut url = "https://api.stripe.com/v1/transfers".to_string();
+        let mut url = auth.url("transfers");
         if let Some(limit) = limit {
             url = format!("{}?limit={}", url, limit);
         }
@@ -217,7 +213,7 @@ impl Transfer {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         #[derive(Deserialize)]
         struct TransferList {
@@ -230,39 +226,36 @@ impl Transfer {
 
     /// List all transfers (async)
     pub async fn list_async(auth: &Auth, limit: Option<u32>) -> Result<Vec<Self>> {
-        let rate_limiter = get_rate_limiter();
+        let client = get_shared_client();
+        let mut url = auth.url("transfers");
+        if let Some(limit) = limit {
+            url = format!("{}?limit={}", url, limit);
+        }
+        
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
+        
+        #[derive(Deserialize)]
+        struct TransferList {
+            data: Vec<Transfer>,
+        }
         
-        rate_limiter.execute_with_retry_async("stripe", move || async move {
-            let client = get_shared_client();
-            let mut url = "https://api.stripe.com/v1/transfers".to_string();
-            if let Some(limit) = limit {
-                url = format!("{}?limit={}", url, limit);
-            }
-            
-            let response = client
-                .get(&url)
-                .header("Authorization", format!("Bearer {}", auth.secret))
-                .send()
-                .await?;
-            
-            #[derive(Deserialize)]
-            struct TransferList {
-                data: Vec<Transfer>,
-            }
-            
-            let list: TransferList = response.json().await?;
-            Ok(list.data)
-        }).await
+        let list: TransferList = response.json().await?;
+        Ok(list.data)
     }
 
     /// Create a transfer reversal
     pub fn create_reversal(auth: &Auth, transfer_id: &str, params: CreateReversalParams) -> Result<TransferReversal> {
         let client = get_shared_blocking_client();
         let response = client
-            .post(&format!("https://api.stripe.com/v1/transfers/{}/reversals", transfer_id))
+            .post(auth.url(&format!("transfers/{}/reversals", transfer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .form(&params)
-            .send()?;
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
         
         let reversal: TransferReversal = response.json()?;
         Ok(reversal)
@@ -270,34 +263,26 @@ impl Transfer {
 
     /// Create a transfer reversal (async)
     pub async fn create_reversal_async(auth: &Auth, transfer_id: &str, params: CreateReversalParams) -> Result<TransferReversal> {
-        let rate_limiter = get_rate_limiter();
-        let transfer_id = transfer_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .post(auth.url(&format!("transfers/{}/reversals", transfer_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let transfer_id = transfer_id.clone();
-            let params = params.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .post(&format!("https://api.stripe.com/v1/transfers/{}/reversals", transfer_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .form(&params)
-                    .send()
-                    .await?;
-                
-                let reversal: TransferReversal = response.json().await?;
-                Ok(reversal)
-            }
-        }).await
+        let reversal: TransferReversal = response.json().await?;
+        Ok(reversal)
     }
 
     /// Retrieve a transfer reversal
     pub fn retrieve_reversal(auth: &Auth, transfer_id: &str, reversal_id: &str) -> Result<TransferReversal> {
         let client = get_shared_blocking_client();
         let response = client
-            .get(&format!("https://api.stripe.com/v1/transfers/{}/reversals/{}", transfer_id, reversal_id))
+            .get(auth.url(&format!("transfers/{}/reversals/{}", transfer_id, reversal_id)))
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         let reversal: TransferReversal = response.json()?;
         Ok(reversal)
@@ -305,31 +290,31 @@ impl Transfer {
 
     /// Retrieve a transfer reversal (async)
     pub async fn retrieve_reversal_async(auth: &Auth, transfer_id: &str, reversal_id: &str) -> Result<TransferReversal> {
-        let rate_limiter = get_rate_limiter();
-        let transfer_id = transfer_id.to_string();
-        let reversal_id = reversal_id.to_string();
+        let client = get_shared_client();
+        let response = client
+            .get(auth.url(&format!("transfers/{}/reversals/{}", transfer_id, reversal_id)))
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let transfer_id = transfer_id.clone();
-            let reversal_id = reversal_id.clone();
-            async move {
-                let client = get_shared_client();
-                let response = client
-                    .get(&format!("https://api.stripe.com/v1/transfers/{}/reversals/{}", transfer_id, reversal_id))
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                let reversal: TransferReversal = response.json().await?;
-                Ok(reversal)
-            }
-        }).await
+        let reversal: TransferReversal = response.json().await?;
+        Ok(reversal)
+    }
+
+    /// Iterate over all reversals of a transfer, fetching further pages as needed
+    pub fn list_reversals_iter(auth: &Auth, transfer_id: &str, page: PageRequest) -> Paginator<TransferReversal> {
+        pagination::list_iter(auth.clone(), &format!("transfers/{}/reversals", transfer_id), page)
+    }
+
+    /// Stream all reversals of a transfer, fetching further pages as needed
+    pub fn list_reversals_stream(auth: &Auth, transfer_id: &str, page: PageRequest) -> PageStream<TransferReversal> {
+        pagination::list_stream(auth.clone(), &format!("transfers/{}/reversals", transfer_id), page)
     }
 
     /// List all reversals for a transfer
     pub fn list_reversals(auth: &Auth, transfer_id: &str, limit: Option<u32>) -> Result<Vec<TransferReversal>> {
         let client = get_shared_blocking_client();
-        let mut url = format!("https://api.stripe.com/v1/transfers/{}/reversals", transfer_id);
+        let mut url = auth.url(&format!("transfers/{}/reversals", transfer_id));
         if let Some(limit) = limit {
             url = format!("{}?limit={}", url, limit);
         }
@@ -337,7 +322,7 @@ impl Transfer {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", auth.secret))
-            .send()?;
+            .send_limited("stripe", auth.transport())?;
         
         #[derive(Deserialize)]
         struct ReversalList {
@@ -350,32 +335,24 @@ impl Transfer {
 
     /// List all reversals for a transfer (async)
     pub async fn list_reversals_async(auth: &Auth, transfer_id: &str, limit: Option<u32>) -> Result<Vec<TransferReversal>> {
-        let rate_limiter = get_rate_limiter();
-        let transfer_id = transfer_id.to_string();
+        let client = get_shared_client();
+        let mut url = auth.url(&format!("transfers/{}/reversals", transfer_id));
+        if let Some(limit) = limit {
+            url = format!("{}?limit={}", url, limit);
+        }
+        
+        let response = client
+            .get(&url)
+            .header("Authorization", format!("Bearer {}", auth.secret))
+            .send_limited("stripe", auth.transport())
+            .await?;
         
-        rate_limiter.execute_with_retry_async("stripe", move || {
-            let transfer_id = transfer_id.clone();
-            async move {
-                let client = get_shared_client();
-                let mut url = format!("https://api.stripe.com/v1/transfers/{}/reversals", transfer_id);
-                if let Some(limit) = limit {
-                    url = format!("{}?limit={}", url, limit);
-                }
-                
-                let response = client
-                    .get(&url)
-                    .header("Authorization", format!("Bearer {}", auth.secret))
-                    .send()
-                    .await?;
-                
-                #[derive(Deserialize)]
-                struct ReversalList {
-                    data: Vec<TransferReversal>,
-                }
-                
-                let list: ReversalList = response.json().await?;
-                Ok(list.data)
-            }
-        }).await
+        #[derive(Deserialize)]
+        struct ReversalList {
+            data: Vec<TransferReversal>,
+        }
+        
+        let list: ReversalList = response.json().await?;
+        Ok(list.data)
     }
 }
\ No newline at end of file
@@ -0,0 +1,178 @@
+//! Client-side batching of metered usage.
+//!
+//! Reporting every API call or message as its own usage record costs a Stripe
+//! request each. [`UsageBatcher`] sums usage in memory per subscription item or
+//! per meter and customer, and reports each total with a single request when
+//! flushed, either by hand or on an interval from [`UsageBatcher::spawn`].
+//!
+//! A report that fails may still have reached Stripe, so it is retried as-is
+//! with its original idempotency key (and meter event identifier) rather than
+//! folded into newer usage under a fresh key.
+
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use crate::error::Result;
+use super::billing_meter::{CreateMeterEventParams, MeterEvent};
+use super::usage_record::{CreateUsageRecordParams, UsageRecord};
+use super::Auth;
+
+/// What batched usage is reported against
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum UsageTarget {
+    /// A subscription item on a metered price, reported as usage records
+    SubscriptionItem(String),
+    /// A billing meter, reported as meter events for one customer
+    Meter { event_name: String, customer_id: String },
+}
+
+/// A total sent to Stripe, kept with its keys so a retry is deduplicated
+#[derive(Debug, Clone)]
+struct Report {
+    target: UsageTarget,
+    quantity: u64,
+    idempotency_key: String,
+    /// Meter event identifier; `None` for subscription items
+    identifier: Option<String>,
+}
+
+impl Report {
+    fn new(target: UsageTarget, quantity: u64) -> Self {
+        let identifier = match target {
+            UsageTarget::Meter { .. } => Some(uuid::Uuid::new_v4().to_string()),
+            UsageTarget::SubscriptionItem(_) => None,
+        };
+        Self {
+            target,
+            quantity,
+            idempotency_key: uuid::Uuid::new_v4().to_string(),
+            identifier,
+        }
+    }
+}
+
+#[derive(Debug, Default)]
+struct Pending {
+    totals: HashMap<UsageTarget, u64>,
+    retries: Vec<Report>,
+}
+
+/// Sums usage per target and reports the totals to Stripe on flush
+pub struct UsageBatcher {
+    auth: Auth,
+    pending: Mutex<Pending>,
+}
+
+impl UsageBatcher {
+    /// A batcher reporting with `auth`. Each report gets its own idempotency
+    /// key, so any key set on `auth` is dropped.
+    pub fn new(mut auth: Auth) -> Self {
+        auth.idempotency_key = Default::default();
+        Self {
+            auth,
+            pending: Mutex::new(Pending::default()),
+        }
+    }
+
+    /// Add `quantity` to the pending total for `target`
+    pub fn record(&self, target: UsageTarget, quantity: u64) {
+        if quantity == 0 {
+            return;
+        }
+        *self.lock().totals.entry(target).or_insert(0) += quantity;
+    }
+
+    /// Add `quantity` to the pending usage of a subscription item
+    pub fn record_item(&self, subscription_item_id: &str, quantity: u64) {
+        self.record(UsageTarget::SubscriptionItem(subscription_item_id.to_string()), quantity);
+    }
+
+    /// Add `value` to the pending usage of a customer on the meter for `event_name`
+    pub fn record_meter(&self, event_name: &str, customer_id: &str, value: u64) {
+        let target = UsageTarget::Meter {
+            event_name: event_name.to_string(),
+            customer_id: customer_id.to_string(),
+        };
+        self.record(target, value);
+    }
+
+    /// The usage recorded but not reported yet, including failed reports
+    /// waiting to be retried
+    pub fn pending(&self) -> HashMap<UsageTarget, u64> {
+        let pending = self.lock();
+        let mut totals = pending.totals.clone();
+        for report in &pending.retries {
+            *totals.entry(report.target.clone()).or_insert(0) += report.quantity;
+        }
+        totals
+    }
+
+    /// Report every pending total, returning how many requests were sent.
+    ///
+    /// Reports that fail are retried unchanged on the next flush, separately
+    /// from usage recorded since, and the first error is returned once the
+    /// remaining reports have been tried.
+    pub async fn flush(&self) -> Result<usize> {
+        let batch: Vec<Report> = {
+            let mut pending = self.lock();
+            let totals = std::mem::take(&mut pending.totals);
+            let mut batch = std::mem::take(&mut pending.retries);
+            batch.extend(totals.into_iter().map(|(target, quantity)| Report::new(target, quantity)));
+            batch
+        };
+        let mut sent = 0;
+        let mut first_error = None;
+
+        for report in batch {
+            match self.report(&report).await {
+                Ok(()) => sent += 1,
+                Err(err) => {
+                    self.lock().retries.push(report);
+                    first_error.get_or_insert(err);
+                }
+            }
+        }
+
+        match first_error {
+            Some(err) => Err(err),
+            None => Ok(sent),
+        }
+    }
+
+    /// Flush every `every` on the current tokio runtime until the returned
+    /// handle is aborted. Failed reports stay pending and are retried on the
+    /// next tick; call [`UsageBatcher::flush`] once more on shutdown.
+    pub fn spawn(self: Arc<Self>, every: Duration) -> tokio::task::JoinHandle<()> {
+        tokio::spawn(async move {
+            let mut ticker = tokio::time::interval(every);
+            ticker.set_missed_tick_behavior(tokio::time::MissedTickBehavior::Delay);
+            // The first tick completes immediately; there is nothing to flush yet
+            ticker.tick().await;
+            loop {
+                ticker.tick().await;
+                if let Err(err) = self.flush().await {
+                    log::warn!("failed to report batched Stripe usage: {}", err);
+                }
+            }
+        })
+    }
+
+    async fn report(&self, report: &Report) -> Result<()> {
+        let auth = self.auth.clone().with_idempotency_key(report.idempotency_key.clone());
+        match &report.target {
+            UsageTarget::SubscriptionItem(item_id) => {
+                UsageRecord::create_async(&auth, item_id, CreateUsageRecordParams::increment(report.quantity)).await?;
+            }
+            UsageTarget::Meter { event_name, customer_id } => {
+                let mut params = CreateMeterEventParams::new(event_name, customer_id, report.quantity);
+                params.identifier = report.identifier.clone();
+                MeterEvent::create_async(&auth, params).await?;
+            }
+        }
+        Ok(())
+    }
+
+    fn lock(&self) -> std::sync::MutexGuard<'_, Pending> {
+        self.pending.lock().unwrap_or_else(|poisoned| poisoned.into_inner())
+    }
+}
@@ -0,0 +1,150 @@
+use serde::{Deserialize, Serialize};
+use crate::error::Result;
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::{form, pagination};
+use super::Auth;
+
+/// How a usage record's quantity combines with usage already reported
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "snake_case")]
+pub enum UsageAction {
+    /// Add to the usage at the timestamp (the default)
+    Increment,
+    /// Replace the usage at the timestamp
+    Set,
+}
+
+/// Usage of a metered price reported against a subscription item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecord {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "usage_record")
+    pub object: String,
+    pub livemode: bool,
+    /// The usage quantity for the timestamp
+    pub quantity: u64,
+    /// The subscription item this record is for
+    pub subscription_item: String,
+    /// The time the usage occurred (Unix timestamp)
+    pub timestamp: i64,
+}
+
+/// Parameters for reporting usage on a subscription item
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct CreateUsageRecordParams {
+    pub quantity: u64,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub action: Option<UsageAction>,
+    /// When the usage occurred (Unix timestamp), defaults to now
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timestamp: Option<i64>,
+}
+
+impl CreateUsageRecordParams {
+    /// Add `quantity` to the item's usage now
+    pub fn increment(quantity: u64) -> Self {
+        Self {
+            quantity,
+            action: Some(UsageAction::Increment),
+            timestamp: None,
+        }
+    }
+
+    /// Set the item's usage at `timestamp` to `quantity`
+    pub fn set(quantity: u64, timestamp: i64) -> Self {
+        Self {
+            quantity,
+            action: Some(UsageAction::Set),
+            timestamp: Some(timestamp),
+        }
+    }
+}
+
+/// Total usage of a subscription item over one billing period
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsageRecordSummary {
+    /// Unique identifier for the object
+    pub id: String,
+    /// String representing the object's type (always "usage_record_summary")
+    pub object: String,
+    /// The invoice the usage was billed on, if any
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub invoice: Option<String>,
+    pub livemode: bool,
+    pub period: UsagePeriod,
+    pub subscription_item: String,
+    pub total_usage: u64,
+}
+
+/// A billing period, open-ended while it is still running
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct UsagePeriod {
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start: Option<i64>,
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub end: Option<i64>,
+}
+
+impl UsageRecord {
+    /// Report usage for a subscription item on a metered price
+    ///
+    /// # Example
+    /// ```ignore
+    /// use payup::stripe::usage_record::{CreateUsageRecordParams, UsageRecord};
+    ///
+    /// UsageRecord::create(&auth, "si_123", CreateUsageRecordParams::increment(250))?;
+    /// ```
+    pub fn create(auth: &Auth, subscription_item_id: &str, params: CreateUsageRecordParams) -> Result<Self> {
+        let response = get_shared_blocking_client()
+            .post(auth.url(&format!("subscription_items/{}/usage_records", subscription_item_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Report usage for a subscription item on a metered price (async)
+    pub async fn create_async(
+        auth: &Auth,
+        subscription_item_id: &str,
+        params: CreateUsageRecordParams,
+    ) -> Result<Self> {
+        let response = get_shared_client()
+            .post(auth.url(&format!("subscription_items/{}/usage_records", subscription_item_id)))
+            .header(HEADER_IDEMPOTENCY_KEY, auth.next_idempotency_key())
+            .basic_auth(&auth.client, Some(&auth.secret))
+            .form(&form::to_form(&params))
+            .send_limited("stripe", auth.transport())
+            .await?;
+
+        Ok(response.json().await?)
+    }
+}
+
+impl UsageRecordSummary {
+    /// Iterate over a subscription item's usage per billing period, newest
+    /// first, fetching further pages as needed
+    pub fn list_iter(auth: &Auth, subscription_item_id: &str, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(
+            auth.clone(),
+            &format!("subscription_items/{}/usage_record_summaries", subscription_item_id),
+            page,
+        )
+    }
+
+    /// Stream a subscription item's usage per billing period, newest first,
+    /// fetching further pages as needed
+    pub fn list_stream(auth: &Auth, subscription_item_id: &str, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(
+            auth.clone(),
+            &format!("subscription_items/{}/usage_record_summaries", subscription_item_id),
+            page,
+        )
+    }
+}
@@ -1,8 +1,12 @@
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
+use serde::de::DeserializeOwned;
 use serde::{Deserialize, Serialize};
 use serde_json::Value;
 use crate::error::{PayupError, Result};
+use super::checkout::Session as CheckoutSession;
+use super::radar::{EarlyFraudWarning, Review};
+use super::{Charge, Customer, Dispute, Invoice, PaymentIntent, SetupIntent, Subscription};
 
 /// Stripe webhook event handler
 pub struct StripeWebhookHandler {
@@ -33,6 +37,90 @@ pub struct WebhookEventData {
     pub previous_attributes: Option<Value>,
 }
 
+impl WebhookEventData {
+    /// The object the event is about, typed by its `object` field
+    pub fn typed_object(&self) -> StripeEventObject {
+        StripeEventObject::from_value(self.object.clone())
+    }
+}
+
+/// The object carried by a webhook event.
+///
+/// The variant is chosen by the payload's `object` field. Objects of other
+/// types, and payloads that don't parse as their type, are kept as
+/// [`StripeEventObject::Unknown`] so no event is ever rejected for its body.
+/// Typed objects are boxed to keep the enum small.
+#[derive(Debug, Clone, Serialize)]
+#[serde(untagged)]
+pub enum StripeEventObject {
+    Charge(Box<Charge>),
+    CheckoutSession(Box<CheckoutSession>),
+    Customer(Box<Customer>),
+    Dispute(Box<Dispute>),
+    EarlyFraudWarning(Box<EarlyFraudWarning>),
+    Invoice(Box<Invoice>),
+    PaymentIntent(Box<PaymentIntent>),
+    Review(Box<Review>),
+    SetupIntent(Box<SetupIntent>),
+    Subscription(Box<Subscription>),
+    Unknown(Value),
+}
+
+impl StripeEventObject {
+    /// Type a raw event object by its `object` field
+    pub fn from_value(value: Value) -> Self {
+        fn typed<T: DeserializeOwned>(value: Value, variant: fn(Box<T>) -> StripeEventObject) -> StripeEventObject {
+            match T::deserialize(&value) {
+                Ok(object) => variant(Box::new(object)),
+                Err(_) => StripeEventObject::Unknown(value),
+            }
+        }
+
+        // `Charge` reads `amount` into its string field; keep the integer
+        // in `stripe_amount` as well
+        fn charge(value: Value) -> StripeEventObject {
+            match Charge::deserialize(&value) {
+                Ok(mut charge) => {
+                    charge.stripe_amount = value.get("amount").and_then(Value::as_i64);
+                    StripeEventObject::Charge(Box::new(charge))
+                }
+                Err(_) => StripeEventObject::Unknown(value),
+            }
+        }
+
+        match value.get("object").and_then(Value::as_str) {
+            Some("charge") => charge(value),
+            Some("checkout.session") => typed(value, Self::CheckoutSession),
+            Some("customer") => typed(value, Self::Customer),
+            Some("dispute") => typed(value, Self::Dispute),
+            Some("invoice") => typed(value, Self::Invoice),
+            Some("payment_intent") => typed(value, Self::PaymentIntent),
+            Some("radar.early_fraud_warning") => typed(value, Self::EarlyFraudWarning),
+            Some("review") => typed(value, Self::Review),
+            Some("setup_intent") => typed(value, Self::SetupIntent),
+            Some("subscription") => typed(value, Self::Subscription),
+            _ => Self::Unknown(value),
+        }
+    }
+
+    /// The object's ID, if it has one
+    pub fn id(&self) -> Option<String> {
+        match self {
+            Self::Charge(charge) => charge.id.clone(),
+            Self::CheckoutSession(session) => Some(session.id.clone()),
+            Self::Customer(customer) => customer.id.clone(),
+            Self::Dispute(dispute) => dispute.id.clone(),
+            Self::EarlyFraudWarning(warning) => Some(warning.id.clone()),
+            Self::Invoice(invoice) => Some(invoice.id.clone()).filter(|id| !id.is_empty()),
+            Self::PaymentIntent(payment_intent) => Some(payment_intent.id.clone()),
+            Self::Review(review) => Some(review.id.clone()),
+            Self::SetupIntent(setup_intent) => Some(setup_intent.id.clone()),
+            Self::Subscription(subscription) => subscription.id.clone(),
+            Self::Unknown(value) => value.get("id").and_then(Value::as_str).map(str::to_string),
+        }
+    }
+}
+
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct WebhookRequest {
     pub id: Option<String>,
@@ -197,12 +285,24 @@ pub enum WebhookEventType {
     PaymentIntentProcessing,
     PaymentIntentRequiresAction,
     
+    // Setup Intent Events
+    SetupIntentSucceeded,
+    SetupIntentFailed,
+    SetupIntentCanceled,
+    SetupIntentRequiresAction,
+    
     // Charge Events
     ChargeSucceeded,
     ChargeFailed,
     ChargeRefunded,
     ChargeDisputed,
     
+    // Checkout Events
+    CheckoutSessionCompleted,
+    CheckoutSessionExpired,
+    CheckoutSessionAsyncPaymentSucceeded,
+    CheckoutSessionAsyncPaymentFailed,
+    
     // Customer Events
     CustomerCreated,
     CustomerUpdated,
@@ -241,11 +341,21 @@ impl From<&str> for WebhookEventType {
             "payment_intent.processing" => Self::PaymentIntentProcessing,
             "payment_intent.requires_action" => Self::PaymentIntentRequiresAction,
             
+            "setup_intent.succeeded" => Self::SetupIntentSucceeded,
+            "setup_intent.setup_failed" => Self::SetupIntentFailed,
+            "setup_intent.canceled" => Self::SetupIntentCanceled,
+            "setup_intent.requires_action" => Self::SetupIntentRequiresAction,
+            
             "charge.succeeded" => Self::ChargeSucceeded,
             "charge.failed" => Self::ChargeFailed,
             "charge.refunded" => Self::ChargeRefunded,
             "charge.dispute.created" => Self::ChargeDisputed,
             
+            "checkout.session.completed" => Self::CheckoutSessionCompleted,
+            "checkout.session.expired" => Self::CheckoutSessionExpired,
+            "checkout.session.async_payment_succeeded" => Self::CheckoutSessionAsyncPaymentSucceeded,
+            "checkout.session.async_payment_failed" => Self::CheckoutSessionAsyncPaymentFailed,
+            
             "customer.created" => Self::CustomerCreated,
             "customer.updated" => Self::CustomerUpdated,
             "customer.deleted" => Self::CustomerDeleted,
@@ -318,6 +428,10 @@ mod tests {
             WebhookEventType::from("payment_intent.succeeded"),
             WebhookEventType::PaymentIntentSucceeded
         );
+        assert_eq!(
+            WebhookEventType::from("checkout.session.completed"),
+            WebhookEventType::CheckoutSessionCompleted
+        );
         assert_eq!(
             WebhookEventType::from("unknown.event"),
             WebhookEventType::Other("unknown.event".to_string())
@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use crate::stripe::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Payout {
@@ -104,7 +108,7 @@ impl Payout {
     /// payout.description = Some("Monthly payout".to_string());
     /// let created_payout = payout.post(auth)?;
     /// ```
-    pub fn post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
         let mut params = std::collections::HashMap::new();
         
@@ -128,32 +132,33 @@ impl Payout {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/payouts")
+            .post(creds.url("payouts"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Retrieve a payout
-    pub fn get(creds: Auth, payout_id: String) -> Result<Self, reqwest::Error> {
+    pub fn get(creds: Auth, payout_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/payouts/{}", payout_id);
+        let url = creds.url(&format!("payouts/{}", payout_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Update a payout
-    pub fn update(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         if let Some(id) = &self.id {
             let client = get_shared_blocking_client();
-            let url = format!("https://api.stripe.com/v1/payouts/{}", id);
+            let url = creds.url(&format!("payouts/{}", id));
             let mut params = std::collections::HashMap::new();
 
             if let Some(metadata) = &self.metadata {
@@ -164,49 +169,67 @@ impl Payout {
 
             let response = client
                 .post(&url)
+                .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
                 .header("Authorization", format!("Bearer {}", creds.client))
                 .form(&params)
-                .send()?;
+                .send_limited("stripe", creds.transport())?;
 
-            response.json()
+            Ok(response.json()?)
         } else {
-            // Return a mock error - in production, use proper error handling
-            let client = get_shared_blocking_client();
-            let response = client.get("https://invalid.url").send()?;
-            response.json()
+            Err(crate::error::PayupError::ValidationError("Payout ID is required for update".to_string()))
         }
     }
 
     /// Cancel a payout
-    pub fn cancel(creds: Auth, payout_id: String) -> Result<Self, reqwest::Error> {
+    pub fn cancel(creds: Auth, payout_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/payouts/{}/cancel", payout_id);
+        let url = creds.url(&format!("payouts/{}/cancel", payout_id));
 
         let response = client
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Reverse a payout
-    pub fn reverse(creds: Auth, payout_id: String) -> Result<Self, reqwest::Error> {
+    pub fn reverse(creds: Auth, payout_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/payouts/{}/reverse", payout_id);
+        let url = creds.url(&format!("payouts/{}/reverse", payout_id));
 
         let response = client
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Iterate over all payouts, optionally only those with `status`, fetching further pages as needed
+    pub fn list_iter(creds: Auth, status: Option<PayoutStatus>, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, &Self::list_path(status), page)
+    }
+
+    /// Stream all payouts, optionally only those with `status`, fetching further pages as needed
+    pub fn list_stream(creds: Auth, status: Option<PayoutStatus>, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, &Self::list_path(status), page)
+    }
 
-        response.json()
+    fn list_path(status: Option<PayoutStatus>) -> String {
+        // Serialize rather than Debug-format so `InTransit` becomes `in_transit`
+        match status.and_then(|s| serde_json::to_value(s).ok()) {
+            Some(serde_json::Value::String(s)) => format!("payouts?status={}", s),
+            _ => "payouts".to_string(),
+        }
     }
 
     /// List all payouts
-    pub fn list(creds: Auth, status: Option<PayoutStatus>, limit: Option<i32>) -> Result<PayoutList, reqwest::Error> {
+    pub fn list(creds: Auth, status: Option<PayoutStatus>, limit: Option<i32>) -> Result<PayoutList, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let mut url = String::from("https://api.stripe.com/v1/payouts?");
+        let mut url = creds.url("payouts?");
         
         if let Some(s) = status {
             url.push_str(&format!("status={}&", format!("{:?}", s).to_lowercase()));
@@ -219,13 +242,13 @@ impl Payout {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Async create a payout
-    pub async fn async_post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
         let mut params = std::collections::HashMap::new();
         
@@ -241,47 +264,49 @@ impl Payout {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/payouts")
+            .post(creds.url("payouts"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async retrieve a payout
-    pub async fn async_get(creds: Auth, payout_id: String) -> Result<Self, reqwest::Error> {
+    pub async fn async_get(creds: Auth, payout_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
-        let url = format!("https://api.stripe.com/v1/payouts/{}", payout_id);
+        let url = creds.url(&format!("payouts/{}", payout_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async cancel a payout
-    pub async fn async_cancel(creds: Auth, payout_id: String) -> Result<Self, reqwest::Error> {
+    pub async fn async_cancel(creds: Auth, payout_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
-        let url = format!("https://api.stripe.com/v1/payouts/{}/cancel", payout_id);
+        let url = creds.url(&format!("payouts/{}/cancel", payout_id));
 
         let response = client
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async list all payouts
-    pub async fn async_list(creds: Auth, status: Option<PayoutStatus>, limit: Option<i32>) -> Result<PayoutList, reqwest::Error> {
+    pub async fn async_list(creds: Auth, status: Option<PayoutStatus>, limit: Option<i32>) -> Result<PayoutList, crate::error::PayupError> {
         let client = get_shared_client();
-        let mut url = String::from("https://api.stripe.com/v1/payouts?");
+        let mut url = creds.url("payouts?");
         
         if let Some(s) = status {
             url.push_str(&format!("status={}&", format!("{:?}", s).to_lowercase()));
@@ -294,10 +319,10 @@ impl Payout {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 }
 
@@ -1,6 +1,12 @@
 use serde::{Deserialize, Serialize};
+use futures::TryStreamExt;
 use crate::stripe::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
+use crate::stripe::search::SearchQuery;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Product {
@@ -67,7 +73,7 @@ impl Product {
     /// product.description = Some("A high-quality widget".to_string());
     /// let created_product = product.post(auth)?;
     /// ```
-    pub fn post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
         let mut params = std::collections::HashMap::new();
         
@@ -86,32 +92,33 @@ impl Product {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/products")
+            .post(creds.url("products"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Retrieve a product
-    pub fn get(creds: Auth, product_id: String) -> Result<Self, reqwest::Error> {
+    pub fn get(creds: Auth, product_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/products/{}", product_id);
+        let url = creds.url(&format!("products/{}", product_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Update a product
-    pub fn update(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         if let Some(id) = &self.id {
             let client = get_shared_blocking_client();
-            let url = format!("https://api.stripe.com/v1/products/{}", id);
+            let url = creds.url(&format!("products/{}", id));
             let mut params = std::collections::HashMap::new();
 
             if let Some(desc) = &self.description {
@@ -126,23 +133,51 @@ impl Product {
 
             let response = client
                 .post(&url)
+                .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
                 .header("Authorization", format!("Bearer {}", creds.client))
                 .form(&params)
-                .send()?;
+                .send_limited("stripe", creds.transport())?;
 
-            response.json()
+            Ok(response.json()?)
         } else {
-            // Return a mock error - in production, use proper error handling
-            let client = get_shared_blocking_client();
-            let response = client.get("https://invalid.url").send()?;
-            response.json()
+            Err(crate::error::PayupError::ValidationError("Product ID is required for update".to_string()))
         }
     }
 
+    /// Iterate over all products, fetching further pages as needed
+    pub fn list_iter(creds: Auth, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, "products", page)
+    }
+
+    /// Stream all products, fetching further pages as needed
+    pub fn list_stream(creds: Auth, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, "products", page)
+    }
+
+    /// Iterate over products matching `query`, fetching further pages as needed
+    pub fn search_iter(creds: Auth, query: &SearchQuery, page: PageRequest) -> Paginator<Self> {
+        pagination::search_iter(creds, "products/search", query, page)
+    }
+
+    /// Stream products matching `query`, fetching further pages as needed
+    pub fn search_stream(creds: Auth, query: &SearchQuery, page: PageRequest) -> PageStream<Self> {
+        pagination::search_stream(creds, "products/search", query, page)
+    }
+
+    /// Search all products, e.g. `SearchClause::field("active").eq("true")`
+    pub fn search(creds: Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_iter(creds, query, PageRequest::new()).collect()
+    }
+
+    /// Async search all products
+    pub async fn async_search(creds: Auth, query: &SearchQuery) -> Result<Vec<Self>, crate::error::PayupError> {
+        Self::search_stream(creds, query, PageRequest::new()).try_collect().await
+    }
+
     /// List all products
-    pub fn list(creds: Auth, limit: Option<i32>) -> Result<ProductList, reqwest::Error> {
+    pub fn list(creds: Auth, limit: Option<i32>) -> Result<ProductList, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let mut url = String::from("https://api.stripe.com/v1/products");
+        let mut url = creds.url("products");
         
         if let Some(lim) = limit {
             url.push_str(&format!("?limit={}", lim));
@@ -151,26 +186,26 @@ impl Product {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Delete a product
-    pub fn delete(creds: Auth, product_id: String) -> Result<DeletedProduct, reqwest::Error> {
+    pub fn delete(creds: Auth, product_id: String) -> Result<DeletedProduct, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/products/{}", product_id);
+        let url = creds.url(&format!("products/{}", product_id));
 
         let response = client
             .delete(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Async create a new product
-    pub async fn async_post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
         let mut params = std::collections::HashMap::new();
         
@@ -185,33 +220,34 @@ impl Product {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/products")
+            .post(creds.url("products"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async retrieve a product
-    pub async fn async_get(creds: Auth, product_id: String) -> Result<Self, reqwest::Error> {
+    pub async fn async_get(creds: Auth, product_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
-        let url = format!("https://api.stripe.com/v1/products/{}", product_id);
+        let url = creds.url(&format!("products/{}", product_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async list all products
-    pub async fn async_list(creds: Auth, limit: Option<i32>) -> Result<ProductList, reqwest::Error> {
+    pub async fn async_list(creds: Auth, limit: Option<i32>) -> Result<ProductList, crate::error::PayupError> {
         let client = get_shared_client();
-        let mut url = String::from("https://api.stripe.com/v1/products");
+        let mut url = creds.url("products");
         
         if let Some(lim) = limit {
             url.push_str(&format!("?limit={}", lim));
@@ -220,10 +256,10 @@ impl Product {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 }
 
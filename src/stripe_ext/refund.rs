@@ -1,6 +1,10 @@
 use serde::{Deserialize, Serialize};
 use crate::stripe::Auth;
-use crate::http_client::{get_shared_client, get_shared_blocking_client};
+use crate::http_client::{get_shared_client, get_shared_blocking_client, SendVia, SendViaBlocking};
+use crate::config::http::HEADER_IDEMPOTENCY_KEY;
+use crate::builders::PageRequest;
+use crate::pagination::{PageStream, Paginator};
+use crate::stripe::pagination;
 
 #[derive(Serialize, Deserialize, Debug, Clone)]
 pub struct Refund {
@@ -79,7 +83,7 @@ impl Refund {
     /// refund.reason = Some(RefundReason::RequestedByCustomer);
     /// let created_refund = refund.post(auth)?;
     /// ```
-    pub fn post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
         let mut params = std::collections::HashMap::new();
         
@@ -104,32 +108,33 @@ impl Refund {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(creds.url("refunds"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Retrieve a refund
-    pub fn get(creds: Auth, refund_id: String) -> Result<Self, reqwest::Error> {
+    pub fn get(creds: Auth, refund_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/refunds/{}", refund_id);
+        let url = creds.url(&format!("refunds/{}", refund_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Update a refund
-    pub fn update(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub fn update(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         if let Some(id) = &self.id {
             let client = get_shared_blocking_client();
-            let url = format!("https://api.stripe.com/v1/refunds/{}", id);
+            let url = creds.url(&format!("refunds/{}", id));
             let mut params = std::collections::HashMap::new();
 
             if let Some(metadata) = &self.metadata {
@@ -140,36 +145,52 @@ impl Refund {
 
             let response = client
                 .post(&url)
+                .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
                 .header("Authorization", format!("Bearer {}", creds.client))
                 .form(&params)
-                .send()?;
+                .send_limited("stripe", creds.transport())?;
 
-            response.json()
+            Ok(response.json()?)
         } else {
-            // Return a mock error - in production, use proper error handling
-            let client = get_shared_blocking_client();
-            let response = client.get("https://invalid.url").send()?;
-            response.json()
+            Err(crate::error::PayupError::ValidationError("Refund ID is required for update".to_string()))
         }
     }
 
     /// Cancel a refund
-    pub fn cancel(creds: Auth, refund_id: String) -> Result<Self, reqwest::Error> {
+    pub fn cancel(creds: Auth, refund_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let url = format!("https://api.stripe.com/v1/refunds/{}/cancel", refund_id);
+        let url = creds.url(&format!("refunds/{}/cancel", refund_id));
 
         let response = client
             .post(&url)
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
+
+        Ok(response.json()?)
+    }
+
+    /// Iterate over all refunds, optionally only those of one charge, fetching further pages as needed
+    pub fn list_iter(creds: Auth, charge_id: Option<String>, page: PageRequest) -> Paginator<Self> {
+        pagination::list_iter(creds, &Self::list_path(charge_id), page)
+    }
+
+    /// Stream all refunds, optionally only those of one charge, fetching further pages as needed
+    pub fn list_stream(creds: Auth, charge_id: Option<String>, page: PageRequest) -> PageStream<Self> {
+        pagination::list_stream(creds, &Self::list_path(charge_id), page)
+    }
 
-        response.json()
+    fn list_path(charge_id: Option<String>) -> String {
+        match charge_id {
+            Some(charge) => format!("refunds?charge={}", charge),
+            None => "refunds".to_string(),
+        }
     }
 
     /// List all refunds
-    pub fn list(creds: Auth, charge_id: Option<String>, limit: Option<i32>) -> Result<RefundList, reqwest::Error> {
+    pub fn list(creds: Auth, charge_id: Option<String>, limit: Option<i32>) -> Result<RefundList, crate::error::PayupError> {
         let client = get_shared_blocking_client();
-        let mut url = String::from("https://api.stripe.com/v1/refunds?");
+        let mut url = creds.url("refunds?");
         
         if let Some(charge) = charge_id {
             url.push_str(&format!("charge={}&", charge));
@@ -182,13 +203,13 @@ impl Refund {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()?;
+            .send_limited("stripe", creds.transport())?;
 
-        response.json()
+        Ok(response.json()?)
     }
 
     /// Async create a refund
-    pub async fn async_post(&self, creds: Auth) -> Result<Self, reqwest::Error> {
+    pub async fn async_post(&self, creds: Auth) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
         let mut params = std::collections::HashMap::new();
         
@@ -209,33 +230,34 @@ impl Refund {
         }
 
         let response = client
-            .post("https://api.stripe.com/v1/refunds")
+            .post(creds.url("refunds"))
+            .header(HEADER_IDEMPOTENCY_KEY, creds.next_idempotency_key())
             .header("Authorization", format!("Bearer {}", creds.client))
             .form(&params)
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async retrieve a refund
-    pub async fn async_get(creds: Auth, refund_id: String) -> Result<Self, reqwest::Error> {
+    pub async fn async_get(creds: Auth, refund_id: String) -> Result<Self, crate::error::PayupError> {
         let client = get_shared_client();
-        let url = format!("https://api.stripe.com/v1/refunds/{}", refund_id);
+        let url = creds.url(&format!("refunds/{}", refund_id));
 
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 
     /// Async list all refunds
-    pub async fn async_list(creds: Auth, charge_id: Option<String>, limit: Option<i32>) -> Result<RefundList, reqwest::Error> {
+    pub async fn async_list(creds: Auth, charge_id: Option<String>, limit: Option<i32>) -> Result<RefundList, crate::error::PayupError> {
         let client = get_shared_client();
-        let mut url = String::from("https://api.stripe.com/v1/refunds?");
+        let mut url = creds.url("refunds?");
         
         if let Some(charge) = charge_id {
             url.push_str(&format!("charge={}&", charge));
@@ -248,10 +270,10 @@ impl Refund {
         let response = client
             .get(&url)
             .header("Authorization", format!("Bearer {}", creds.client))
-            .send()
+            .send_limited("stripe", creds.transport())
             .await?;
 
-        response.json().await
+        Ok(response.json().await?)
     }
 }
 
@@ -0,0 +1,442 @@
+#![cfg(feature = "braintree")]
+
+use base64::{Engine as _, engine::general_purpose};
+use payup::braintree::{
+    BraintreeClient, BraintreeConfig, BraintreeProvider, BraintreeWebhookHandler, Customer,
+    Environment, PaymentMethod, Subscription, SubscriptionStatus, Transaction, TransactionRequest,
+    TransactionStatus, TransactionType, WebhookKind,
+};
+use payup::braintree::payment_methods::PaymentMethodKind;
+use payup::braintree::transactions::TransactionSearch;
+use payup::error::PayupError;
+use payup::payment_provider::{
+    Charge, ChargeStatus, Customer as UnifiedCustomer, Money, PaymentProvider, Refund, RefundStatus, SubscriptionStatus as UnifiedSubscriptionStatus,
+};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::mpsc;
+use std::thread;
+
+const TRANSACTION_SALE: &str = include_str!("fixtures/braintree/transaction_sale.xml");
+const TRANSACTION_REFUND: &str = include_str!("fixtures/braintree/transaction_refund.xml");
+const TRANSACTION_SEARCH: &str = include_str!("fixtures/braintree/transaction_search.xml");
+const SEARCH_IDS: &str = include_str!("fixtures/braintree/search_ids.xml");
+const CUSTOMER: &str = include_str!("fixtures/braintree/customer.xml");
+const SUBSCRIPTION: &str = include_str!("fixtures/braintree/subscription.xml");
+const PROCESSOR_DECLINED: &str = include_str!("fixtures/braintree/processor_declined.xml");
+const NOTIFICATION: &str = include_str!("fixtures/braintree/notification_subscription_charged.xml");
+
+/// A request as seen by the fixture server.
+struct Recorded {
+    request_line: String,
+    headers: Vec<String>,
+    body: String,
+}
+
+/// Serves the given `(status, body)` fixtures in order, one per connection,
+/// and reports each request it received.
+fn serve(responses: Vec<(u16, &'static str)>) -> (String, mpsc::Receiver<Recorded>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        for (status, body) in responses {
+            let (mut stream, _) = listener.accept().unwrap();
+            let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+            let mut request_line = String::new();
+            reader.read_line(&mut request_line).unwrap();
+
+            let mut headers = Vec::new();
+            let mut content_length = 0;
+            loop {
+                let mut line = String::new();
+                reader.read_line(&mut line).unwrap();
+                let line = line.trim_end().to_string();
+                if line.is_empty() {
+                    break;
+                }
+                if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                    content_length = len.trim().parse().unwrap();
+                }
+                headers.push(line);
+            }
+            let mut request_body = vec![0; content_length];
+            reader.read_exact(&mut request_body).unwrap();
+
+            let response = format!(
+                "HTTP/1.1 {} Fixture\r\nContent-Type: application/xml\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+                status,
+                body.len(),
+                body
+            );
+            stream.write_all(response.as_bytes()).unwrap();
+            tx.send(Recorded {
+                request_line: request_line.trim_end().to_string(),
+                headers,
+                body: String::from_utf8(request_body).unwrap(),
+            }).unwrap();
+        }
+    });
+
+    (format!("http://{}", addr), rx)
+}
+
+fn config(base_url: &str) -> BraintreeConfig {
+    BraintreeConfig::new(
+        "merchant_1".to_string(),
+        "public_key".to_string(),
+        "private_key".to_string(),
+        Environment::Sandbox,
+    ).with_base_url(base_url)
+}
+
+fn header<'a>(headers: &'a [String], name: &str) -> Option<&'a str> {
+    let prefix = format!("{}:", name.to_ascii_lowercase());
+    headers.iter().find_map(|h| {
+        h.to_ascii_lowercase()
+            .starts_with(&prefix)
+            .then(|| h[prefix.len()..].trim())
+    })
+}
+
+#[test]
+fn test_sale_posts_xml_and_parses_transaction() {
+    let (base_url, requests) = serve(vec![(201, TRANSACTION_SALE)]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let request = TransactionRequest::sale("10.00".to_string(), "fake-valid-nonce");
+    let transaction = Transaction::sale(&client, &request).unwrap();
+
+    assert_eq!(transaction.id, "7c4wx9ne");
+    assert_eq!(transaction.status, TransactionStatus::SubmittedForSettlement);
+    assert_eq!(transaction.transaction_type, TransactionType::Sale);
+    assert_eq!(transaction.amount, "10.00");
+    assert_eq!(transaction.customer.unwrap().id.as_deref(), Some("cust_42"));
+    assert_eq!(transaction.credit_card.unwrap().last_4.as_deref(), Some("1111"));
+    assert!(transaction.refund_ids.is_empty());
+    assert!(transaction.custom_fields.is_none());
+
+    let recorded = requests.recv().unwrap();
+    assert_eq!(recorded.request_line, "POST /merchants/merchant_1/transactions HTTP/1.1");
+    assert_eq!(header(&recorded.headers, "X-ApiVersion"), Some("6"));
+    assert_eq!(header(&recorded.headers, "Content-Type"), Some("application/xml"));
+    assert!(header(&recorded.headers, "Authorization").unwrap().starts_with("Basic "));
+    assert!(recorded.body.starts_with("<?xml version=\"1.0\" encoding=\"UTF-8\"?><transaction>"));
+    assert!(recorded.body.contains("<type>sale</type>"));
+    assert!(recorded.body.contains("<amount>10.00</amount>"));
+    assert!(recorded.body.contains("<payment-method-nonce>fake-valid-nonce</payment-method-nonce>"));
+}
+
+#[test]
+fn test_processor_decline_surfaces_response_code() {
+    let (base_url, _requests) = serve(vec![(422, PROCESSOR_DECLINED)]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let request = TransactionRequest::sale("2001.00".to_string(), "fake-valid-nonce");
+    match Transaction::sale(&client, &request) {
+        Err(PayupError::ApiError { code, message, provider }) => {
+            assert_eq!(code, "2001");
+            assert_eq!(message, "Insufficient Funds");
+            assert_eq!(provider, "Braintree");
+        }
+        other => panic!("expected ApiError, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_not_found_and_auth_failures() {
+    let (base_url, _requests) = serve(vec![(404, ""), (401, "")]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    assert!(matches!(
+        Transaction::find(&client, "missing"),
+        Err(PayupError::ApiError { .. })
+    ));
+    assert!(matches!(
+        Transaction::find(&client, "missing"),
+        Err(PayupError::AuthenticationError(_))
+    ));
+}
+
+#[test]
+fn test_reads_are_retried_after_server_errors() {
+    let (base_url, requests) = serve(vec![(503, ""), (201, TRANSACTION_SALE)]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let transaction = Transaction::find(&client, "7c4wx9ne").unwrap();
+
+    assert_eq!(transaction.id, "7c4wx9ne");
+    for _ in 0..2 {
+        let recorded = requests.recv().unwrap();
+        assert_eq!(recorded.request_line, "GET /merchants/merchant_1/transactions/7c4wx9ne HTTP/1.1");
+    }
+}
+
+#[test]
+fn test_writes_are_not_retried_after_server_errors() {
+    let (base_url, requests) = serve(vec![(503, "")]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let request = TransactionRequest::sale("10.00".to_string(), "fake-valid-nonce");
+    assert!(matches!(
+        Transaction::sale(&client, &request),
+        Err(PayupError::ServerError(503))
+    ));
+    assert_eq!(requests.recv().unwrap().request_line, "POST /merchants/merchant_1/transactions HTTP/1.1");
+}
+
+#[test]
+fn test_search_fetches_ids_then_records() {
+    let (base_url, requests) = serve(vec![(200, SEARCH_IDS), (200, TRANSACTION_SEARCH)]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let criteria = TransactionSearch {
+        customer_id: Some(payup::braintree::SearchText::is("cust_42")),
+        transaction_type: Some(vec![TransactionType::Sale]),
+        ..Default::default()
+    };
+    let transactions = Transaction::search(&client, &criteria, None).unwrap();
+
+    assert_eq!(transactions.len(), 2);
+    assert_eq!(transactions[1].status, TransactionStatus::ProcessorDeclined);
+
+    let ids_request = requests.recv().unwrap();
+    assert_eq!(ids_request.request_line, "POST /merchants/merchant_1/transactions/advanced_search_ids HTTP/1.1");
+    assert!(ids_request.body.contains("<customer-id><is>cust_42</is></customer-id>"));
+    assert!(ids_request.body.contains("<type type=\"array\"><item>sale</item></type>"));
+
+    let records_request = requests.recv().unwrap();
+    assert_eq!(records_request.request_line, "POST /merchants/merchant_1/transactions/advanced_search HTTP/1.1");
+    assert!(records_request.body.contains("<ids type=\"array\"><item>7c4wx9ne</item><item>9hp3qw1m</item></ids>"));
+}
+
+#[test]
+fn test_customer_fixture_exposes_payment_methods() {
+    let customer: Customer = payup::braintree::xml::from_xml(CUSTOMER, "customer").unwrap();
+
+    assert_eq!(customer.custom_fields.as_ref().unwrap()["loyalty_tier"], "gold");
+
+    let methods = customer.payment_methods();
+    assert_eq!(methods.len(), 2);
+    assert_eq!(methods[0].kind, PaymentMethodKind::CreditCard);
+    assert_eq!(methods[1].kind, PaymentMethodKind::PayPalAccount);
+    assert_eq!(methods[1].email.as_deref(), Some("ada@example.com"));
+
+    let default = customer.default_payment_method().unwrap();
+    assert_eq!(default.token, "8kzzq7");
+    assert_eq!(default.subscriptions[0].id, "sub_monthly");
+}
+
+#[test]
+fn test_payment_method_kind_follows_root() {
+    let (base_url, requests) = serve(vec![(200, r#"<paypal-account><token>pp_ada</token><email>ada@example.com</email></paypal-account>"#)]);
+    let client = BraintreeClient::new(config(&base_url)).unwrap();
+
+    let method = PaymentMethod::find(&client, "pp_ada").unwrap();
+    assert_eq!(method.kind, PaymentMethodKind::PayPalAccount);
+    assert_eq!(requests.recv().unwrap().request_line, "GET /merchants/merchant_1/payment_methods/any/pp_ada HTTP/1.1");
+}
+
+#[test]
+fn test_subscription_fixture() {
+    let subscription: Subscription = payup::braintree::xml::from_xml(SUBSCRIPTION, "subscription").unwrap();
+
+    assert_eq!(subscription.status, SubscriptionStatus::PastDue);
+    assert_eq!(subscription.current_billing_cycle, Some(2));
+    assert_eq!(subscription.never_expires, Some(true));
+    assert!(subscription.number_of_billing_cycles.is_none());
+    assert_eq!(subscription.transactions[0].processor_response_code.as_deref(), Some("2001"));
+}
+
+#[tokio::test]
+async fn test_provider_maps_charge_and_refund() {
+    let (base_url, requests) = serve(vec![(201, TRANSACTION_SALE), (201, TRANSACTION_REFUND)]);
+    let provider = BraintreeProvider::with_config(config(&base_url)).unwrap();
+
+    let charge = provider.create_charge(&Charge {
+        id: None,
+        amount: Money { amount: 1000, currency: "usd".to_string() },
+        customer_id: Some("cust_42".to_string()),
+        payment_method_id: Some("8kzzq7".to_string()),
+        status: ChargeStatus::Pending,
+        description: None,
+        metadata: None,
+        created_at: None,
+    }).await.unwrap();
+
+    assert_eq!(charge.id.as_deref(), Some("7c4wx9ne"));
+    assert_eq!(charge.amount.amount, 1000);
+    assert!(matches!(charge.status, ChargeStatus::Processing));
+    assert_eq!(charge.created_at, Some(1705312800));
+    assert_eq!(charge.metadata.unwrap()["order_id"], "order-1001");
+
+    let sale = requests.recv().unwrap();
+    assert!(sale.body.contains("<payment-method-token>8kzzq7</payment-method-token>"));
+    assert!(sale.body.contains("<submit-for-settlement type=\"boolean\">true</submit-for-settlement>"));
+
+    let refund = provider.create_refund(&Refund {
+        id: None,
+        charge_id: "7c4wx9ne".to_string(),
+        amount: Some(Money { amount: 250, currency: "usd".to_string() }),
+        reason: None,
+        status: RefundStatus::Pending,
+        metadata: None,
+    }).await.unwrap();
+
+    assert_eq!(refund.id.as_deref(), Some("p2bhr5kz"));
+    assert_eq!(refund.charge_id, "7c4wx9ne");
+    assert_eq!(refund.amount.unwrap().amount, 250);
+
+    let refund_request = requests.recv().unwrap();
+    assert_eq!(refund_request.request_line, "POST /merchants/merchant_1/transactions/7c4wx9ne/refund HTTP/1.1");
+    assert!(refund_request.body.contains("<amount>2.50</amount>"));
+}
+
+#[tokio::test]
+async fn test_provider_lists_customer_subscriptions_from_vault() {
+    let (base_url, _requests) = serve(vec![(200, CUSTOMER)]);
+    let provider = BraintreeProvider::with_config(config(&base_url)).unwrap();
+
+    let subscriptions = provider.list_subscriptions(Some("cust_42"), None).await.unwrap();
+
+    assert_eq!(subscriptions.len(), 1);
+    assert_eq!(subscriptions[0].customer_id, "cust_42");
+    assert_eq!(subscriptions[0].plan_id.as_deref(), Some("gold-monthly"));
+    assert!(matches!(subscriptions[0].status, UnifiedSubscriptionStatus::Active));
+    assert_eq!(subscriptions[0].current_period_start, Some(1705276800));
+}
+
+#[tokio::test]
+async fn test_provider_rejects_foreign_currency() {
+    let provider = BraintreeProvider::with_config(config("http://127.0.0.1:9")).unwrap();
+
+    let result = provider.create_charge(&Charge {
+        id: None,
+        amount: Money { amount: 1000, currency: "eur".to_string() },
+        customer_id: None,
+        payment_method_id: Some("fake-valid-nonce".to_string()),
+        status: ChargeStatus::Pending,
+        description: None,
+        metadata: None,
+        created_at: None,
+    }).await;
+
+    assert!(matches!(result, Err(PayupError::ValidationError(_))));
+}
+
+fn charge(payment_method_id: &str) -> Charge {
+    Charge {
+        id: None,
+        amount: Money { amount: 1000, currency: "usd".to_string() },
+        customer_id: Some("cust_42".to_string()),
+        payment_method_id: Some(payment_method_id.to_string()),
+        status: ChargeStatus::Pending,
+        description: None,
+        metadata: None,
+        created_at: None,
+    }
+}
+
+#[tokio::test]
+async fn test_idempotent_charge_is_tagged_with_the_key() {
+    let empty_search = r#"<search-results><page-size type="integer">50</page-size><ids type="array"/></search-results>"#;
+    let (base_url, requests) = serve(vec![(200, empty_search), (201, TRANSACTION_SALE)]);
+    let provider = BraintreeProvider::with_config(config(&base_url)).unwrap();
+
+    let charge = provider.create_charge_idempotent(&charge("8kzzq7"), "order-key-1").await.unwrap();
+    assert_eq!(charge.id.as_deref(), Some("7c4wx9ne"));
+
+    let search = requests.recv().unwrap();
+    assert_eq!(search.request_line, "POST /merchants/merchant_1/transactions/advanced_search_ids HTTP/1.1");
+    assert!(search.body.contains("<order-id><is>order-key-1</is></order-id>"));
+
+    let sale = requests.recv().unwrap();
+    assert_eq!(sale.request_line, "POST /merchants/merchant_1/transactions HTTP/1.1");
+    assert!(sale.body.contains("<order-id>order-key-1</order-id>"));
+}
+
+#[tokio::test]
+async fn test_idempotent_charge_retry_returns_the_first_sale() {
+    let (base_url, requests) = serve(vec![(200, SEARCH_IDS), (200, TRANSACTION_SEARCH)]);
+    let provider = BraintreeProvider::with_config(config(&base_url)).unwrap();
+
+    let charge = provider.create_charge_idempotent(&charge("8kzzq7"), "order-key-1").await.unwrap();
+    assert_eq!(charge.id.as_deref(), Some("7c4wx9ne"));
+
+    requests.recv().unwrap();
+    let records = requests.recv().unwrap();
+    assert_eq!(records.request_line, "POST /merchants/merchant_1/transactions/advanced_search HTTP/1.1");
+    assert!(records.body.contains("<ids type=\"array\"><item>7c4wx9ne</item></ids>"));
+    assert!(requests.try_recv().is_err(), "no second sale should be sent");
+}
+
+#[tokio::test]
+async fn test_idempotent_customer_uses_the_key_as_id() {
+    let (base_url, requests) = serve(vec![(404, ""), (201, CUSTOMER), (200, CUSTOMER)]);
+    let provider = BraintreeProvider::with_config(config(&base_url)).unwrap();
+    let customer = UnifiedCustomer {
+        id: None,
+        email: Some("ada@example.com".to_string()),
+        name: None,
+        phone: None,
+        metadata: None,
+    };
+
+    provider.create_customer_idempotent(&customer, "signup-42").await.unwrap();
+    let lookup = requests.recv().unwrap();
+    assert_eq!(lookup.request_line, "GET /merchants/merchant_1/customers/signup-42 HTTP/1.1");
+    let create = requests.recv().unwrap();
+    assert_eq!(create.request_line, "POST /merchants/merchant_1/customers HTTP/1.1");
+    assert!(create.body.contains("<id>signup-42</id>"));
+
+    // The retry finds the customer and creates nothing
+    provider.create_customer_idempotent(&customer, "signup-42").await.unwrap();
+    assert_eq!(requests.recv().unwrap().request_line, "GET /merchants/merchant_1/customers/signup-42 HTTP/1.1");
+    assert!(requests.try_recv().is_err());
+
+    let invalid = provider.create_customer_idempotent(&customer, "not a valid id").await;
+    assert!(matches!(invalid, Err(PayupError::ValidationError(_))));
+}
+
+#[test]
+fn test_webhook_notification_round_trip() {
+    let handler = BraintreeWebhookHandler::new("public_key".to_string(), "private_key".to_string());
+    let bt_payload = general_purpose::STANDARD.encode(NOTIFICATION);
+    let bt_signature = format!("other_key|deadbeef&public_key|{}", handler.sign(&bt_payload));
+
+    let notification = handler.parse(&bt_signature, &bt_payload).unwrap();
+
+    assert_eq!(notification.kind_enum(), WebhookKind::SubscriptionChargedSuccessfully);
+    assert_eq!(notification.timestamp, "2024-02-15T10:00:05Z");
+    let subscription = notification.subscription().unwrap();
+    assert_eq!(subscription.id, "sub_monthly");
+    assert_eq!(subscription.transactions[0].id, "3rjbaxnm");
+    assert!(notification.transaction().is_err());
+}
+
+#[test]
+fn test_webhook_rejects_tampered_payload() {
+    let handler = BraintreeWebhookHandler::new("public_key".to_string(), "private_key".to_string());
+    let bt_payload = general_purpose::STANDARD.encode(NOTIFICATION);
+    let bt_signature = format!("public_key|{}", handler.sign(&bt_payload));
+
+    let tampered = general_purpose::STANDARD.encode(NOTIFICATION.replace("19.99", "0.01"));
+    assert!(matches!(
+        handler.parse(&bt_signature, &tampered),
+        Err(PayupError::WebhookVerificationFailed(_))
+    ));
+    assert!(handler.parse(&bt_signature, "not base64!").is_err());
+}
+
+#[tokio::test]
+async fn test_provider_verify_webhook() {
+    let provider = BraintreeProvider::with_config(config("http://127.0.0.1:9")).unwrap();
+    let handler = BraintreeWebhookHandler::new("public_key".to_string(), "private_key".to_string());
+    let bt_payload = general_purpose::STANDARD.encode(NOTIFICATION);
+    let bt_signature = format!("public_key|{}", handler.sign(&bt_payload));
+
+    assert!(provider.verify_webhook(bt_payload.as_bytes(), &bt_signature, "").await.unwrap());
+    assert!(!provider.verify_webhook(bt_payload.as_bytes(), &bt_signature, "wrong_private_key").await.unwrap());
+}
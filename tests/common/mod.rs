@@ -0,0 +1,272 @@
+//! Test doubles shared by the integration tests.
+//!
+//! Each test binary pulls this in with `mod common;` and uses whichever
+//! helpers it needs, so not every item is used by every binary.
+#![allow(dead_code)]
+
+use async_trait::async_trait;
+use payup::http_client::Transport;
+use std::collections::{HashMap, VecDeque};
+use std::io::{BufRead, BufReader, Read, Write};
+use std::net::TcpListener;
+use std::sync::atomic::{AtomicUsize, Ordering};
+use std::sync::{mpsc, Arc, Mutex};
+use std::thread;
+
+/// A request seen by [`FakeTransport`], with its query string and form body decoded.
+#[derive(Debug, Clone)]
+pub struct Recorded {
+    /// `METHOD path`
+    pub target: String,
+    /// `path?query` as sent
+    pub url: String,
+    pub query: Vec<(String, String)>,
+    pub form: Vec<(String, String)>,
+    pub headers: reqwest::header::HeaderMap,
+}
+
+impl Recorded {
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers.get(name).map(|value| value.to_str().unwrap())
+    }
+}
+
+/// Answers every request in memory from canned JSON bodies, recording each
+/// request it sees. A body is looked up by `METHOD path?query`, then
+/// `METHOD path`, then `path?query`, then `path`; anything else gets a 404.
+#[derive(Debug, Default)]
+pub struct FakeTransport {
+    bodies: HashMap<&'static str, &'static str>,
+    requests: Mutex<Vec<Recorded>>,
+    failures: AtomicUsize,
+}
+
+impl FakeTransport {
+    pub fn new(bodies: &[(&'static str, &'static str)]) -> Arc<Self> {
+        Arc::new(Self {
+            bodies: bodies.iter().copied().collect(),
+            requests: Mutex::new(Vec::new()),
+            failures: AtomicUsize::new(0),
+        })
+    }
+
+    /// Fails the next `count` requests with a connection error after recording them.
+    pub fn fail_next(&self, count: usize) {
+        self.failures.store(count, Ordering::SeqCst);
+    }
+
+    fn fail(&self) -> Option<reqwest::Error> {
+        self.failures
+            .fetch_update(Ordering::SeqCst, Ordering::SeqCst, |left| left.checked_sub(1))
+            .ok()
+            .map(|_| reqwest::Client::new().get("http://[::1").build().unwrap_err())
+    }
+
+    fn respond(
+        &self,
+        method: &reqwest::Method,
+        url: &reqwest::Url,
+        headers: &reqwest::header::HeaderMap,
+        body: &[u8],
+    ) -> http::Response<&'static str> {
+        let target = format!("{} {}", method, url.path());
+        let path_and_query = match url.query() {
+            Some(query) => format!("{}?{}", url.path(), query),
+            None => url.path().to_string(),
+        };
+        self.requests.lock().unwrap().push(Recorded {
+            target: target.clone(),
+            url: path_and_query.clone(),
+            query: url.query_pairs().into_owned().collect(),
+            form: url::form_urlencoded::parse(body).into_owned().collect(),
+            headers: headers.clone(),
+        });
+
+        let keys = [
+            format!("{} {}", method, path_and_query),
+            target,
+            path_and_query,
+            url.path().to_string(),
+        ];
+        match keys.iter().find_map(|key| self.bodies.get(key.as_str())) {
+            Some(body) => http::Response::builder()
+                .status(200)
+                .header("content-type", "application/json")
+                .body(*body)
+                .unwrap(),
+            None => http::Response::builder().status(404).body("{}").unwrap(),
+        }
+    }
+
+    pub fn requests(&self) -> Vec<Recorded> {
+        self.requests.lock().unwrap().clone()
+    }
+
+    /// `METHOD path` of each request, in order.
+    pub fn targets(&self) -> Vec<String> {
+        self.requests().into_iter().map(|request| request.target).collect()
+    }
+
+    /// `path?query` of each request, in order.
+    pub fn urls(&self) -> Vec<String> {
+        self.requests().into_iter().map(|request| request.url).collect()
+    }
+
+    pub fn last_request(&self) -> Recorded {
+        self.requests().pop().expect("no request was sent")
+    }
+}
+
+#[async_trait]
+impl Transport for FakeTransport {
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, reqwest::Error> {
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+        let response = self.respond(request.method(), request.url(), request.headers(), body);
+        match self.fail() {
+            Some(err) => Err(err),
+            None => Ok(response.into()),
+        }
+    }
+
+    fn execute_blocking(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        let body = request.body().and_then(|b| b.as_bytes()).unwrap_or_default();
+        let response = self.respond(request.method(), request.url(), request.headers(), body);
+        match self.fail() {
+            Some(err) => Err(err),
+            None => Ok(response.into()),
+        }
+    }
+}
+
+/// Stripe credentials that send every request through `transport`.
+#[cfg(feature = "stripe")]
+pub fn auth(transport: Arc<FakeTransport>) -> payup::stripe::Auth {
+    payup::stripe::Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport)
+}
+
+/// Whether a decoded query string or form body contains `key=value`.
+pub fn has(pairs: &[(String, String)], key: &str, value: &str) -> bool {
+    pairs.iter().any(|(k, v)| k == key && v == value)
+}
+
+/// Plays back canned `(status, Retry-After, body)` responses in order,
+/// recording `METHOD path` for each request it sees.
+#[derive(Debug, Default)]
+pub struct ScriptedTransport {
+    responses: Mutex<VecDeque<(u16, Option<&'static str>, &'static str)>>,
+    requests: Mutex<Vec<String>>,
+}
+
+impl ScriptedTransport {
+    pub fn new(responses: &[(u16, Option<&'static str>, &'static str)]) -> Arc<Self> {
+        Arc::new(Self {
+            responses: Mutex::new(responses.iter().copied().collect()),
+            requests: Mutex::new(Vec::new()),
+        })
+    }
+
+    fn respond(&self, method: &reqwest::Method, url: &reqwest::Url) -> http::Response<&'static str> {
+        self.requests.lock().unwrap().push(format!("{} {}", method, url.path()));
+        let (status, retry_after, body) = self
+            .responses
+            .lock()
+            .unwrap()
+            .pop_front()
+            .expect("no more scripted responses");
+
+        let mut response = http::Response::builder()
+            .status(status)
+            .header("content-type", "application/json");
+        if let Some(retry_after) = retry_after {
+            response = response.header("retry-after", retry_after);
+        }
+        response.body(body).unwrap()
+    }
+
+    pub fn requests(&self) -> Vec<String> {
+        self.requests.lock().unwrap().clone()
+    }
+}
+
+#[async_trait]
+impl Transport for ScriptedTransport {
+    async fn execute(&self, request: reqwest::Request) -> Result<reqwest::Response, reqwest::Error> {
+        Ok(self.respond(request.method(), request.url()).into())
+    }
+
+    fn execute_blocking(
+        &self,
+        request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        Ok(self.respond(request.method(), request.url()).into())
+    }
+}
+
+/// A request captured by [`serve_once`]: request line and headers, then the body.
+#[derive(Debug, Clone)]
+pub struct Captured {
+    pub headers: Vec<String>,
+    pub body: String,
+}
+
+impl Captured {
+    /// The request line, e.g. `GET /v1/balance HTTP/1.1`.
+    pub fn request_line(&self) -> &str {
+        &self.headers[0]
+    }
+
+    /// The value of the first header called `name`, compared case-insensitively.
+    pub fn header(&self, name: &str) -> Option<&str> {
+        self.headers[1..].iter().find_map(|line| {
+            let (key, value) = line.split_once(':')?;
+            key.eq_ignore_ascii_case(name).then_some(value.trim())
+        })
+    }
+}
+
+/// Serves a single canned JSON response on a local port and reports the
+/// request it received. Returns a Stripe-style base URL ending in `/v1`.
+pub fn serve_once(body: &'static str) -> (String, mpsc::Receiver<Captured>) {
+    let listener = TcpListener::bind("127.0.0.1:0").unwrap();
+    let addr = listener.local_addr().unwrap();
+    let (tx, rx) = mpsc::channel();
+
+    thread::spawn(move || {
+        let (mut stream, _) = listener.accept().unwrap();
+        let mut reader = BufReader::new(stream.try_clone().unwrap());
+
+        let mut headers = Vec::new();
+        let mut content_length = 0;
+        loop {
+            let mut line = String::new();
+            reader.read_line(&mut line).unwrap();
+            let line = line.trim_end().to_string();
+            if line.is_empty() {
+                break;
+            }
+            if let Some(len) = line.to_ascii_lowercase().strip_prefix("content-length:") {
+                content_length = len.trim().parse().unwrap();
+            }
+            headers.push(line);
+        }
+        let mut request_body = vec![0; content_length];
+        reader.read_exact(&mut request_body).unwrap();
+
+        let response = format!(
+            "HTTP/1.1 200 OK\r\nContent-Type: application/json\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+            body.len(),
+            body
+        );
+        stream.write_all(response.as_bytes()).unwrap();
+        tx.send(Captured {
+            headers,
+            body: String::from_utf8_lossy(&request_body).into_owned(),
+        })
+        .unwrap();
+    });
+
+    (format!("http://{}/v1", addr), rx)
+}
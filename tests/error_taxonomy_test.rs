@@ -0,0 +1,133 @@
+#![cfg(feature = "stripe")]
+
+use async_trait::async_trait;
+use payup::error::{ErrorCategory, PayupError};
+use payup::http_client::Transport;
+use std::sync::{Arc, Mutex};
+
+/// Answers every request with the same canned error response, counting requests.
+#[derive(Debug)]
+struct FailingTransport {
+    status: u16,
+    headers: Vec<(&'static str, &'static str)>,
+    body: &'static str,
+    requests: Mutex<usize>,
+}
+
+impl FailingTransport {
+    fn new(status: u16, headers: &[(&'static str, &'static str)], body: &'static str) -> Arc<Self> {
+        Arc::new(Self {
+            status,
+            headers: headers.to_vec(),
+            body,
+            requests: Mutex::new(0),
+        })
+    }
+
+    fn respond(&self) -> http::Response<&'static str> {
+        *self.requests.lock().unwrap() += 1;
+        let mut response = http::Response::builder()
+            .status(self.status)
+            .header("content-type", "application/json");
+        for (name, value) in &self.headers {
+            response = response.header(*name, *value);
+        }
+        response.body(self.body).unwrap()
+    }
+
+    fn requests(&self) -> usize {
+        *self.requests.lock().unwrap()
+    }
+}
+
+#[async_trait]
+impl Transport for FailingTransport {
+    async fn execute(&self, _request: reqwest::Request) -> Result<reqwest::Response, reqwest::Error> {
+        Ok(self.respond().into())
+    }
+
+    fn execute_blocking(
+        &self,
+        _request: reqwest::blocking::Request,
+    ) -> Result<reqwest::blocking::Response, reqwest::Error> {
+        Ok(self.respond().into())
+    }
+}
+
+#[tokio::test]
+async fn test_stripe_card_decline_is_categorised() {
+    use payup::stripe::{Auth, Dispute};
+
+    let transport = FailingTransport::new(
+        402,
+        &[("request-id", "req_abc123")],
+        r#"{"error":{"type":"card_error","code":"card_declined","decline_code":"stolen_card",
+            "message":"Your card was declined.","param":"payment_method"}}"#,
+    );
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport.clone());
+
+    let error = Dispute::async_get(auth, "dp_123".to_string()).await.unwrap_err();
+    assert!(matches!(&error, PayupError::StripeError(details) if details.status == 402), "{:?}", error);
+    assert_eq!(error.category(), ErrorCategory::CardDeclined);
+    assert_eq!(error.category().as_str(), "card_declined");
+    assert_eq!(error.decline_code(), Some("stolen_card"));
+    assert_eq!(error.request_id(), Some("req_abc123"));
+    assert!(!error.is_retryable());
+    assert_eq!(transport.requests(), 1);
+}
+
+#[test]
+fn test_stripe_invalid_request_is_not_retried() {
+    use payup::stripe::{Auth, Dispute};
+
+    let transport = FailingTransport::new(
+        404,
+        &[("request-id", "req_missing")],
+        r#"{"error":{"type":"invalid_request_error","code":"resource_missing","message":"No such dispute: 'dp_404'","param":"id"}}"#,
+    );
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport.clone());
+
+    let error = Dispute::get(auth, "dp_404".to_string()).unwrap_err();
+    assert_eq!(error.category(), ErrorCategory::InvalidRequest);
+    assert_eq!(error.decline_code(), None);
+    assert_eq!(error.request_id(), Some("req_missing"));
+    assert_eq!(transport.requests(), 1);
+}
+
+#[cfg(feature = "square")]
+#[test]
+fn test_square_insufficient_funds_is_categorised() {
+    use payup::square::{Environment, SquareClient, SquareConfig};
+    use serde_json::{json, Value};
+
+    let transport = FailingTransport::new(
+        402,
+        &[],
+        r#"{"errors":[{"category":"PAYMENT_METHOD_ERROR","code":"INSUFFICIENT_FUNDS","detail":"Authorization error: 'INSUFFICIENT_FUNDS'"}]}"#,
+    );
+    let client = SquareClient::new(SquareConfig {
+        access_token: "token".to_string(),
+        environment: Environment::Sandbox,
+        location_id: None,
+    })
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let result: Result<Value, PayupError> =
+        client.post("/v2/payments", &json!({"idempotency_key": "k-1", "source_id": "cnon:card-nonce-declined"}));
+    let error = result.unwrap_err();
+    assert_eq!(error.category(), ErrorCategory::InsufficientFunds);
+    assert_eq!(error.decline_code(), Some("INSUFFICIENT_FUNDS"));
+    assert!(!error.is_retryable());
+    assert_eq!(transport.requests(), 1);
+}
+
+#[test]
+#[allow(deprecated)]
+fn test_deprecated_variants_keep_their_categories() {
+    assert_eq!(PayupError::Http("connection reset".to_string()).category(), ErrorCategory::Network);
+    assert_eq!(PayupError::RateLimited("slow down".to_string()).category(), ErrorCategory::RateLimited);
+    assert_eq!(PayupError::Validation("amount is required".to_string()).category(), ErrorCategory::InvalidRequest);
+    assert_eq!(PayupError::Serialization("bad json".to_string()).category(), ErrorCategory::Internal);
+    assert_eq!(PayupError::RateLimitExceeded("stripe".to_string()).category(), ErrorCategory::RateLimited);
+}
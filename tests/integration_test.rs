@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 use payup::stripe::{Auth, Customer, PaymentMethod, Card, Subscription, Plan, Price};
 
 #[test]
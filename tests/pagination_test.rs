@@ -0,0 +1,150 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::FakeTransport;
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::error::PayupError;
+use payup::stripe::{Auth, Charge};
+use std::sync::Arc;
+
+const FIRST_PAGE: &str = r#"{"object":"list","url":"/v1/charges","has_more":true,
+    "data":[{"id":"ch_1","object":"charge"},{"id":"ch_2","object":"charge"}]}"#;
+const LAST_PAGE: &str = r#"{"object":"list","url":"/v1/charges","has_more":false,
+    "data":[{"id":"ch_3","object":"charge"}]}"#;
+
+fn charges() -> Arc<FakeTransport> {
+    FakeTransport::new(&[
+        ("/v1/charges?limit=2", FIRST_PAGE),
+        ("/v1/charges?limit=2&starting_after=ch_2", LAST_PAGE),
+    ])
+}
+
+fn auth(transport: Arc<FakeTransport>) -> Auth {
+    Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport)
+}
+
+fn ids(charges: Vec<Charge>) -> Vec<String> {
+    charges.into_iter().map(|charge| charge.id.unwrap()).collect()
+}
+
+#[test]
+fn test_list_iter_follows_starting_after() {
+    let transport = charges();
+
+    let all: Vec<Charge> = Charge::list_iter(auth(transport.clone()), PageRequest::new().limit(2))
+        .collect::<Result<_, _>>()
+        .expect("fake transport should answer");
+    assert_eq!(ids(all), vec!["ch_1", "ch_2", "ch_3"]);
+    assert_eq!(
+        transport.urls(),
+        vec!["/v1/charges?limit=2", "/v1/charges?limit=2&starting_after=ch_2"]
+    );
+}
+
+#[test]
+fn test_list_iter_stops_requesting_at_max_items() {
+    let transport = charges();
+
+    let capped: Vec<Charge> = Charge::list_iter(auth(transport.clone()), PageRequest::new().limit(2).max_items(2))
+        .collect::<Result<_, _>>()
+        .expect("fake transport should answer");
+    assert_eq!(ids(capped), vec!["ch_1", "ch_2"]);
+    assert_eq!(transport.urls(), vec!["/v1/charges?limit=2"]);
+}
+
+#[test]
+fn test_list_iter_yields_page_errors() {
+    // The second page is missing, so the fake transport answers 404
+    let transport = FakeTransport::new(&[("/v1/charges?limit=2", FIRST_PAGE)]);
+
+    let results: Vec<Result<Charge, PayupError>> =
+        Charge::list_iter(auth(transport), PageRequest::new().limit(2)).collect();
+    assert_eq!(results.len(), 3);
+    assert!(results[..2].iter().all(Result::is_ok));
+    assert!(results[2].is_err());
+}
+
+#[tokio::test]
+async fn test_list_stream_follows_starting_after() {
+    let transport = charges();
+
+    let all: Vec<Charge> = Charge::list_stream(auth(transport.clone()), PageRequest::new().limit(2))
+        .try_collect()
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(ids(all), vec!["ch_1", "ch_2", "ch_3"]);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[tokio::test]
+async fn test_async_list_collects_every_page() {
+    let transport = FakeTransport::new(&[
+        ("/v1/charges", FIRST_PAGE),
+        ("/v1/charges?starting_after=ch_2", LAST_PAGE),
+    ]);
+
+    let all = Charge::async_list(auth(transport)).await.expect("fake transport should answer");
+    assert_eq!(ids(all), vec!["ch_1", "ch_2", "ch_3"]);
+}
+
+#[cfg(feature = "square")]
+#[test]
+fn test_square_list_iter_follows_cursor() {
+    use payup::square::customers::Customer;
+    use payup::square::{Environment, SquareClient, SquareConfig};
+
+    let transport = FakeTransport::new(&[
+        ("/v2/customers?limit=1", r#"{"customers":[{"id":"sq_c_1"}],"cursor":"c2"}"#),
+        ("/v2/customers?cursor=c2&limit=1", r#"{"customers":[{"id":"sq_c_2"}]}"#),
+    ]);
+    let client = SquareClient::new(SquareConfig {
+        access_token: "token".to_string(),
+        environment: Environment::Sandbox,
+        location_id: None,
+    })
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let customers: Vec<Customer> = Customer::list_iter(&client, PageRequest::new().limit(1))
+        .collect::<Result<_, _>>()
+        .expect("fake transport should answer");
+    let ids: Vec<_> = customers.into_iter().map(|customer| customer.id.unwrap()).collect();
+    assert_eq!(ids, vec!["sq_c_1", "sq_c_2"]);
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[cfg(feature = "square")]
+#[tokio::test]
+async fn test_square_list_stream_rejects_pages_without_items_key() {
+    use payup::square::disputes::Dispute;
+    use payup::square::{Environment, SquareClient, SquareConfig};
+
+    let transport = FakeTransport::new(&[
+        ("/v2/disputes", r#"{"disputes":[{"id":"sq_dp_1"}],"cursor":"c2"}"#),
+        ("/v2/disputes?cursor=c2", r#"{}"#),
+        ("/v2/disputes?cursor=c3", r#"{"data":[{"id":"sq_dp_2"}]}"#),
+    ]);
+    let client = SquareClient::new(SquareConfig {
+        access_token: "token".to_string(),
+        environment: Environment::Sandbox,
+        location_id: None,
+    })
+    .unwrap()
+    .with_transport(transport);
+
+    // An empty last page comes back as `{}`
+    let disputes: Vec<Dispute> = Dispute::list_stream(&client, None, PageRequest::new())
+        .try_collect()
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(disputes.len(), 1);
+
+    // Any other body without `disputes` is an error, not an empty page
+    let result: Result<Vec<Dispute>, PayupError> =
+        Dispute::list_stream(&client, None, PageRequest::new().starting_after("c3"))
+            .try_collect()
+            .await;
+    assert!(matches!(result, Err(PayupError::Deserialization(_))));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "paypal")]
+
 #[cfg(test)]
 mod paypal_webhook_tests {
     use payup::paypal::webhooks::{
@@ -0,0 +1,100 @@
+#![cfg(feature = "stripe")]
+
+//! Stripe and Square requests must honour the global rate limiter and circuit
+//! breaker. Each provider's circuit is process-wide, so each is exercised by a
+//! single test to keep them from interfering.
+
+mod common;
+
+use common::FakeTransport;
+use payup::circuit_breaker::{get_circuit_breaker, CircuitState};
+use payup::error::PayupError;
+use payup::rate_limit_config::{CircuitBreakerBuilder, RateLimitConfig};
+use std::time::Duration;
+
+fn assert_circuit_open<T: std::fmt::Debug>(result: Result<T, PayupError>) {
+    match result {
+        Err(PayupError::GenericError(message)) => assert!(message.contains("Circuit breaker is open"), "{}", message),
+        other => panic!("Expected the open circuit to reject the request, got {:?}", other),
+    }
+}
+
+/// Opens after one failure and stays open for the rest of the test.
+fn trip_on_first_failure() -> payup::circuit_breaker::CircuitBreakerConfig {
+    CircuitBreakerBuilder::new()
+        .failure_threshold(1)
+        .timeout(Duration::from_secs(600))
+        .build()
+}
+
+#[tokio::test]
+async fn test_stripe_requests_respect_the_stripe_circuit() {
+    use payup::stripe::{Auth, Dispute};
+    use payup::stripe_ext::refund::Refund;
+
+    RateLimitConfig::new().with_stripe_circuit(trip_on_first_failure()).apply();
+    let circuit_breaker = get_circuit_breaker();
+    circuit_breaker.reset("stripe");
+
+    let transport = FakeTransport::new(&[(
+        "/v1/disputes/dp_123",
+        r#"{"id":"dp_123","object":"dispute","status":"won"}"#,
+    )]);
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport.clone());
+
+    let dispute = tokio::task::spawn_blocking({
+        let auth = auth.clone();
+        move || Dispute::get(auth, "dp_123".to_string())
+    })
+    .await
+    .unwrap()
+    .expect("closed circuit should let the request through");
+    assert_eq!(dispute.status.as_deref(), Some("won"));
+
+    circuit_breaker.record_failure("stripe");
+    assert_eq!(circuit_breaker.get_state("stripe"), CircuitState::Open);
+
+    let blocking = tokio::task::spawn_blocking({
+        let auth = auth.clone();
+        move || Dispute::get(auth, "dp_123".to_string())
+    })
+    .await
+    .unwrap();
+    assert_circuit_open(blocking);
+    assert_circuit_open(Refund::async_get(auth.clone(), "re_123".to_string()).await);
+
+    assert_eq!(transport.targets(), vec!["GET /v1/disputes/dp_123"]);
+    circuit_breaker.reset("stripe");
+}
+
+#[cfg(feature = "square")]
+#[test]
+fn test_square_blocking_requests_respect_the_square_circuit() {
+    use payup::square::disputes::Dispute;
+    use payup::square::{Environment, SquareClient, SquareConfig};
+
+    RateLimitConfig::new().with_square_circuit(trip_on_first_failure()).apply();
+    let circuit_breaker = get_circuit_breaker();
+    circuit_breaker.reset("square");
+
+    let transport = FakeTransport::new(&[(
+        "/v2/disputes/sq_dp_1",
+        r#"{"data":{"id":"sq_dp_1","state":"ACCEPTED"}}"#,
+    )]);
+    let client = SquareClient::new(SquareConfig {
+        access_token: "token".to_string(),
+        environment: Environment::Sandbox,
+        location_id: None,
+    })
+    .unwrap()
+    .with_transport(transport.clone());
+
+    let dispute = Dispute::get(&client, "sq_dp_1").expect("closed circuit should let the request through");
+    assert_eq!(dispute.state.as_deref(), Some("ACCEPTED"));
+
+    circuit_breaker.record_failure("square");
+    assert_circuit_open(Dispute::get(&client, "sq_dp_1"));
+
+    assert_eq!(transport.targets(), vec!["GET /v2/disputes/sq_dp_1"]);
+    circuit_breaker.reset("square");
+}
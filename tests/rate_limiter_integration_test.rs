@@ -316,22 +316,22 @@ async fn test_exponential_backoff() {
     let times = attempt_times.lock().unwrap();
     assert_eq!(times.len(), 4); // Initial attempt + 3 retries
     
-    // Check that backoff is increasing
+    // Each wait is jittered between half and all of the exponential step
     if times.len() >= 2 {
         let first_backoff = times[1].duration_since(times[0]);
-        assert!(first_backoff >= Duration::from_millis(90)); // Allow some tolerance
+        assert!(first_backoff >= Duration::from_millis(45)); // Allow some tolerance
         assert!(first_backoff <= Duration::from_millis(110));
     }
     
     if times.len() >= 3 {
         let second_backoff = times[2].duration_since(times[1]);
-        assert!(second_backoff >= Duration::from_millis(190)); // ~200ms
+        assert!(second_backoff >= Duration::from_millis(95)); // ~100-200ms
         assert!(second_backoff <= Duration::from_millis(210));
     }
     
     if times.len() >= 4 {
         let third_backoff = times[3].duration_since(times[2]);
-        assert!(third_backoff >= Duration::from_millis(390)); // Capped at max_backoff (400ms)
+        assert!(third_backoff >= Duration::from_millis(195)); // Capped at max_backoff (400ms)
         assert!(third_backoff <= Duration::from_millis(410));
     }
 }
\ No newline at end of file
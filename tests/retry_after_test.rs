@@ -0,0 +1,95 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::ScriptedTransport;
+use payup::error::PayupError;
+use payup::stripe::{Auth, Dispute};
+use std::sync::Arc;
+
+const TOO_MANY: &str = r#"{"error":{"type":"rate_limit_error","message":"Too many requests"}}"#;
+const DISPUTE: &str = r#"{"id":"dp_123","object":"dispute","status":"won"}"#;
+
+fn auth(transport: Arc<ScriptedTransport>) -> Auth {
+    Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport)
+}
+
+#[tokio::test]
+async fn test_stripe_get_is_retried_after_retry_after() {
+    let transport = ScriptedTransport::new(&[(429, Some("0"), TOO_MANY), (200, None, DISPUTE)]);
+
+    let dispute = Dispute::async_get(auth(transport.clone()), "dp_123".to_string())
+        .await
+        .expect("second attempt should succeed");
+    assert_eq!(dispute.status.as_deref(), Some("won"));
+    assert_eq!(transport.requests(), vec!["GET /v1/disputes/dp_123", "GET /v1/disputes/dp_123"]);
+}
+
+#[tokio::test]
+async fn test_stripe_keyed_post_is_retried() {
+    let transport = ScriptedTransport::new(&[(429, Some("0"), TOO_MANY), (200, None, DISPUTE)]);
+
+    let mut dispute = Dispute::new();
+    dispute.id = Some("dp_123".to_string());
+    let closed = dispute
+        .async_close(auth(transport.clone()).with_idempotency_key("close-dp_123"))
+        .await
+        .expect("second attempt should succeed");
+    assert_eq!(closed.status.as_deref(), Some("won"));
+    assert_eq!(transport.requests().len(), 2);
+}
+
+#[test]
+fn test_retry_after_beyond_max_backoff_is_surfaced() {
+    let transport = ScriptedTransport::new(&[(429, Some("3600"), TOO_MANY)]);
+
+    match Dispute::get(auth(transport.clone()), "dp_123".to_string()) {
+        Err(PayupError::RateLimitError { retry_after }) => assert_eq!(retry_after, Some(3600)),
+        other => panic!("Expected a rate limit error, got {:?}", other),
+    }
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[cfg(feature = "square")]
+mod square {
+    use super::*;
+    use payup::square::{Environment, SquareClient, SquareConfig};
+    use serde_json::{json, Value};
+
+    const CREATED: &str = r#"{"data":{"id":"cust_1"}}"#;
+
+    fn client(transport: Arc<ScriptedTransport>) -> SquareClient {
+        SquareClient::new(SquareConfig {
+            access_token: "token".to_string(),
+            environment: Environment::Sandbox,
+            location_id: None,
+        })
+        .unwrap()
+        .with_transport(transport)
+    }
+
+    #[tokio::test]
+    async fn test_square_post_with_idempotency_key_is_retried() {
+        let transport = ScriptedTransport::new(&[(429, Some("0"), "{}"), (200, None, CREATED)]);
+
+        let created: Value = client(transport.clone())
+            .async_post("/v2/customers", &json!({"idempotency_key": "k-1", "given_name": "Ada"}))
+            .await
+            .expect("second attempt should succeed");
+        assert_eq!(created["id"], "cust_1");
+        assert_eq!(transport.requests().len(), 2);
+    }
+
+    #[test]
+    fn test_square_post_without_idempotency_key_is_sent_once() {
+        let transport = ScriptedTransport::new(&[(429, Some("0"), "{}")]);
+
+        let result: Result<Value, PayupError> =
+            client(transport.clone()).post("/v2/customers", &json!({"given_name": "Ada"}));
+        match result {
+            Err(PayupError::RateLimitError { retry_after }) => assert_eq!(retry_after, Some(0)),
+            other => panic!("Expected a rate limit error, got {:?}", other),
+        }
+        assert_eq!(transport.requests().len(), 1);
+    }
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "square")]
+
 #[cfg(test)]
 mod square_provider_tests {
     use payup::square::{SquareProvider, Environment};
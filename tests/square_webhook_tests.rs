@@ -1,3 +1,5 @@
+#![cfg(feature = "square")]
+
 use payup::square::webhooks::{
     SquareWebhookHandler, WebhookEvent, WebhookEventType, WebhookEventHandler,
 };
@@ -0,0 +1,32 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::serve_once;
+use payup::stripe::{Auth, Balance, Customer};
+
+#[test]
+fn test_blocking_request_uses_custom_base_url() {
+    let (base_url, requests) = serve_once(
+        r#"{"object":"balance","available":[],"livemode":false,"pending":[]}"#,
+    );
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_base_url(base_url);
+
+    let balance = Balance::get(auth).expect("mock server should answer");
+
+    assert_eq!(balance.object, "balance");
+    assert_eq!(requests.recv().unwrap().request_line(), "GET /v1/balance HTTP/1.1");
+}
+
+#[tokio::test]
+async fn test_async_request_uses_custom_base_url() {
+    let (base_url, requests) = serve_once(r#"{"id":"cus_123","object":"customer"}"#);
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_base_url(base_url);
+
+    let customer = Customer::async_get(auth, "cus_123".to_string())
+        .await
+        .expect("mock server should answer");
+
+    assert_eq!(customer.id.as_deref(), Some("cus_123"));
+    assert_eq!(requests.recv().unwrap().request_line(), "GET /v1/customers/cus_123 HTTP/1.1");
+}
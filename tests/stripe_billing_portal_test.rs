@@ -0,0 +1,193 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use futures::TryStreamExt;
+use payup::stripe::billing_portal::{
+    PortalCancelMode, PortalFeature, PortalFlowType, PortalProduct, PortalSubscriptionCancel, PortalSubscriptionUpdate,
+};
+use payup::builders::PageRequest;
+use payup::stripe::subscription_item::ProrationBehavior;
+use payup::stripe::{BillingPortalConfiguration, BillingPortalSession, CreateBillingPortalConfigurationParams, CreateBillingPortalSessionParams, Customer, ListBillingPortalConfigurationsParams, PortalFeatures, PortalFlowParams, SubscriptionItemParams, UpdateBillingPortalConfigurationParams};
+
+const SESSION: &str = r#"{
+    "id": "bps_123",
+    "object": "billing_portal.session",
+    "configuration": "bpc_123",
+    "created": 1700000000,
+    "customer": "cus_123",
+    "flow": {
+        "type": "subscription_cancel",
+        "after_completion": {"type": "redirect", "redirect": {"return_url": "https://example.com/done"}},
+        "subscription_cancel": {"subscription": "sub_123"}
+    },
+    "livemode": false,
+    "locale": null,
+    "on_behalf_of": null,
+    "return_url": "https://example.com/account",
+    "url": "https://billing.stripe.com/p/session/test_123"
+}"#;
+
+const CONFIGURATION: &str = r#"{
+    "id": "bpc_123",
+    "object": "billing_portal.configuration",
+    "active": true,
+    "application": null,
+    "business_profile": {"headline": "Acme plans", "privacy_policy_url": null, "terms_of_service_url": null},
+    "created": 1700000000,
+    "default_return_url": "https://example.com/account",
+    "features": {
+        "customer_update": {"enabled": true, "allowed_updates": ["email", "address"]},
+        "invoice_history": {"enabled": true},
+        "payment_method_update": {"enabled": true},
+        "subscription_cancel": {
+            "enabled": true,
+            "mode": "at_period_end",
+            "proration_behavior": "none",
+            "cancellation_reason": {"enabled": false, "options": []}
+        },
+        "subscription_update": {
+            "enabled": true,
+            "default_allowed_updates": ["price"],
+            "products": [{"product": "prod_123", "prices": ["price_basic", "price_pro"]}],
+            "proration_behavior": "create_prorations"
+        }
+    },
+    "is_default": true,
+    "livemode": false,
+    "login_page": {"enabled": false, "url": null},
+    "metadata": {},
+    "updated": 1700000000
+}"#;
+
+#[tokio::test]
+async fn session_opens_a_cancel_flow_for_a_subscription() {
+    let transport = FakeTransport::new(&[("POST /v1/billing_portal/sessions", SESSION)]);
+    let params = CreateBillingPortalSessionParams::new("cus_123", "https://example.com/account")
+        .flow(PortalFlowParams::subscription_cancel("sub_123").redirect_to("https://example.com/done"));
+
+    let session = BillingPortalSession::create_async(&auth(transport.clone()), params).await.unwrap();
+
+    assert_eq!(session.url, "https://billing.stripe.com/p/session/test_123");
+    assert_eq!(session.flow.unwrap().type_field, PortalFlowType::SubscriptionCancel);
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "customer", "cus_123"));
+    assert!(has(form, "return_url", "https://example.com/account"));
+    assert!(has(form, "flow_data[type]", "subscription_cancel"));
+    assert!(has(form, "flow_data[subscription_cancel][subscription]", "sub_123"));
+    assert!(has(form, "flow_data[after_completion][type]", "redirect"));
+    assert!(has(form, "flow_data[after_completion][redirect][return_url]", "https://example.com/done"));
+}
+
+#[tokio::test]
+async fn session_confirms_a_plan_change_from_subscription_items() {
+    let transport = FakeTransport::new(&[("POST /v1/billing_portal/sessions", SESSION)]);
+    let item = SubscriptionItemParams {
+        id: Some("si_123".to_string()),
+        price: Some("price_pro".to_string()),
+        ..Default::default()
+    };
+    let params = CreateBillingPortalSessionParams::new("cus_123", "https://example.com/account")
+        .flow(PortalFlowParams::subscription_update_confirm("sub_123", item));
+
+    BillingPortalSession::create_async(&auth(transport.clone()), params).await.unwrap();
+
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "flow_data[type]", "subscription_update_confirm"));
+    assert!(has(form, "flow_data[subscription_update_confirm][subscription]", "sub_123"));
+    assert!(has(form, "flow_data[subscription_update_confirm][items][0][id]", "si_123"));
+    assert!(has(form, "flow_data[subscription_update_confirm][items][0][price]", "price_pro"));
+    assert!(!form.iter().any(|(k, _)| k.ends_with("[deleted]")));
+}
+
+#[tokio::test]
+async fn customer_opens_a_portal_session() {
+    let transport = FakeTransport::new(&[("POST /v1/billing_portal/sessions", SESSION)]);
+
+    let session = Customer::async_portal_session(
+        auth(transport.clone()),
+        "cus_123".to_string(),
+        "https://example.com/account".to_string(),
+    )
+    .await
+    .unwrap();
+
+    assert_eq!(session.customer, "cus_123");
+    let requests = transport.requests();
+    assert_eq!(requests[0].target, "POST /v1/billing_portal/sessions");
+    assert!(!requests[0].form.iter().any(|(k, _)| k.starts_with("flow_data")));
+}
+
+#[tokio::test]
+async fn configuration_limits_plan_changes_to_listed_prices() {
+    let transport = FakeTransport::new(&[("POST /v1/billing_portal/configurations", CONFIGURATION)]);
+    let params = CreateBillingPortalConfigurationParams {
+        features: PortalFeatures {
+            subscription_update: Some(PortalSubscriptionUpdate {
+                enabled: true,
+                default_allowed_updates: vec!["price".to_string()],
+                products: vec![PortalProduct {
+                    product: "prod_123".to_string(),
+                    prices: vec!["price_basic".to_string(), "price_pro".to_string()],
+                }],
+                proration_behavior: Some(ProrationBehavior::CreateProrations),
+            }),
+            subscription_cancel: Some(PortalSubscriptionCancel {
+                enabled: true,
+                mode: Some(PortalCancelMode::AtPeriodEnd),
+                proration_behavior: None,
+                cancellation_reason: None,
+            }),
+            ..Default::default()
+        },
+        default_return_url: Some("https://example.com/account".to_string()),
+        ..Default::default()
+    };
+
+    let configuration = BillingPortalConfiguration::create_async(&auth(transport.clone()), params).await.unwrap();
+
+    assert!(configuration.is_default);
+    let update = configuration.features.subscription_update.unwrap();
+    assert_eq!(update.products[0].prices, vec!["price_basic", "price_pro"]);
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "features[subscription_update][enabled]", "true"));
+    assert!(has(form, "features[subscription_update][default_allowed_updates][0]", "price"));
+    assert!(has(form, "features[subscription_update][products][0][product]", "prod_123"));
+    assert!(has(form, "features[subscription_update][products][0][prices][1]", "price_pro"));
+    assert!(has(form, "features[subscription_update][proration_behavior]", "create_prorations"));
+    assert!(has(form, "features[subscription_cancel][mode]", "at_period_end"));
+    assert!(!form.iter().any(|(k, _)| k.starts_with("features[invoice_history]")));
+}
+
+#[tokio::test]
+async fn configurations_update_and_list() {
+    let list = format!(r#"{{"object": "list", "data": [{}], "has_more": false}}"#, CONFIGURATION);
+    let list: &'static str = Box::leak(list.into_boxed_str());
+    let transport = FakeTransport::new(&[
+        ("POST /v1/billing_portal/configurations/bpc_123", CONFIGURATION),
+        ("GET /v1/billing_portal/configurations", list),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = UpdateBillingPortalConfigurationParams {
+        features: Some(PortalFeatures {
+            invoice_history: Some(PortalFeature { enabled: false }),
+            ..Default::default()
+        }),
+        ..Default::default()
+    };
+    BillingPortalConfiguration::update_async(&auth, "bpc_123", params).await.unwrap();
+
+    let filters = ListBillingPortalConfigurationsParams { is_default: Some(true), ..Default::default() };
+    let configurations: Vec<_> = BillingPortalConfiguration::list_stream(&auth, &filters, PageRequest::new())
+        .try_collect()
+        .await
+        .unwrap();
+
+    assert_eq!(configurations.len(), 1);
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "features[invoice_history][enabled]", "false"));
+    assert_eq!(requests[1].target, "GET /v1/billing_portal/configurations");
+    assert!(has(&requests[1].query, "is_default", "true"));
+}
@@ -0,0 +1,116 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::stripe::checkout::{
+    CreateSessionParams, PriceData, Session, SessionLineItemParams, SessionMode, SessionPaymentStatus,
+};
+use payup::stripe::payment_link::{
+    CreatePaymentLinkParams, PaymentLink, PaymentLinkLineItemParams, UpdatePaymentLinkParams,
+};
+use payup::stripe::{StripeEventObject, WebhookEvent, WebhookEventType};
+use payup::stripe_ext::product::Product;
+
+const SESSION: &str = r#"{"id":"cs_test_1","object":"checkout.session","created":1700000000,
+    "expires_at":1700086400,"livemode":false,"mode":"payment","payment_status":"unpaid",
+    "status":"open","url":"https://checkout.stripe.com/c/pay/cs_test_1"}"#;
+
+const EXPIRED_SESSION: &str = r#"{"id":"cs_test_1","object":"checkout.session","created":1700000000,
+    "expires_at":1700086400,"livemode":false,"mode":"payment","payment_status":"unpaid","status":"expired"}"#;
+
+const LINE_ITEMS: &str = r#"{"object":"list","url":"/v1/checkout/sessions/cs_test_1/line_items","has_more":false,
+    "data":[{"id":"li_1","object":"item","amount_subtotal":2000,"amount_total":2000,"currency":"usd",
+    "description":"T-shirt","quantity":2,
+    "price":{"id":"price_1","object":"price","active":true,"billing_scheme":"per_unit","created":1690000000,
+    "currency":"usd","livemode":false,"product":"prod_1","type":"one_time","unit_amount":1000}}]}"#;
+
+const PAYMENT_LINK: &str = r#"{"id":"plink_1","object":"payment_link","active":true,"livemode":false,
+    "url":"https://buy.stripe.com/test_1"}"#;
+
+#[test]
+fn test_session_create_encodes_line_items() {
+    let transport = FakeTransport::new(&[("POST /v1/checkout/sessions", SESSION)]);
+
+    let mut product = Product::new();
+    product.name = "T-shirt".to_string();
+    let mut params = CreateSessionParams::new(
+        SessionMode::Payment,
+        vec![SessionLineItemParams::price_data(PriceData::for_product(&product, "usd", 1000), 2)],
+    );
+    params.success_url = Some("https://example.com/success".to_string());
+
+    let session = Session::create(&auth(transport.clone()), params).expect("fake transport should answer");
+    assert_eq!(session.payment_status, SessionPaymentStatus::Unpaid);
+    assert!(session.url.is_some());
+
+    let request = transport.last_request();
+    assert!(has(&request.form, "mode", "payment"));
+    assert!(has(&request.form, "success_url", "https://example.com/success"));
+    assert!(has(&request.form, "line_items[0][quantity]", "2"));
+    assert!(has(&request.form, "line_items[0][price_data][currency]", "usd"));
+    assert!(has(&request.form, "line_items[0][price_data][unit_amount]", "1000"));
+    assert!(has(&request.form, "line_items[0][price_data][product_data][name]", "T-shirt"));
+}
+
+#[tokio::test]
+async fn test_session_expire_and_line_items() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/checkout/sessions/cs_test_1/expire", EXPIRED_SESSION),
+        ("GET /v1/checkout/sessions/cs_test_1/line_items", LINE_ITEMS),
+    ]);
+    let auth = auth(transport.clone());
+
+    let session = Session::expire_async(&auth, "cs_test_1").await.expect("fake transport should answer");
+    assert_eq!(session.status, Some(payup::stripe::SessionStatus::Expired));
+
+    let items = Session::list_line_items_async(&auth, "cs_test_1", PageRequest::new())
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(items.len(), 1);
+    assert_eq!(items[0].quantity, Some(2));
+    assert_eq!(items[0].price.as_ref().unwrap().id, "price_1");
+}
+
+#[test]
+fn test_payment_link_create_and_deactivate() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/payment_links", PAYMENT_LINK),
+        ("POST /v1/payment_links/plink_1", PAYMENT_LINK),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = CreatePaymentLinkParams::new(vec![PaymentLinkLineItemParams {
+        price: "price_1".to_string(),
+        quantity: 3,
+        adjustable_quantity: None,
+    }]);
+    let link = PaymentLink::create(&auth, params).expect("fake transport should answer");
+    assert_eq!(link.url, "https://buy.stripe.com/test_1");
+    let request = transport.last_request();
+    assert!(has(&request.form, "line_items[0][price]", "price_1"));
+    assert!(has(&request.form, "line_items[0][quantity]", "3"));
+
+    let update = UpdatePaymentLinkParams { active: Some(false), ..Default::default() };
+    PaymentLink::update(&auth, "plink_1", update).expect("fake transport should answer");
+    let request = transport.last_request();
+    assert_eq!(request.target, "POST /v1/payment_links/plink_1");
+    assert_eq!(request.form, vec![("active".to_string(), "false".to_string())]);
+}
+
+#[test]
+fn test_checkout_session_completed_event() {
+    let event: WebhookEvent = serde_json::from_str(&format!(
+        r#"{{"id":"evt_1","object":"event","type":"checkout.session.completed","created":1700000000,
+            "livemode":false,"pending_webhooks":1,"data":{{"object":{}}}}}"#,
+        SESSION
+    ))
+    .unwrap();
+
+    assert_eq!(event.event_type_enum(), WebhookEventType::CheckoutSessionCompleted);
+    let StripeEventObject::CheckoutSession(session) = event.data.typed_object() else {
+        panic!("expected a checkout session, got {:?}", event.data.object);
+    };
+    assert_eq!(session.id, "cs_test_1");
+}
@@ -0,0 +1,105 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::stripe::account_link::{AccountLink, CreateAccountLinkParams};
+use payup::stripe::application_fee::{ApplicationFee, CreateApplicationFeeRefundParams, ListApplicationFeesParams};
+use payup::stripe::builders::PaymentIntentBuilder;
+use payup::stripe::login_link::LoginLink;
+use payup::stripe::PaymentIntent;
+
+const ACCOUNT_LINK: &str = r#"{"object":"account_link","created":1700000000,"expires_at":1700000300,
+    "url":"https://connect.stripe.com/setup/s/acct_1/abc"}"#;
+
+const LOGIN_LINK: &str = r#"{"object":"login_link","created":1700000000,
+    "url":"https://connect.stripe.com/express/acct_1/xyz"}"#;
+
+const PAYMENT_INTENT: &str = r#"{"id":"pi_1","object":"payment_intent","amount":10000,"application_fee_amount":1230,
+    "capture_method":"automatic","confirmation_method":"automatic","created":1700000000,"currency":"usd",
+    "livemode":false,"on_behalf_of":"acct_1","status":"requires_payment_method",
+    "transfer_data":{"destination":"acct_1"}}"#;
+
+const APPLICATION_FEES: &str = r#"{"object":"list","url":"/v1/application_fees","has_more":false,"data":[
+    {"id":"fee_1","object":"application_fee","account":"acct_1","amount":1230,"amount_refunded":0,
+    "application":"ca_1","balance_transaction":"txn_1","charge":"ch_1","created":1700000000,"currency":"usd",
+    "livemode":false,"originating_transaction":null,"refunded":false,
+    "refunds":{"object":"list","data":[],"has_more":false,"url":"/v1/application_fees/fee_1/refunds"}}]}"#;
+
+const FEE_REFUND: &str = r#"{"id":"fr_1","object":"fee_refund","amount":500,"balance_transaction":null,
+    "created":1700000100,"currency":"usd","fee":"fee_1","metadata":{}}"#;
+
+#[test]
+fn test_onboarding_and_login_links() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/account_links", ACCOUNT_LINK),
+        ("POST /v1/accounts/acct_1/login_links", LOGIN_LINK),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = CreateAccountLinkParams::onboarding("acct_1", "https://example.com/refresh", "https://example.com/done");
+    let link = AccountLink::create(&auth, params).expect("fake transport should answer");
+    assert!(link.url.starts_with("https://connect.stripe.com/setup"));
+
+    let login = LoginLink::create(&auth, "acct_1").expect("fake transport should answer");
+    assert!(login.url.contains("express"));
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "account", "acct_1"));
+    assert!(has(&requests[0].form, "type", "account_onboarding"));
+    assert!(has(&requests[0].form, "refresh_url", "https://example.com/refresh"));
+    assert_eq!(requests[1].target, "POST /v1/accounts/acct_1/login_links");
+    assert!(requests.iter().all(|r| r.header("Stripe-Account").is_none()));
+}
+
+#[tokio::test]
+async fn test_destination_charge() {
+    let transport = FakeTransport::new(&[("POST /v1/payment_intents", PAYMENT_INTENT)]);
+    let auth = auth(transport.clone());
+
+    let params = PaymentIntentBuilder::new(10000, "usd")
+        .application_fee_amount(1230)
+        .on_behalf_of("acct_1")
+        .transfer_destination("acct_1")
+        .build();
+    let intent = PaymentIntent::create_async(&auth, params).await.expect("fake transport should answer");
+    assert_eq!(intent.application_fee_amount, Some(1230));
+
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "amount", "10000"));
+    assert!(has(form, "application_fee_amount", "1230"));
+    assert!(has(form, "on_behalf_of", "acct_1"));
+    assert!(has(form, "transfer_data[destination]", "acct_1"));
+}
+
+#[tokio::test]
+async fn test_requests_on_behalf_of_connected_account() {
+    let transport = FakeTransport::new(&[
+        ("GET /v1/application_fees", APPLICATION_FEES),
+        ("POST /v1/application_fees/fee_1/refunds", FEE_REFUND),
+    ]);
+    let platform = auth(transport.clone());
+    let connected = platform.for_account("acct_1").unwrap();
+
+    // Paginated requests carry the header as well
+    let filters = ListApplicationFeesParams { charge: Some("ch_1".to_string()) };
+    let fees: Vec<_> = ApplicationFee::list_stream(&connected, &filters, PageRequest::new())
+        .try_collect()
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(fees[0].amount, 1230);
+
+    let params = CreateApplicationFeeRefundParams { amount: Some(500), ..Default::default() };
+    let refund = ApplicationFee::refund_async(&platform, "fee_1", params)
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(refund.fee, "fee_1");
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].header("Stripe-Account"), Some("acct_1"));
+    assert!(has(&requests[0].query, "charge", "ch_1"));
+    assert_eq!(requests[1].header("Stripe-Account"), None);
+    assert!(has(&requests[1].form, "amount", "500"));
+}
@@ -0,0 +1,130 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::stripe::builders::{CouponBuilder, PromotionCodeBuilder};
+use payup::stripe::{Coupon, CouponDuration, Customer, DiscountItem, PromotionCode, Subscription};
+
+const COUPON: &str = r#"{"id":"co_1","object":"coupon","amount_off":null,"created":1700000000,"currency":null,
+    "duration":"repeating","duration_in_months":3,"livemode":false,"max_redemptions":100,"metadata":{},
+    "name":"Spring","percent_off":25.0,"redeem_by":null,"times_redeemed":0,"valid":true}"#;
+
+const PROMOTION_CODE: &str = r#"{"id":"promo_1","object":"promotion_code","active":true,"code":"SPRING25",
+    "coupon":{"id":"co_1","object":"coupon","duration":"repeating","duration_in_months":3,"livemode":false,
+    "percent_off":25.0,"times_redeemed":4,"valid":true},"created":1700000000,"customer":null,"expires_at":null,
+    "livemode":false,"max_redemptions":null,"metadata":{},"restrictions":{"first_time_transaction":true,
+    "minimum_amount":5000,"minimum_amount_currency":"usd"},"times_redeemed":4}"#;
+
+const PROMOTION_CODES: &str = r#"{"object":"list","url":"/v1/promotion_codes","has_more":false,"data":[
+    {"id":"promo_1","object":"promotion_code","active":true,"code":"SPRING25","coupon":{"id":"co_1",
+    "object":"coupon","duration":"once","livemode":false,"percent_off":25.0,"valid":true},
+    "created":1700000000,"livemode":false}]}"#;
+
+const CUSTOMER: &str = r#"{"id":"cus_1","object":"customer","discount":{"id":"di_1","coupon":{"id":"co_1",
+    "object":"coupon","duration":"repeating","duration_in_months":3,"livemode":false,"percent_off":25.0,
+    "valid":true},"customer":"cus_1","promotion_code":"promo_1","start":1700000000}}"#;
+
+const DELETED_DISCOUNT: &str = r#"{"id":"di_1","object":"discount","deleted":true,"customer":"cus_1"}"#;
+
+const SUBSCRIPTION: &str = r#"{"id":"sub_1","object":"subscription","customer":"cus_1","discounts":["di_2"]}"#;
+
+#[test]
+fn test_coupon_and_promotion_code_create() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/coupons", COUPON),
+        ("POST /v1/promotion_codes", PROMOTION_CODE),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = CouponBuilder::percent_off(25.0, CouponDuration::Repeating)
+        .duration_in_months(3)
+        .max_redemptions(100)
+        .name("Spring")
+        .build();
+    let coupon = Coupon::create(&auth, params).expect("fake transport should answer");
+    assert_eq!(coupon.duration, CouponDuration::Repeating);
+    assert_eq!(coupon.percent_off, Some(25.0));
+
+    let params = PromotionCodeBuilder::new(&coupon.id)
+        .code("SPRING25")
+        .first_time_transaction(true)
+        .minimum_amount(5000, "usd")
+        .build();
+    let promotion_code = PromotionCode::create(&auth, params).expect("fake transport should answer");
+    assert_eq!(promotion_code.coupon.id, "co_1");
+    assert!(promotion_code.restrictions.first_time_transaction);
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "percent_off", "25"));
+    assert!(has(&requests[0].form, "duration", "repeating"));
+    assert!(has(&requests[0].form, "duration_in_months", "3"));
+    assert!(has(&requests[1].form, "coupon", "co_1"));
+    assert!(has(&requests[1].form, "code", "SPRING25"));
+    assert!(has(&requests[1].form, "restrictions[first_time_transaction]", "true"));
+    assert!(has(&requests[1].form, "restrictions[minimum_amount]", "5000"));
+    assert!(has(&requests[1].form, "restrictions[minimum_amount_currency]", "usd"));
+}
+
+#[tokio::test]
+async fn test_find_promotion_code_by_code() {
+    let transport = FakeTransport::new(&[("GET /v1/promotion_codes", PROMOTION_CODES)]);
+    let auth = auth(transport.clone());
+
+    let found = PromotionCode::find_by_code_async(&auth, "SPRING25")
+        .await
+        .expect("fake transport should answer")
+        .expect("the code should be found");
+    assert_eq!(found.id, "promo_1");
+
+    let query = &transport.requests()[0].query;
+    assert!(has(query, "code", "SPRING25"));
+    assert!(has(query, "active", "true"));
+    assert!(has(query, "limit", "1"));
+}
+
+#[tokio::test]
+async fn test_customer_discount_apply_and_remove() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/customers/cus_1", CUSTOMER),
+        ("DELETE /v1/customers/cus_1/discount", DELETED_DISCOUNT),
+    ]);
+    let auth = auth(transport.clone());
+
+    let customer = Customer::async_apply_discount(auth.clone(), "cus_1".to_string(), DiscountItem::promotion_code("promo_1"))
+        .await
+        .expect("fake transport should answer");
+    let discount = customer.discount.expect("the customer should carry the discount");
+    assert_eq!(discount.coupon.unwrap().id, "co_1");
+
+    let removed = Customer::async_delete_discount(auth, "cus_1".to_string())
+        .await
+        .expect("fake transport should answer");
+    assert!(removed.deleted);
+
+    let requests = transport.requests();
+    assert_eq!(requests[1].target, "DELETE /v1/customers/cus_1/discount");
+    assert!(has(&requests[0].form, "promotion_code", "promo_1"));
+    assert!(!requests[0].form.iter().any(|(k, _)| k == "coupon"));
+}
+
+#[tokio::test]
+async fn test_subscription_discounts() {
+    let transport = FakeTransport::new(&[("POST /v1/subscriptions/sub_1", SUBSCRIPTION)]);
+    let auth = auth(transport.clone());
+
+    let discounts = vec![DiscountItem::coupon("co_1"), DiscountItem::promotion_code("promo_1")];
+    let subscription = Subscription::async_apply_discount(auth.clone(), "sub_1".to_string(), discounts)
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(subscription.discounts, Some(vec!["di_2".to_string()]));
+
+    Subscription::async_apply_discount(auth, "sub_1".to_string(), Vec::new())
+        .await
+        .expect("fake transport should answer");
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "discounts[0][coupon]", "co_1"));
+    assert!(has(&requests[0].form, "discounts[1][promotion_code]", "promo_1"));
+    assert!(has(&requests[1].form, "discounts", ""));
+}
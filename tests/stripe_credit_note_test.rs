@@ -0,0 +1,209 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::stripe::{CreateCreditNoteParams, CreateCustomerBalanceTransactionParams, CreditNote, CreditNoteLineParams, CreditNoteLineType, CreditNoteReason, CreditNoteStatus, CreditNoteType, Customer, CustomerBalanceTransaction, ListCreditNotesParams, UpdateCustomerBalanceTransactionParams};
+use std::collections::HashMap;
+
+const CREDIT_NOTE: &str = r#"{
+    "id": "cn_123",
+    "object": "credit_note",
+    "amount": 1500,
+    "amount_shipping": 0,
+    "created": 1700000000,
+    "currency": "usd",
+    "customer": "cus_123",
+    "customer_balance_transaction": "cbtxn_123",
+    "discount_amount": 0,
+    "invoice": "in_123",
+    "lines": {
+        "object": "list",
+        "data": [{
+            "id": "cnli_123",
+            "object": "credit_note_line_item",
+            "amount": 1500,
+            "description": "Gold plan",
+            "discount_amount": 0,
+            "invoice_line_item": "il_123",
+            "livemode": false,
+            "quantity": 1,
+            "tax_amounts": [],
+            "tax_rates": [],
+            "type": "invoice_line_item",
+            "unit_amount": null
+        }],
+        "has_more": false,
+        "url": "/v1/credit_notes/cn_123/lines"
+    },
+    "livemode": false,
+    "memo": "Partial outage",
+    "metadata": {},
+    "number": "ABCD-1234-0001-CN-01",
+    "out_of_band_amount": null,
+    "pdf": "https://pay.stripe.com/credit_notes/cn_123/pdf",
+    "reason": "product_unsatisfactory",
+    "refund": null,
+    "status": "issued",
+    "subtotal": 1500,
+    "tax_amounts": [],
+    "total": 1500,
+    "type": "post_payment",
+    "voided_at": null
+}"#;
+
+const VOIDED: &str = r#"{
+    "id": "cn_123", "object": "credit_note", "amount": 1500, "created": 1700000000, "currency": "usd",
+    "customer": "cus_123", "invoice": "in_123", "livemode": false, "number": "ABCD-1234-0001-CN-01",
+    "pdf": "https://pay.stripe.com/credit_notes/cn_123/pdf", "status": "void", "subtotal": 1500,
+    "total": 1500, "type": "post_payment", "voided_at": 1700000100
+}"#;
+
+const LINES: &str = r#"{"object": "list", "has_more": false, "url": "/v1/credit_notes/cn_123/lines", "data": [{
+    "id": "cnli_123", "object": "credit_note_line_item", "amount": 1500, "livemode": false,
+    "type": "custom_line_item", "description": "Goodwill", "quantity": 1, "unit_amount": 1500
+}]}"#;
+
+const BALANCE_TRANSACTION: &str = r#"{
+    "id": "cbtxn_123",
+    "object": "customer_balance_transaction",
+    "amount": -500,
+    "created": 1700000000,
+    "credit_note": null,
+    "currency": "usd",
+    "customer": "cus_123",
+    "description": "Goodwill credit",
+    "ending_balance": -500,
+    "invoice": null,
+    "livemode": false,
+    "metadata": {"ticket": "42"},
+    "type": "adjustment"
+}"#;
+
+fn credit_params() -> CreateCreditNoteParams {
+    let mut params = CreateCreditNoteParams::new("in_123").line(CreditNoteLineParams::quantity("il_123", 1));
+    params.reason = Some(CreditNoteReason::ProductUnsatisfactory);
+    params.memo = Some("Partial outage".to_string());
+    params
+}
+
+#[tokio::test]
+async fn credit_note_preview_sends_lines_as_query() {
+    let transport = FakeTransport::new(&[("GET /v1/credit_notes/preview", CREDIT_NOTE)]);
+
+    let preview = CreditNote::preview_async(&auth(transport.clone()), &credit_params()).await.unwrap();
+
+    assert_eq!(preview.total, 1500);
+    let request = &transport.requests()[0];
+    assert!(has(&request.query, "invoice", "in_123"));
+    assert!(has(&request.query, "lines[0][type]", "invoice_line_item"));
+    assert!(has(&request.query, "lines[0][invoice_line_item]", "il_123"));
+    assert!(has(&request.query, "lines[0][quantity]", "1"));
+    assert!(has(&request.query, "reason", "product_unsatisfactory"));
+    assert!(request.form.is_empty());
+}
+
+#[tokio::test]
+async fn credit_note_create_and_void() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/credit_notes", CREDIT_NOTE),
+        ("POST /v1/credit_notes/cn_123/void", VOIDED),
+    ]);
+    let auth = auth(transport.clone());
+    let mut params = credit_params().line(CreditNoteLineParams::custom("Goodwill", 1, 500));
+    params.credit_amount = Some(2000);
+
+    let credit_note = CreditNote::create_async(&auth, params).await.unwrap();
+    let voided = CreditNote::void_async(&auth, &credit_note.id).await.unwrap();
+
+    assert_eq!(credit_note.status, CreditNoteStatus::Issued);
+    assert_eq!(credit_note.type_field, CreditNoteType::PostPayment);
+    assert_eq!(credit_note.customer_balance_transaction.as_deref(), Some("cbtxn_123"));
+    assert_eq!(credit_note.lines.unwrap().data[0].invoice_line_item.as_deref(), Some("il_123"));
+    assert_eq!(voided.status, CreditNoteStatus::Void);
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "lines[1][type]", "custom_line_item"));
+    assert!(has(&requests[0].form, "lines[1][description]", "Goodwill"));
+    assert!(has(&requests[0].form, "lines[1][unit_amount]", "500"));
+    assert!(has(&requests[0].form, "credit_amount", "2000"));
+    assert_eq!(requests[1].target, "POST /v1/credit_notes/cn_123/void");
+}
+
+#[tokio::test]
+async fn credit_note_lines_and_list() {
+    let list = format!(r#"{{"object": "list", "has_more": false, "url": "/v1/credit_notes", "data": [{}]}}"#, VOIDED);
+    let list: &'static str = Box::leak(list.into_boxed_str());
+    let transport = FakeTransport::new(&[
+        ("GET /v1/credit_notes/cn_123/lines", LINES),
+        ("GET /v1/credit_notes/preview/lines", LINES),
+        ("GET /v1/credit_notes", list),
+    ]);
+    let auth = auth(transport.clone());
+
+    let lines = CreditNote::lines_async(&auth, "cn_123", PageRequest::new()).await.unwrap();
+    let preview_lines: Vec<_> = CreditNote::preview_lines_stream(&auth, &credit_params(), PageRequest::new().limit(5))
+        .try_collect()
+        .await
+        .unwrap();
+    let filters = ListCreditNotesParams { invoice: Some("in_123".to_string()), ..Default::default() };
+    let credit_notes = CreditNote::list_async(&auth, &filters, PageRequest::new()).await.unwrap();
+
+    assert_eq!(lines[0].type_field, CreditNoteLineType::CustomLineItem);
+    assert_eq!(preview_lines.len(), 1);
+    assert_eq!(credit_notes[0].voided_at, Some(1700000100));
+    let requests = transport.requests();
+    assert!(has(&requests[1].query, "invoice", "in_123"));
+    assert!(has(&requests[1].query, "lines[0][invoice_line_item]", "il_123"));
+    assert!(has(&requests[1].query, "limit", "5"));
+    assert!(has(&requests[2].query, "invoice", "in_123"));
+}
+
+#[tokio::test]
+async fn customer_balance_transactions_create_update_and_list() {
+    let list = format!(
+        r#"{{"object": "list", "has_more": false, "url": "/v1/customers/cus_123/balance_transactions", "data": [{}]}}"#,
+        BALANCE_TRANSACTION
+    );
+    let list: &'static str = Box::leak(list.into_boxed_str());
+    let transport = FakeTransport::new(&[
+        ("POST /v1/customers/cus_123/balance_transactions", BALANCE_TRANSACTION),
+        ("POST /v1/customers/cus_123/balance_transactions/cbtxn_123", BALANCE_TRANSACTION),
+        ("GET /v1/customers/cus_123/balance_transactions", list),
+    ]);
+    let auth = auth(transport.clone());
+
+    let mut params = CreateCustomerBalanceTransactionParams::new(-500, "usd");
+    params.description = Some("Goodwill credit".to_string());
+    let transaction = CustomerBalanceTransaction::create_async(&auth, "cus_123", params).await.unwrap();
+    let mut metadata = HashMap::new();
+    metadata.insert("ticket".to_string(), "42".to_string());
+    let update = UpdateCustomerBalanceTransactionParams { metadata: Some(metadata), ..Default::default() };
+    CustomerBalanceTransaction::update_async(&auth, "cus_123", &transaction.id, update).await.unwrap();
+    let transactions = CustomerBalanceTransaction::list_async(&auth, "cus_123", PageRequest::new()).await.unwrap();
+
+    assert_eq!(transaction.ending_balance, -500);
+    assert_eq!(transaction.type_field, "adjustment");
+    assert_eq!(transactions.len(), 1);
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "amount", "-500"));
+    assert!(has(&requests[0].form, "currency", "usd"));
+    assert!(has(&requests[0].form, "description", "Goodwill credit"));
+    assert!(has(&requests[1].form, "metadata[ticket]", "42"));
+}
+
+#[tokio::test]
+async fn customer_adjusts_its_balance() {
+    let transport = FakeTransport::new(&[("POST /v1/customers/cus_123/balance_transactions", BALANCE_TRANSACTION)]);
+
+    let transaction =
+        Customer::async_adjust_balance(auth(transport.clone()), "cus_123".to_string(), -500, "usd".to_string())
+            .await
+            .unwrap();
+
+    assert_eq!(transaction.customer, "cus_123");
+    let form = &transport.requests()[0].form;
+    assert_eq!(form.len(), 2);
+    assert!(has(form, "amount", "-500"));
+}
@@ -0,0 +1,229 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::serve_once;
+use payup::error::PayupError;
+use payup::stripe::{Auth, Dispute, Evidence, File, FileLink, Mandate};
+
+fn auth(base_url: String) -> Auth {
+    Auth::new("sk_test".to_string(), "sk_test".to_string()).with_base_url(base_url)
+}
+
+#[test]
+fn test_file_upload_is_multipart() {
+    let (base_url, requests) = serve_once(
+        r#"{"id":"file_123","object":"file","purpose":"dispute_evidence","filename":"receipt.pdf","size":9}"#,
+    );
+
+    let mut file = File::new();
+    file.file = Some(b"%PDF-1.4\n".to_vec());
+    file.filename = Some("receipt.pdf".to_string());
+    file.purpose = Some("dispute_evidence".to_string());
+
+    let uploaded = file.post(auth(base_url)).expect("mock server should answer");
+    assert_eq!(uploaded.id.as_deref(), Some("file_123"));
+    assert!(uploaded.file.is_none());
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "POST /v1/files HTTP/1.1");
+    assert!(request
+        .header("Content-Type")
+        .is_some_and(|value| value.starts_with("multipart/form-data")));
+    assert!(request.body.contains("name=\"purpose\"\r\n\r\ndispute_evidence"));
+    assert!(request.body.contains("name=\"file\"; filename=\"receipt.pdf\""));
+    assert!(request.body.contains("%PDF-1.4"));
+}
+
+#[test]
+fn test_file_upload_requires_contents() {
+    let mut file = File::new();
+    file.purpose = Some("dispute_evidence".to_string());
+
+    match file.post(Auth::new("sk_test".to_string(), "sk_test".to_string())) {
+        Err(PayupError::ValidationError(message)) => assert!(message.contains("contents")),
+        other => panic!("Expected a validation error, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_dispute_update_submits_evidence() {
+    let (base_url, requests) = serve_once(
+        r#"{"id":"dp_123","object":"dispute","amount":1000,"status":"under_review",
+            "evidence":{"receipt":"file_123","customer_name":"Jane Doe"},
+            "evidence_details":{"due_by":1700000000,"has_evidence":true,"past_due":false,"submission_count":1}}"#,
+    );
+
+    let mut evidence = Evidence::new();
+    evidence.receipt = Some("file_123".to_string());
+    evidence.customer_name = Some("Jane Doe".to_string());
+
+    let mut dispute = Dispute::new();
+    dispute.id = Some("dp_123".to_string());
+    dispute.evidence = Some(evidence);
+    dispute.submit = Some(true);
+
+    let updated = dispute
+        .async_update(auth(base_url))
+        .await
+        .expect("mock server should answer");
+    assert_eq!(updated.status.as_deref(), Some("under_review"));
+    assert_eq!(updated.evidence_details.unwrap().submission_count, 1);
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "POST /v1/disputes/dp_123 HTTP/1.1");
+    assert!(request.body.contains("evidence%5Breceipt%5D=file_123"));
+    assert!(request.body.contains("evidence%5Bcustomer_name%5D=Jane+Doe"));
+    assert!(request.body.contains("submit=true"));
+}
+
+#[test]
+fn test_dispute_close() {
+    let (base_url, requests) = serve_once(r#"{"id":"dp_123","object":"dispute","status":"lost"}"#);
+
+    let mut dispute = Dispute::new();
+    dispute.id = Some("dp_123".to_string());
+
+    let closed = dispute.close(auth(base_url)).expect("mock server should answer");
+    assert_eq!(closed.status.as_deref(), Some("lost"));
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "POST /v1/disputes/dp_123/close HTTP/1.1");
+}
+
+#[test]
+fn test_dispute_close_without_id_returns_error() {
+    let result = Dispute::new().close(Auth::new("sk_test".to_string(), "sk_test".to_string()));
+    match result {
+        Err(PayupError::ValidationError(message)) => {
+            assert!(message.contains("Dispute ID is required for close"))
+        }
+        other => panic!("Expected a validation error, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_file_link_update_only_sends_expiry() {
+    let (base_url, requests) = serve_once(
+        r#"{"id":"link_123","object":"file_link","expired":true,"file":"file_123","url":"https://files.stripe.com/links/abc"}"#,
+    );
+
+    let mut file_link = FileLink::new();
+    file_link.id = Some("link_123".to_string());
+    file_link.file = Some("file_123".to_string());
+    file_link.link_expires_at = Some("now".to_string());
+
+    let updated = file_link.update(auth(base_url)).expect("mock server should answer");
+    assert_eq!(updated.expired, Some(true));
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "POST /v1/file_links/link_123 HTTP/1.1");
+    assert_eq!(request.body, "expires_at=now");
+}
+
+#[tokio::test]
+async fn test_mandate_get() {
+    let (base_url, requests) = serve_once(
+        r#"{"id":"mandate_123","object":"mandate","livemode":false,
+            "customer_acceptance":{"accepted_at":1700000000,"online":{"ip_address":"127.0.0.1","user_agent":"curl"},"type":"online"},
+            "multi_use":{},"payment_method":"pm_123",
+            "payment_method_details":{"sepa_debit":{"reference":"REF123","url":"https://example.com/mandate"},"type":"sepa_debit"},
+            "status":"active","type":"multi_use"}"#,
+    );
+
+    let mandate = Mandate::async_get(auth(base_url), "mandate_123".to_string())
+        .await
+        .expect("mock server should answer");
+    assert_eq!(mandate.status, "active");
+    assert_eq!(mandate.customer_acceptance.online.unwrap().ip_address.as_deref(), Some("127.0.0.1"));
+    assert_eq!(mandate.payment_method_details.sepa_debit.unwrap().reference, "REF123");
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "GET /v1/mandates/mandate_123 HTTP/1.1");
+}
+
+#[tokio::test]
+async fn test_provider_submit_dispute_evidence() {
+    use payup::payment_provider::{DisputeEvidence, DisputeStatus, PaymentProvider};
+    use payup::stripe::StripeProvider;
+
+    let (base_url, requests) = serve_once(
+        r#"{"id":"dp_123","object":"dispute","amount":1000,"currency":"usd","charge":"ch_123",
+            "reason":"product_not_received","status":"under_review","created":1690000000,
+            "evidence_details":{"due_by":1700000000,"has_evidence":true,"past_due":false,"submission_count":1}}"#,
+    );
+
+    let provider = StripeProvider::new("sk_test".to_string()).with_base_url(base_url);
+    let evidence = DisputeEvidence {
+        explanation: Some("Delivered on time".to_string()),
+        shipping_carrier: Some("UPS".to_string()),
+        tracking_number: Some("1Z999".to_string()),
+        files: vec![],
+    };
+
+    let dispute = provider
+        .submit_dispute_evidence("dp_123", &evidence)
+        .await
+        .expect("mock server should answer");
+    assert_eq!(dispute.status, DisputeStatus::UnderReview);
+    assert_eq!(dispute.charge_id.as_deref(), Some("ch_123"));
+    assert_eq!(dispute.amount.amount, 1000);
+    assert_eq!(dispute.evidence_due_by, Some(1700000000));
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.headers[0], "POST /v1/disputes/dp_123 HTTP/1.1");
+    assert!(request.body.contains("evidence%5Buncategorized_text%5D=Delivered+on+time"));
+    assert!(request.body.contains("evidence%5Bshipping_carrier%5D=UPS"));
+    assert!(request.body.contains("evidence%5Bshipping_tracking_number%5D=1Z999"));
+    assert!(request.body.contains("submit=true"));
+}
+
+#[tokio::test]
+async fn test_provider_accept_dispute_closes_it() {
+    use payup::payment_provider::{DisputeStatus, PaymentProvider};
+    use payup::stripe::StripeProvider;
+
+    let (base_url, requests) = serve_once(r#"{"id":"dp_123","object":"dispute","status":"lost"}"#);
+
+    let provider = StripeProvider::new("sk_test".to_string()).with_base_url(base_url);
+    let dispute = provider.accept_dispute("dp_123").await.expect("mock server should answer");
+    assert_eq!(dispute.status, DisputeStatus::Lost);
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.headers[0], "POST /v1/disputes/dp_123/close HTTP/1.1");
+}
+
+#[tokio::test]
+async fn test_provider_list_disputes_stops_at_limit() {
+    use payup::payment_provider::{DisputeStatus, PaymentProvider};
+    use payup::stripe::StripeProvider;
+
+    // Only one response is served, so fetching the next page would fail
+    let (base_url, requests) = serve_once(
+        r#"{"object":"list","url":"/v1/disputes","has_more":true,"data":[
+            {"id":"dp_1","object":"dispute","status":"warning_needs_response"},
+            {"id":"dp_2","object":"dispute","status":"prevented"},
+            {"id":"dp_3","object":"dispute","status":"won"}]}"#,
+    );
+
+    let provider = StripeProvider::new("sk_test".to_string()).with_base_url(base_url);
+    let disputes = provider.list_disputes(Some(2)).await.expect("mock server should answer");
+    assert_eq!(disputes.len(), 2);
+    assert_eq!(disputes[0].status, DisputeStatus::NeedsResponse);
+    assert_eq!(disputes[1].status, DisputeStatus::Closed);
+
+    let request = requests.recv().unwrap();
+    assert!(request.request_line().starts_with("GET /v1/disputes"));
+}
+
+#[tokio::test]
+async fn test_provider_maps_unknown_dispute_status_to_other() {
+    use payup::payment_provider::{DisputeStatus, PaymentProvider};
+    use payup::stripe::StripeProvider;
+
+    let (base_url, _requests) = serve_once(r#"{"id":"dp_123","object":"dispute","status":"some_new_status"}"#);
+
+    let provider = StripeProvider::new("sk_test".to_string()).with_base_url(base_url);
+    let dispute = provider.get_dispute("dp_123").await.expect("mock server should answer");
+    assert_eq!(dispute.status, DisputeStatus::Other("some_new_status".to_string()));
+}
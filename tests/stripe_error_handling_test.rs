@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 use payup::error::PayupError;
 use payup::stripe::{Auth, Charge, Customer};
 use payup::stripe_original::{
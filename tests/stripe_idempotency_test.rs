@@ -0,0 +1,53 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, serve_once, FakeTransport};
+use payup::stripe::{Auth, Customer};
+use payup::stripe_ext::refund::Refund;
+
+#[tokio::test]
+async fn test_caller_supplied_idempotency_key_is_sent() {
+    let (base_url, requests) = serve_once(r#"{"id":"cus_123","object":"customer"}"#);
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string())
+        .with_base_url(base_url)
+        .with_idempotency_key("signup-42");
+
+    Customer::new()
+        .async_post(auth)
+        .await
+        .expect("mock server should answer");
+
+    let request = requests.recv().unwrap();
+    assert_eq!(request.request_line(), "POST /v1/customers HTTP/1.1");
+    assert_eq!(request.header("Idempotency-Key"), Some("signup-42"));
+}
+
+#[test]
+fn test_idempotency_key_is_generated_when_not_supplied() {
+    let (base_url, requests) = serve_once(r#"{"id":"re_123","object":"refund"}"#);
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_base_url(base_url);
+
+    let mut refund = Refund::new();
+    refund.charge = Some("ch_123".to_string());
+    refund.post(auth).expect("mock server should answer");
+
+    let request = requests.recv().unwrap();
+    let key = request.header("Idempotency-Key").expect("an Idempotency-Key header should be sent");
+    assert!(uuid::Uuid::parse_str(key).is_ok());
+}
+
+#[tokio::test]
+async fn test_caller_supplied_idempotency_key_is_sent_once() {
+    let transport = FakeTransport::new(&[("POST /v1/customers", r#"{"id":"cus_123","object":"customer"}"#)]);
+    let auth = auth(transport.clone()).with_idempotency_key("signup-42");
+
+    Customer::new().async_post(auth.clone()).await.expect("fake transport should answer");
+    // A later, unrelated call with the same `Auth` must not replay the first
+    Customer::new().async_post(auth).await.expect("fake transport should answer");
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].header("Idempotency-Key"), Some("signup-42"));
+    let second = requests[1].header("Idempotency-Key").expect("an Idempotency-Key header should be sent");
+    assert!(uuid::Uuid::parse_str(second).is_ok());
+}
@@ -0,0 +1,173 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport, Recorded};
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::stripe::billing_meter::{
+    CreateMeterEventParams, CreateMeterParams, Meter, MeterAggregation, MeterEvent, MeterEventSummaryParams,
+};
+use payup::stripe::usage_record::{CreateUsageRecordParams, UsageRecord, UsageRecordSummary};
+use payup::stripe::{UsageBatcher, UsageTarget};
+
+const USAGE_RECORD: &str = r#"{"id":"mbur_1","object":"usage_record","livemode":false,"quantity":250,
+    "subscription_item":"si_1","timestamp":1700000000}"#;
+
+const USAGE_RECORD_2: &str = r#"{"id":"mbur_2","object":"usage_record","livemode":false,"quantity":7,
+    "subscription_item":"si_2","timestamp":1700000000}"#;
+
+const SUMMARIES: &str = r#"{"object":"list","url":"/v1/subscription_items/si_1/usage_record_summaries",
+    "has_more":false,"data":[{"id":"sis_1","object":"usage_record_summary","invoice":null,"livemode":false,
+    "period":{"start":1700000000,"end":null},"subscription_item":"si_1","total_usage":1250}]}"#;
+
+const METER: &str = r#"{"id":"mtr_1","object":"billing.meter","created":1700000000,
+    "customer_mapping":{"event_payload_key":"stripe_customer_id","type":"by_id"},
+    "default_aggregation":{"formula":"sum"},"display_name":"API requests","event_name":"api_requests",
+    "livemode":false,"status":"active","updated":1700000000,"value_settings":{"event_payload_key":"value"}}"#;
+
+const METER_EVENT: &str = r#"{"object":"billing.meter_event","created":1700000000,"event_name":"api_requests",
+    "identifier":"evt_local_1","livemode":false,"payload":{"stripe_customer_id":"cus_1","value":"25"},
+    "timestamp":1700000000}"#;
+
+const EVENT_SUMMARIES: &str = r#"{"object":"list","url":"/v1/billing/meters/mtr_1/event_summaries",
+    "has_more":false,"data":[{"id":"mtrusg_1","object":"billing.meter_event_summary","aggregated_value":1500.0,
+    "end_time":1700086400,"livemode":false,"meter":"mtr_1","start_time":1700000000}]}"#;
+
+#[test]
+fn test_usage_record_create_and_summaries() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/subscription_items/si_1/usage_records", USAGE_RECORD),
+        ("GET /v1/subscription_items/si_1/usage_record_summaries", SUMMARIES),
+    ]);
+    let auth = auth(transport.clone());
+
+    let record = UsageRecord::create(&auth, "si_1", CreateUsageRecordParams::set(250, 1700000000))
+        .expect("fake transport should answer");
+    assert_eq!(record.quantity, 250);
+    let request = &transport.requests()[0];
+    assert!(has(&request.form, "quantity", "250"));
+    assert!(has(&request.form, "action", "set"));
+    assert!(has(&request.form, "timestamp", "1700000000"));
+
+    let summaries: Vec<_> = UsageRecordSummary::list_iter(&auth, "si_1", PageRequest::new())
+        .collect::<Result<_, _>>()
+        .expect("fake transport should answer");
+    assert_eq!(summaries[0].total_usage, 1250);
+    assert_eq!(summaries[0].period.end, None);
+}
+
+#[tokio::test]
+async fn test_meter_create_event_and_summaries() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/billing/meters", METER),
+        ("POST /v1/billing/meter_events", METER_EVENT),
+        ("GET /v1/billing/meters/mtr_1/event_summaries", EVENT_SUMMARIES),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = CreateMeterParams::new("API requests", "api_requests", MeterAggregation::Sum);
+    let meter = Meter::create_async(&auth, params).await.expect("fake transport should answer");
+    assert_eq!(meter.default_aggregation.formula, MeterAggregation::Sum);
+
+    let event = MeterEvent::create_async(&auth, CreateMeterEventParams::new("api_requests", "cus_1", 25))
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(event.payload["value"], "25");
+
+    let params = MeterEventSummaryParams {
+        customer: "cus_1".to_string(),
+        start_time: 1700000000,
+        end_time: 1700086400,
+        value_grouping_window: None,
+    };
+    let summaries: Vec<_> = Meter::event_summaries_stream(&auth, "mtr_1", &params, PageRequest::new())
+        .try_collect()
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(summaries[0].aggregated_value, 1500.0);
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "default_aggregation[formula]", "sum"));
+    assert!(has(&requests[0].form, "event_name", "api_requests"));
+    assert!(has(&requests[1].form, "payload[stripe_customer_id]", "cus_1"));
+    assert!(has(&requests[1].form, "payload[value]", "25"));
+    assert!(has(&requests[2].query, "customer", "cus_1"));
+    assert!(has(&requests[2].query, "start_time", "1700000000"));
+}
+
+#[tokio::test]
+async fn test_batcher_sends_one_request_per_target() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/subscription_items/si_1/usage_records", USAGE_RECORD),
+        ("POST /v1/subscription_items/si_2/usage_records", USAGE_RECORD_2),
+        ("POST /v1/billing/meter_events", METER_EVENT),
+    ]);
+    let batcher = UsageBatcher::new(auth(transport.clone()));
+
+    for _ in 0..50 {
+        batcher.record_item("si_1", 5);
+    }
+    batcher.record_item("si_2", 7);
+    batcher.record_meter("api_requests", "cus_1", 10);
+    batcher.record_meter("api_requests", "cus_1", 15);
+    assert_eq!(batcher.pending().len(), 3);
+
+    let sent = batcher.flush().await.expect("every report should succeed");
+    assert_eq!(sent, 3);
+    assert!(batcher.pending().is_empty());
+
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 3);
+    let item = requests.iter().find(|r| r.target.contains("si_1")).unwrap();
+    assert!(has(&item.form, "quantity", "250"));
+    assert!(has(&item.form, "action", "increment"));
+    let meter = requests.iter().find(|r| r.target.contains("meter_events")).unwrap();
+    assert!(has(&meter.form, "payload[value]", "25"));
+    assert!(meter.form.iter().any(|(k, _)| k == "identifier"));
+}
+
+#[tokio::test]
+async fn test_batcher_keeps_failed_totals() {
+    let transport = FakeTransport::new(&[("POST /v1/subscription_items/si_1/usage_records", USAGE_RECORD)]);
+    let batcher = UsageBatcher::new(auth(transport.clone()));
+
+    batcher.record_item("si_1", 250);
+    batcher.record_item("si_missing", 3);
+
+    assert!(batcher.flush().await.is_err());
+    let pending = batcher.pending();
+    assert_eq!(pending.len(), 1);
+    assert_eq!(pending[&UsageTarget::SubscriptionItem("si_missing".to_string())], 3);
+
+    // Usage recorded after a failure counts towards the pending total
+    batcher.record_item("si_missing", 2);
+    assert_eq!(batcher.pending()[&UsageTarget::SubscriptionItem("si_missing".to_string())], 5);
+}
+
+#[tokio::test]
+async fn test_batcher_retries_failed_report_with_same_keys() {
+    let transport = FakeTransport::new(&[("POST /v1/billing/meter_events", METER_EVENT)]);
+    let batcher = UsageBatcher::new(auth(transport.clone()));
+
+    batcher.record_meter("api_requests", "cus_1", 25);
+    // Outlast the client's own retries so the flush fails
+    transport.fail_next(4);
+    assert!(batcher.flush().await.is_err());
+    let failed = transport.last_request();
+
+    batcher.record_meter("api_requests", "cus_1", 5);
+    assert_eq!(batcher.flush().await.expect("both reports should succeed"), 2);
+    assert!(batcher.pending().is_empty());
+
+    let requests = transport.requests();
+    let identifier = |request: &Recorded| request.form.iter().find(|(k, _)| k == "identifier").unwrap().1.clone();
+    let retry = requests.iter().rev().find(|r| has(&r.form, "payload[value]", "25")).unwrap();
+    assert_eq!(retry.header("Idempotency-Key"), failed.header("Idempotency-Key"));
+    assert_eq!(identifier(retry), identifier(&failed));
+
+    // Newer usage goes out as its own event instead of joining the retried one
+    let fresh = requests.iter().find(|r| has(&r.form, "payload[value]", "5")).unwrap();
+    assert_ne!(fresh.header("Idempotency-Key"), failed.header("Idempotency-Key"));
+    assert_ne!(identifier(fresh), identifier(&failed));
+}
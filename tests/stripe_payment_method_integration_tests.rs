@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 use payup::stripe::{Auth, PaymentMethod, StripePaymentMethodType, CreatePaymentMethodParams, CreateCardParams, PaymentMethodBillingDetails, PaymentMethodAddress};
 use payup::payment_provider::{PaymentProvider, PaymentMethod as UnifiedPaymentMethod, PaymentMethodType, CardDetails};
 use payup::stripe::StripeProvider;
@@ -0,0 +1,202 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::stripe::coupon::DiscountItem;
+use payup::stripe::quote::{CreateQuoteParams, FinalizeQuoteParams, ListQuotesParams, Quote, QuoteLineItemParams, QuoteStatus};
+
+const DRAFT: &str = r#"{
+    "id": "qt_123",
+    "object": "quote",
+    "amount_subtotal": 50000,
+    "amount_total": 45000,
+    "application_fee_amount": null,
+    "application_fee_percent": null,
+    "automatic_tax": {"enabled": false, "status": null},
+    "collection_method": "send_invoice",
+    "computed": {
+        "recurring": {
+            "amount_subtotal": 50000,
+            "amount_total": 45000,
+            "interval": "year",
+            "interval_count": 1,
+            "total_details": {"amount_discount": 5000, "amount_shipping": 0, "amount_tax": 0}
+        },
+        "upfront": {
+            "amount_subtotal": 50000,
+            "amount_total": 45000,
+            "total_details": {"amount_discount": 5000, "amount_shipping": 0, "amount_tax": 0}
+        }
+    },
+    "created": 1700000000,
+    "currency": "usd",
+    "customer": "cus_123",
+    "description": null,
+    "discounts": ["di_123"],
+    "expires_at": 1702592000,
+    "footer": null,
+    "header": "Annual seats",
+    "invoice": null,
+    "invoice_settings": {"days_until_due": 30},
+    "livemode": false,
+    "metadata": {},
+    "number": null,
+    "on_behalf_of": null,
+    "status": "draft",
+    "status_transitions": {"accepted_at": null, "canceled_at": null, "finalized_at": null},
+    "subscription": null,
+    "subscription_data": {"description": null, "effective_date": null, "trial_period_days": null},
+    "subscription_schedule": null,
+    "total_details": {"amount_discount": 5000, "amount_shipping": 0, "amount_tax": 0}
+}"#;
+
+const ACCEPTED: &str = r#"{
+    "id": "qt_123", "object": "quote", "amount_subtotal": 50000, "amount_total": 45000,
+    "collection_method": "send_invoice", "created": 1700000000, "customer": "cus_123",
+    "discounts": ["di_123"], "expires_at": 1702592000, "invoice": "in_123", "livemode": false,
+    "number": "QT-0001", "status": "accepted",
+    "status_transitions": {"accepted_at": 1700000200, "canceled_at": null, "finalized_at": 1700000100},
+    "subscription": "sub_123", "subscription_schedule": null
+}"#;
+
+const SUBSCRIPTION: &str = r#"{"id": "sub_123", "object": "subscription", "customer": "cus_123", "status": "active"}"#;
+
+const LINE_ITEMS: &str = r#"{"object": "list", "has_more": false, "url": "/v1/quotes/qt_123/line_items", "data": [{
+    "id": "li_123",
+    "object": "item",
+    "amount_subtotal": 50000,
+    "amount_total": 45000,
+    "currency": "usd",
+    "description": "Seats",
+    "discounts": [{
+        "amount": 5000,
+        "discount": {"id": "di_123", "coupon": null, "customer": "cus_123", "promotion_code": null, "start": 1700000000}
+    }],
+    "price": {
+        "id": "price_seats", "object": "price", "active": true, "billing_scheme": "per_unit",
+        "created": 1690000000, "currency": "usd", "livemode": false, "product": "prod_123",
+        "recurring": {"interval": "year", "interval_count": 1, "usage_type": "licensed"},
+        "type": "recurring", "unit_amount": 2000
+    },
+    "quantity": 25
+}]}"#;
+
+const PDF: &str = "%PDF-1.7\nquote qt_123\n%%EOF";
+
+#[tokio::test]
+async fn quote_create_sends_line_items_and_discounts() {
+    let transport = FakeTransport::new(&[("POST /v1/quotes", DRAFT)]);
+    let mut params = CreateQuoteParams::new("cus_123")
+        .line_item(QuoteLineItemParams::price("price_seats", 25))
+        .discount(DiscountItem::coupon("ANNUAL10"));
+    params.header = Some("Annual seats".to_string());
+
+    let quote = Quote::create_async(&auth(transport.clone()), params).await.unwrap();
+
+    assert_eq!(quote.status, QuoteStatus::Draft);
+    assert_eq!(quote.discounts, vec!["di_123".to_string()]);
+    assert_eq!(quote.computed.unwrap().recurring.unwrap().amount_total, 45000);
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "customer", "cus_123"));
+    assert!(has(form, "line_items[0][price]", "price_seats"));
+    assert!(has(form, "line_items[0][quantity]", "25"));
+    assert!(has(form, "discounts[0][coupon]", "ANNUAL10"));
+    assert!(has(form, "header", "Annual seats"));
+}
+
+#[tokio::test]
+async fn accepted_quote_links_to_its_subscription() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/quotes/qt_123/finalize", DRAFT),
+        ("POST /v1/quotes/qt_123/accept", ACCEPTED),
+        ("GET /v1/subscriptions/sub_123", SUBSCRIPTION),
+    ]);
+    let auth = auth(transport.clone());
+    let finalize = FinalizeQuoteParams { expires_at: Some(1702592000) };
+
+    Quote::finalize_async(&auth, "qt_123", finalize).await.unwrap();
+    let accepted = Quote::accept_async(&auth, "qt_123").await.unwrap();
+    let subscription = accepted.subscription_async(&auth).await.unwrap().unwrap();
+
+    assert_eq!(accepted.status, QuoteStatus::Accepted);
+    assert_eq!(accepted.invoice.as_deref(), Some("in_123"));
+    assert_eq!(subscription.id.as_deref(), Some("sub_123"));
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "expires_at", "1702592000"));
+    assert_eq!(requests[2].target, "GET /v1/subscriptions/sub_123");
+}
+
+#[tokio::test]
+async fn draft_quote_has_no_subscription_yet() {
+    let transport = FakeTransport::new(&[("GET /v1/quotes/qt_123", DRAFT)]);
+    let auth = auth(transport.clone());
+
+    let quote = Quote::retrieve_async(&auth, "qt_123").await.unwrap();
+
+    assert!(quote.subscription_async(&auth).await.unwrap().is_none());
+    assert!(quote.invoice_async(&auth).await.unwrap().is_none());
+    assert_eq!(transport.requests().len(), 1);
+}
+
+#[tokio::test]
+async fn quote_line_items_carry_price_and_discounts() {
+    let transport = FakeTransport::new(&[("GET /v1/quotes/qt_123/line_items", LINE_ITEMS)]);
+
+    let items = Quote::line_items_async(&auth(transport), "qt_123", PageRequest::new()).await.unwrap();
+
+    let price = items[0].price.as_ref().unwrap();
+    assert_eq!(price.id, "price_seats");
+    assert_eq!(price.unit_amount, Some(2000));
+    let discount = &items[0].discounts.as_ref().unwrap()[0];
+    assert_eq!(discount.amount, 5000);
+    assert_eq!(discount.discount.id, "di_123");
+}
+
+#[tokio::test]
+async fn quote_list_and_cancel() {
+    let list: &'static str = Box::leak(format!(
+        r#"{{"object": "list", "has_more": false, "url": "/v1/quotes", "data": [{}]}}"#,
+        DRAFT
+    ).into_boxed_str());
+    let transport = FakeTransport::new(&[("GET /v1/quotes", list), ("POST /v1/quotes/qt_123/cancel", DRAFT)]);
+    let auth = auth(transport.clone());
+    let filters = ListQuotesParams { customer: Some("cus_123".to_string()), status: Some(QuoteStatus::Draft) };
+
+    let quotes = Quote::list_async(&auth, &filters, PageRequest::new()).await.unwrap();
+    Quote::cancel_async(&auth, &quotes[0].id).await.unwrap();
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].query, "customer", "cus_123"));
+    assert!(has(&requests[0].query, "status", "draft"));
+    assert_eq!(requests[1].target, "POST /v1/quotes/qt_123/cancel");
+}
+
+#[tokio::test]
+async fn quote_pdf_streams_in_chunks() {
+    let transport = FakeTransport::new(&[("GET /v1/quotes/qt_123/pdf", PDF)]);
+
+    let chunks: Vec<Vec<u8>> = Quote::pdf_stream(&auth(transport), "qt_123").await.unwrap().try_collect().await.unwrap();
+
+    assert_eq!(chunks.concat(), PDF.as_bytes());
+}
+
+#[test]
+fn quote_pdf_copies_into_writer() {
+    let transport = FakeTransport::new(&[("GET /v1/quotes/qt_123/pdf", PDF)]);
+    let mut pdf = Vec::new();
+
+    let written = Quote::pdf(&auth(transport), "qt_123", &mut pdf).unwrap();
+
+    assert_eq!(written, PDF.len() as u64);
+    assert_eq!(pdf, PDF.as_bytes());
+}
+
+#[test]
+fn quote_pdf_surfaces_missing_quote() {
+    let transport = FakeTransport::new(&[]);
+
+    assert!(Quote::pdf(&auth(transport), "qt_missing", &mut Vec::new()).is_err());
+}
@@ -0,0 +1,170 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::stripe::radar::{
+    CreateValueListParams, EarlyFraudWarning, EarlyFraudWarningType, ListEarlyFraudWarningsParams, ListValueListItemsParams,
+    Review, ReviewOpenedReason, ValueList, ValueListItem, ValueListItemType,
+};
+use payup::stripe::Charge;
+
+const REVIEW: &str = r#"{
+    "id": "prv_123",
+    "object": "review",
+    "billing_zip": "94107",
+    "charge": "ch_123",
+    "closed_reason": null,
+    "created": 1700000000,
+    "ip_address": "203.0.113.7",
+    "ip_address_location": {"city": "Lisbon", "country": "PT", "latitude": 38.7, "longitude": -9.1, "region": "11"},
+    "livemode": false,
+    "open": true,
+    "opened_reason": "rule",
+    "payment_intent": "pi_123",
+    "reason": "rule",
+    "session": {"browser": "Firefox", "device": "Other", "platform": "Linux", "version": "118.0"}
+}"#;
+
+const APPROVED: &str = r#"{
+    "id": "prv_123", "object": "review", "charge": "ch_123", "closed_reason": "approved",
+    "created": 1700000000, "livemode": false, "open": false, "opened_reason": "rule", "reason": "approved"
+}"#;
+
+const VALUE_LIST: &str = r#"{
+    "id": "rsl_123",
+    "object": "radar.value_list",
+    "alias": "blocked_emails",
+    "created": 1700000000,
+    "created_by": "jenny@example.com",
+    "item_type": "email",
+    "list_items": {"object": "list", "data": [], "has_more": false, "url": "/v1/radar/value_list_items?value_list=rsl_123"},
+    "livemode": false,
+    "metadata": {},
+    "name": "Blocked emails"
+}"#;
+
+const VALUE_LIST_ITEM: &str = r#"{
+    "id": "rsli_123",
+    "object": "radar.value_list_item",
+    "created": 1700000100,
+    "created_by": "jenny@example.com",
+    "livemode": false,
+    "value": "fraudster@example.com",
+    "value_list": "rsl_123"
+}"#;
+
+const EARLY_FRAUD_WARNING: &str = r#"{
+    "id": "issfr_123",
+    "object": "radar.early_fraud_warning",
+    "actionable": true,
+    "charge": "ch_123",
+    "created": 1700000000,
+    "fraud_type": "made_with_stolen_card",
+    "livemode": false,
+    "payment_intent": "pi_123"
+}"#;
+
+const CHARGE: &str = r#"{
+    "id": "ch_123",
+    "object": "charge",
+    "amount": 5000,
+    "currency": "usd",
+    "fraud_details": {"stripe_report": "fraudulent"},
+    "outcome": {
+        "network_status": "approved_by_network",
+        "reason": "elevated_risk_level",
+        "risk_level": "elevated",
+        "risk_score": 74,
+        "rule": "rule_123",
+        "seller_message": "Stripe evaluated this payment as having elevated risk, and placed it in your manual review queue.",
+        "type": "manual_review"
+    },
+    "review": "prv_123"
+}"#;
+
+fn list(url: &str, item: &str) -> &'static str {
+    Box::leak(format!(r#"{{"object": "list", "has_more": false, "url": "{}", "data": [{}]}}"#, url, item).into_boxed_str())
+}
+
+#[tokio::test]
+async fn open_reviews_can_be_approved() {
+    let transport = FakeTransport::new(&[
+        ("GET /v1/reviews", list("/v1/reviews", REVIEW)),
+        ("POST /v1/reviews/prv_123/approve", APPROVED),
+    ]);
+    let auth = auth(transport.clone());
+
+    let reviews = Review::list_async(&auth, PageRequest::new()).await.unwrap();
+    let approved = Review::approve_async(&auth, &reviews[0].id).await.unwrap();
+
+    assert!(reviews[0].open);
+    assert_eq!(reviews[0].opened_reason, ReviewOpenedReason::Rule);
+    assert_eq!(reviews[0].ip_address_location.as_ref().unwrap().country.as_deref(), Some("PT"));
+    assert!(!approved.open);
+    assert_eq!(approved.closed_reason.as_deref(), Some("approved"));
+    assert_eq!(transport.requests()[1].target, "POST /v1/reviews/prv_123/approve");
+}
+
+#[tokio::test]
+async fn value_list_and_items_round_trip() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/radar/value_lists", VALUE_LIST),
+        ("POST /v1/radar/value_list_items", VALUE_LIST_ITEM),
+        ("GET /v1/radar/value_list_items", list("/v1/radar/value_list_items", VALUE_LIST_ITEM)),
+        ("DELETE /v1/radar/value_list_items/rsli_123", r#"{"id": "rsli_123", "object": "radar.value_list_item", "deleted": true}"#),
+        ("DELETE /v1/radar/value_lists/rsl_123", r#"{"id": "rsl_123", "object": "radar.value_list", "deleted": true}"#),
+    ]);
+    let auth = auth(transport.clone());
+    let params = CreateValueListParams::new("blocked_emails", "Blocked emails", ValueListItemType::Email);
+
+    let value_list = ValueList::create_async(&auth, params).await.unwrap();
+    let item = ValueListItem::create_async(&auth, &value_list.id, "fraudster@example.com").await.unwrap();
+    let items = ValueListItem::list_async(&auth, &ListValueListItemsParams::new(&value_list.id), PageRequest::new())
+        .await
+        .unwrap();
+    let deleted_item = ValueListItem::delete_async(&auth, &item.id).await.unwrap();
+    let deleted_list = ValueList::delete_async(&auth, &value_list.id).await.unwrap();
+
+    assert_eq!(value_list.item_type, ValueListItemType::Email);
+    assert_eq!(items[0].value, "fraudster@example.com");
+    assert!(deleted_item.deleted && deleted_list.deleted);
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "alias", "blocked_emails"));
+    assert!(has(&requests[0].form, "item_type", "email"));
+    assert!(has(&requests[1].form, "value_list", "rsl_123"));
+    assert!(has(&requests[1].form, "value", "fraudster@example.com"));
+    assert!(has(&requests[2].query, "value_list", "rsl_123"));
+}
+
+#[tokio::test]
+async fn early_fraud_warnings_filter_by_charge() {
+    let transport = FakeTransport::new(&[(
+        "GET /v1/radar/early_fraud_warnings",
+        list("/v1/radar/early_fraud_warnings", EARLY_FRAUD_WARNING),
+    )]);
+    let filters = ListEarlyFraudWarningsParams { charge: Some("ch_123".to_string()), ..Default::default() };
+
+    let warnings = EarlyFraudWarning::list_async(&auth(transport.clone()), &filters, PageRequest::new()).await.unwrap();
+
+    assert!(warnings[0].actionable);
+    assert_eq!(warnings[0].fraud_type, EarlyFraudWarningType::MadeWithStolenCard);
+    assert!(has(&transport.requests()[0].query, "charge", "ch_123"));
+}
+
+#[tokio::test]
+async fn charge_carries_fraud_details_and_outcome() {
+    let transport = FakeTransport::new(&[("GET /v1/charges/ch_123", CHARGE)]);
+
+    let charge = Charge::async_get(auth(transport), "ch_123".to_string()).await.unwrap();
+
+    let fraud_details = charge.fraud_details.unwrap();
+    assert_eq!(fraud_details.stripe_report.as_deref(), Some("fraudulent"));
+    assert!(fraud_details.is_fraudulent());
+    let outcome = charge.outcome.unwrap();
+    assert_eq!(outcome.risk_level.as_deref(), Some("elevated"));
+    assert_eq!(outcome.risk_score, Some(74));
+    assert_eq!(outcome.type_field.as_deref(), Some("manual_review"));
+    assert_eq!(charge.review.as_deref(), Some("prv_123"));
+}
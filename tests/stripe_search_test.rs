@@ -0,0 +1,136 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use futures::TryStreamExt;
+use payup::builders::PageRequest;
+use payup::error::PayupError;
+use payup::stripe::{Charge, Customer, Invoice, PaymentIntent, SearchClause, SearchQuery, Subscription};
+use payup::stripe_ext::product::Product;
+
+/// A search result page holding `object`, pointing at `next_page` if given.
+fn page_of(object: &str, next_page: Option<&str>) -> &'static str {
+    let next_page = next_page.map(|token| format!("\"{}\"", token)).unwrap_or_else(|| "null".to_string());
+    let body = format!(
+        r#"{{"object":"search_result","url":"/v1/search","has_more":{},"data":[{}],"next_page":{}}}"#,
+        next_page != "null",
+        object,
+        next_page
+    );
+    Box::leak(body.into_boxed_str())
+}
+
+#[tokio::test]
+async fn customer_search_follows_next_page_tokens() {
+    let transport = FakeTransport::new(&[(
+        "GET /v1/customers/search",
+        page_of(r#"{"id":"cus_1","object":"customer","email":"jenny@example.com"}"#, Some("page_2")),
+    )]);
+    let query = SearchQuery::new(SearchClause::field("email").eq("jenny@example.com"))
+        .and(SearchClause::metadata("tier").eq("gold"));
+
+    let customers: Vec<Customer> =
+        Customer::search_stream(auth(transport.clone()), &query, PageRequest::new().limit(1).max_items(2))
+            .try_collect()
+            .await
+            .unwrap();
+
+    assert_eq!(customers.len(), 2);
+    let requests = transport.requests();
+    assert_eq!(requests.len(), 2);
+    assert_eq!(requests[0].target, "GET /v1/customers/search");
+    assert!(has(&requests[0].query, "query", "email:'jenny@example.com' AND metadata['tier']:'gold'"));
+    assert!(has(&requests[0].query, "limit", "1"));
+    assert!(!requests[0].query.iter().any(|(k, _)| k == "page"));
+    assert!(has(&requests[1].query, "page", "page_2"));
+}
+
+#[tokio::test]
+async fn search_resumes_from_a_saved_token() {
+    let transport = FakeTransport::new(&[(
+        "GET /v1/charges/search",
+        page_of(r#"{"id":"ch_1","object":"charge","amount":1500}"#, None),
+    )]);
+    let query = SearchQuery::new(SearchClause::field("amount").gt(1000));
+
+    let charges: Vec<Charge> =
+        Charge::search_stream(auth(transport.clone()), &query, PageRequest::new().starting_after("page_5"))
+            .try_collect()
+            .await
+            .unwrap();
+
+    assert_eq!(charges[0].amount.as_deref(), Some("1500"));
+    let requests = transport.requests();
+    assert!(has(&requests[0].query, "query", "amount>1000"));
+    assert!(has(&requests[0].query, "page", "page_5"));
+}
+
+#[tokio::test]
+async fn invalid_queries_fail_without_a_request() {
+    let transport = FakeTransport::new(&[]);
+    let query = SearchQuery::new(SearchClause::field("status").eq("active"))
+        .and(SearchClause::field("currency").eq("usd"))
+        .or(SearchClause::field("currency").eq("eur"));
+
+    let result = Customer::async_search(auth(transport.clone()), &query).await;
+
+    assert!(matches!(result, Err(PayupError::ValidationError(_))));
+    assert!(transport.requests().is_empty());
+}
+
+#[tokio::test]
+async fn each_resource_searches_its_own_endpoint() {
+    let transport = FakeTransport::new(&[
+        (
+            "GET /v1/payment_intents/search",
+            page_of(
+                r#"{"id":"pi_1","object":"payment_intent","amount":2000,"capture_method":"automatic",
+                    "confirmation_method":"automatic","created":1700000000,"currency":"usd","livemode":false,
+                    "status":"succeeded"}"#,
+                None,
+            ),
+        ),
+        (
+            "GET /v1/invoices/search",
+            page_of(
+                r#"{"id":"in_1","object":"invoice","amount_due":500,"amount_paid":0,"amount_remaining":500,
+                    "attempt_count":0,"attempted":false,"collection_method":"send_invoice","created":1700000000,
+                    "currency":"usd","livemode":false,"paid":false,"post_payment_credit_notes_amount":0,
+                    "pre_payment_credit_notes_amount":0,"starting_balance":0,"subtotal":500,"total":500}"#,
+                None,
+            ),
+        ),
+        ("GET /v1/subscriptions/search", page_of(r#"{"id":"sub_1","object":"subscription","status":"past_due"}"#, None)),
+        ("GET /v1/products/search", page_of(r#"{"id":"prod_1","object":"product","name":"Gold"}"#, None)),
+    ]);
+    let auth = auth(transport.clone());
+    let query = SearchQuery::new(SearchClause::field("status").eq("succeeded"));
+
+    let payment_intents: Vec<PaymentIntent> =
+        PaymentIntent::search_stream(&auth, &query, PageRequest::new()).try_collect().await.unwrap();
+    let invoices = Invoice::search(&auth, &SearchQuery::new(SearchClause::field("total").gte(500))).await.unwrap();
+    let subscriptions = Subscription::async_search(auth.clone(), &SearchQuery::raw("status:'past_due'")).await.unwrap();
+    let products = Product::async_search(auth.clone(), &SearchQuery::new(SearchClause::field("name").contains("Gol")))
+        .await
+        .unwrap();
+
+    assert_eq!(payment_intents[0].id, "pi_1");
+    assert_eq!(invoices[0].id, "in_1");
+    assert_eq!(subscriptions[0].id.as_deref(), Some("sub_1"));
+    assert_eq!(products[0].name, "Gold");
+    let queries: Vec<_> = transport
+        .requests()
+        .into_iter()
+        .map(|r| (r.target, r.query.into_iter().find(|(k, _)| k == "query").unwrap().1))
+        .collect();
+    assert_eq!(
+        queries,
+        vec![
+            ("GET /v1/payment_intents/search".to_string(), "status:'succeeded'".to_string()),
+            ("GET /v1/invoices/search".to_string(), "total>=500".to_string()),
+            ("GET /v1/subscriptions/search".to_string(), "status:'past_due'".to_string()),
+            ("GET /v1/products/search".to_string(), "name~'Gol'".to_string()),
+        ]
+    );
+}
@@ -0,0 +1,149 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::error::PayupError;
+use payup::payment_provider::{PaymentMethod as UnifiedPaymentMethod, PaymentMethodType, PaymentProvider};
+use payup::stripe::setup_intent::{
+    CancelSetupIntentParams, ConfirmSetupIntentParams, CreateSetupIntentParams, SetupIntent,
+    SetupIntentCancellationReason, SetupIntentStatus,
+};
+use payup::stripe::{StripeProvider, WebhookEventType};
+
+fn card(id: Option<&str>) -> UnifiedPaymentMethod {
+    UnifiedPaymentMethod {
+        id: id.map(str::to_string),
+        method_type: PaymentMethodType::Card,
+        card: None,
+        bank_account: None,
+    }
+}
+
+const REQUIRES_PAYMENT_METHOD: &str = r#"{"id":"seti_1","object":"setup_intent","client_secret":"seti_1_secret_abc",
+    "created":1700000000,"livemode":false,"payment_method_types":["card"],"status":"requires_payment_method",
+    "usage":"off_session"}"#;
+
+const REQUIRES_ACTION: &str = r#"{"id":"seti_1","object":"setup_intent","client_secret":"seti_1_secret_abc",
+    "created":1700000000,"livemode":false,"payment_method":"pm_1","payment_method_types":["card"],
+    "status":"requires_action","usage":"off_session","next_action":{"type":"redirect_to_url",
+    "redirect_to_url":{"return_url":"https://example.com/return","url":"https://hooks.stripe.com/3d_secure/seti_1"}}}"#;
+
+const SUCCEEDED: &str = r#"{"id":"seti_1","object":"setup_intent","client_secret":"seti_1_secret_abc",
+    "created":1700000000,"livemode":false,"payment_method":"pm_1","payment_method_types":["card"],
+    "status":"succeeded","usage":"off_session"}"#;
+
+const CANCELED: &str = r#"{"id":"seti_1","object":"setup_intent","created":1700000000,"livemode":false,
+    "cancellation_reason":"abandoned","payment_method_types":["card"],"status":"canceled"}"#;
+
+const SETUP_INTENTS: &str = r#"{"object":"list","url":"/v1/setup_intents","has_more":false,"data":[
+    {"id":"seti_1","object":"setup_intent","created":1700000000,"livemode":false,"status":"succeeded"}]}"#;
+
+const PAYMENT_METHOD: &str = r#"{"id":"pm_1","object":"payment_method","type":"card","created":1700000000,
+    "livemode":false,"card":{"brand":"visa","last4":"4242","exp_month":12,"exp_year":2030}}"#;
+
+#[test]
+fn test_confirm_surfaces_redirect_next_action() {
+    let transport = FakeTransport::new(&[("POST /v1/setup_intents/seti_1/confirm", REQUIRES_ACTION)]);
+
+    let params = ConfirmSetupIntentParams {
+        payment_method: Some("pm_1".to_string()),
+        return_url: Some("https://example.com/return".to_string()),
+    };
+    let intent = SetupIntent::confirm(&auth(transport.clone()), "seti_1", params).expect("fake transport should answer");
+
+    assert!(intent.requires_action());
+    let next_action = intent.next_action.as_ref().unwrap();
+    assert_eq!(next_action.redirect_url(), Some("https://hooks.stripe.com/3d_secure/seti_1"));
+    let request = &transport.requests()[0];
+    assert!(has(&request.form, "payment_method", "pm_1"));
+    assert!(has(&request.form, "return_url", "https://example.com/return"));
+}
+
+#[tokio::test]
+async fn test_create_cancel_and_list() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/setup_intents", REQUIRES_PAYMENT_METHOD),
+        ("POST /v1/setup_intents/seti_1/cancel", CANCELED),
+        ("GET /v1/setup_intents", SETUP_INTENTS),
+    ]);
+    let auth = auth(transport.clone());
+
+    let params = CreateSetupIntentParams {
+        customer: Some("cus_1".to_string()),
+        payment_method_types: Some(vec!["card".to_string()]),
+        ..Default::default()
+    };
+    let intent = SetupIntent::create_async(&auth, params).await.expect("fake transport should answer");
+    assert_eq!(intent.status, SetupIntentStatus::RequiresPaymentMethod);
+    assert_eq!(intent.client_secret.as_deref(), Some("seti_1_secret_abc"));
+
+    let params = CancelSetupIntentParams { cancellation_reason: Some(SetupIntentCancellationReason::Abandoned) };
+    let intent = SetupIntent::cancel_async(&auth, "seti_1", params).await.expect("fake transport should answer");
+    assert_eq!(intent.status, SetupIntentStatus::Canceled);
+
+    let intents = SetupIntent::list_async(&auth, Some("cus_1"), PageRequest::new())
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(intents.len(), 1);
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "customer", "cus_1"));
+    assert!(has(&requests[0].form, "payment_method_types[0]", "card"));
+    assert!(has(&requests[1].form, "cancellation_reason", "abandoned"));
+}
+
+#[tokio::test]
+async fn test_provider_saves_tokenized_card_through_setup_intent() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/setup_intents", SUCCEEDED),
+        ("GET /v1/payment_methods/pm_1", PAYMENT_METHOD),
+    ]);
+    let provider = StripeProvider::new("sk_test".to_string()).with_transport(transport.clone());
+
+    let saved = provider.create_payment_method(&card(Some("pm_1"))).await.expect("setup should succeed");
+    assert_eq!(saved.id.as_deref(), Some("pm_1"));
+
+    let requests = transport.requests();
+    assert_eq!(requests[0].target, "POST /v1/setup_intents");
+    assert!(has(&requests[0].form, "payment_method", "pm_1"));
+    assert!(has(&requests[0].form, "confirm", "true"));
+    assert!(has(&requests[0].form, "usage", "off_session"));
+    assert!(requests.iter().all(|r| r.target != "POST /v1/payment_methods"));
+}
+
+#[tokio::test]
+async fn test_provider_reports_required_action() {
+    let transport = FakeTransport::new(&[("POST /v1/setup_intents", REQUIRES_ACTION)]);
+    let provider = StripeProvider::new("sk_test".to_string()).with_transport(transport.clone());
+
+    match provider.create_payment_method(&card(Some("pm_1"))).await {
+        Err(PayupError::ActionRequired { intent_id, client_secret, redirect_url, .. }) => {
+            assert_eq!(intent_id, "seti_1");
+            assert_eq!(client_secret.as_deref(), Some("seti_1_secret_abc"));
+            assert_eq!(redirect_url.as_deref(), Some("https://hooks.stripe.com/3d_secure/seti_1"));
+        }
+        other => panic!("expected ActionRequired, got {:?}", other),
+    }
+}
+
+#[tokio::test]
+async fn test_provider_without_token_hands_back_client_secret() {
+    let transport = FakeTransport::new(&[("POST /v1/setup_intents", REQUIRES_PAYMENT_METHOD)]);
+    let provider = StripeProvider::new("sk_test".to_string()).with_transport(transport.clone());
+
+    let err = provider.create_payment_method(&card(None)).await.unwrap_err();
+    assert!(matches!(
+        &err,
+        PayupError::ActionRequired { client_secret: Some(secret), .. } if secret == "seti_1_secret_abc"
+    ));
+    assert_eq!(err.decline_code(), Some("authentication_required"));
+    assert!(!has(&transport.requests()[0].form, "confirm", "true"));
+}
+
+#[test]
+fn test_setup_intent_webhook_event_types() {
+    assert_eq!(WebhookEventType::from("setup_intent.succeeded"), WebhookEventType::SetupIntentSucceeded);
+    assert_eq!(WebhookEventType::from("setup_intent.setup_failed"), WebhookEventType::SetupIntentFailed);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 use payup::stripe::{Auth, StripeProvider};
 use payup::payment_provider::{PaymentProvider, Subscription, SubscriptionStatus};
 use std::env;
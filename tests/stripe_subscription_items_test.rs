@@ -0,0 +1,163 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::stripe::invoice::{Invoice, UpcomingInvoiceItemParams, UpcomingInvoiceParams};
+use payup::stripe::subscription_item::{
+    CreateSubscriptionItemParams, DeleteSubscriptionItemParams, ProrationBehavior, SubscriptionItem,
+};
+use payup::stripe::subscription_schedule::{
+    CreateSubscriptionScheduleParams, EndBehavior, PhaseItemParams, PhaseParams, ScheduleStartDate,
+    SubscriptionSchedule, SubscriptionScheduleStatus,
+};
+use payup::stripe::{Subscription, SubscriptionItemParams};
+
+const ITEM: &str = r#"{"id":"si_1","object":"subscription_item","created":1700000000,"quantity":5,
+    "subscription":"sub_1","price":{"id":"price_seats","object":"price","active":true,"billing_scheme":"per_unit",
+    "created":1690000000,"currency":"usd","livemode":false,"product":"prod_1","type":"recurring","unit_amount":800}}"#;
+
+const ITEMS: &str = r#"{"object":"list","url":"/v1/subscription_items","has_more":false,"data":[{"id":"si_1",
+    "object":"subscription_item","created":1700000000,"quantity":5,"subscription":"sub_1"}]}"#;
+
+const DELETED_ITEM: &str = r#"{"id":"si_1","object":"subscription_item","deleted":true}"#;
+
+const SCHEDULE: &str = r#"{"id":"sub_sched_1","object":"subscription_schedule","created":1700000000,
+    "customer":"cus_1","end_behavior":"release","livemode":false,"status":"active","subscription":"sub_1",
+    "current_phase":{"start_date":1700000000,"end_date":1702592000},
+    "phases":[{"start_date":1700000000,"end_date":1702592000,"items":[{"price":"price_starter","quantity":1}],
+    "proration_behavior":"create_prorations"},{"start_date":1702592000,"end_date":1705270400,
+    "items":[{"price":"price_pro","quantity":1}]}]}"#;
+
+const UPCOMING: &str = r#"{"object":"invoice","amount_due":1500,"amount_paid":0,"amount_remaining":1500,
+    "attempt_count":0,"attempted":false,"collection_method":"charge_automatically","created":1700000000,
+    "currency":"usd","customer":"cus_1","livemode":false,"paid":false,"post_payment_credit_notes_amount":0,
+    "pre_payment_credit_notes_amount":0,"starting_balance":0,"subtotal":1500,"total":1500,
+    "lines":{"object":"list","url":"/v1/invoices/upcoming/lines","has_more":false,"data":[
+    {"id":"il_tmp_1","object":"line_item","amount":-500,"currency":"usd","discountable":false,"livemode":false,
+    "period":{"start":1700000000,"end":1702592000},"proration":true,"type":"invoiceitem",
+    "price":{"id":"price_starter","object":"price"}},
+    {"id":"il_tmp_2","object":"line_item","amount":2000,"currency":"usd","discountable":true,"livemode":false,
+    "period":{"start":1700000000,"end":1702592000},"proration":false,"type":"subscription",
+    "price":{"id":"price_pro","object":"price"}}]}}"#;
+
+#[test]
+fn test_subscription_item_create_and_delete() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/subscription_items", ITEM),
+        ("DELETE /v1/subscription_items/si_1", DELETED_ITEM),
+    ]);
+    let auth = auth(transport.clone());
+
+    let mut params = CreateSubscriptionItemParams::new("sub_1", "price_seats", 5);
+    params.proration_behavior = Some(ProrationBehavior::AlwaysInvoice);
+    let item = SubscriptionItem::create(&auth, params).expect("fake transport should answer");
+    assert_eq!(item.quantity, Some(5));
+    assert_eq!(item.price.unwrap().id, "price_seats");
+    let request = transport.last_request();
+    assert!(has(&request.form, "subscription", "sub_1"));
+    assert!(has(&request.form, "quantity", "5"));
+    assert!(has(&request.form, "proration_behavior", "always_invoice"));
+
+    let params = DeleteSubscriptionItemParams {
+        proration_behavior: Some(ProrationBehavior::None),
+        ..Default::default()
+    };
+    let deleted = SubscriptionItem::delete(&auth, "si_1", params).expect("fake transport should answer");
+    assert!(deleted.deleted);
+    assert!(has(&transport.last_request().query, "proration_behavior", "none"));
+}
+
+#[tokio::test]
+async fn test_subscription_items_list_filters_by_subscription() {
+    let transport = FakeTransport::new(&[("GET /v1/subscription_items", ITEMS)]);
+
+    let items = SubscriptionItem::list_async(&auth(transport.clone()), "sub_1", PageRequest::new())
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(items.len(), 1);
+    assert!(has(&transport.last_request().query, "subscription", "sub_1"));
+}
+
+#[test]
+fn test_schedule_create_encodes_phases() {
+    let transport = FakeTransport::new(&[("POST /v1/subscription_schedules", SCHEDULE)]);
+
+    let params = CreateSubscriptionScheduleParams {
+        customer: Some("cus_1".to_string()),
+        start_date: Some(ScheduleStartDate::Now),
+        end_behavior: Some(EndBehavior::Release),
+        phases: Some(vec![
+            PhaseParams {
+                items: vec![PhaseItemParams::new("price_starter", 1)],
+                iterations: Some(1),
+                ..Default::default()
+            },
+            PhaseParams {
+                items: vec![PhaseItemParams::new("price_pro", 1)],
+                ..Default::default()
+            },
+        ]),
+        ..Default::default()
+    };
+    let schedule = SubscriptionSchedule::create(&auth(transport.clone()), params).expect("fake transport should answer");
+    assert_eq!(schedule.status, SubscriptionScheduleStatus::Active);
+    assert_eq!(schedule.phases.len(), 2);
+    assert_eq!(schedule.phases[1].items[0].price, "price_pro");
+
+    let request = transport.last_request();
+    assert!(has(&request.form, "start_date", "now"));
+    assert!(has(&request.form, "end_behavior", "release"));
+    assert!(has(&request.form, "phases[0][items][0][price]", "price_starter"));
+    assert!(has(&request.form, "phases[0][iterations]", "1"));
+    assert!(has(&request.form, "phases[1][items][0][quantity]", "1"));
+}
+
+#[tokio::test]
+async fn test_upcoming_invoice_previews_proration() {
+    let transport = FakeTransport::new(&[("GET /v1/invoices/upcoming", UPCOMING)]);
+
+    let params = UpcomingInvoiceParams {
+        subscription: Some("sub_1".to_string()),
+        subscription_items: Some(vec![UpcomingInvoiceItemParams::swap("si_1", "price_pro")]),
+        subscription_proration_behavior: Some(ProrationBehavior::CreateProrations),
+        subscription_proration_date: Some(1700000000),
+        ..Default::default()
+    };
+    let preview = Invoice::upcoming(&auth(transport.clone()), params).await.expect("fake transport should answer");
+
+    assert!(preview.id.is_empty());
+    let lines = preview.lines.unwrap().data;
+    let prorations: i64 = lines.iter().filter(|line| line.proration).map(|line| line.amount).sum();
+    assert_eq!(prorations, -500);
+    assert_eq!(lines[1].price.as_deref(), Some("price_pro"));
+
+    let request = transport.last_request();
+    assert!(has(&request.query, "subscription", "sub_1"));
+    assert!(has(&request.query, "subscription_items[0][id]", "si_1"));
+    assert!(has(&request.query, "subscription_items[0][price]", "price_pro"));
+    assert!(has(&request.query, "subscription_proration_behavior", "create_prorations"));
+    assert!(has(&request.query, "subscription_proration_date", "1700000000"));
+}
+
+#[test]
+fn test_subscription_params_carry_item_quantities() {
+    let mut subscription = Subscription::new();
+    subscription.price_items = Some(vec!["price_ignored".to_string()]);
+    subscription.items = Some(vec![
+        SubscriptionItemParams::quantity("si_1", 10),
+        SubscriptionItemParams::delete("si_2"),
+        SubscriptionItemParams::price("price_addon", 2),
+    ]);
+    subscription.proration_behavior = Some(ProrationBehavior::None);
+
+    let params = subscription.to_params();
+    let has = |key: &str, value: &str| params.iter().any(|(k, v)| *k == key && v == value);
+    assert!(has("items[0][id]", "si_1"));
+    assert!(has("items[0][quantity]", "10"));
+    assert!(has("items[1][deleted]", "true"));
+    assert!(has("items[2][price]", "price_addon"));
+    assert!(has("proration_behavior", "none"));
+    assert!(!params.iter().any(|(_, v)| v == "price_ignored"));
+}
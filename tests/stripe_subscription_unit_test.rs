@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 #[cfg(test)]
 mod tests {
     use payup::stripe::subscription::Subscription;
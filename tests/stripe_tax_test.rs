@@ -0,0 +1,157 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::stripe::builders::InvoiceBuilder;
+use payup::stripe::invoice::{self, Address};
+use payup::stripe::tax::{
+    CreateTaxCalculationParams, CreateTaxTransactionParams, ReverseTaxTransactionParams, TaxCalculation,
+    TaxCustomerDetails, TaxLineItemParams, TaxTransaction, TaxTransactionType,
+};
+use payup::stripe::tax_id::{CreateTaxIdParams, TaxId, TaxIdVerificationStatus};
+use payup::stripe::tax_rate::{CreateTaxRateParams, ListTaxRatesParams, TaxRate};
+use payup::stripe::Invoice;
+
+const TAX_RATE: &str = r#"{"id":"txr_1","object":"tax_rate","active":true,"country":"DE","created":1700000000,
+    "description":null,"display_name":"VAT","effective_percentage":19.0,"inclusive":false,"jurisdiction":"DE",
+    "livemode":false,"metadata":{},"percentage":19.0,"state":null,"tax_type":"vat"}"#;
+
+const TAX_RATES: &str = r#"{"object":"list","url":"/v1/tax_rates","has_more":false,"data":[
+    {"id":"txr_1","object":"tax_rate","active":true,"created":1700000000,"display_name":"VAT",
+    "inclusive":false,"livemode":false,"percentage":19.0}]}"#;
+
+const TAX_ID: &str = r#"{"id":"txi_1","object":"tax_id","country":"DE","created":1700000000,"customer":"cus_1",
+    "livemode":false,"type":"eu_vat","value":"DE123456789","verification":{"status":"pending",
+    "verified_address":null,"verified_name":null}}"#;
+
+const DELETED_TAX_ID: &str = r#"{"id":"txi_1","object":"tax_id","deleted":true}"#;
+
+const CALCULATION: &str = r#"{"id":"taxcalc_1","object":"tax.calculation","amount_total":10000,"currency":"eur",
+    "customer":null,"customer_details":{"address":{"country":"FR","postal_code":"75001"},"address_source":"billing",
+    "ip_address":null,"tax_ids":[{"type":"eu_vat","value":"FR12345678901"}],"taxability_override":"none"},
+    "expires_at":1707776000,"livemode":false,"shipping_cost":null,"tax_amount_exclusive":0,"tax_amount_inclusive":0,
+    "tax_breakdown":[{"amount":0,"inclusive":false,"tax_rate_details":{"country":"FR","percentage_decimal":"0.0",
+    "state":null,"tax_type":"vat"},"taxability_reason":"reverse_charge","taxable_amount":10000}],
+    "tax_date":1700000000}"#;
+
+const TRANSACTION: &str = r#"{"id":"tax_1","object":"tax.transaction","created":1700000000,"currency":"eur",
+    "customer":null,"customer_details":{"address":{"country":"FR"},"address_source":"billing","tax_ids":[]},
+    "livemode":false,"metadata":{},"reference":"order-42","reversal":null,"shipping_cost":null,
+    "tax_date":1700000000,"type":"transaction"}"#;
+
+const REVERSAL: &str = r#"{"id":"tax_2","object":"tax.transaction","created":1700000500,"currency":"eur",
+    "customer":null,"customer_details":{"address":{"country":"FR"},"address_source":"billing","tax_ids":[]},
+    "livemode":false,"metadata":{},"reference":"order-42-refund","reversal":{"original_transaction":"tax_1"},
+    "shipping_cost":null,"tax_date":1700000000,"type":"reversal"}"#;
+
+const INVOICE: &str = r#"{"id":"in_1","object":"invoice","amount_due":11900,"amount_paid":0,"amount_remaining":11900,
+    "attempt_count":0,"attempted":false,"automatic_tax":{"enabled":true,"status":"complete"},
+    "collection_method":"charge_automatically","created":1700000000,"currency":"eur","customer":"cus_1",
+    "livemode":false,"paid":false,"post_payment_credit_notes_amount":0,"pre_payment_credit_notes_amount":0,
+    "starting_balance":0,"subtotal":10000,"total":11900,
+    "lines":{"object":"list","url":"/v1/invoices/in_1/lines","has_more":false,"data":[]}}"#;
+
+#[tokio::test]
+async fn test_tax_rates_and_customer_tax_ids() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/tax_rates", TAX_RATE),
+        ("GET /v1/tax_rates", TAX_RATES),
+        ("POST /v1/customers/cus_1/tax_ids", TAX_ID),
+        ("DELETE /v1/customers/cus_1/tax_ids/txi_1", DELETED_TAX_ID),
+    ]);
+    let auth = auth(transport.clone());
+
+    let mut params = CreateTaxRateParams::new("VAT", 19.0, false);
+    params.country = Some("DE".to_string());
+    params.tax_type = Some("vat".to_string());
+    let rate = TaxRate::create_async(&auth, params).await.expect("fake transport should answer");
+    assert_eq!(rate.effective_percentage, Some(19.0));
+
+    let filters = ListTaxRatesParams { active: Some(true), ..Default::default() };
+    let rates = TaxRate::list_async(&auth, &filters, PageRequest::new())
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(rates.len(), 1);
+
+    let tax_id = TaxId::create_async(&auth, "cus_1", CreateTaxIdParams::new("eu_vat", "DE123456789"))
+        .await
+        .expect("fake transport should answer");
+    assert_eq!(tax_id.type_field, "eu_vat");
+    assert_eq!(tax_id.verification.unwrap().status, TaxIdVerificationStatus::Pending);
+
+    let deleted = TaxId::delete_async(&auth, "cus_1", "txi_1").await.expect("fake transport should answer");
+    assert!(deleted.deleted);
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "display_name", "VAT"));
+    assert!(has(&requests[0].form, "inclusive", "false"));
+    assert!(has(&requests[0].form, "country", "DE"));
+    assert!(has(&requests[1].query, "active", "true"));
+    assert!(has(&requests[2].form, "type", "eu_vat"));
+    assert!(has(&requests[2].form, "value", "DE123456789"));
+    assert_eq!(requests[3].target, "DELETE /v1/customers/cus_1/tax_ids/txi_1");
+}
+
+#[test]
+fn test_calculation_and_transactions() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/tax/calculations", CALCULATION),
+        ("POST /v1/tax/transactions/create_from_calculation", TRANSACTION),
+        ("POST /v1/tax/transactions/create_reversal", REVERSAL),
+    ]);
+    let auth = auth(transport.clone());
+
+    let details = TaxCustomerDetails {
+        address: Some(Address {
+            city: None,
+            country: Some("FR".to_string()),
+            line1: None,
+            line2: None,
+            postal_code: Some("75001".to_string()),
+            state: None,
+        }),
+        tax_ids: vec![invoice::TaxId { type_field: "eu_vat".to_string(), value: "FR12345678901".to_string() }],
+        ..Default::default()
+    };
+    let params = CreateTaxCalculationParams::for_details("eur", details, vec![TaxLineItemParams::new(10000, "line-1")]);
+    let calculation = TaxCalculation::create(&auth, params).expect("fake transport should answer");
+    assert_eq!(calculation.tax_amount_exclusive, 0);
+    assert_eq!(calculation.tax_breakdown[0].taxability_reason, "reverse_charge");
+
+    let calculation_id = calculation.id.unwrap();
+    let transaction =
+        TaxTransaction::create_from_calculation(&auth, CreateTaxTransactionParams::new(&calculation_id, "order-42"))
+            .expect("fake transport should answer");
+    assert_eq!(transaction.type_field, TaxTransactionType::Transaction);
+
+    let reversal = TaxTransaction::create_reversal(&auth, ReverseTaxTransactionParams::full(&transaction.id, "order-42-refund"))
+        .expect("fake transport should answer");
+    assert_eq!(reversal.reversal.unwrap().original_transaction, Some("tax_1".to_string()));
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "currency", "eur"));
+    assert!(has(&requests[0].form, "customer_details[address][country]", "FR"));
+    assert!(has(&requests[0].form, "customer_details[tax_ids][0][type]", "eu_vat"));
+    assert!(has(&requests[0].form, "line_items[0][amount]", "10000"));
+    assert!(has(&requests[0].form, "line_items[0][reference]", "line-1"));
+    assert!(has(&requests[1].form, "calculation", "taxcalc_1"));
+    assert!(has(&requests[1].form, "reference", "order-42"));
+    assert!(has(&requests[2].form, "mode", "full"));
+    assert!(has(&requests[2].form, "original_transaction", "tax_1"));
+}
+
+#[tokio::test]
+async fn test_invoice_with_automatic_tax() {
+    let transport = FakeTransport::new(&[("POST /v1/invoices", INVOICE)]);
+    let auth = auth(transport.clone());
+
+    let params = InvoiceBuilder::new("cus_1").automatic_tax(true).build();
+    let invoice = Invoice::create(&auth, params).await.expect("fake transport should answer");
+    assert_eq!(invoice.automatic_tax.unwrap().status, Some("complete".to_string()));
+
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "customer", "cus_1"));
+    assert!(has(&requests[0].form, "automatic_tax[enabled]", "true"));
+}
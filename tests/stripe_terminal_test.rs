@@ -0,0 +1,216 @@
+#![cfg(feature = "stripe")]
+
+mod common;
+
+use common::{auth, has, FakeTransport};
+use payup::builders::PageRequest;
+use payup::stripe::invoice::Address;
+use payup::stripe::payment_intent::{CardPresentOptions, CreatePaymentIntentParams, PaymentIntent, PaymentMethodOptions};
+use payup::stripe::terminal::{
+    Configuration, ConfigurationParams, ConnectionToken, CreateConnectionTokenParams, CreateLocationParams,
+    CreateReaderParams, ListReadersParams, Location, PresentPaymentMethodParams, ProcessConfig,
+    ProcessPaymentIntentParams, Reader, ReaderActionStatus, ReaderStatus, TippingSettings,
+};
+
+const LOCATION: &str = r#"{
+    "id": "tml_123",
+    "object": "terminal.location",
+    "address": {"city": "Portland", "country": "US", "line1": "1 Main St", "line2": null, "postal_code": "97201", "state": "OR"},
+    "configuration_overrides": null,
+    "display_name": "Kiosk 1",
+    "livemode": false,
+    "metadata": {}
+}"#;
+
+const READER_IDLE: &str = r#"{
+    "id": "tmr_123",
+    "object": "terminal.reader",
+    "action": null,
+    "device_sw_version": "2.6.2.0",
+    "device_type": "simulated_wisepos_e",
+    "ip_address": "0.0.0.0",
+    "label": "Front counter",
+    "livemode": false,
+    "location": "tml_123",
+    "metadata": {},
+    "serial_number": "259cd19c-b902-4730-96a1-09183be6e7f7",
+    "status": "online"
+}"#;
+
+const READER_PROCESSING: &str = r#"{
+    "id": "tmr_123", "object": "terminal.reader", "device_type": "simulated_wisepos_e", "label": "Front counter",
+    "livemode": false, "location": "tml_123", "serial_number": "259cd19c", "status": "online",
+    "action": {
+        "failure_code": null,
+        "failure_message": null,
+        "process_payment_intent": {"payment_intent": "pi_123"},
+        "status": "in_progress",
+        "type": "process_payment_intent"
+    }
+}"#;
+
+const READER_SUCCEEDED: &str = r#"{
+    "id": "tmr_123", "object": "terminal.reader", "device_type": "simulated_wisepos_e", "label": "Front counter",
+    "livemode": false, "location": "tml_123", "serial_number": "259cd19c", "status": "online",
+    "action": {
+        "process_payment_intent": {"payment_intent": "pi_123"},
+        "status": "succeeded",
+        "type": "process_payment_intent"
+    }
+}"#;
+
+const PAYMENT_INTENT: &str = r#"{
+    "id": "pi_123",
+    "object": "payment_intent",
+    "amount": 1299,
+    "capture_method": "automatic",
+    "client_secret": "pi_123_secret",
+    "confirmation_method": "automatic",
+    "created": 1700000000,
+    "currency": "usd",
+    "livemode": false,
+    "payment_method_options": {"card_present": {"request_extended_authorization": false, "request_incremental_authorization_support": true}},
+    "payment_method_types": ["card_present"],
+    "status": "requires_payment_method"
+}"#;
+
+const CONFIGURATION: &str = r#"{
+    "id": "tmc_123",
+    "object": "terminal.configuration",
+    "bbpos_wisepos_e": {"splashscreen": "file_123"},
+    "is_account_default": false,
+    "livemode": false,
+    "name": "Kiosks",
+    "offline": {"enabled": true},
+    "tipping": {"usd": {"fixed_amounts": [100, 200], "percentages": [15, 18, 20], "smart_tip_threshold": 1000}}
+}"#;
+
+#[tokio::test]
+async fn connection_token_is_scoped_to_location() {
+    let transport = FakeTransport::new(&[(
+        "POST /v1/terminal/connection_tokens",
+        r#"{"object": "terminal.connection_token", "location": "tml_123", "secret": "pst_test_123"}"#,
+    )]);
+    let params = CreateConnectionTokenParams { location: Some("tml_123".to_string()) };
+
+    let token = ConnectionToken::create_async(&auth(transport.clone()), params).await.unwrap();
+
+    assert_eq!(token.secret, "pst_test_123");
+    assert!(has(&transport.requests()[0].form, "location", "tml_123"));
+}
+
+#[tokio::test]
+async fn location_and_reader_registration() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/terminal/locations", LOCATION),
+        ("POST /v1/terminal/readers", READER_IDLE),
+        ("GET /v1/terminal/readers", Box::leak(format!(
+            r#"{{"object": "list", "has_more": false, "url": "/v1/terminal/readers", "data": [{}]}}"#,
+            READER_IDLE
+        ).into_boxed_str())),
+    ]);
+    let auth = auth(transport.clone());
+    let address = Address {
+        city: Some("Portland".to_string()),
+        country: Some("US".to_string()),
+        line1: Some("1 Main St".to_string()),
+        line2: None,
+        postal_code: Some("97201".to_string()),
+        state: Some("OR".to_string()),
+    };
+
+    let location = Location::create_async(&auth, CreateLocationParams::new("Kiosk 1", address)).await.unwrap();
+    let mut params = CreateReaderParams::new("simulated-wpe", &location.id);
+    params.label = Some("Front counter".to_string());
+    let reader = Reader::create_async(&auth, params).await.unwrap();
+    let filters = ListReadersParams { location: Some(location.id.clone()), status: Some(ReaderStatus::Online), ..Default::default() };
+    let readers = Reader::list_async(&auth, &filters, PageRequest::new()).await.unwrap();
+
+    assert_eq!(reader.status, Some(ReaderStatus::Online));
+    assert_eq!(readers[0].id, "tmr_123");
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "display_name", "Kiosk 1"));
+    assert!(has(&requests[0].form, "address[postal_code]", "97201"));
+    assert!(has(&requests[1].form, "registration_code", "simulated-wpe"));
+    assert!(has(&requests[1].form, "location", "tml_123"));
+    assert!(has(&requests[2].query, "location", "tml_123"));
+    assert!(has(&requests[2].query, "status", "online"));
+}
+
+#[tokio::test]
+async fn reader_processes_card_present_payment_intent() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/payment_intents", PAYMENT_INTENT),
+        ("POST /v1/terminal/readers/tmr_123/process_payment_intent", READER_PROCESSING),
+        ("POST /v1/test_helpers/terminal/readers/tmr_123/present_payment_method", READER_SUCCEEDED),
+    ]);
+    let auth = auth(transport.clone());
+    let mut params = CreatePaymentIntentParams::card_present(1299, "usd");
+    params.payment_method_options = Some(PaymentMethodOptions {
+        card_present: Some(CardPresentOptions {
+            request_incremental_authorization_support: Some(true),
+            ..Default::default()
+        }),
+        ..Default::default()
+    });
+
+    let payment_intent = PaymentIntent::create_async(&auth, params).await.unwrap();
+    let mut process = ProcessPaymentIntentParams::new(&payment_intent.id);
+    process.process_config = Some(ProcessConfig { skip_tipping: Some(true), ..Default::default() });
+    let processing = Reader::process_payment_intent_async(&auth, "tmr_123", process).await.unwrap();
+    let done = Reader::present_payment_method_async(&auth, "tmr_123", PresentPaymentMethodParams::card("4242424242424242"))
+        .await
+        .unwrap();
+
+    let card_present = payment_intent.payment_method_options.unwrap().card_present.unwrap();
+    assert_eq!(card_present.request_incremental_authorization_support, Some(true));
+    let action = processing.action.unwrap();
+    assert_eq!(action.status, ReaderActionStatus::InProgress);
+    assert_eq!(action.process_payment_intent.unwrap().payment_intent, "pi_123");
+    assert_eq!(done.action.unwrap().status, ReaderActionStatus::Succeeded);
+    let requests = transport.requests();
+    assert!(has(&requests[0].form, "payment_method_types[0]", "card_present"));
+    assert!(has(&requests[0].form, "payment_method_options[card_present][request_incremental_authorization_support]", "true"));
+    assert!(has(&requests[1].form, "payment_intent", "pi_123"));
+    assert!(has(&requests[1].form, "process_config[skip_tipping]", "true"));
+    assert!(has(&requests[2].form, "card_present[number]", "4242424242424242"));
+}
+
+#[tokio::test]
+async fn reader_action_can_be_canceled() {
+    let transport = FakeTransport::new(&[("POST /v1/terminal/readers/tmr_123/cancel_action", READER_IDLE)]);
+
+    let reader = Reader::cancel_action_async(&auth(transport.clone()), "tmr_123").await.unwrap();
+
+    assert!(reader.action.is_none());
+    assert_eq!(transport.requests()[0].target, "POST /v1/terminal/readers/tmr_123/cancel_action");
+}
+
+#[tokio::test]
+async fn configuration_sends_tipping_by_currency() {
+    let transport = FakeTransport::new(&[
+        ("POST /v1/terminal/configurations", CONFIGURATION),
+        ("DELETE /v1/terminal/configurations/tmc_123", r#"{"id": "tmc_123", "object": "terminal.configuration", "deleted": true}"#),
+    ]);
+    let auth = auth(transport.clone());
+    let mut params = ConfigurationParams::default().tipping(
+        "USD",
+        TippingSettings {
+            fixed_amounts: Some(vec![100, 200]),
+            percentages: Some(vec![15, 18, 20]),
+            smart_tip_threshold: Some(1000),
+        },
+    );
+    params.name = Some("Kiosks".to_string());
+
+    let configuration = Configuration::create_async(&auth, params).await.unwrap();
+    let deleted = Configuration::delete_async(&auth, &configuration.id).await.unwrap();
+
+    assert_eq!(configuration.tipping.unwrap()["usd"].percentages, Some(vec![15, 18, 20]));
+    assert!(configuration.offline.unwrap().enabled.unwrap());
+    assert!(deleted.deleted);
+    let form = &transport.requests()[0].form;
+    assert!(has(form, "tipping[usd][percentages][1]", "18"));
+    assert!(has(form, "tipping[usd][smart_tip_threshold]", "1000"));
+    assert!(has(form, "name", "Kiosks"));
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 #[cfg(test)]
 mod stripe_unit_tests {
     use payup::stripe::{
@@ -1,4 +1,6 @@
-use payup::stripe::{StripeWebhookHandler, WebhookEvent, WebhookEventType};
+#![cfg(feature = "stripe")]
+
+use payup::stripe::{PaymentIntentStatus, StripeEventObject, StripeWebhookHandler, WebhookEvent, WebhookEventType};
 use hmac::{Hmac, Mac};
 use sha2::Sha256;
 
@@ -220,4 +222,125 @@ fn test_webhook_event_data_extraction() {
     let request = event.request.unwrap();
     assert_eq!(request.id, Some("req_test123".to_string()));
     assert_eq!(request.idempotency_key, Some("idem_key_123".to_string()));
-}
\ No newline at end of file
+}
+
+/// Wrap `object` in an event of type `event_type`
+fn event_json(event_type: &str, object: &str) -> String {
+    format!(
+        r#"{{"id":"evt_typed","object":"event","type":"{}","created":1614556800,"livemode":false,
+            "pending_webhooks":1,"data":{{"object":{}}}}}"#,
+        event_type, object
+    )
+}
+
+#[test]
+fn test_construct_event_types_payment_intents() {
+    let secret = "whsec_test_secret";
+    let handler = StripeWebhookHandler::new(secret.to_string());
+    let payload = event_json(
+        "payment_intent.succeeded",
+        r#"{"id":"pi_typed","object":"payment_intent","amount":2000,"capture_method":"automatic",
+            "confirmation_method":"automatic","created":1614556800,"currency":"usd","livemode":false,
+            "status":"succeeded"}"#,
+    );
+    let timestamp = chrono::Utc::now().timestamp();
+    let header = format!("t={} v1={}", timestamp, generate_test_signature(secret, timestamp, &payload));
+
+    let event = handler.construct_event(&payload, &header).unwrap();
+
+    match event.data.typed_object() {
+        StripeEventObject::PaymentIntent(payment_intent) => {
+            assert_eq!(payment_intent.id, "pi_typed");
+            assert_eq!(payment_intent.amount, 2000);
+            assert!(matches!(payment_intent.status, PaymentIntentStatus::Succeeded));
+        }
+        other => panic!("expected a payment intent, got {:?}", other),
+    }
+}
+
+#[test]
+fn test_webhook_event_types_invoices() {
+    let event: WebhookEvent = serde_json::from_str(&event_json(
+        "invoice.paid",
+        r#"{"id":"in_typed","object":"invoice","amount_due":500,"amount_paid":500,"amount_remaining":0,
+            "attempt_count":1,"attempted":true,"collection_method":"charge_automatically","created":1614556800,
+            "currency":"usd","livemode":false,"paid":true,"post_payment_credit_notes_amount":0,
+            "pre_payment_credit_notes_amount":0,"starting_balance":0,"subtotal":500,"total":500,
+            "subscription":"sub_typed"}"#,
+    ))
+    .unwrap();
+
+    assert_eq!(event.object_id(), Some("in_typed".to_string()));
+    let StripeEventObject::Invoice(invoice) = event.data.typed_object() else {
+        panic!("expected an invoice, got {:?}", event.data.object);
+    };
+    assert_eq!(invoice.amount_paid, 500);
+    assert_eq!(invoice.subscription.as_deref(), Some("sub_typed"));
+}
+
+#[test]
+fn test_webhook_event_types_subscriptions_charges_and_disputes() {
+    let subscription: WebhookEvent = serde_json::from_str(&event_json(
+        "customer.subscription.deleted",
+        r#"{"id":"sub_typed","object":"subscription","customer":"cus_typed","status":"canceled"}"#,
+    ))
+    .unwrap();
+    let charge: WebhookEvent = serde_json::from_str(&event_json(
+        "charge.refunded",
+        r#"{"id":"ch_typed","object":"charge","amount":1500,"refunded":true}"#,
+    ))
+    .unwrap();
+    let dispute: WebhookEvent = serde_json::from_str(&event_json(
+        "charge.dispute.created",
+        r#"{"id":"dp_typed","object":"dispute","amount":1500,"charge":"ch_typed"}"#,
+    ))
+    .unwrap();
+
+    assert!(matches!(
+        subscription.data.typed_object(),
+        StripeEventObject::Subscription(ref s) if s.status.as_deref() == Some("canceled")
+    ));
+    assert!(matches!(charge.data.typed_object(), StripeEventObject::Charge(ref c) if c.stripe_amount == Some(1500)));
+    assert!(matches!(dispute.data.typed_object(), StripeEventObject::Dispute(ref d) if d.charge.as_deref() == Some("ch_typed")));
+}
+
+#[test]
+fn test_webhook_event_types_radar_objects() {
+    let warning: WebhookEvent = serde_json::from_str(&event_json(
+        "radar.early_fraud_warning.created",
+        r#"{"id":"issfr_typed","object":"radar.early_fraud_warning","actionable":true,"charge":"ch_typed",
+            "created":1700000000,"fraud_type":"unauthorized_use_of_card","livemode":false}"#,
+    ))
+    .unwrap();
+    let review: WebhookEvent = serde_json::from_str(&event_json(
+        "review.opened",
+        r#"{"id":"prv_typed","object":"review","charge":"ch_typed","created":1700000000,"livemode":false,
+            "open":true,"opened_reason":"rule","reason":"rule"}"#,
+    ))
+    .unwrap();
+
+    assert!(matches!(warning.data.typed_object(), StripeEventObject::EarlyFraudWarning(ref w) if w.actionable));
+    assert!(matches!(review.data.typed_object(), StripeEventObject::Review(ref r) if r.charge.as_deref() == Some("ch_typed")));
+    assert_eq!(review.object_id(), Some("prv_typed".to_string()));
+}
+
+#[test]
+fn test_webhook_event_falls_back_to_unknown_objects() {
+    // A type with no variant of its own
+    let payout: WebhookEvent = serde_json::from_str(&event_json(
+        "payout.paid",
+        r#"{"id":"po_typed","object":"payout","amount":1000}"#,
+    ))
+    .unwrap();
+    // A known type whose body doesn't match it
+    let partial: WebhookEvent = serde_json::from_str(&event_json(
+        "payment_intent.succeeded",
+        r#"{"id":"pi_partial","object":"payment_intent"}"#,
+    ))
+    .unwrap();
+
+    assert_eq!(payout.object_id(), Some("po_typed".to_string()));
+    assert!(matches!(payout.data.typed_object(), StripeEventObject::Unknown(ref v) if v["amount"] == 1000));
+    assert_eq!(partial.object_id(), Some("pi_partial".to_string()));
+    assert!(matches!(partial.data.typed_object(), StripeEventObject::Unknown(_)));
+}
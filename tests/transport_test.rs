@@ -0,0 +1,82 @@
+#![cfg(all(feature = "stripe", feature = "paypal"))]
+
+mod common;
+
+use common::FakeTransport;
+use payup::payment_provider::{DisputeStatus, PaymentProvider};
+use payup::paypal::{PayPalEnvironment, PayPalProvider};
+use payup::stripe::{Auth, Dispute, StripeProvider};
+
+const DISPUTE: &str = r#"{"id":"dp_123","object":"dispute","amount":500,"currency":"usd","status":"won"}"#;
+
+#[test]
+fn test_stripe_blocking_request_uses_injected_transport() {
+    let transport = FakeTransport::new(&[("/v1/disputes/dp_123", DISPUTE)]);
+    let auth = Auth::new("sk_test".to_string(), "sk_test".to_string()).with_transport(transport.clone());
+
+    let dispute = Dispute::get(auth, "dp_123".to_string()).expect("fake transport should answer");
+    assert_eq!(dispute.status.as_deref(), Some("won"));
+    assert_eq!(transport.targets(), vec!["GET /v1/disputes/dp_123"]);
+}
+
+#[tokio::test]
+async fn test_stripe_provider_uses_injected_transport() {
+    let transport = FakeTransport::new(&[("/v1/disputes/dp_123", DISPUTE)]);
+    let provider = StripeProvider::new("sk_test".to_string()).with_transport(transport.clone());
+
+    let dispute = provider.get_dispute("dp_123").await.expect("fake transport should answer");
+    assert_eq!(dispute.status, DisputeStatus::Won);
+    assert_eq!(dispute.amount.amount, 500);
+    assert_eq!(transport.targets(), vec!["GET /v1/disputes/dp_123"]);
+}
+
+#[tokio::test(flavor = "multi_thread")]
+async fn test_paypal_token_and_requests_use_injected_transport() {
+    let transport = FakeTransport::new(&[
+        (
+            "/v1/oauth2/token",
+            r#"{"access_token":"A21","token_type":"Bearer","app_id":"APP-1","expires_in":32400,"nonce":"n"}"#,
+        ),
+        (
+            "/v1/customer/disputes/PP-D-1",
+            r#"{"dispute_id":"PP-D-1","reason":"MERCHANDISE_OR_SERVICE_NOT_RECEIVED","status":"UNDER_REVIEW",
+                "dispute_amount":{"currency_code":"USD","value":"12.50"}}"#,
+        ),
+    ]);
+
+    // The token fetch in the constructor must not reach the real sandbox
+    let provider = tokio::task::block_in_place(|| {
+        PayPalProvider::with_transport(
+            "client".to_string(),
+            "secret".to_string(),
+            PayPalEnvironment::Sandbox,
+            transport.clone(),
+        )
+    })
+    .expect("fake transport should answer");
+
+    let dispute = provider.get_dispute("PP-D-1").await.expect("fake transport should answer");
+    assert_eq!(dispute.status, DisputeStatus::UnderReview);
+    assert_eq!(dispute.amount.amount, 1250);
+    assert_eq!(
+        transport.targets(),
+        vec!["POST /v1/oauth2/token", "GET /v1/customer/disputes/PP-D-1"]
+    );
+}
+
+#[cfg(feature = "square")]
+#[tokio::test]
+async fn test_square_provider_uses_injected_transport() {
+    use payup::square::{Environment, SquareProvider};
+
+    let transport = FakeTransport::new(&[(
+        "/v2/disputes/sq_dp_1",
+        r#"{"data":{"id":"sq_dp_1","state":"ACCEPTED","amount_money":{"amount":900,"currency":"USD"}}}"#,
+    )]);
+    let provider = SquareProvider::with_transport("token".to_string(), Environment::Sandbox, transport.clone())
+        .unwrap();
+
+    let dispute = provider.get_dispute("sq_dp_1").await.expect("fake transport should answer");
+    assert_eq!(dispute.status, DisputeStatus::Accepted);
+    assert_eq!(transport.targets(), vec!["GET /v2/disputes/sq_dp_1"]);
+}
@@ -1,3 +1,5 @@
+#![cfg(feature = "stripe")]
+
 use payup::stripe::*;
 
 #[cfg(test)]
@@ -233,15 +235,19 @@ mod payment_intent_tests {
         let params = CreatePaymentIntentParams {
             amount: 2000,
             currency: "usd".to_string(),
+            application_fee_amount: None,
             automatic_payment_methods: Some(AutomaticPaymentMethods {
                 enabled: true,
                 allow_redirects: None,
             }),
             capture_method: Some(CaptureMethod::Automatic),
+            confirm: None,
             confirmation_method: Some(ConfirmationMethod::Automatic),
             customer: Some("cus_123".to_string()),
             description: Some("Test payment".to_string()),
             metadata: None,
+            off_session: None,
+            on_behalf_of: None,
             payment_method: None,
             payment_method_options: None,
             payment_method_types: Some(vec!["card".to_string()]),